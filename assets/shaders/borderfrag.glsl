@@ -0,0 +1,26 @@
+#version 330 core
+
+uniform vec3 campos;
+uniform float total_time;
+//How far away the border starts to fade in
+uniform float fadedist;
+uniform vec4 bordercolor;
+
+out vec4 color;
+in vec3 fragpos;
+
+const float stripewidth = 2.0;
+const float scrollspeed = 0.5;
+
+void main() {
+	//Diagonal stripes that slowly scroll upwards
+	float stripe = fract((fragpos.x + fragpos.z - fragpos.y) / stripewidth + total_time * scrollspeed);
+	if(stripe > 0.5)
+		discard;
+
+	float d = length(fragpos - campos);
+	float alpha = clamp(1.0 - d / fadedist, 0.0, 1.0);
+	color = vec4(bordercolor.rgb, bordercolor.a * alpha);
+	if(color.a < 0.01)
+		discard;
+}
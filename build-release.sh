@@ -7,6 +7,7 @@ rm -rf release/
 mkdir release/$VOXELWORLD_DIR -p
 # Copy the binary over
 cp target/release/voxelworld release/$VOXELWORLD_DIR
+cp target/release/voxelworld-tool release/$VOXELWORLD_DIR
 # Copy assets/
 cp -r assets/ release/$VOXELWORLD_DIR
 cd release/ && tar -czf voxelworld-linux.tar.gz $VOXELWORLD_DIR
@@ -1,32 +1,37 @@
 pub mod assets;
 pub mod block_menu;
 pub mod camera;
-pub mod crafting;
 pub mod entities;
 pub mod gameloop;
 pub mod input;
-pub mod inventory;
 pub mod inventory_screen;
 pub mod load;
-pub mod physics;
 pub mod player;
+pub mod portals;
 pub mod save;
+pub mod schematics;
 pub mod settings;
 pub mod update;
 
+pub use voxelworld_core::{crafting, inventory, physics};
+
 use self::crafting::RecipeTable;
 use self::entities::EntitiesTable;
 use self::inventory::Item;
 use self::settings::Settings;
 use crate::game::inventory::Hotbar;
-use crate::impfile;
-use crate::voxel::block_info::{load_block_info, BlockInfo, BlockInfoTable};
-use crate::voxel::world::WorldGenType;
+use crate::impfile::from_entry::{load_file, print_diagnostics};
+pub use crate::voxel::world::GameMode;
+use crate::voxel::world::{
+    border::WorldBorder, gen_settings::GenSettings, terrain_generator::TerrainGenerator,
+};
 use crate::voxel::Block;
+use crate::voxel::{block_info::BlockInfo, block_registry::block_registry};
 use crate::{assets::texture::load_image_pixels, game::player::PLAYER_HEIGHT, World};
 use assets::models::ModelManager;
 use assets::shaders::ShaderManager;
 use assets::textures::TextureManager;
+use block_menu::BlockMenu;
 pub use camera::Camera;
 use cgmath::{Matrix4, SquareMatrix};
 use egui_gl_glfw::egui::FontDefinitions;
@@ -36,13 +41,9 @@ pub use glfw::{Context, CursorMode, Key, PWindow};
 pub use input::{release_cursor, EventHandler, KeyState};
 use physics::Hitbox;
 use player::Player;
+use schematics::SchematicTool;
 pub use std::collections::HashMap;
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum GameMode {
-    Creative,
-    Survival,
-}
+use std::thread::JoinHandle;
 
 #[derive(Copy, Clone)]
 pub enum BlockMenuShape {
@@ -115,7 +116,7 @@ pub struct Game {
     pub models: ModelManager,
     pub shaders: ShaderManager,
     pub textures: TextureManager,
-    block_menu: Vec<u8>,
+    block_menu: Vec<u16>,
     //Debug info
     display_debug: bool,
     pub invert_backface_culling: bool,
@@ -124,8 +125,12 @@ pub struct Game {
     block_menu_shape: BlockMenuShape,
     pub block_menu_start_row: usize,
     pub display_hud: bool,
+    //Sky and fog tint of the biome the player is in
+    pub sky_tint: (f32, f32, f32),
+    //Schematic menu
+    display_schematic_menu: bool,
+    pub schematic_tool: SchematicTool,
     //Block info table
-    block_info: BlockInfoTable,
     //Crafting recipes
     pub recipe_table: RecipeTable,
     //Item that is left over when it is used
@@ -134,6 +139,8 @@ pub struct Game {
     pub entities: EntitiesTable,
     //Settings
     pub settings: Settings,
+    //Backs up the last world that was played
+    backup_thread: Option<JoinHandle<()>>,
 }
 
 impl Game {
@@ -170,11 +177,14 @@ impl Game {
             display_block_menu: false,
             block_menu_start_row: 0,
             display_hud: true,
-            block_info: BlockInfoTable::new(),
+            sky_tint: (1.0, 1.0, 1.0),
+            display_schematic_menu: false,
+            schematic_tool: SchematicTool::new(),
             recipe_table: RecipeTable::new(),
             leftover_table: HashMap::new(),
             entities: EntitiesTable::new(),
             settings: Settings::default(),
+            backup_thread: None,
         }
     }
 
@@ -186,6 +196,9 @@ impl Game {
         self.paused = false;
         self.invert_backface_culling = false;
         self.entities = EntitiesTable::new();
+        self.sky_tint = (1.0, 1.0, 1.0);
+        self.display_schematic_menu = false;
+        self.schematic_tool = SchematicTool::new();
     }
 
     //Initialize game state
@@ -201,15 +214,22 @@ impl Game {
         &mut self,
         seed: u32,
         range: i32,
-        gen_type: WorldGenType,
+        gen_type: &'static dyn TerrainGenerator,
+        settings: GenSettings,
+        border: Option<WorldBorder>,
         game_mode: GameMode,
     ) {
         self.world = World::new(seed, range, gen_type, game_mode);
+        self.world.set_gen_settings(settings);
+        self.world.set_border(border);
         eprintln!("Created world with seed: {}", self.world.get_seed());
         self.world.generate_world();
 
         //Set position of the player
-        for ref y in (-64..=128).rev() {
+        let (spawnx, spawnz) = self.world.spawn_point();
+        self.player.position.x = spawnx;
+        self.player.position.z = spawnz;
+        for ref y in (-64..=256).rev() {
             self.player.position.y = *y as f32;
             if self.player.check_collision(&self.world).is_some() {
                 self.player.position.y += PLAYER_HEIGHT / 2.0;
@@ -223,36 +243,20 @@ impl Game {
         }
     }
 
-    fn load_font_path(&mut self, path: &str) -> Result<String, ()> {
-        let entries = impfile::parse_file(path);
-        if entries.is_empty() {
-            eprintln!("Error: empty font path file");
-            return Err(());
-        }
-        let e = &entries[0];
-        Ok(e.get_var("font_path"))
-    }
-
     pub fn load_block_menu(&mut self, path: &str) {
-        let entries = impfile::parse_file(path);
-        if entries.is_empty() {
-            eprintln!("Error: empty block menu file");
-            return;
+        let (menus, diagnostics) = load_file::<BlockMenu>(path, &());
+        print_diagnostics(&diagnostics);
+        match menus.into_iter().next() {
+            Some(menu) => self.block_menu = menu.0,
+            None => eprintln!("Error: empty block menu file"),
         }
-
-        let e = &entries[0];
-        self.block_menu = e
-            .get_var("block_menu")
-            .split(",")
-            .map(|s| s.parse::<u8>().unwrap_or(1))
-            .collect();
     }
 
     pub fn load_settings(&mut self, path: &str) {
         self.settings = Settings::load(path);
     }
 
-    pub fn get_block_menu(&self) -> &[u8] {
+    pub fn get_block_menu(&self) -> &[u16] {
         &self.block_menu
     }
 
@@ -268,15 +272,8 @@ impl Game {
         self.world.game_mode
     }
 
-    pub fn load_block_info(&mut self, path: &str) {
-        self.block_info = load_block_info(path);
-    }
-
-    pub fn get_block_info(&self, id: u8) -> BlockInfo {
-        self.block_info
-            .get(&id)
-            .cloned()
-            .unwrap_or(BlockInfo::default())
+    pub fn get_block_info(&self, id: u16) -> BlockInfo {
+        block_registry().info(id).cloned().unwrap_or_default()
     }
 }
 
@@ -2,10 +2,52 @@ pub mod models;
 pub mod shaders;
 pub mod textures;
 
-use super::{inventory::load_leftover_table, Game};
+use super::{block_menu::BlockMenu, inventory::load_leftover_table, Game};
+use crate::impfile::{
+    from_entry::{load_file, print_diagnostics},
+    Diagnostic, EntryReader, FromEntry,
+};
 use egui_backend::egui::{FontData, FontDefinitions, FontFamily};
 use egui_gl_glfw as egui_backend;
 use std::{fs::File, io::Read};
+use voxelworld_core::check_assets::{check_assets, AssetCheck};
+
+const ASSETS_PATH: &str = "assets/";
+const FONT_PATH_FILE: &str = "font_path.impfile";
+const SHADERS_FILE: &str = "shaders.impfile";
+const TEXTURES_FILE: &str = "textures.impfile";
+const BLOCK_MENU_FILE: &str = "block_menu.impfile";
+
+struct FontPath(String);
+
+impl FromEntry for FontPath {
+    type Context = ();
+
+    fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+        reader.required_path("font_path").map(Self)
+    }
+}
+
+//If the user provides the argument --check-assets, check every file in
+//assets/, print every problem that is found and then exit the program
+pub fn run_check_assets(args: &[String]) {
+    if !args.contains(&"--check-assets".to_string()) {
+        return;
+    }
+
+    let game_checks: [(&str, AssetCheck); 4] = [
+        (FONT_PATH_FILE, |path| load_file::<FontPath>(path, &()).1),
+        (SHADERS_FILE, shaders::check_shaders),
+        (TEXTURES_FILE, textures::check_textures),
+        (BLOCK_MENU_FILE, |path| load_file::<BlockMenu>(path, &()).1),
+    ];
+    let diagnostics: Vec<Diagnostic> = check_assets(ASSETS_PATH, &game_checks);
+    print_diagnostics(&diagnostics);
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    let warnings = diagnostics.len() - errors;
+    eprintln!("{errors} error(s), {warnings} warning(s)");
+    std::process::exit(if errors > 0 { 1 } else { 0 });
+}
 
 pub fn load_font(path: &str, fonts: &mut FontDefinitions) {
     let font_file = File::open(path);
@@ -42,15 +84,18 @@ impl Game {
     }
 
     pub fn load_assets(&mut self) {
-        let font_path = self.load_font_path("assets/font_path.impfile");
-        if let Ok(font_path) = font_path {
-            load_font(&font_path, &mut self.fonts);
+        let (font_paths, diagnostics) =
+            load_file::<FontPath>(&(ASSETS_PATH.to_string() + FONT_PATH_FILE), &());
+        print_diagnostics(&diagnostics);
+        if let Some(font_path) = font_paths.first() {
+            load_font(&font_path.0, &mut self.fonts);
         }
         self.models.add_default_models();
-        self.shaders.load_shaders("assets/shaders.impfile");
-        self.textures.load_textures("assets/textures.impfile");
+        self.shaders
+            .load_shaders(&(ASSETS_PATH.to_string() + SHADERS_FILE));
+        self.textures
+            .load_textures(&(ASSETS_PATH.to_string() + TEXTURES_FILE));
 
-        self.load_block_info("assets/block_info.impfile");
         self.recipe_table
             .load_recipes("assets/item_aliases.impfile", "assets/recipes.impfile");
         self.recipe_table
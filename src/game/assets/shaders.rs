@@ -1,6 +1,9 @@
+use crate::assets;
 use crate::assets::shader::ShaderProgram;
-use crate::impfile::Entry;
-use crate::{assets, impfile};
+use crate::impfile::{
+    from_entry::{load_file, print_diagnostics},
+    Diagnostic, EntryReader, FromEntry,
+};
 use std::collections::HashMap;
 
 struct ShaderMetaData {
@@ -9,13 +12,17 @@ struct ShaderMetaData {
     frag: String,
 }
 
-impl ShaderMetaData {
-    pub fn from_entry(entry: &Entry) -> Self {
-        Self {
-            name: entry.get_name(),
-            vert: entry.get_var("vert"),
-            frag: entry.get_var("frag"),
-        }
+impl FromEntry for ShaderMetaData {
+    type Context = ();
+
+    fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+        let vert = reader.required_path("vert");
+        let frag = reader.required_path("frag");
+        Some(Self {
+            name: reader.name().to_string(),
+            vert: vert?,
+            frag: frag?,
+        })
     }
 }
 
@@ -52,11 +59,16 @@ impl ShaderManager {
 
     //Loads shaders, should be called at the beginning of the program
     pub fn load_shaders(&mut self, path: &str) {
-        let shaders = impfile::parse_file(path);
-        for entry in shaders {
-            let metadata = ShaderMetaData::from_entry(&entry);
+        let (shaders, diagnostics) = load_file::<ShaderMetaData>(path, &());
+        print_diagnostics(&diagnostics);
+        for metadata in shaders {
             let shader = assets::program_from_vert_and_frag(&metadata.vert, &metadata.frag);
             self.shaders.insert(metadata.name, shader);
         }
     }
 }
+
+//Used by --check-assets
+pub fn check_shaders(path: &str) -> Vec<Diagnostic> {
+    load_file::<ShaderMetaData>(path, &()).1
+}
@@ -1,6 +1,9 @@
 use crate::{
     assets::Texture,
-    impfile::{self, Entry},
+    impfile::{
+        from_entry::{load_file, print_diagnostics},
+        Diagnostic, EntryReader, FromEntry,
+    },
 };
 use std::collections::HashMap;
 
@@ -9,12 +12,15 @@ struct TextureMetaData {
     path: String,
 }
 
-impl TextureMetaData {
-    pub fn from_entry(entry: &Entry) -> Self {
-        Self {
-            name: entry.get_name(),
-            path: entry.get_var("path"),
-        }
+impl FromEntry for TextureMetaData {
+    type Context = ();
+
+    fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+        let path = reader.required_path("path");
+        Some(Self {
+            name: reader.name().to_string(),
+            path: path?,
+        })
     }
 }
 
@@ -52,11 +58,16 @@ impl TextureManager {
 
     //Loads all textures, should be called at the beginning of the game
     pub fn load_textures(&mut self, path: &str) {
-        let textures = impfile::parse_file(path);
-        for entry in textures {
-            let metadata = TextureMetaData::from_entry(&entry);
+        let (textures, diagnostics) = load_file::<TextureMetaData>(path, &());
+        print_diagnostics(&diagnostics);
+        for metadata in textures {
             let texture = load_texture(&metadata.path);
             self.textures.insert(metadata.name, texture);
         }
     }
 }
+
+//Used by --check-assets
+pub fn check_textures(path: &str) -> Vec<Diagnostic> {
+    load_file::<TextureMetaData>(path, &()).1
+}
@@ -2,7 +2,10 @@ use super::{
     inventory::{Item, MAX_STACK_SIZE},
     set_block_shape, BlockMenuShape, Game, KeyState,
 };
-use crate::voxel::Block;
+use crate::{
+    impfile::{EntryReader, FromEntry},
+    voxel::{block_registry::block_registry, Block},
+};
 use cgmath::{vec2, Vector2};
 use glfw::{Key, MouseButton};
 
@@ -10,6 +13,23 @@ pub const ICON_SIZE: f32 = 32.0;
 pub const ROW_LENGTH: usize = 11;
 const NUM_OF_ROWS: usize = 7;
 
+//The list of block ids shown in the block menu
+pub struct BlockMenu(pub Vec<u16>);
+
+impl FromEntry for BlockMenu {
+    type Context = ();
+
+    fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+        let ids: Vec<u16> = reader.required("block_menu")?;
+        for id in &ids {
+            if block_registry().get(*id).is_none() {
+                reader.error("block_menu", &format!("unknown block id {id}"));
+            }
+        }
+        Some(Self(ids))
+    }
+}
+
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum BlockMenuActions {
     Destroy,
@@ -52,7 +72,7 @@ pub fn get_action_icon_positions(
         .collect()
 }
 
-pub fn get_positions(gamestate: &Game, startx: f32, starty: f32) -> Vec<(u8, Vector2<f32>)> {
+pub fn get_positions(gamestate: &Game, startx: f32, starty: f32) -> Vec<(u16, Vector2<f32>)> {
     let mut positions = vec![];
 
     for (i, block) in gamestate.get_block_menu().iter().enumerate() {
@@ -92,7 +112,7 @@ pub fn get_selected<T>(menu: &[(T, Vector2<f32>)], mousex: f32, mousey: f32) ->
     None
 }
 
-pub fn select_block(gamestate: &mut Game, menu: &[(u8, Vector2<f32>)], mousex: f32, mousey: f32) {
+pub fn select_block(gamestate: &mut Game, menu: &[(u16, Vector2<f32>)], mousex: f32, mousey: f32) {
     if let Some(i) = get_selected(menu, mousex, mousey) {
         if gamestate.get_mouse_state(MouseButton::Left) == KeyState::JustPressed {
             let mut block = Block::new_id(menu[i].0);
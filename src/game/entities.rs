@@ -1,8 +1,9 @@
 pub mod dropped_item;
+pub mod falling_block;
 pub mod region;
 pub mod save;
 
-use self::dropped_item::DroppedItemTable;
+use self::{dropped_item::DroppedItemTable, falling_block::FallingBlock};
 use super::{
     physics::{get_block_collision, scan_block_hitbox, Hitbox},
     player::Player,
@@ -10,7 +11,10 @@ use super::{
 use crate::{
     bin_data::DataTable,
     gfx::chunktable::get_hand_light,
-    voxel::{World, CHUNK_SIZE_F32},
+    voxel::{
+        world::{get_simulation_dist, in_sim_range},
+        World, CHUNK_SIZE_F32,
+    },
 };
 use cgmath::{vec3, InnerSpace};
 
@@ -219,6 +223,14 @@ impl Entity {
             dz -= vz;
             dist_remaining = (dx * dx + dy * dy + dz * dz).sqrt();
         }
+
+        //Entities can not cross the world border
+        if let Some(border) = world.border() {
+            let size = self.dimensions.x.max(self.dimensions.z) / 2.0;
+            let (x, z) = border.clamp(self.position.x, self.position.z, size);
+            self.position.x = x;
+            self.position.z = z;
+        }
     }
 
     pub fn stuck(&mut self, world: &World) -> bool {
@@ -265,7 +277,7 @@ impl Entity {
     }
 
     //Returns true if the player is intersecting a specific block type
-    pub fn is_intersecting(&self, world: &World, block_id: u8) -> bool {
+    pub fn is_intersecting(&self, world: &World, block_id: u16) -> bool {
         let ix = self.position.x.floor() as i32;
         let iy = self.position.y.floor() as i32;
         let iz = self.position.z.floor() as i32;
@@ -303,17 +315,47 @@ impl Entity {
 //Entities
 pub struct EntitiesTable {
     pub dropped_items: DroppedItemTable,
+    pub falling_blocks: Vec<FallingBlock>,
 }
 
 impl EntitiesTable {
     pub fn new() -> Self {
         Self {
             dropped_items: DroppedItemTable::new(),
+            falling_blocks: vec![],
         }
     }
 
     pub fn update(&mut self, dt: f32, world: &World, player: &mut Player) {
         self.dropped_items.simulate(dt, world, player);
+
+        let sim_dist = get_simulation_dist(world);
+        let center = world.get_center();
+        for falling_block in &mut self.falling_blocks {
+            let chunkpos = falling_block.get_chunk();
+            if !world.chunks.contains_key(&chunkpos) {
+                continue;
+            }
+
+            //Out of range
+            if !in_sim_range(center, chunkpos, sim_dist) {
+                continue;
+            }
+
+            falling_block.update(dt, world);
+        }
+        self.falling_blocks
+            .retain(|falling_block| !falling_block.destroyed());
+    }
+
+    //Removes and returns the falling blocks that have hit the ground
+    pub fn take_landed_blocks(&mut self) -> Vec<FallingBlock> {
+        let (landed, falling) = self
+            .falling_blocks
+            .drain(..)
+            .partition(|falling_block| falling_block.landed());
+        self.falling_blocks = falling;
+        landed
     }
 
     pub fn init_empty(&mut self, world: &World) {
@@ -236,6 +236,23 @@ impl DroppedItemTable {
         &self.item_list
     }
 
+    //Items that move into another chunk are moved to the list for that chunk
+    //the next time the items are simulated
+    pub fn items_mut(&mut self) -> impl Iterator<Item = &mut DroppedItem> {
+        self.item_list.values_mut().flatten()
+    }
+
+    //Removes and returns every item that `f` returns true for
+    pub fn take_items(&mut self, f: impl Fn(&DroppedItem) -> bool) -> Vec<DroppedItem> {
+        let mut taken = vec![];
+        for list in self.item_list.values_mut() {
+            let (take, keep) = list.drain(..).partition(|dropped_item| f(dropped_item));
+            *list = keep;
+            taken.extend::<Vec<DroppedItem>>(take);
+        }
+        taken
+    }
+
     pub fn remove(&mut self, pos: (i32, i32, i32)) {
         self.item_list.remove(&pos);
     }
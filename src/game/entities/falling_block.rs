@@ -0,0 +1,122 @@
+use super::{Entity, GRAVITY};
+use crate::{
+    bin_data::DataTable,
+    voxel::{Block, World, EMPTY_BLOCK, FULL_BLOCK},
+};
+use cgmath::vec3;
+
+//Slightly smaller than a block so that it can fall through one block holes
+pub const FALLING_BLOCK_SIZE: f32 = 0.98;
+const MAX_FALL_SPEED: f32 = 40.0;
+
+#[derive(Clone)]
+pub struct FallingBlock {
+    pub block: Block,
+    pub entity: Entity,
+    landed: bool,
+}
+
+//What happens to a falling block once it lands
+pub enum Landing {
+    //Placed back into the world at (x, y, z)
+    Place(i32, i32, i32),
+    //Dropped as an item since it can not be placed
+    Drop,
+}
+
+impl FallingBlock {
+    //(x, y, z) is the position of the block that started falling
+    pub fn new(block: Block, x: i32, y: i32, z: i32) -> Self {
+        Self {
+            block,
+            entity: Entity::new(
+                vec3(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5),
+                vec3(FALLING_BLOCK_SIZE, FALLING_BLOCK_SIZE, FALLING_BLOCK_SIZE),
+                vec3(0.0, 0.0, 0.0),
+            ),
+            landed: false,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32, world: &World) {
+        if self.entity.stuck(world) {
+            self.landed = true;
+            return;
+        }
+
+        self.entity.check_y_collision(world);
+        self.entity.translate(dt * 0.5, world);
+        if self.entity.falling {
+            self.entity.velocity.y -= GRAVITY * dt;
+            self.entity.velocity.y = self.entity.velocity.y.max(-MAX_FALL_SPEED);
+        }
+        self.entity.translate(dt * 0.5, world);
+
+        if !self.entity.falling {
+            self.landed = true;
+        }
+
+        //Destroy the block if it falls into the void
+        if self.entity.position.y < world.bottom() as f32 {
+            self.entity.destroy();
+        }
+    }
+
+    //Returns true if the block hit the ground and should be turned back into
+    //a block or dropped as an item
+    pub fn landed(&self) -> bool {
+        self.landed && !self.destroyed()
+    }
+
+    pub fn destroyed(&self) -> bool {
+        self.entity.destroyed
+    }
+
+    //The block is placed where it landed if that space is empty (or can be
+    //replaced) and it is on top of a full block, otherwise it is dropped
+    pub fn get_landing(&self, world: &World) -> Landing {
+        let pos = self.entity.position;
+        let x = pos.x.floor() as i32;
+        let y = pos.y.floor() as i32;
+        let z = pos.z.floor() as i32;
+
+        let block = world.get_block(x, y, z);
+        if block.id != EMPTY_BLOCK && !block.is_fluid() && !block.replaceable() {
+            return Landing::Drop;
+        }
+
+        let below = world.get_block(x, y - 1, z);
+        if below.id == EMPTY_BLOCK
+            || below.shape() != FULL_BLOCK
+            || below.non_voxel_geometry()
+            || below.no_hitbox()
+        {
+            return Landing::Drop;
+        }
+
+        Landing::Place(x, y, z)
+    }
+
+    pub fn get_chunk(&self) -> (i32, i32, i32) {
+        self.entity.get_chunk()
+    }
+
+    pub fn to_data_table(&self) -> DataTable {
+        let mut data_table = self.entity.to_data_table();
+        data_table.add_int("block", self.block.id as i64);
+        data_table.add_int("geometry", self.block.geometry as i64);
+        data_table
+    }
+
+    pub fn from_data_table(data_table: &DataTable) -> Option<Self> {
+        let entity = Entity::from_data_table(data_table)?;
+        let mut block = Block::new_id(data_table.get_int("block")? as u16);
+        block.geometry = data_table.get_int("geometry").unwrap_or(0) as u8;
+
+        Some(Self {
+            block,
+            entity,
+            landed: false,
+        })
+    }
+}
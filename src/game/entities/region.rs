@@ -1,19 +1,16 @@
-use super::{dropped_item::DroppedItem, EntitiesTable, ENTITIES_PATH};
+use super::{dropped_item::DroppedItem, falling_block::FallingBlock, EntitiesTable, ENTITIES_PATH};
 use crate::{
     bin_data,
+    game::save::save_bytes_atomic,
     voxel::region::{
         chunkpos_to_regionpos, regionpos_to_chunkpos, save::region_file_name, REGION_SIZE_I32,
     },
 };
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{Read, Write},
-    path::Path,
-};
+use std::{collections::HashSet, fs::File, io::Read, path::Path};
 
 pub struct EntityRegion {
     pub dropped_items: Vec<DroppedItem>,
+    pub falling_blocks: Vec<FallingBlock>,
     pub loaded: HashSet<(i32, i32, i32)>,
     pub x: i32,
     pub y: i32,
@@ -24,6 +21,7 @@ impl EntityRegion {
     pub fn new(x: i32, y: i32, z: i32) -> Self {
         Self {
             dropped_items: vec![],
+            falling_blocks: vec![],
             loaded: HashSet::new(),
             x,
             y,
@@ -52,6 +50,21 @@ impl EntityRegion {
         }
     }
 
+    pub fn add_falling_block(&mut self, falling_block: FallingBlock) {
+        if falling_block.destroyed() {
+            return;
+        }
+
+        let (chunkx, chunky, chunkz) = falling_block.get_chunk();
+        let (x, y, z) = chunkpos_to_regionpos(chunkx, chunky, chunkz);
+        //Not in this region, ignore
+        if x != self.x || y != self.y || z != self.z {
+            return;
+        }
+        self.loaded.insert((chunkx, chunky, chunkz));
+        self.falling_blocks.push(falling_block);
+    }
+
     pub fn get_data(&self) -> Vec<u8> {
         let mut data = vec![];
 
@@ -65,6 +78,17 @@ impl EntityRegion {
             &dropped_item_tables,
         ));
 
+        //Add falling blocks
+        let falling_block_tables: Vec<_> = self
+            .falling_blocks
+            .iter()
+            .map(|falling_block| falling_block.to_data_table())
+            .collect();
+        data.extend(bin_data::get_table_list_bytes(
+            "falling_blocks",
+            &falling_block_tables,
+        ));
+
         data
     }
 
@@ -81,19 +105,10 @@ impl EntityRegion {
         }
 
         let data_to_write = self.get_data();
-        match File::create(&entities_path) {
-            Ok(mut file) => {
-                if let Err(msg) = file.write_all(&data_to_write) {
-                    eprintln!("Error when saving {}, {}, {}", self.x, self.y, self.z);
-                    eprintln!("E: {msg}");
-                    return Err(entities_path);
-                }
-            }
-            Err(msg) => {
-                eprintln!("Failed to save entities {}, {}, {}", self.x, self.y, self.z);
-                eprintln!("{msg}");
-                return Err(entities_path);
-            }
+        if let Err(msg) = save_bytes_atomic(&entities_path, &data_to_write) {
+            eprintln!("Failed to save entities {}, {}, {}", self.x, self.y, self.z);
+            eprintln!("{msg}");
+            return Err(entities_path);
         }
 
         Ok(())
@@ -107,6 +122,13 @@ impl EntityRegion {
                 .filter_map(DroppedItem::from_data_table)
                 .collect();
         }
+        //Regions from before falling blocks were added do not have a list
+        if let Some(falling_blocks) = parsed_data.get("falling_blocks") {
+            region.falling_blocks = falling_blocks
+                .iter()
+                .filter_map(FallingBlock::from_data_table)
+                .collect();
+        }
         region
     }
 
@@ -124,8 +146,13 @@ impl EntityRegion {
                 }
 
                 let mut stream = bin_data::ByteStream::new(buf);
-                let parsed_data = bin_data::parse_binary_data(&mut stream);
-                Some(Self::from_data_tables(parsed_data, x, y, z))
+                match bin_data::decode_binary_data(&mut stream) {
+                    Ok(parsed_data) => Some(Self::from_data_tables(parsed_data, x, y, z)),
+                    Err(msg) => {
+                        eprintln!("E: Failed to load {path}: {msg}");
+                        None
+                    }
+                }
             }
             Err(_msg) => None,
         }
@@ -146,6 +173,11 @@ pub fn get_region_entities(region: &mut EntityRegion, entities_table: &EntitiesT
             }
         }
     }
+
+    //Add falling blocks
+    for falling_block in &entities_table.falling_blocks {
+        region.add_falling_block(falling_block.clone());
+    }
 }
 
 fn merge_regions(updated: &mut EntityRegion, original: &EntityRegion) {
@@ -159,6 +191,15 @@ fn merge_regions(updated: &mut EntityRegion, original: &EntityRegion) {
         dropped_items.push(dropped_item.clone());
     }
     updated.add_dropped_item_list(&dropped_items);
+
+    //Merge falling blocks
+    for falling_block in &original.falling_blocks {
+        let chunkpos = falling_block.get_chunk();
+        if updated.loaded.contains(&chunkpos) {
+            continue;
+        }
+        updated.add_falling_block(falling_block.clone());
+    }
 }
 
 pub fn serialize_entities(worldpath: &str, mut region: EntityRegion) -> Result<(), String> {
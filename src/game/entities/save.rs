@@ -10,11 +10,9 @@ use crate::voxel::{
 use std::collections::HashSet;
 
 fn save_entity_region(worldpath: &str, region: EntityRegion) {
+    //Entity regions are saved atomically so on failure the old file is kept
     if let Err(path) = serialize_entities(worldpath, region) {
-        if let Err(msg) = std::fs::remove_file(&path) {
-            eprintln!("Failed to remove {path}");
-            eprintln!("{msg}");
-        }
+        eprintln!("E: Failed to save {path}");
     }
 }
 
@@ -30,7 +28,7 @@ impl EntitiesTable {
         for (rx, ry, rz) in regions_to_save {
             let mut region = EntityRegion::new(rx, ry, rz);
             get_region_entities(&mut region, self);
-            save_entity_region(&world.path, region);
+            save_entity_region(&world.data_path(), region);
         }
     }
 
@@ -42,10 +40,15 @@ impl EntitiesTable {
             regions_to_save.insert(regionpos);
         }
 
+        for falling_block in &self.falling_blocks {
+            let (x, y, z) = falling_block.get_chunk();
+            regions_to_save.insert(chunkpos_to_regionpos(x, y, z));
+        }
+
         for (rx, ry, rz) in regions_to_save {
             let mut region = EntityRegion::new(rx, ry, rz);
             get_region_entities(&mut region, self);
-            save_entity_region(&world.path, region);
+            save_entity_region(&world.data_path(), region);
         }
 
         eprintln!("Saved entities.");
@@ -64,21 +67,33 @@ impl EntitiesTable {
             regions_to_save.insert(chunkpos_to_regionpos(x, y, z));
         }
 
+        for falling_block in &self.falling_blocks {
+            let (x, y, z) = falling_block.get_chunk();
+            if world.is_loaded((x, y, z)) {
+                continue;
+            }
+            regions_to_save.insert(chunkpos_to_regionpos(x, y, z));
+        }
+
         for (rx, ry, rz) in regions_to_save {
             let mut region = EntityRegion::new(rx, ry, rz);
             get_region_entities(&mut region, self);
-            save_entity_region(&world.path, region);
+            save_entity_region(&world.data_path(), region);
         }
 
         for pos in to_deload {
             self.dropped_items.remove(pos);
         }
+        self.falling_blocks
+            .retain(|falling_block| world.is_loaded(falling_block.get_chunk()));
     }
 
     pub fn add_region(&mut self, region: EntityRegion) {
         for dropped_item in &region.dropped_items {
             self.dropped_items.add_item(dropped_item.clone());
         }
+        self.falling_blocks
+            .extend(region.falling_blocks.iter().cloned());
 
         let (startx, starty, startz) = regionpos_to_chunkpos(region.x, region.y, region.z);
         for x in startx..(startx + REGION_SIZE_I32) {
@@ -96,7 +111,7 @@ impl EntitiesTable {
             return false;
         }
 
-        if let Some(region) = EntityRegion::load_region(&world.path, x, y, z) {
+        if let Some(region) = EntityRegion::load_region(&world.data_path(), x, y, z) {
             self.add_region(region);
             return true;
         }
@@ -146,7 +161,7 @@ impl EntitiesTable {
         }
 
         for (x, y, z) in to_load {
-            if let Some(region) = EntityRegion::load_region(&world.path, x, y, z) {
+            if let Some(region) = EntityRegion::load_region(&world.data_path(), x, y, z) {
                 self.add_region(region);
             }
         }
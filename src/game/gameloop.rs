@@ -36,7 +36,6 @@ pub fn run(gamestate: &mut Game, window: &mut PWindow, glfw: &mut Glfw, events:
     //Initialize egui input state
     let mut input_state = gui::init_egui_input_state(window);
 
-    gamestate.world.update_all_chunks();
     //Main loop
     let mut dt = 0.0f32;
     let mut fps = 0;
@@ -110,6 +109,7 @@ pub fn run(gamestate: &mut Game, window: &mut PWindow, glfw: &mut Glfw, events:
             gl::Disable(gl::POLYGON_OFFSET_FILL);
             gl::PolygonOffset(0.0, 0.0);
         }
+        gfx::display::display_border(gamestate, time_passed);
 
         display::display_hud(gamestate, w, h);
         //Display gui
@@ -140,6 +140,16 @@ pub fn run(gamestate: &mut Game, window: &mut PWindow, glfw: &mut Glfw, events:
             let menu =
                 game::block_menu::get_action_icon_positions(BLOCK_MENU_WIDTH, -BLOCK_MENU_HEIGHT);
             game::block_menu::handle_block_menu_action(gamestate, &menu, mousex_f32, mousey_f32);
+        } else if gamestate.get_display_schematic_menu() {
+            let action = gui::run_schematic_menu(
+                &ctx,
+                &mut input_state,
+                &mut painter,
+                &mut gamestate.schematic_tool,
+            );
+            if let Some(action) = action {
+                gamestate.handle_schematic_action(action);
+            }
         } else if gamestate.paused {
             pause_action = gui::run_pause_menu(&ctx, &mut input_state, &mut painter);
         } else if gamestate.player.is_dead() {
@@ -229,6 +239,7 @@ pub fn run(gamestate: &mut Game, window: &mut PWindow, glfw: &mut Glfw, events:
     }
     gamestate.close_inventory();
     gamestate.save_entire_world();
+    gamestate.backup_world();
     gamestate.reset();
     chunktables.clear();
 }
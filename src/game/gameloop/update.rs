@@ -1,8 +1,22 @@
 use crate::{
-    game::{entities::dropped_item::DroppedItem, inventory::Item, Game},
+    game::{
+        entities::{
+            dropped_item::DroppedItem,
+            falling_block::{FallingBlock, Landing},
+        },
+        inventory::Item,
+        physics::{clamp_push, push_distance, Hitbox},
+        Game,
+    },
     gfx::ChunkTables,
-    voxel::{block_info::get_drop, world::get_simulation_dist, EMPTY_BLOCK},
+    voxel::{
+        block_info::get_drop,
+        coordinates::f32coord_to_int,
+        world::{block_update::get_chunktable_updates, get_simulation_dist},
+        EMPTY_BLOCK,
+    },
 };
+use cgmath::{vec3, Vector3};
 use glfw::{CursorMode, PWindow};
 
 fn handle_hotbar_scroll(gamestate: &mut Game) {
@@ -47,6 +61,88 @@ pub fn rotate_player(gamestate: &mut Game, sensitivity: f32, window: &PWindow) {
     gamestate.rotate_player(sensitivity);
 }
 
+//Presses the pressure plates that the player and dropped items are on
+fn press_pressure_plates(gamestate: &mut Game) {
+    let mut positions = vec![];
+    if !gamestate.player.is_dead() {
+        let pos = gamestate.player.position;
+        let y = pos.y - gamestate.player.dimensions.y / 2.0 + 0.05;
+        positions.push((pos.x, y, pos.z));
+    }
+    for items in gamestate.entities.dropped_items.items().values() {
+        for item in items {
+            let pos = item.entity.position;
+            let y = pos.y - item.entity.dimensions.y / 2.0 + 0.05;
+            positions.push((pos.x, y, pos.z));
+        }
+    }
+
+    for (x, y, z) in positions {
+        let (ix, iy, iz) = f32coord_to_int(x, y, z);
+        gamestate.world.press_plate(ix, iy, iz);
+    }
+}
+
+//Moves the player and dropped items out of the way of blocks that are being
+//pushed by pistons
+fn push_entities(gamestate: &mut Game) {
+    for moving in gamestate.world.moving_blocks() {
+        let (x, y, z) = moving.position();
+        let pusher = Hitbox::from_vecs(vec3(x + 0.5, y + 0.5, z + 0.5), vec3(1.0, 1.0, 1.0));
+        let (dx, dy, dz) = moving.dir;
+        let dir = Vector3::new(dx, dy, dz);
+        let dir_f32 = vec3(dx as f32, dy as f32, dz as f32);
+
+        if !gamestate.player.is_dead() {
+            let hitbox = gamestate.player.get_hitbox();
+            if let Some(dist) = push_distance(&hitbox, &pusher, dir) {
+                let dist = clamp_push(&gamestate.world, &hitbox, dir, dist);
+                gamestate.player.position += dir_f32 * dist;
+            }
+        }
+
+        for item in gamestate.entities.dropped_items.items_mut() {
+            let hitbox = item.entity.get_hitbox();
+            if let Some(dist) = push_distance(&hitbox, &pusher, dir) {
+                let dist = clamp_push(&gamestate.world, &hitbox, dir, dist);
+                item.entity.position += dir_f32 * dist;
+            }
+        }
+    }
+}
+
+//Turns falling blocks that hit the ground back into blocks, or into dropped
+//items if they can not be placed where they landed
+fn land_falling_blocks(gamestate: &mut Game) {
+    for falling_block in gamestate.entities.take_landed_blocks() {
+        match falling_block.get_landing(&gamestate.world) {
+            Landing::Place(x, y, z) => {
+                let prev = gamestate.world.get_block(x, y, z);
+                let item = get_drop(Item::Empty, prev);
+                if !prev.is_fluid() && prev.id != EMPTY_BLOCK && !item.is_empty() {
+                    let (fx, fy, fz) = (x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5);
+                    let dropped_item = DroppedItem::new(item, fx, fy, fz);
+                    gamestate.entities.dropped_items.add_item(dropped_item);
+                }
+
+                gamestate.world.set_block(x, y, z, falling_block.block);
+                let mut update_mesh = gamestate.world.update_single_block_light(Some((x, y, z)));
+                get_chunktable_updates(x, y, z, &mut update_mesh);
+                gamestate.world.add_mesh_updates(update_mesh);
+            }
+            Landing::Drop => {
+                let item = get_drop(Item::Empty, falling_block.block);
+                if item.is_empty() {
+                    continue;
+                }
+                let pos = falling_block.entity.position;
+                let dropped_item = DroppedItem::new(item, pos.x, pos.y, pos.z);
+                gamestate.entities.dropped_items.add_item(dropped_item);
+            }
+        }
+    }
+}
+
 pub fn update_game(gamestate: &mut Game, chunktables: &mut ChunkTables, dt: f32) {
     if gamestate.is_paused() {
         return;
@@ -68,6 +164,10 @@ pub fn update_game(gamestate: &mut Game, chunktables: &mut ChunkTables, dt: f32)
     gamestate
         .entities
         .update(dt, &gamestate.world, &mut gamestate.player);
+    land_falling_blocks(gamestate);
+    press_pressure_plates(gamestate);
+    gamestate.world.update_moving_blocks(dt);
+    push_entities(gamestate);
     //Destroy and place blocks
     gamestate.build(chunktables, dt);
     gamestate.update_build_cooldown(dt);
@@ -75,32 +175,34 @@ pub fn update_game(gamestate: &mut Game, chunktables: &mut ChunkTables, dt: f32)
     gamestate.update_hand_animation(dt);
     //Update blocks
     let sim_range = get_simulation_dist(&gamestate.world);
-    gamestate.world.update_sim_range(sim_range);
     let mut destroyed = vec![];
-    destroyed.extend(gamestate.world.update_blocks(dt, chunktables, sim_range));
-    destroyed.extend(
-        gamestate
-            .world
-            .rand_block_update(dt, Some(chunktables), sim_range),
-    );
+    destroyed.extend(gamestate.world.update_blocks(dt, sim_range));
+    destroyed.extend(gamestate.world.rand_block_update(dt, true, sim_range));
     //Add block drops for blocks broken by block updates
     for ((x, y, z), block) in destroyed {
         if block.is_fluid() || block.id == EMPTY_BLOCK {
             continue;
         }
 
-        let item = get_drop(&gamestate.block_info, Item::Empty, block);
+        let item = get_drop(Item::Empty, block);
         if item.is_empty() {
             continue;
         }
         let dropped_item = DroppedItem::new(item, x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5);
         gamestate.entities.dropped_items.add_item(dropped_item);
     }
+    //Blocks that lost the block under them start falling
+    for ((x, y, z), block) in gamestate.world.take_falling_blocks() {
+        let falling_block = FallingBlock::new(block, x, y, z);
+        gamestate.entities.falling_blocks.push(falling_block);
+    }
     gamestate
         .world
-        .update_tile_data(dt, sim_range, &gamestate.recipe_table, chunktables);
+        .update_tile_data(dt, sim_range, &gamestate.recipe_table);
+    chunktables.update_changed_chunks(&mut gamestate.world);
     //Update day night cycle
     gamestate.world.update_daynight(dt);
+    gamestate.update_sky_tint(dt);
 
     //Generate new chunks
     gamestate.world.clean_cache();
@@ -110,6 +212,6 @@ pub fn update_game(gamestate: &mut Game, chunktables: &mut ChunkTables, dt: f32)
         .update_generation_queue(gamestate.player.position);
     let loaded = gamestate.world.load_from_queue(0.01);
     gamestate.entities.load_from_list(&gamestate.world, loaded);
-    gamestate.world.update_chunktables(chunktables);
+    chunktables.add_loaded_chunks(&mut gamestate.world);
     chunktables.update_tables(gamestate);
 }
@@ -10,7 +10,10 @@ use crate::gfx::display::inventory::{
 };
 use crate::{
     gfx::display::inventory::{CRAFTING_GRID_POS, HOTBAR_POS, MAIN_INVENTORY_POS, OUTPUT_POS},
-    voxel::Block,
+    voxel::{
+        block_ids::{CHEST, FURNACE, LIT_FURNACE},
+        Block,
+    },
 };
 use glfw::{Key, MouseButtonLeft, MouseButtonRight};
 
@@ -248,7 +251,7 @@ fn handle_shift_left_click(gamestate: &mut Game, mousepos: (f32, f32)) -> bool {
     } else {
         match gamestate.player.opened_block_id {
             //Chest
-            37 => {
+            CHEST => {
                 shift_left_click(
                     &mut gamestate.player.inventory,
                     &mut gamestate.player.open_block_data.inventory,
@@ -280,7 +283,7 @@ fn handle_shift_left_click(gamestate: &mut Game, mousepos: (f32, f32)) -> bool {
                 }
             }
             //Furnace
-            40 | 70 => {
+            FURNACE | LIT_FURNACE => {
                 if let Some((ix, iy)) = selected_furnace {
                     let item = gamestate.player.open_block_data.inventory.get_item(ix, iy);
                     let leftover = gamestate.player.add_item(item);
@@ -373,13 +376,13 @@ fn handle_left_click(gamestate: &mut Game, mousepos: (f32, f32)) {
     } else {
         let i = match gamestate.player.opened_block_id {
             //Chest
-            37 => {
+            CHEST => {
                 let chest = &mut gamestate.player.open_block_data.inventory;
                 let selected_pos = get_selected_slot(chest, CHEST_INVENTORY_POS, SLOT_SZ, mousepos);
                 left_click(chest, selected_pos, mouse_item)
             }
             //Furnace
-            40 | 70 => {
+            FURNACE | LIT_FURNACE => {
                 let furnace = &mut gamestate.player.open_block_data.inventory;
                 //Output
                 if let Some((2, 0)) = selected_furnace {
@@ -644,12 +647,12 @@ fn handle_right_click(gamestate: &mut Game, mousepos: (f32, f32)) {
     } else {
         let i = match gamestate.player.opened_block_id {
             //Chest/Furnace
-            37 => right_click(
+            CHEST => right_click(
                 &mut gamestate.player.open_block_data.inventory,
                 selected_chest,
                 mouse_item,
             ),
-            40 | 70 => right_click(
+            FURNACE | LIT_FURNACE => right_click(
                 &mut gamestate.player.open_block_data.inventory,
                 selected_furnace,
                 mouse_item,
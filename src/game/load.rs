@@ -46,6 +46,7 @@ fn load_inventory(path: &str) -> (Hotbar, Inventory) {
 
 impl Game {
     pub fn load_world(&mut self, world_path: &str) {
+        self.wait_for_backup();
         let cam_path = world_path.to_string() + "camera.impfile";
         self.cam = load_camera(&cam_path);
         let player_path = world_path.to_string() + "player.impfile";
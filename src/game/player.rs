@@ -9,6 +9,7 @@ use super::Hitbox;
 use super::KeyState;
 use crate::game::entities::GRAVITY;
 use crate::impfile;
+use crate::voxel::build::Builder;
 use crate::voxel::tile_data::TileData;
 use crate::voxel::World;
 use cgmath::{vec3, Deg, InnerSpace, Matrix4, Vector3, Vector4};
@@ -66,12 +67,26 @@ pub struct Player {
     //None if no block is open
     //Some(position) if a block is opened
     pub opened_block: Option<(i32, i32, i32)>,
-    pub opened_block_id: u8,
+    pub opened_block_id: u16,
     //Flying
     pub spacebar_timer: f32,
     pub flying: bool,
 }
 
+impl Builder for Player {
+    fn held_item(&self) -> Item {
+        self.hotbar.get_selected()
+    }
+
+    fn is_crouching(&self) -> bool {
+        self.crouching
+    }
+
+    fn get_hitbox(&self) -> Hitbox {
+        Player::get_hitbox(self)
+    }
+}
+
 impl Player {
     //Create new player object
     pub fn new(x: f32, y: f32, z: f32) -> Self {
@@ -151,6 +166,13 @@ impl Player {
         }
     }
 
+    //Moves the player without them falling or taking fall damage
+    pub fn teleport(&mut self, position: Vector3<f32>) {
+        self.position = position;
+        self.velocity_y = 0.0;
+        self.dist_fallen = 0.0;
+    }
+
     pub fn select_hotbar_item(&mut self, keystate: KeyState, index: usize) {
         if keystate.is_held() {
             self.hotbar.selected = index;
@@ -382,6 +404,18 @@ impl Player {
             dz -= vz;
             dist_remaining = (dx * dx + dy * dy + dz * dz).sqrt();
         }
+
+        self.stay_in_border(world);
+    }
+
+    //Stops the player from crossing the world border
+    fn stay_in_border(&mut self, world: &World) {
+        if let Some(border) = world.border() {
+            let size = self.dimensions.x.max(self.dimensions.z) / 2.0;
+            let (x, z) = border.clamp(self.position.x, self.position.z, size);
+            self.position.x = x;
+            self.position.z = z;
+        }
     }
 
     pub fn climbing(&self, world: &World) -> bool {
@@ -13,7 +13,7 @@ impl Player {
     }
 
     //Returns true if the head is intersecting a specified block
-    pub fn head_intersection(&self, world: &World, block_id: u8) -> bool {
+    pub fn head_intersection(&self, world: &World, block_id: u16) -> bool {
         let ix = self.position.x.floor() as i32;
         let iy = self.position.y.floor() as i32;
         let iz = self.position.z.floor() as i32;
@@ -34,7 +34,7 @@ impl Player {
     }
 
     //Returns true if the player is intersecting a specific block type
-    pub fn is_intersecting(&self, world: &World, block_id: u8) -> bool {
+    pub fn is_intersecting(&self, world: &World, block_id: u16) -> bool {
         let ix = self.position.x.floor() as i32;
         let iy = self.position.y.floor() as i32;
         let iz = self.position.z.floor() as i32;
@@ -45,7 +45,7 @@ impl Player {
 
     //Is the top `fract` portion of the player intersecting a block
     #[allow(dead_code)]
-    pub fn top_intersecting(&self, world: &World, block_id: u8, fract: f32) -> bool {
+    pub fn top_intersecting(&self, world: &World, block_id: u16, fract: f32) -> bool {
         let ix = self.position.x.floor() as i32;
         let iy = self.position.y.floor() as i32;
         let iz = self.position.z.floor() as i32;
@@ -63,7 +63,7 @@ impl Player {
     }
 
     //Is the top `fract` portion of the player intersecting a block (but with swimming)
-    pub fn is_swimming(&self, world: &World, block_id: u8, fract: f32) -> bool {
+    pub fn is_swimming(&self, world: &World, block_id: u16, fract: f32) -> bool {
         let ix = self.position.x.floor() as i32;
         let iy = self.position.y.floor() as i32;
         let iz = self.position.z.floor() as i32;
@@ -82,7 +82,7 @@ impl Player {
     }
 
     //Is the bottom `fract` portion of the player intersecting a block
-    pub fn bot_intersecting(&self, world: &World, block_id: u8, fract: f32) -> bool {
+    pub fn bot_intersecting(&self, world: &World, block_id: u16, fract: f32) -> bool {
         let ix = self.position.x.floor() as i32;
         let iy = self.position.y.floor() as i32;
         let iz = self.position.z.floor() as i32;
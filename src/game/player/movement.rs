@@ -47,10 +47,6 @@ impl Player {
         }
     }
 
-    pub fn is_crouching(&self) -> bool {
-        self.crouching
-    }
-
     //Jump up in the y direction
     pub fn jump(&mut self, jump_key: KeyState) {
         if self.falling {
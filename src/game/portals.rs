@@ -0,0 +1,119 @@
+/*
+ * Traveling between dimensions through portals, the dimensions themselves
+ * are defined in `voxel/world/dimension.rs`.
+ *
+ * Only one dimension is loaded at a time, so traveling saves the loaded
+ * dimension and then loads the chunks around the portal in the destination.
+ * Dropped items lying on top of the portal travel along with the player.
+ * */
+
+use super::entities::{dropped_item::DroppedItem, EntitiesTable};
+use super::player::PLAYER_HEIGHT;
+use super::Game;
+use crate::gfx::ChunkTables;
+use crate::voxel::world::dimension::{
+    overworld, portal_destination, scale_position, Dimension, PortalEnd,
+};
+use crate::voxel::World;
+use cgmath::vec3;
+
+//Returns true if the dropped item is lying on top of the portal at `pos`
+fn on_portal(dropped_item: &DroppedItem, (x, y, z): (i32, i32, i32)) -> bool {
+    let pos = dropped_item.entity.position;
+    pos.x.floor() as i32 == x
+        && pos.z.floor() as i32 == z
+        && pos.y >= (y + 1) as f32
+        && pos.y < (y + 2) as f32
+}
+
+impl Game {
+    //Saves the loaded dimension and replaces it with `dimension`, none of the
+    //chunks in the new dimension are loaded
+    fn switch_dimension(&mut self, dimension: &'static Dimension) {
+        self.save_entire_world();
+        let path = self.world.path.clone();
+        let range = self.settings.get_range() as i32;
+        self.world = World::load_world_metadata(&path, range);
+        self.world.set_dimension(dimension);
+        self.entities = EntitiesTable::new();
+    }
+
+    //Players always respawn in the overworld
+    pub fn leave_dimension(&mut self) {
+        if self.world.dimension.is_overworld() {
+            return;
+        }
+        self.switch_dimension(overworld());
+    }
+
+    //Moves the player through the portal at `pos` to the dimension that it
+    //leads to, returns false if the block is not a portal
+    pub fn use_portal(&mut self, pos: (i32, i32, i32), chunktables: &mut ChunkTables) -> bool {
+        let (x, y, z) = pos;
+        let portal_id = self.world.get_block(x, y, z).id;
+        let from = self.world.dimension;
+        let Some(to) = portal_destination(from, portal_id) else {
+            return false;
+        };
+
+        let from_end = PortalEnd::new(from, pos);
+        let linked = self
+            .world
+            .portal_links
+            .get(from_end)
+            .filter(|end| end.dimension == to.name)
+            .map(|end| end.pos);
+        let target = linked.unwrap_or_else(|| scale_position(from, to, pos));
+        let items = self
+            .entities
+            .dropped_items
+            .take_items(|dropped_item| on_portal(dropped_item, pos));
+
+        eprintln!("Traveling to the {}...", to.display_name);
+        self.switch_dimension(to);
+        //Portals that lead past the border arrive just inside of it with room
+        //for the arrival platform
+        let target = match self.world.border() {
+            Some(border) => {
+                let (x, y, z) = target;
+                let (bx, bz) = border.clamp(x as f32 + 0.5, z as f32 + 0.5, 3.0);
+                (bx.floor() as i32, y, bz.floor() as i32)
+            }
+            None => target,
+        };
+        let (tx, ty, tz) = target;
+        let feet = vec3(tx as f32 + 0.5, ty as f32, tz as f32 + 0.5);
+        self.player
+            .teleport(feet + vec3(0.0, PLAYER_HEIGHT / 2.0, 0.0));
+        self.world.update_generation_queue(self.player.position);
+        self.world.load_chunks();
+
+        //Find (or build) the portal to arrive at and link it to the portal
+        //that the player used
+        let portal = self.world.arrival_portal(linked, target, portal_id);
+        self.world
+            .portal_links
+            .link(from_end, PortalEnd::new(to, portal));
+        let (sx, sy, sz) = self.world.portal_exit(portal);
+        let feet = vec3(sx as f32 + 0.5, sy as f32, sz as f32 + 0.5);
+        self.player
+            .teleport(feet + vec3(0.0, PLAYER_HEIGHT / 2.0, 0.0));
+        self.world.update_generation_queue(self.player.position);
+        self.world.load_chunks();
+        self.world.init_block_light();
+        self.world.init_sky_light();
+        self.entities.load(&self.world);
+
+        for mut dropped_item in items {
+            dropped_item.entity.position = feet + vec3(0.0, 0.5, 0.0);
+            dropped_item.entity.velocity = vec3(0.0, 0.0, 0.0);
+            self.entities.dropped_items.add_item(dropped_item);
+        }
+
+        self.cam.position = self.player.position;
+        chunktables.clear();
+        chunktables.init_tables(&self.world);
+        self.save_entire_world();
+        true
+    }
+}
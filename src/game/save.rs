@@ -1,47 +1,11 @@
+pub use voxelworld_core::save::{
+    backup, create_save_dir, create_world_dir, get_world_path, save_atomic, save_bytes_atomic,
+    SAVE_PATH,
+};
+
 use super::Game;
 use crate::impfile;
-use std::{fs::File, io::Write, path::Path};
-
-pub const SAVE_PATH: &str = "saves/";
-pub const CHUNK_PATH: &str = "chunkdata/";
-
-pub fn create_save_dir() {
-    if Path::new(SAVE_PATH).exists() {
-        return;
-    }
-
-    if let Err(msg) = std::fs::create_dir_all(SAVE_PATH) {
-        eprintln!("{msg}");
-        panic!("Failed to create save directory, killing program.");
-    }
-}
-
-pub fn get_world_path(world_name: &str) -> String {
-    let mut path = SAVE_PATH.to_string() + world_name.to_lowercase().as_str() + "/";
-
-    let mut id = 1i32;
-    while Path::new(&path).exists() {
-        path = SAVE_PATH.to_string()
-            + world_name.to_lowercase().as_str()
-            + "_"
-            + id.to_string().as_str()
-            + "/";
-        id += 1;
-    }
-
-    path
-}
-
-pub fn create_world_dir(world_path: &str) -> Result<(), String> {
-    if Path::new(world_path).exists() {
-        return Err("Path exists".to_string());
-    }
-
-    std::fs::create_dir_all(world_path).map_err(|e| e.to_string())?;
-    let chunk_path = world_path.to_string() + CHUNK_PATH;
-    std::fs::create_dir_all(chunk_path).map_err(|e| e.to_string())?;
-    Ok(())
-}
+use std::{io::Write, thread};
 
 impl Game {
     fn save_player(&self) {
@@ -49,13 +13,10 @@ impl Game {
         let player_entry = self.player.to_entry();
         let player_save_path = self.world.path.clone() + "player.impfile";
         let player_entry_str = player_entry.to_impfile_string();
-        let res = match File::create(player_save_path) {
-            Ok(mut player_file) => {
-                impfile::write_comment(&mut player_file, "This file contains saved player data");
-                player_file.write_all(player_entry_str.as_bytes())
-            }
-            Err(msg) => Err(msg),
-        };
+        let res = save_atomic(&player_save_path, |player_file| {
+            impfile::write_comment(player_file, "This file contains saved player data");
+            player_file.write_all(player_entry_str.as_bytes())
+        });
 
         if let Err(msg) = res {
             eprintln!("E: Failed to save player: {msg}");
@@ -67,13 +28,10 @@ impl Game {
         let camera_entry = self.cam.to_entry();
         let camera_save_path = self.world.path.clone() + "camera.impfile";
         let camera_entry_str = camera_entry.to_impfile_string();
-        let res = match File::create(camera_save_path) {
-            Ok(mut camera_file) => {
-                impfile::write_comment(&mut camera_file, "This file contains saved camera data");
-                camera_file.write_all(camera_entry_str.as_bytes())
-            }
-            Err(msg) => Err(msg),
-        };
+        let res = save_atomic(&camera_save_path, |camera_file| {
+            impfile::write_comment(camera_file, "This file contains saved camera data");
+            camera_file.write_all(camera_entry_str.as_bytes())
+        });
 
         if let Err(msg) = res {
             eprintln!("E: Failed to save camera: {msg}");
@@ -88,14 +46,11 @@ impl Game {
         //Save inventory
         let inventory_entry = self.player.inventory.to_entry();
         let inventory_entry_str = inventory_entry.to_impfile_string();
-        let res = match File::create(save_path) {
-            Ok(mut inventory_file) => {
-                let save_str = hotbar_entry_str + "\n\n" + &inventory_entry_str;
-                impfile::write_comment(&mut inventory_file, "This files contains inventory data");
-                inventory_file.write_all(save_str.as_bytes())
-            }
-            Err(msg) => Err(msg),
-        };
+        let save_str = hotbar_entry_str + "\n\n" + &inventory_entry_str;
+        let res = save_atomic(&save_path, |inventory_file| {
+            impfile::write_comment(inventory_file, "This files contains inventory data");
+            inventory_file.write_all(save_str.as_bytes())
+        });
 
         if let Err(msg) = res {
             eprintln!("E: Failed to save hotbar: {msg}");
@@ -117,4 +72,29 @@ impl Game {
         self.entities.save_all(&self.world);
         self.world.save_all();
     }
+
+    //Backs up the world in the background so that leaving the world does
+    //not have to wait for it, the world should be saved first
+    pub fn backup_world(&mut self) {
+        self.wait_for_backup();
+        let world_path = self.world.path.clone();
+        self.backup_thread = Some(thread::spawn(move || {
+            let backup_dir = backup::get_backup_dir(&world_path);
+            if let Err(msg) =
+                backup::backup_if_changed(&world_path, &backup_dir, backup::MAX_BACKUPS)
+            {
+                eprintln!("E: {msg}");
+            }
+        }));
+    }
+
+    //Should be called before anything else reads or changes saved worlds
+    //or their backups
+    pub fn wait_for_backup(&mut self) {
+        if let Some(backup_thread) = self.backup_thread.take() {
+            if backup_thread.join().is_err() {
+                eprintln!("E: Backup thread panicked");
+            }
+        }
+    }
 }
@@ -0,0 +1,168 @@
+/*
+ * Creative mode tool for copying a box of blocks into a schematic, saving and
+ * loading schematics in the `schematics/` directory of the world and pasting
+ * them back into the world.
+ * */
+
+use super::{Game, GameMode};
+use crate::voxel::schematic::{
+    list_schematics, load_schematic, save_schematic, Schematic, SchematicTransform,
+};
+
+pub enum SchematicMenuAction {
+    SetCorner1,
+    SetCorner2,
+    ClearSelection,
+    Copy,
+    Save,
+    Load(String),
+    Paste,
+    Close,
+}
+
+pub struct SchematicTool {
+    pub corner1: Option<(i32, i32, i32)>,
+    pub corner2: Option<(i32, i32, i32)>,
+    //Schematic that was last copied or loaded
+    pub clipboard: Option<Schematic>,
+    //Name to save the schematic as
+    pub name: String,
+    pub transform: SchematicTransform,
+    //Schematics saved in the current world
+    pub saved: Vec<String>,
+    //Result of the last action, (message, is_error)
+    pub status: Option<(String, bool)>,
+}
+
+impl Default for SchematicTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SchematicTool {
+    pub fn new() -> Self {
+        Self {
+            corner1: None,
+            corner2: None,
+            clipboard: None,
+            name: String::new(),
+            transform: SchematicTransform::default(),
+            saved: vec![],
+            status: None,
+        }
+    }
+
+    //Returns the size of the selection (width, height, depth)
+    pub fn selection_size(&self) -> Option<(u32, u32, u32)> {
+        let (x1, y1, z1) = self.corner1?;
+        let (x2, y2, z2) = self.corner2?;
+        Some((
+            x1.abs_diff(x2) + 1,
+            y1.abs_diff(y2) + 1,
+            z1.abs_diff(z2) + 1,
+        ))
+    }
+
+    fn set_status(&mut self, msg: &str) {
+        self.status = Some((msg.to_string(), false));
+    }
+
+    fn set_error(&mut self, msg: &str) {
+        eprintln!("E: {msg}");
+        self.status = Some((msg.to_string(), true));
+    }
+}
+
+impl Game {
+    pub fn open_schematic_menu(&mut self) {
+        self.schematic_tool.saved = list_schematics(&self.world.path);
+        self.schematic_tool.status = None;
+        self.display_schematic_menu = true;
+        self.paused = true;
+    }
+
+    pub fn close_schematic_menu(&mut self) {
+        self.display_schematic_menu = false;
+        self.paused = false;
+    }
+
+    pub fn get_display_schematic_menu(&self) -> bool {
+        self.display_schematic_menu && self.game_mode() == GameMode::Creative
+    }
+
+    pub fn handle_schematic_action(&mut self, action: SchematicMenuAction) {
+        let target = self.player.target_block;
+        let tool = &mut self.schematic_tool;
+        match action {
+            SchematicMenuAction::SetCorner1 | SchematicMenuAction::SetCorner2 => {
+                let Some(pos) = target else {
+                    tool.set_error("Look at a block to select it as a corner.");
+                    return;
+                };
+                if matches!(action, SchematicMenuAction::SetCorner1) {
+                    tool.corner1 = Some(pos);
+                } else {
+                    tool.corner2 = Some(pos);
+                }
+                tool.status = None;
+            }
+            SchematicMenuAction::ClearSelection => {
+                tool.corner1 = None;
+                tool.corner2 = None;
+                tool.status = None;
+            }
+            SchematicMenuAction::Copy => {
+                let (Some(corner1), Some(corner2)) = (tool.corner1, tool.corner2) else {
+                    tool.set_error("Select two corners first.");
+                    return;
+                };
+                match self.world.copy_schematic(corner1, corner2) {
+                    Ok(schematic) => {
+                        let msg = format!("Copied {} blocks.", schematic.block_count());
+                        tool.clipboard = Some(schematic);
+                        tool.set_status(&msg);
+                    }
+                    Err(msg) => tool.set_error(&msg),
+                }
+            }
+            SchematicMenuAction::Save => {
+                let Some(schematic) = &tool.clipboard else {
+                    tool.set_error("Copy a selection before saving.");
+                    return;
+                };
+                match save_schematic(&self.world.path, &tool.name, schematic) {
+                    Ok(()) => {
+                        let msg = format!("Saved \"{}\".", tool.name);
+                        tool.saved = list_schematics(&self.world.path);
+                        tool.set_status(&msg);
+                    }
+                    Err(msg) => tool.set_error(&msg),
+                }
+            }
+            SchematicMenuAction::Load(name) => match load_schematic(&self.world.path, &name) {
+                Ok(schematic) => {
+                    tool.clipboard = Some(schematic);
+                    tool.set_status(&format!("Loaded \"{name}\"."));
+                    tool.name = name;
+                }
+                Err(msg) => tool.set_error(&msg),
+            },
+            SchematicMenuAction::Paste => {
+                let Some(schematic) = &tool.clipboard else {
+                    tool.set_error("Copy or load a schematic before pasting.");
+                    return;
+                };
+                //Paste on top of the block the player is looking at
+                let Some((x, y, z)) = target else {
+                    tool.set_error("Look at a block to paste on top of it.");
+                    return;
+                };
+                self.world
+                    .paste_schematic(schematic, (x, y + 1, z), tool.transform);
+                self.close_schematic_menu();
+            }
+            SchematicMenuAction::Close => self.close_schematic_menu(),
+        }
+    }
+}
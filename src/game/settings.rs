@@ -1,4 +1,8 @@
-use crate::impfile::{self, Entry};
+use crate::impfile::{
+    self,
+    from_entry::{load_file, print_diagnostics},
+    Entry, EntryReader, FromEntry,
+};
 use std::{fs::File, io::Write};
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -40,6 +44,32 @@ pub struct Settings {
     pub mouse_sensitivity_multiplier: u32,
 }
 
+impl FromEntry for Settings {
+    type Context = ();
+
+    fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+        let cloud_display = match reader.get_str("cloud_display") {
+            Some(s) => string_to_cloud_display(s).unwrap_or_else(|_| {
+                let msg =
+                    format!("\"cloud_display\" should be fancy, flat or disabled, found \"{s}\"");
+                reader.error("cloud_display", &msg);
+                CloudDisplay::Fancy
+            }),
+            None => CloudDisplay::Fancy,
+        };
+
+        Some(Self {
+            cloud_display,
+            render_distance: reader
+                .get_or("render_distance", DEFAULT_RENDER_DIST)
+                .clamp(MIN_RENDER_DIST, MAX_RENDER_DIST),
+            mouse_sensitivity_multiplier: reader
+                .get_or("mouse_sensitivity", DEFAULT_MOUSE_SENSITIVITY_MULTIPLIER)
+                .clamp(MIN_MOUSE_SENSITIVITY, MAX_MOUSE_SENSITIVITY),
+        })
+    }
+}
+
 impl Settings {
     pub fn default() -> Self {
         Self {
@@ -54,26 +84,18 @@ impl Settings {
     }
 
     pub fn load(path: &str) -> Self {
-        let entries = impfile::parse_file(path);
-        if entries.len() != 1 {
+        //Settings are created the first time the game is closed
+        if !std::path::Path::new(path).exists() {
             return Self::default();
         }
 
-        let cloud_display = string_to_cloud_display(&entries[0].get_var("cloud_display"))
-            .unwrap_or(CloudDisplay::Fancy);
-        Self {
-            cloud_display,
-            render_distance: entries[0]
-                .get_var("render_distance")
-                .parse::<u32>()
-                .unwrap_or(DEFAULT_RENDER_DIST)
-                .clamp(MIN_RENDER_DIST, MAX_RENDER_DIST),
-            mouse_sensitivity_multiplier: entries[0]
-                .get_var("mouse_sensitivity")
-                .parse::<u32>()
-                .unwrap_or(DEFAULT_MOUSE_SENSITIVITY_MULTIPLIER)
-                .clamp(MIN_MOUSE_SENSITIVITY, MAX_MOUSE_SENSITIVITY),
+        let (mut settings, diagnostics) = load_file::<Self>(path, &());
+        print_diagnostics(&diagnostics);
+        if settings.len() != 1 {
+            eprintln!("E: {path} should have exactly one entry, using default settings");
+            return Self::default();
         }
+        settings.remove(0)
     }
 
     pub fn save(&self, path: &str) {
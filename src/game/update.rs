@@ -5,16 +5,19 @@ use super::player::{DEFAULT_MAX_HEALTH, PLAYER_HEIGHT};
 use super::{Game, GameMode, KeyState};
 use crate::game::entities::EntitiesTable;
 use crate::gfx::{self, ChunkTables};
+use crate::voxel::block_ids::{DIRT, DRY_FARMLAND, GRASS, ICE, SNOWY_GRASS};
 use crate::voxel::block_info::get_drop;
 use crate::voxel::build::{destroy_block_suffocating, interact_with_block, BLOCK_REACH};
 use crate::voxel::tile_data::TileData;
-use crate::voxel::world::block_update::break_ice;
+use crate::voxel::world::block_update::{break_ice, finite_fluid::FluidMode};
+use crate::voxel::world::dimension::is_portal;
 use crate::voxel::{self, destroy_block, place_block, Block, World, EMPTY_BLOCK, FULL_BLOCK};
 use cgmath::{vec3, InnerSpace};
 use glfw::{Key, MouseButtonLeft, MouseButtonRight};
 
 const BUILD_COOLDOWN: f32 = 0.15;
 const INVENTORY_DELAY: f32 = 0.5;
+const SKY_TINT_SPEED: f32 = 0.5;
 
 const HOTBAR_KEYS: [Key; 9] = [
     Key::Num1,
@@ -182,10 +185,10 @@ impl Game {
     fn handle_block_destruction(&mut self, destroyed: Option<(i32, i32, i32)>, block: Block) {
         if let Some((x, y, z)) = destroyed {
             let held_item = self.player.hotbar.get_selected();
-            let block_drop = get_drop(&self.block_info, held_item, block);
+            let block_drop = get_drop(held_item, block);
             //If it's ice, then set it to be water if there is a non-empty
             //block beneath it, this only applies if nothing is dropped from the ice
-            if block.id == 85
+            if block.id == ICE
                 && block.shape() == FULL_BLOCK
                 && block_drop.is_empty()
                 && self.game_mode() == GameMode::Survival
@@ -217,12 +220,8 @@ impl Game {
             _ => return,
         };
 
-        let block_info = self.block_info.get(&destroyed_block.id);
-        let (preferred_tool, break_time) = if let Some(block_info) = block_info {
-            (block_info.preferred_tool, block_info.break_time)
-        } else {
-            (None, 0.0)
-        };
+        let block_info = self.get_block_info(destroyed_block.id);
+        let (preferred_tool, break_time) = (block_info.preferred_tool, block_info.break_time);
 
         if break_time > 0.0 && Some(tool.tool_type) == preferred_tool {
             tool.update_durability(1);
@@ -329,7 +328,7 @@ impl Game {
         let break_time = info.get_break_time(held);
         if self.player.break_timer > break_time && block.id != EMPTY_BLOCK {
             if self.destroy_block(chunktables) {
-                let drop = get_drop(&self.block_info, held, block);
+                let drop = get_drop(held, block);
                 let dropped_item =
                     DroppedItem::new(drop, x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5);
                 self.entities.dropped_items.add_item(dropped_item);
@@ -346,6 +345,12 @@ impl Game {
         let interacted = interact_with_block(pos, dir, &mut self.world, &self.player);
         if let Some((ix, iy, iz)) = interacted {
             let interacted_block = self.world.get_block(ix, iy, iz);
+            if is_portal(interacted_block.id) {
+                self.use_portal((ix, iy, iz), chunktables);
+                self.build_cooldown = BUILD_COOLDOWN;
+                return true;
+            }
+
             if interacted_block.open_inventory() && !self.display_debug {
                 self.display_inventory = true;
                 self.player.inventory_delay_timer = INVENTORY_DELAY;
@@ -432,7 +437,7 @@ impl Game {
                 return false;
             }
             //Ignore non dirt and non grass blocks
-            if target.id != 1 && target.id != 4 && target.id != 87 {
+            if target.id != GRASS && target.id != DIRT && target.id != SNOWY_GRASS {
                 self.place_block(chunktables);
                 return false;
             }
@@ -442,7 +447,7 @@ impl Game {
             }
             self.hand_animation = 0.1;
             //Set it to be dry farmland
-            self.world.set_block(x, y, z, Block::new_id(45));
+            self.world.set_block(x, y, z, Block::new_id(DRY_FARMLAND));
             let update_mesh = self.world.update_single_block_light(Some((x, y, z)));
             gfx::update_chunk_vaos(chunktables, Some((x, y, z)), &self.world);
             for (x, y, z) in update_mesh {
@@ -473,7 +478,7 @@ impl Game {
         true
     }
 
-    fn use_bucket(&mut self, chunktables: &mut ChunkTables, blockid: u8) {
+    fn use_bucket(&mut self, chunktables: &mut ChunkTables, blockid: u16) {
         if !self.get_mouse_state(MouseButtonRight).is_held() {
             self.build_cooldown = 0.0;
             return;
@@ -498,13 +503,25 @@ impl Game {
             let dir = self.cam.forward();
             let (x, y, z) = voxel::build::get_selected_fluid(pos, dir, &self.world);
             let block = self.world.get_block(x, y, z);
-            if block.is_fluid() && block.geometry == 7 {
+            //Finite fluids can be scooped up from partially filled blocks
+            //as long as there is enough fluid around them
+            let changed = if self.world.fluid_mode() == FluidMode::Finite {
+                self.world.take_bucket(x, y, z)
+            } else if block.is_fluid() && block.geometry == 7 {
                 self.world.set_block(x, y, z, Block::new());
+                Some(vec![(x, y, z)])
+            } else {
+                None
+            };
+
+            if let Some(changed) = changed {
                 self.player.hotbar.update_selected(Item::Bucket(block.id));
-                let update_mesh = self.world.update_single_block_light(Some((x, y, z)));
-                gfx::update_chunk_vaos(chunktables, Some((x, y, z)), &self.world);
-                for (x, y, z) in update_mesh {
-                    chunktables.update_table(&self.world, x, y, z);
+                for pos in changed {
+                    let update_mesh = self.world.update_single_block_light(Some(pos));
+                    gfx::update_chunk_vaos(chunktables, Some(pos), &self.world);
+                    for (x, y, z) in update_mesh {
+                        chunktables.update_table(&self.world, x, y, z);
+                    }
                 }
                 self.hand_animation = 0.1;
                 self.build_cooldown = BUILD_COOLDOWN;
@@ -537,22 +554,18 @@ impl Game {
                     self.player.hotbar.update_selected(item);
                 }
             }
-            Item::Tool(id, info) => {
-                if info.tool_type == ToolType::Hoe {
-                    if self.use_hoe(chunktables) {
-                        let mut info_copy = info;
-                        if self.game_mode() == GameMode::Survival {
-                            info_copy.update_durability(1);
-                        }
-                        let updated_tool = if info_copy.durability > 0 {
-                            Item::Tool(id, info_copy)
-                        } else {
-                            Item::Empty
-                        };
-                        self.player.hotbar.update_selected(updated_tool)
+            Item::Tool(id, info) if info.tool_type == ToolType::Hoe => {
+                if self.use_hoe(chunktables) {
+                    let mut info_copy = info;
+                    if self.game_mode() == GameMode::Survival {
+                        info_copy.update_durability(1);
                     }
-                } else {
-                    self.place_block(chunktables);
+                    let updated_tool = if info_copy.durability > 0 {
+                        Item::Tool(id, info_copy)
+                    } else {
+                        Item::Empty
+                    };
+                    self.player.hotbar.update_selected(updated_tool)
                 }
             }
             Item::Food(_id, info) => {
@@ -608,6 +621,12 @@ impl Game {
                 return;
             }
 
+            //Escape out of the schematic menu
+            if self.display_schematic_menu {
+                self.close_schematic_menu();
+                return;
+            }
+
             //Escape out of inventory
             if self.display_inventory {
                 self.display_inventory = false;
@@ -635,14 +654,26 @@ impl Game {
         //Only enable block menu in creative mode
         if self.game_mode() == GameMode::Survival {
             self.display_block_menu = false;
+            self.display_schematic_menu = false;
             return;
         }
 
         if self.get_key_state(Key::Tab) == KeyState::JustPressed {
             self.display_inventory = false;
+            self.display_schematic_menu = false;
             self.display_block_menu = !self.display_block_menu;
             self.paused = self.display_block_menu;
         }
+
+        //Open the schematic menu with K (it is closed with escape since K
+        //can be typed into the schematic name)
+        if self.get_key_state(Key::K) == KeyState::JustPressed
+            && !self.paused
+            && !self.display_inventory
+            && !self.display_debug
+        {
+            self.open_schematic_menu();
+        }
     }
 
     //Handle pausing
@@ -691,6 +722,16 @@ impl Game {
         }
     }
 
+    //Fade the sky tint towards the tint of the biome the player is in
+    pub fn update_sky_tint(&mut self, dt: f32) {
+        let x = self.player.position.x.floor() as i32;
+        let z = self.player.position.z.floor() as i32;
+        let (r, g, b) = self.world.get_biome(x, z).sky_tint();
+        let t = (dt * SKY_TINT_SPEED).min(1.0);
+        let (tr, tg, tb) = self.sky_tint;
+        self.sky_tint = (tr + (r - tr) * t, tg + (g - tg) * t, tb + (b - tb) * t);
+    }
+
     //Update hand animation
     pub fn update_hand_animation(&mut self, dt: f32) {
         if self.player.is_dead() {
@@ -717,8 +758,11 @@ impl Game {
             return;
         }
 
+        self.leave_dimension();
+
         //Respawn player
-        self.player = self.player.respawn(7.5, 7.5);
+        let (spawnx, spawnz) = self.world.spawn_point();
+        self.player = self.player.respawn(spawnx, spawnz);
         self.player.position.y = 128.0;
         self.world.update_generation_queue(self.player.position);
 
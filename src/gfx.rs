@@ -10,7 +10,7 @@ pub mod nonvoxel;
 use crate::game::{Camera, Game};
 use buildchunk::{generate_chunk_vertex_data, ChunkData};
 use cgmath::Matrix4;
-pub use chunktable::{update_chunk_vaos, ChunkTables, ChunkVaoTable};
+pub use chunktable::{update_chunk_vaos, ChunkTables};
 use glfw::PWindow;
 
 pub fn calculate_perspective(window: &PWindow, cam: &Camera) -> Matrix4<f32> {
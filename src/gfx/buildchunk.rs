@@ -1,12 +1,17 @@
 mod addvertices;
 
 use super::face_data::FACE_INDICES;
-use crate::voxel::{Chunk, CHUNK_SIZE_I32};
+use crate::voxel::{
+    block_registry::{block_registry, BlockModel},
+    light::{Light, LU},
+    Block, Chunk, CHUNK_SIZE_I32,
+};
 pub use addvertices::add_block_vertices_flat;
 pub use addvertices::add_nonvoxel_vertices;
 use addvertices::{
     add_block_vertices_default, add_block_vertices_furnace_rotated, add_block_vertices_grass,
-    add_block_vertices_log, add_block_vertices_plant, add_block_vertices_trans, add_fluid_vertices,
+    add_block_vertices_log, add_block_vertices_piston, add_block_vertices_plant,
+    add_block_vertices_trans, add_fluid_vertices,
 };
 
 pub type Int3 = (i32, i32, i32);
@@ -31,50 +36,23 @@ pub fn add_block_vertices(
         return;
     }
 
-    //TODO: add a better way of specifying how the faces of the blocks are textured
-    //(probably as some kind of resource file) additionally, the unlabelled constants
-    //should probably be deleted at some point
-    match block.id {
-        1 => {
-            //Grass
-            add_block_vertices_grass(chunk, adj_chunks, xyz, vert_data, 17, 4, 254);
-        }
-        8 => {
-            //Log
-            add_block_vertices_log(chunk, adj_chunks, xyz, vert_data, 24, 25);
-        }
-        37 => {
-            //Chest
-            add_block_vertices_furnace_rotated(chunk, adj_chunks, xyz, vert_data, 38, 39);
-        }
-        40 | 70 => {
-            //Furnace/lit furnace
-            add_block_vertices_furnace_rotated(chunk, adj_chunks, xyz, vert_data, 41, 42);
-        }
-        43 => {
-            //Farmland
-            add_block_vertices_grass(chunk, adj_chunks, xyz, vert_data, 44, 43, 43);
-        }
-        45 => {
-            //Dry Farmland
-            add_block_vertices_grass(chunk, adj_chunks, xyz, vert_data, 46, 45, 45);
-        }
-        82 => {
-            //Hay bale
-            add_block_vertices_log(chunk, adj_chunks, xyz, vert_data, 83, 84);
-        }
-        87 => {
-            //Snow "grass" block
-            add_block_vertices_grass(chunk, adj_chunks, xyz, vert_data, 86, 4, 251);
+    match block_registry().model(block.id) {
+        BlockModel::Grass {
+            top,
+            bottom,
+            side_half,
+        } => add_block_vertices_grass(chunk, adj_chunks, xyz, vert_data, top, bottom, side_half),
+        BlockModel::Log { end, side_rotated } => {
+            add_block_vertices_log(chunk, adj_chunks, xyz, vert_data, end, side_rotated)
         }
-        88 => {
-            //Cactus
-            add_block_vertices_log(chunk, adj_chunks, xyz, vert_data, 89, 88);
+        BlockModel::Furnace { side, top } => {
+            add_block_vertices_furnace_rotated(chunk, adj_chunks, xyz, vert_data, side, top)
         }
-        _ => {
-            //Everything else
-            add_block_vertices_default(chunk, adj_chunks, xyz, vert_data);
+        BlockModel::Piston { side, back, open } => {
+            add_block_vertices_piston(chunk, adj_chunks, xyz, vert_data, side, back, open)
         }
+        //Everything else
+        _ => add_block_vertices_default(chunk, adj_chunks, xyz, vert_data),
     }
 }
 
@@ -99,13 +77,19 @@ pub fn add_block_vertices_transparent(
         return;
     }
 
-    match block.id {
-        //Glass
-        9 => add_block_vertices_trans(chunk, adj_chunks, xyz, vert_data, Some(252), Some(253)),
-        //Plants
-        47..=56 | 69 | 90 | 92 | 99..=102 | 104 | 106 | 108 | 110 | 111 => {
-            add_block_vertices_plant(chunk, xyz, vert_data)
-        }
+    match block_registry().model(block.id) {
+        BlockModel::Glass {
+            slab_side1,
+            slab_side2,
+        } => add_block_vertices_trans(
+            chunk,
+            adj_chunks,
+            xyz,
+            vert_data,
+            Some(slab_side1),
+            Some(slab_side2),
+        ),
+        BlockModel::Plant => add_block_vertices_plant(chunk, xyz, vert_data),
         //Everything else
         _ => add_block_vertices_trans(chunk, adj_chunks, xyz, vert_data, None, None),
     }
@@ -159,6 +143,10 @@ pub fn generate_chunk_vertex_data(
     for x in 0..CHUNK_SIZE_I32 {
         for y in 0..CHUNK_SIZE_I32 {
             for z in 0..CHUNK_SIZE_I32 {
+                //Blocks that are being moved by a piston are drawn separately
+                if chunk.is_moving_relative(x as usize, y as usize, z as usize) {
+                    continue;
+                }
                 let pos = (x, y, z);
                 add_block_vertices(chunk, adj_chunks, pos, &mut chunk_vert_data);
                 add_block_vertices_transparent(chunk, adj_chunks, pos, &mut chunk_vert_data);
@@ -170,6 +158,35 @@ pub fn generate_chunk_vertex_data(
     (chunk_vert_data, get_indices(face_count), 7)
 }
 
+//Generates the vertices for a single block at (1, 1, 1) with every face
+//visible, this is used to draw blocks outside of chunk meshes (dropped
+//items and blocks that are moved by pistons)
+pub fn generate_block_vertex_data(block: Block, light: Light) -> (ChunkData, Indices) {
+    let lu = LU::new(
+        Some(light.sky()),
+        Some(light.r()),
+        Some(light.g()),
+        Some(light.b()),
+    );
+    let mut chunk = Chunk::new(0, 0, 0);
+    //Fill in the light for the chunk
+    for x in 0..=2 {
+        for y in 0..=2 {
+            for z in 0..=2 {
+                chunk.update_light(x, y, z, lu);
+            }
+        }
+    }
+    chunk.set_block_relative(1, 1, 1, block);
+    let mut vert_data = vec![];
+    let adj_chunks = [None; 6];
+    add_block_vertices(&chunk, adj_chunks, (1, 1, 1), &mut vert_data);
+    add_block_vertices_transparent(&chunk, adj_chunks, (1, 1, 1), &mut vert_data);
+    add_block_vertices_fluid(&chunk, adj_chunks, (1, 1, 1), &mut vert_data);
+    let face_count = vert_data.len() / (7 * 4);
+    (vert_data, get_indices(face_count))
+}
+
 //Assumes square faces
 pub fn get_indices(face_count: usize) -> Indices {
     let mut indices = Vec::with_capacity(face_count * 6);
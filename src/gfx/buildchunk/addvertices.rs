@@ -3,6 +3,7 @@ mod furnace;
 mod grass;
 mod log;
 mod nonvoxel;
+mod piston;
 mod plant;
 mod skipface;
 mod slab;
@@ -22,6 +23,7 @@ pub use furnace::add_block_vertices_furnace_rotated;
 pub use grass::add_block_vertices_grass;
 pub use log::add_block_vertices_log;
 pub use nonvoxel::add_nonvoxel_vertices;
+pub use piston::add_block_vertices_piston;
 pub use plant::add_block_vertices_plant;
 use skipface::skip_face;
 use stairgeometry::add_stair_geometry;
@@ -33,6 +35,12 @@ struct FaceInfo {
     block_texture_id: u8,
 }
 
+//Blocks use the texture in the atlas at the same index as their id, the
+//atlas only has room for 256 textures so any id past that is untextured
+pub fn texture_index(blockid: u16) -> u8 {
+    u8::try_from(blockid).unwrap_or(0)
+}
+
 impl FaceInfo {
     fn new(blocki: u8, facei: u8) -> Self {
         Self {
@@ -172,9 +180,9 @@ pub fn add_block_vertices_default(
         return;
     }
 
-    let facex = FaceInfo::new(block.id, 0);
-    let facey = FaceInfo::new(block.id, 1);
-    let facez = FaceInfo::new(block.id, 2);
+    let facex = FaceInfo::new(texture_index(block.id), 0);
+    let facey = FaceInfo::new(texture_index(block.id), 1);
+    let facez = FaceInfo::new(texture_index(block.id), 2);
 
     #[rustfmt::skip]
     add_face(chunk, adj_chunks[0], xyz, (0, 1, 0), vert_data, &TOP_FACE, facey);
@@ -200,7 +208,7 @@ pub fn add_block_vertices_flat(chunk: &Chunk, xyz: Int3, vert_data: &mut ChunkDa
         return;
     }
 
-    let facex = FaceInfo::new(blockid, 0);
+    let facex = FaceInfo::new(texture_index(blockid), 0);
     #[rustfmt::skip]
     add_face(chunk, None, xyz, (-1, 0, 0), vert_data, &LEFT_FACE, facex);
 }
@@ -1,4 +1,4 @@
-use super::{ChunkData, Face, FaceInfo, Int3};
+use super::{texture_index, ChunkData, Face, FaceInfo, Int3};
 use crate::gfx::face_data::{BACK_FACE, BOTTOM_FACE, FRONT_FACE, LEFT_FACE, RIGHT_FACE, TOP_FACE};
 use crate::voxel::{out_of_bounds, wrap_coord, Chunk, EMPTY_BLOCK};
 
@@ -81,9 +81,9 @@ pub fn add_fluid_vertices(
         return;
     }
 
-    let facex = FaceInfo::new(blockid, 0);
-    let facey = FaceInfo::new(blockid, 1);
-    let facez = FaceInfo::new(blockid, 2);
+    let facex = FaceInfo::new(texture_index(blockid), 0);
+    let facey = FaceInfo::new(texture_index(blockid), 1);
+    let facez = FaceInfo::new(texture_index(blockid), 2);
 
     #[rustfmt::skip]
     add_face_fluid(chunk, adj_chunks[0], xyz, (0, 1, 0), vert_data, &TOP_FACE, facey);
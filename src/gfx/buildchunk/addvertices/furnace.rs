@@ -1,4 +1,4 @@
-use super::{add_face, ChunkData, FaceInfo, Int3};
+use super::{add_face, texture_index, ChunkData, FaceInfo, Int3};
 use crate::gfx::face_data::{BACK_FACE, BOTTOM_FACE, FRONT_FACE, LEFT_FACE, RIGHT_FACE, TOP_FACE};
 use crate::voxel::{Chunk, EMPTY_BLOCK};
 
@@ -24,7 +24,7 @@ fn add_block_vertices_furnace(
         FaceInfo::new(side, 2), //Back
         FaceInfo::new(side, 2), //Front
     ];
-    faces[front_face_index].block_texture_id = blockid;
+    faces[front_face_index].block_texture_id = texture_index(blockid);
     let topface = FaceInfo::new(top, 1);
 
     #[rustfmt::skip]
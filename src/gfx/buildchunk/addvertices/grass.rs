@@ -1,4 +1,4 @@
-use super::{add_face, ChunkData, FaceInfo, Int3};
+use super::{add_face, texture_index, ChunkData, FaceInfo, Int3};
 use crate::gfx::face_data::{BACK_FACE, BOTTOM_FACE, FRONT_FACE, LEFT_FACE, RIGHT_FACE, TOP_FACE};
 use crate::voxel::{Chunk, EMPTY_BLOCK};
 
@@ -24,7 +24,7 @@ pub fn add_block_vertices_grass(
     let side = if block.shape() == 1 && block.orientation().is_multiple_of(3) {
         side_half
     } else {
-        block.id
+        texture_index(block.id)
     };
 
     let facex = FaceInfo::new(side, 0);
@@ -1,4 +1,4 @@
-use super::{add_face, ChunkData, FaceInfo, Int3};
+use super::{add_face, texture_index, ChunkData, FaceInfo, Int3};
 use crate::gfx::face_data::{BACK_FACE, BOTTOM_FACE, FRONT_FACE, LEFT_FACE, RIGHT_FACE, TOP_FACE};
 use crate::voxel::{Chunk, EMPTY_BLOCK};
 
@@ -22,13 +22,13 @@ pub fn add_block_vertices_log(
 
     let (facex, facey, facez) = match block.orientation() % 3 {
         0 => (
-            FaceInfo::new(block.id, 0),
+            FaceInfo::new(texture_index(block.id), 0),
             FaceInfo::new(faceytexture, 1),
-            FaceInfo::new(block.id, 2),
+            FaceInfo::new(texture_index(block.id), 2),
         ),
         1 => (
             FaceInfo::new(faceytexture, 0),
-            FaceInfo::new(block.id, 1),
+            FaceInfo::new(texture_index(block.id), 1),
             FaceInfo::new(sidetexture_rotated, 2),
         ),
         2 => (
@@ -37,9 +37,9 @@ pub fn add_block_vertices_log(
             FaceInfo::new(faceytexture, 2),
         ),
         _ => (
-            FaceInfo::new(block.id, 0),
+            FaceInfo::new(texture_index(block.id), 0),
             FaceInfo::new(faceytexture, 1),
-            FaceInfo::new(block.id, 2),
+            FaceInfo::new(texture_index(block.id), 2),
         ),
     };
 
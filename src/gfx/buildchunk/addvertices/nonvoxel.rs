@@ -1,7 +1,13 @@
 use std::collections::HashMap;
 
+use crate::gfx::buildchunk::addvertices::texture_index;
 use crate::gfx::buildchunk::{ChunkData, Int3};
 use crate::gfx::models::{CUBE, CUBE_INDICES, CUBE_TEX_INDICES, QUAD_INDICES, TEX_COORDS};
+use crate::voxel::block_ids::{
+    BLUE_FLOWER_SEEDS, BLUE_TORCH, BUTTON, COTTON_SEED, DOOR, DOOR_TOP, FENCE, GATE, INVERTER,
+    LADDER, LEVER, PISTON_HEAD, PRESSURE_PLATE, RED_FLOWER_SEEDS, REPEATER, SEEDS, TORCH,
+    WHITE_FLOWER_SEEDS, WIRE, YELLOW_FLOWER_SEEDS,
+};
 use crate::voxel::light::Light;
 use crate::voxel::{Block, Chunk};
 use cgmath::{Deg, Matrix4, Vector2, Vector3, Vector4};
@@ -35,7 +41,7 @@ fn tc_to_u8(tc: f32) -> (u8, u8) {
 
 fn add_mesh_to_chunk(
     xyz: Int3,
-    id: u8,
+    id: u16,
     vertices: &[Vert],
     tc: &[Tc],
     vert_data: &mut ChunkData,
@@ -76,7 +82,7 @@ fn add_mesh_to_chunk(
         vert_data.push(vertx | (fx1 << 6));
         vert_data.push(verty | (fy1 << 6));
         vert_data.push(vertz | (fz1 << 6));
-        vert_data.push(id);
+        vert_data.push(texture_index(id));
         //Sky light and red channel
         vert_data.push(((light.r() as u8) << 4) | (light.skylight() as u8));
         vert_data.push(fraction | (tcx1 << 6) | (tcy1 << 7));
@@ -504,11 +510,119 @@ fn gen_door_vertices(block: Block) -> BlockMesh {
     (door, tc)
 }
 
+//Returns a box that goes from `min` to `max` (measured in pixels, a block is
+//16 pixels wide), each face is textured with the part of the texture that it
+//covers so that the box looks like it was cut out of a full block
+fn gen_box(min: Vert, max: Vert) -> BlockMesh {
+    let vertices = generate_mesh_vertices(&CUBE, &CUBE_INDICES);
+    let normals = generate_mesh_normals(&vertices);
+    let box_vertices: Vec<Vert> = vertices
+        .iter()
+        .map(|v| {
+            let t = v + Vert::new(0.5, 0.5, 0.5);
+            Vert::new(
+                min.x + (max.x - min.x) * t.x,
+                min.y + (max.y - min.y) * t.y,
+                min.z + (max.z - min.z) * t.z,
+            ) / 16.0
+        })
+        .collect();
+    let texcoords = box_vertices
+        .iter()
+        .zip(normals)
+        .map(|(v, norm)| {
+            if norm.x != 0.0 {
+                Tc::new(v.z, v.y)
+            } else if norm.y != 0.0 {
+                Tc::new(v.x, v.z)
+            } else {
+                Tc::new(v.x, v.y)
+            }
+        })
+        .collect();
+    (box_vertices, texcoords)
+}
+
+//Rotates a mesh that is attached to the floor so that it is attached to the
+//face of the block that its orientation points away from
+fn attach_to_face(mesh: BlockMesh, orientation: u8) -> BlockMesh {
+    let (vertices, tc) = mesh;
+    let attached = transform_vertices(&vertices, |v| {
+        let mut transformed = v - Vert4::new(0.5, 0.5, 0.5, 0.0);
+        transformed = match orientation {
+            1 => Matrix4::from_angle_z(Deg(-90.0)) * transformed,
+            2 => Matrix4::from_angle_x(Deg(90.0)) * transformed,
+            3 => Matrix4::from_angle_x(Deg(180.0)) * transformed,
+            4 => Matrix4::from_angle_z(Deg(90.0)) * transformed,
+            5 => Matrix4::from_angle_x(Deg(-90.0)) * transformed,
+            _ => transformed,
+        };
+        transformed + Vert4::new(0.5, 0.5, 0.5, 0.0)
+    });
+    (attached, tc)
+}
+
+fn gen_lever_vertices(block: Block) -> BlockMesh {
+    let (mut vertices, mut tc) = gen_box(Vert::new(5.0, 0.0, 4.0), Vert::new(11.0, 3.0, 12.0));
+    let (handle, handle_tc) = gen_box(Vert::new(7.0, 2.0, 7.0), Vert::new(9.0, 12.0, 9.0));
+    //The lever is tilted forward when it is on
+    let angle = if block.reflection() == 1 { 35.0 } else { -35.0 };
+    let handle = transform_vertices(&handle, |v| {
+        let mut transformed = v - Vert4::new(0.5, 2.0 / 16.0, 0.5, 0.0);
+        transformed = Matrix4::from_angle_x(Deg(angle)) * transformed;
+        transformed + Vert4::new(0.5, 2.0 / 16.0, 0.5, 0.0)
+    });
+    //The handle uses the left side of the texture
+    let handle_tc = transform_tc(&handle_tc, |tc, _| Tc::new(tc.x - 6.0 / 16.0, tc.y));
+    vertices.extend(handle);
+    tc.extend(handle_tc);
+    attach_to_face((vertices, tc), block.orientation())
+}
+
+fn gen_button_vertices(block: Block) -> BlockMesh {
+    let height = if block.reflection() == 1 { 1.0 } else { 2.0 };
+    let button = gen_box(Vert::new(5.0, 0.0, 6.0), Vert::new(11.0, height, 10.0));
+    attach_to_face(button, block.orientation())
+}
+
+fn gen_pressure_plate_vertices(block: Block) -> BlockMesh {
+    let height = if block.reflection() == 1 { 1.0 } else { 2.0 };
+    gen_box(Vert::new(1.0, 0.0, 1.0), Vert::new(15.0, height, 15.0))
+}
+
+//Repeaters and inverters, the texture points in the +z direction
+fn gen_repeater_vertices(block: Block) -> BlockMesh {
+    let (vertices, tc) = gen_box(Vert::new(0.0, 0.0, 0.0), Vert::new(16.0, 2.0, 16.0));
+    let rotated = transform_vertices(&vertices, |v| {
+        let mut transformed = v - Vert4::new(0.5, 0.5, 0.5, 0.0);
+        transformed = match block.orientation() {
+            1 => Matrix4::from_angle_y(Deg(90.0)) * transformed,
+            4 => Matrix4::from_angle_y(Deg(-90.0)) * transformed,
+            5 => Matrix4::from_angle_y(Deg(180.0)) * transformed,
+            _ => transformed,
+        };
+        transformed + Vert4::new(0.5, 0.5, 0.5, 0.0)
+    });
+    (rotated, tc)
+}
+
+//Piston heads are made of a plate and a rod that goes back into the piston,
+//the rod uses the side texture of the piston
+fn add_piston_head_vertices(block: Block, xyz: Int3, vert_data: &mut ChunkData, light: Light) {
+    let plate_id = if block.reflection() == 1 { 246 } else { 124 };
+    let plate = gen_box(Vert::new(0.0, 12.0, 0.0), Vert::new(16.0, 16.0, 16.0));
+    let (plate, plate_tc) = attach_to_face(plate, block.orientation());
+    let rod = gen_box(Vert::new(6.0, -4.0, 6.0), Vert::new(10.0, 12.0, 10.0));
+    let (rod, rod_tc) = attach_to_face(rod, block.orientation());
+    add_mesh_to_chunk(xyz, plate_id, &plate, &plate_tc, vert_data, light);
+    add_mesh_to_chunk(xyz, 243, &rod, &rod_tc, vert_data, light);
+}
+
 pub fn add_nonvoxel_vertices(
     chunk: &Chunk,
     xyz: Int3,
     vert_data: &mut ChunkData,
-    cached_meshes: &mut HashMap<(u8, u8), BlockMesh>,
+    cached_meshes: &mut HashMap<(u16, u8), BlockMesh>,
 ) {
     let (x, y, z) = xyz;
     let block = chunk.get_block_relative(x as usize, y as usize, z as usize);
@@ -519,13 +633,25 @@ pub fn add_nonvoxel_vertices(
 
     let id = match block.id {
         //Fence and gate
-        76 | 78 => 6,
+        FENCE | GATE => 6,
         //Door
-        79 => 80,
+        DOOR => 80,
+        //Powered wire
+        WIRE if block.geometry & 15 > 0 => 240,
+        //Powered repeater
+        REPEATER if block.reflection() == 1 => 241,
+        //Powered inverter
+        INVERTER if block.reflection() == 1 => 242,
         _ => block.id,
     };
 
     let light = chunk.get_light_relative(x as usize, y as usize, z as usize);
+    //Piston head
+    if block.id == PISTON_HEAD {
+        add_piston_head_vertices(block, xyz, vert_data, light);
+        return;
+    }
+
     let key = (block.id, block.geometry);
     if let Some((vert, tc)) = cached_meshes.get(&key) {
         add_mesh_to_chunk(xyz, id, vert, tc, vert_data, light);
@@ -533,17 +659,28 @@ pub fn add_nonvoxel_vertices(
 
     let (vert, tc) = match block.id {
         //Torch
-        71..=74 => gen_torch_vertices(block),
+        TORCH..=BLUE_TORCH => gen_torch_vertices(block),
         //Ladder
-        75 => gen_ladder_vertices(block),
+        LADDER => gen_ladder_vertices(block),
         //Fence
-        76 => gen_fence_vertices(block),
+        FENCE => gen_fence_vertices(block),
         //Wheat/cotton seeds/red, yellow, blue, white flower seeds
-        77 | 98 | 103 | 105 | 107 | 109 => gen_seed_vertices(block),
+        SEEDS | COTTON_SEED | RED_FLOWER_SEEDS | YELLOW_FLOWER_SEEDS | BLUE_FLOWER_SEEDS
+        | WHITE_FLOWER_SEEDS => gen_seed_vertices(block),
         //Gate
-        78 => gen_gate_vertices(block),
+        GATE => gen_gate_vertices(block),
         //Bottom door
-        79 | 81 => gen_door_vertices(block),
+        DOOR | DOOR_TOP => gen_door_vertices(block),
+        //Lever
+        LEVER => gen_lever_vertices(block),
+        //Button
+        BUTTON => gen_button_vertices(block),
+        //Pressure plate
+        PRESSURE_PLATE => gen_pressure_plate_vertices(block),
+        //Wire
+        WIRE => gen_box(Vert::new(0.0, 0.0, 0.0), Vert::new(16.0, 1.0, 16.0)),
+        //Repeater and inverter
+        REPEATER | INVERTER => gen_repeater_vertices(block),
         //Top door
         _ => (vec![], vec![]),
     };
@@ -0,0 +1,55 @@
+use super::{add_face, texture_index, ChunkData, FaceInfo, Int3};
+use crate::gfx::face_data::{BACK_FACE, BOTTOM_FACE, FRONT_FACE, LEFT_FACE, RIGHT_FACE, TOP_FACE};
+use crate::voxel::{Chunk, EMPTY_BLOCK};
+
+//The face in the direction the piston is facing uses the block texture (or
+//`open` if the piston is extended) and the face opposite of it uses `back`
+pub fn add_block_vertices_piston(
+    chunk: &Chunk,
+    adj_chunks: [Option<&Chunk>; 6],
+    xyz: Int3,
+    vert_data: &mut ChunkData,
+    side: u8,
+    back: u8,
+    open: u8,
+) {
+    let (x, y, z) = xyz;
+    let block = chunk.get_block_relative(x as usize, y as usize, z as usize);
+    if block.id == EMPTY_BLOCK {
+        return;
+    }
+
+    let front = if block.reflection() == 1 {
+        open
+    } else {
+        texture_index(block.id)
+    };
+    //Index of the front and back face in `textures`
+    let (front_index, back_index) = match block.orientation() {
+        0 => (0, 1),
+        1 => (3, 2),
+        2 => (5, 4),
+        3 => (1, 0),
+        4 => (2, 3),
+        5 => (4, 5),
+        _ => (0, 1),
+    };
+    //Top, bottom, left, right, front, back
+    let mut textures = [side; 6];
+    textures[front_index] = front;
+    textures[back_index] = back;
+    let face = |i: usize| FaceInfo::new(textures[i], [1, 1, 0, 0, 2, 2][i]);
+
+    #[rustfmt::skip]
+    add_face(chunk, adj_chunks[0], xyz, (0, 1, 0), vert_data, &TOP_FACE, face(0));
+    #[rustfmt::skip]
+    add_face(chunk, adj_chunks[1], xyz, (0, -1, 0), vert_data, &BOTTOM_FACE, face(1));
+    #[rustfmt::skip]
+    add_face(chunk, adj_chunks[2], xyz, (-1, 0, 0), vert_data, &LEFT_FACE, face(2));
+    #[rustfmt::skip]
+    add_face(chunk, adj_chunks[3], xyz, (1, 0, 0), vert_data, &RIGHT_FACE, face(3));
+    #[rustfmt::skip]
+    add_face(chunk, adj_chunks[4], xyz, (0, 0, -1), vert_data, &FRONT_FACE, face(4));
+    #[rustfmt::skip]
+    add_face(chunk, adj_chunks[5], xyz, (0, 0, 1), vert_data, &BACK_FACE, face(5));
+}
@@ -1,4 +1,4 @@
-use super::{ChunkData, FaceInfo, Int3};
+use super::{texture_index, ChunkData, FaceInfo, Int3};
 use crate::gfx::face_data::{
     Face, DIAGONAL_FACE_1, DIAGONAL_FACE_1_REVERSED, DIAGONAL_FACE_2, DIAGONAL_FACE_2_REVERSED,
 };
@@ -37,8 +37,8 @@ pub fn add_block_vertices_plant(chunk: &Chunk, xyz: Int3, vert_data: &mut ChunkD
         return;
     }
 
-    let facex = FaceInfo::new(blockid, 0);
-    let facez = FaceInfo::new(blockid, 2);
+    let facex = FaceInfo::new(texture_index(blockid), 0);
+    let facez = FaceInfo::new(texture_index(blockid), 2);
     let light = chunk.get_light_relative(x as usize, y as usize, z as usize);
     add_face_plant(xyz, vert_data, &DIAGONAL_FACE_1, facex, light);
     add_face_plant(xyz, vert_data, &DIAGONAL_FACE_2, facez, light);
@@ -1,6 +1,6 @@
 use super::stairgeometry::StairInfo;
 use super::{add_stair_geometry, get_adj_block, get_adj_light};
-use super::{apply_geometry, skipface::skip_face_trans, ChunkData, FaceInfo, Int3};
+use super::{apply_geometry, skipface::skip_face_trans, texture_index, ChunkData, FaceInfo, Int3};
 use crate::gfx::face_data::{
     Face, BACK_FACE, BOTTOM_FACE, FRONT_FACE, LEFT_FACE, RIGHT_FACE, TOP_FACE,
 };
@@ -73,33 +73,33 @@ pub fn add_block_vertices_trans(
 
     let facex = if block.shape() == 1 {
         match block.orientation() % 3 {
-            0 => FaceInfo::new(slab_side1.unwrap_or(block.id), 0),
-            1 => FaceInfo::new(block.id, 0),
-            2 => FaceInfo::new(slab_side2.unwrap_or(block.id), 0),
-            _ => FaceInfo::new(block.id, 0), //Unreachable
+            0 => FaceInfo::new(slab_side1.unwrap_or(texture_index(block.id)), 0),
+            1 => FaceInfo::new(texture_index(block.id), 0),
+            2 => FaceInfo::new(slab_side2.unwrap_or(texture_index(block.id)), 0),
+            _ => FaceInfo::new(texture_index(block.id), 0), //Unreachable
         }
     } else {
-        FaceInfo::new(block.id, 0)
+        FaceInfo::new(texture_index(block.id), 0)
     };
     let facey = if block.shape() == 1 {
         match block.orientation() % 3 {
-            0 => FaceInfo::new(block.id, 1),
-            1 => FaceInfo::new(slab_side1.unwrap_or(block.id), 1),
-            2 => FaceInfo::new(slab_side2.unwrap_or(block.id), 1),
-            _ => FaceInfo::new(block.id, 1), //Unreachable
+            0 => FaceInfo::new(texture_index(block.id), 1),
+            1 => FaceInfo::new(slab_side1.unwrap_or(texture_index(block.id)), 1),
+            2 => FaceInfo::new(slab_side2.unwrap_or(texture_index(block.id)), 1),
+            _ => FaceInfo::new(texture_index(block.id), 1), //Unreachable
         }
     } else {
-        FaceInfo::new(block.id, 1)
+        FaceInfo::new(texture_index(block.id), 1)
     };
     let facez = if block.shape() == 1 {
         match block.orientation() % 3 {
-            0 => FaceInfo::new(slab_side1.unwrap_or(block.id), 2),
-            1 => FaceInfo::new(slab_side2.unwrap_or(block.id), 2),
-            2 => FaceInfo::new(block.id, 2),
-            _ => FaceInfo::new(block.id, 2), //Unreachable
+            0 => FaceInfo::new(slab_side1.unwrap_or(texture_index(block.id)), 2),
+            1 => FaceInfo::new(slab_side2.unwrap_or(texture_index(block.id)), 2),
+            2 => FaceInfo::new(texture_index(block.id), 2),
+            _ => FaceInfo::new(texture_index(block.id), 2), //Unreachable
         }
     } else {
-        FaceInfo::new(block.id, 2)
+        FaceInfo::new(texture_index(block.id), 2)
     };
 
     #[rustfmt::skip]
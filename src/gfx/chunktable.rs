@@ -1,5 +1,5 @@
 use super::buildchunk::Indices;
-use super::display::{get_sky_brightness, get_skycolor};
+use super::display::get_fog_color;
 use super::fluid::generate_fluid_vertex_data;
 use super::frustum::Frustum;
 use super::nonvoxel::generate_non_voxel_vertex_data;
@@ -13,7 +13,7 @@ use crate::voxel::{
 };
 use crate::CHUNK_SIZE_F32;
 use cgmath::Vector3;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::mem::size_of;
 use std::os::raw::c_void;
 
@@ -475,10 +475,10 @@ impl ChunkVaoTable {
             gamestate.cam.position.y,
             gamestate.cam.position.z,
         );
-        chunkshader.uniform_float("skybrightness", get_sky_brightness(gamestate.world.time));
+        chunkshader.uniform_float("skybrightness", gamestate.world.sky_brightness());
 
         //Set fog color
-        set_fog(gamestate, &chunkshader, get_skycolor(gamestate.world.time));
+        set_fog(gamestate, &chunkshader, get_fog_color(gamestate));
         //Dynamic lighting
         set_dyn_light(gamestate, &chunkshader);
 
@@ -603,6 +603,50 @@ pub fn update_chunk_vaos(chunks: &mut ChunkTables, pos: Option<(i32, i32, i32)>,
     }
 }
 
+//Marks chunks that need to be deleted from the chunk vao table and those that
+//need to be updated and those that need to be added
+fn update_chunk_vao_table(
+    chunktable: &mut ChunkVaoTable,
+    centerx: i32,
+    centery: i32,
+    centerz: i32,
+    range: i32,
+    chunks: &HashMap<(i32, i32, i32), Chunk>,
+    to_generate: &HashSet<(i32, i32, i32)>,
+) {
+    //Delete chunks that are out of range
+    chunktable.delete_chunks(centerx, centery, centerz, range);
+
+    let mut to_update = HashSet::<(i32, i32, i32)>::new();
+    //Mark chunks that need to have a vao generated
+    for (chunkx, chunky, chunkz) in to_generate {
+        to_update.insert((*chunkx, *chunky, *chunkz));
+    }
+
+    //Mark any chunks adjacent to the new border chunks that need to be regenerated
+    for (chunkx, chunky, chunkz) in to_generate {
+        let (x, y, z) = (*chunkx, *chunky, *chunkz);
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if dx == 0 && dy == 0 && dz == 0 {
+                        continue;
+                    }
+
+                    if chunks.contains_key(&(x + dx, y + dy, z + dz)) {
+                        to_update.insert((x + dx, y + dy, z + dz));
+                    }
+                }
+            }
+        }
+    }
+
+    for (x, y, z) in to_update {
+        chunktable.add_to_update(x, y, z);
+    }
+}
+
 pub struct ChunkTables {
     pub chunk_vaos: ChunkVaoTable,
     pub lava_vaos: ChunkVaoTable,
@@ -672,6 +716,33 @@ impl ChunkTables {
             });
     }
 
+    //Adds chunks that were loaded/generated by the world to the tables
+    pub fn add_loaded_chunks(&mut self, world: &mut World) {
+        let to_generate = world.take_chunktable_updates();
+        if to_generate.is_empty() {
+            return;
+        }
+
+        let (x, y, z) = world.get_center();
+        let range = world.get_range();
+        let chunks = &world.chunks;
+        for table in [
+            &mut self.chunk_vaos,
+            &mut self.lava_vaos,
+            &mut self.water_vaos,
+            &mut self.non_voxel_vaos,
+        ] {
+            update_chunk_vao_table(table, x, y, z, range, chunks, &to_generate);
+        }
+    }
+
+    //Updates the chunks that were changed by block updates
+    pub fn update_changed_chunks(&mut self, world: &mut World) {
+        for (x, y, z) in world.take_mesh_updates() {
+            self.update_table(world, x, y, z);
+        }
+    }
+
     pub fn clear(&mut self) {
         self.chunk_vaos.clear();
         self.lava_vaos.clear();
@@ -1,8 +1,10 @@
 pub mod block_menu;
 pub mod dropped_items;
+mod falling_blocks;
 mod hand;
 pub mod hud;
 pub mod inventory;
+mod moving_blocks;
 
 use super::chunktable::set_fog;
 use super::ChunkTables;
@@ -11,16 +13,25 @@ use crate::assets::Texture;
 use crate::game::assets::models::{draw_elements, draw_elements_instanced};
 use crate::game::physics::Hitbox;
 use crate::game::settings::CloudDisplay;
+use crate::voxel::light::{lerp, TRANSITION_TIME};
+use crate::voxel::world::{
+    border::{BorderShape, WorldBorder},
+    dimension::Sky,
+};
 use crate::voxel::{self, CHUNK_SIZE_F32};
 use crate::{game::Game, EMPTY_BLOCK};
 pub use block_menu::display_block_menu;
-use cgmath::{vec3, Deg, Matrix4, SquareMatrix, Vector3};
+use cgmath::{vec3, Deg, Matrix4, Rad, SquareMatrix, Vector3};
 pub use dropped_items::display_dropped_items;
+pub use falling_blocks::display_falling_blocks;
 pub use hand::display_hand_item;
 pub use inventory::{display_hotbar, display_inventory_screen, display_mouse_item};
+pub use moving_blocks::display_moving_blocks;
 
 pub fn display_entities(gamestate: &Game) {
     display_dropped_items(gamestate);
+    display_falling_blocks(gamestate);
+    display_moving_blocks(gamestate);
 }
 
 pub fn display_selected_outline(gamestate: &Game) {
@@ -227,6 +238,11 @@ pub fn display_clouds_menu(gamestate: &Game, time_passed: f32) {
 }
 
 pub fn display_clouds(gamestate: &Game, time_passed: f32) {
+    //Dimensions with a fixed sky have no clouds
+    if gamestate.world.dimension.sky != Sky::DayNight {
+        return;
+    }
+
     unsafe {
         gl::Disable(gl::CULL_FACE);
     }
@@ -261,11 +277,11 @@ pub fn display_clouds(gamestate: &Game, time_passed: f32) {
         gamestate.cam.position.z,
     );
     cloud_shader.uniform_float("total_time", time_passed);
-    cloud_shader.uniform_float("skybrightness", get_sky_brightness(gamestate.world.time));
+    cloud_shader.uniform_float("skybrightness", gamestate.world.sky_brightness());
     cloud_shader.uniform_uint("layers", cloud_thickness as u32);
     let worldsz = gamestate.world.get_range() + 1;
     cloud_shader.uniform_uint("worldsz", worldsz as u32);
-    set_fog(gamestate, &cloud_shader, get_skycolor(gamestate.world.time));
+    set_fog(gamestate, &cloud_shader, get_fog_color(gamestate));
     draw_elements_instanced(quad, cloud_thickness * worldsz * worldsz);
 
     unsafe {
@@ -273,10 +289,91 @@ pub fn display_clouds(gamestate: &Game, time_passed: f32) {
     }
 }
 
-//Assumes 0.0 < t < 1.0
-fn lerp(a: f32, b: f32, t: f32) -> f32 {
-    let t = t.clamp(0.0, 1.0);
-    a * (1.0 - t) + b * t
+//The world border is only displayed when the player is this close to it
+const BORDER_DISPLAY_DIST: f32 = 24.0;
+//Number of quads used to draw the part of a circular border near the player
+const BORDER_ARC_SEGMENTS: usize = 16;
+const BORDER_COLOR: (f32, f32, f32, f32) = (0.3, 0.6, 1.0, 0.6);
+
+//Returns the sections of the border that are close to (x, z) as pairs of
+//endpoints
+fn border_sections(border: &WorldBorder, x: f32, z: f32) -> Vec<((f32, f32), (f32, f32))> {
+    let d = BORDER_DISPLAY_DIST;
+    let (cx, cz, r) = (border.x, border.z, border.radius);
+    match border.shape {
+        BorderShape::Square => {
+            let mut sections = vec![];
+            //Only the part of each side that is near the player is drawn
+            let (minx, maxx) = ((x - d).max(cx - r), (x + d).min(cx + r));
+            let (minz, maxz) = ((z - d).max(cz - r), (z + d).min(cz + r));
+            for side in [cx - r, cx + r] {
+                if (side - x).abs() < d && minz < maxz {
+                    sections.push(((side, minz), (side, maxz)));
+                }
+            }
+            for side in [cz - r, cz + r] {
+                if (side - z).abs() < d && minx < maxx {
+                    sections.push(((minx, side), (maxx, side)));
+                }
+            }
+            sections
+        }
+        BorderShape::Circle => {
+            let angle = (z - cz).atan2(x - cx);
+            let span = (d / r).min(std::f32::consts::PI);
+            let point = |i: usize| {
+                let a = angle - span + 2.0 * span * i as f32 / BORDER_ARC_SEGMENTS as f32;
+                (cx + a.cos() * r, cz + a.sin() * r)
+            };
+            (0..BORDER_ARC_SEGMENTS)
+                .map(|i| (point(i), point(i + 1)))
+                .collect()
+        }
+    }
+}
+
+//Displays the world border as a wall of scrolling stripes that fades in as
+//the player gets close to it
+pub fn display_border(gamestate: &Game, time_passed: f32) {
+    let Some(border) = gamestate.world.border() else {
+        return;
+    };
+    let campos = gamestate.cam.position;
+    if border.distance(campos.x, campos.z) > BORDER_DISPLAY_DIST {
+        return;
+    }
+
+    unsafe {
+        gl::Disable(gl::CULL_FACE);
+        gl::DepthMask(gl::FALSE);
+    }
+
+    let quad = gamestate.models.bind("quad2d");
+    let shader = gamestate.shaders.use_program("border");
+    shader.uniform_matrix4f("persp", &gamestate.persp);
+    shader.uniform_matrix4f("view", &gamestate.cam.get_view());
+    shader.uniform_vec3f("campos", campos.x, campos.y, campos.z);
+    shader.uniform_float("total_time", time_passed);
+    shader.uniform_float("fadedist", BORDER_DISPLAY_DIST);
+    let (r, g, b, a) = BORDER_COLOR;
+    shader.uniform_vec4f("bordercolor", r, g, b, a);
+    for ((x0, z0), (x1, z1)) in border_sections(&border, campos.x, campos.z) {
+        let (dx, dz) = (x1 - x0, z1 - z0);
+        let half_width = (dx * dx + dz * dz).sqrt() / 2.0;
+        let center = vec3((x0 + x1) / 2.0, campos.y, (z0 + z1) / 2.0);
+        //The quad is in the xy plane so it is rotated to line up with the
+        //section of the border
+        let transform = Matrix4::from_translation(center)
+            * Matrix4::from_angle_y(Rad((-dz).atan2(dx)))
+            * Matrix4::from_nonuniform_scale(half_width, BORDER_DISPLAY_DIST, 1.0);
+        shader.uniform_matrix4f("transform", &transform);
+        draw_elements(quad.clone());
+    }
+
+    unsafe {
+        gl::DepthMask(gl::TRUE);
+        gl::Enable(gl::CULL_FACE);
+    }
 }
 
 fn lerp_col(a: (f32, f32, f32), b: (f32, f32, f32), t: f32) -> (f32, f32, f32) {
@@ -290,7 +387,6 @@ const DAY: (f32, f32, f32) = (0.4, 0.8, 1.0);
 const NIGHT: (f32, f32, f32) = (0.1, 0.1, 0.1);
 const ORANGE: (f32, f32, f32) = (1.0, 0.25, 0.0);
 const YELLOW: (f32, f32, f32) = (1.0, 0.8, 0.0);
-const TRANSITION_TIME: f32 = 0.04;
 
 //Returns rgb
 pub fn get_skycolor(t: f32) -> (f32, f32, f32) {
@@ -333,10 +429,28 @@ fn get_bot_skycolor(t: f32) -> (f32, f32, f32) {
     }
 }
 
-fn set_sky_color(shader: &ShaderProgram, t: f32) {
-    let (topr, topg, topb) = get_skycolor(t);
+fn tint_color(color: (f32, f32, f32), tint: (f32, f32, f32)) -> (f32, f32, f32) {
+    let (r, g, b) = color;
+    let (tr, tg, tb) = tint;
+    ((r * tr).min(1.0), (g * tg).min(1.0), (b * tb).min(1.0))
+}
+
+//Returns the sky color tinted by the biome the player is in
+pub fn get_fog_color(gamestate: &Game) -> (f32, f32, f32) {
+    match gamestate.world.dimension.sky {
+        Sky::DayNight => tint_color(get_skycolor(gamestate.world.time), gamestate.sky_tint),
+        Sky::Fixed { color, .. } => color,
+    }
+}
+
+fn set_sky_color(shader: &ShaderProgram, gamestate: &Game) {
+    let t = gamestate.world.time;
+    let (topr, topg, topb) = get_fog_color(gamestate);
     shader.uniform_vec3f("topcolor", topr, topg, topb);
-    let (botr, botg, botb) = get_bot_skycolor(t);
+    let (botr, botg, botb) = match gamestate.world.dimension.sky {
+        Sky::DayNight => tint_color(get_bot_skycolor(t), gamestate.sky_tint),
+        Sky::Fixed { color, .. } => color,
+    };
     shader.uniform_vec3f("botcolor", botr, botg, botb);
 }
 
@@ -393,9 +507,17 @@ pub fn display_sky(gamestate: &Game) {
     skybox_shader.uniform_matrix4f("persp", &persp);
     skybox_shader.uniform_matrix4f("view", &view);
     skybox_shader.uniform_matrix4f("transform", &Matrix4::identity());
-    set_sky_color(&skybox_shader, gamestate.world.time);
+    set_sky_color(&skybox_shader, gamestate);
     draw_elements(cube);
 
+    //Dimensions with a fixed sky have no sun, moon or stars
+    if gamestate.world.dimension.sky != Sky::DayNight {
+        unsafe {
+            gl::Enable(gl::CULL_FACE);
+        }
+        return;
+    }
+
     let rotation = -gamestate.world.time * 360.0;
     let quad = gamestate.models.bind("quad2d");
 
@@ -463,28 +585,6 @@ pub fn display_sky(gamestate: &Game) {
     }
 }
 
-pub fn get_sky_brightness(t: f32) -> f32 {
-    if t < TRANSITION_TIME {
-        lerp(0.0, 1.0, t / TRANSITION_TIME * 0.5 + 0.5)
-    } else if t > 1.0 - TRANSITION_TIME {
-        lerp(
-            0.0,
-            1.0,
-            (t - 1.0 + TRANSITION_TIME) / TRANSITION_TIME * 0.5,
-        )
-    } else if ((0.5 - TRANSITION_TIME)..(0.5 + TRANSITION_TIME)).contains(&t) {
-        lerp(
-            1.0,
-            0.0,
-            (t - 0.5 + TRANSITION_TIME) / TRANSITION_TIME * 0.5,
-        )
-    } else if (0.5..1.0).contains(&t) {
-        0.0
-    } else {
-        1.0
-    }
-}
-
 pub fn display_title(gamestate: &Game, w: i32, h: i32) {
     unsafe {
         gl::Disable(gl::CULL_FACE);
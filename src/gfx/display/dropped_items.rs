@@ -1,5 +1,5 @@
 use super::{
-    get_sky_brightness, get_skycolor,
+    get_fog_color,
     inventory::{ITEM_TEX_SCALE, ITEM_TEX_SIZE},
 };
 use crate::{
@@ -11,18 +11,11 @@ use crate::{
         Game,
     },
     gfx::{
-        buildchunk::{
-            add_block_vertices, add_block_vertices_fluid, add_block_vertices_transparent,
-            get_indices,
-        },
+        buildchunk::generate_block_vertex_data,
         chunktable::{set_dyn_light, set_fog, ChunkVao},
         frustum::Frustum,
     },
-    voxel::{
-        light::LU,
-        world::{get_simulation_dist, in_sim_range},
-        Chunk,
-    },
+    voxel::world::{get_simulation_dist, in_sim_range},
 };
 use cgmath::{vec3, Deg, Matrix4, SquareMatrix};
 
@@ -68,7 +61,7 @@ pub fn display_dropped_items(gamestate: &Game) {
     quadshader.uniform_matrix4f("view", &gamestate.cam.get_view());
     quadshader.uniform_vec2f("texscale", ITEM_TEX_SCALE, ITEM_TEX_SCALE);
     quadshader.uniform_vec3f("campos", campos.x, campos.y, campos.z);
-    set_fog(gamestate, &quadshader, get_skycolor(gamestate.world.time));
+    set_fog(gamestate, &quadshader, get_fog_color(gamestate));
 
     unsafe {
         gl::Disable(gl::CULL_FACE);
@@ -143,7 +136,7 @@ pub fn display_dropped_items(gamestate: &Game) {
                     if !block.is_flat_item() {
                         continue;
                     }
-                    (block.id as u16, amt)
+                    (block.id, amt)
                 }
                 _ => continue,
             };
@@ -183,10 +176,10 @@ pub fn display_dropped_items(gamestate: &Game) {
     let chunkshader = gamestate.shaders.use_program("droppedblock");
     chunkshader.uniform_matrix4f("persp", &gamestate.persp);
     chunkshader.uniform_matrix4f("view", &gamestate.cam.get_view());
-    chunkshader.uniform_float("skybrightness", get_sky_brightness(gamestate.world.time));
+    chunkshader.uniform_float("skybrightness", gamestate.world.sky_brightness());
     chunkshader.uniform_vec3f("campos", campos.x, campos.y, campos.z);
     set_dyn_light(gamestate, &chunkshader);
-    set_fog(gamestate, &chunkshader, get_skycolor(gamestate.world.time));
+    set_fog(gamestate, &chunkshader, get_fog_color(gamestate));
     for (pos, list) in gamestate.entities.dropped_items.items() {
         if !in_sim_range(center, *pos, sim_dist) {
             continue;
@@ -217,29 +210,7 @@ pub fn display_dropped_items(gamestate: &Game) {
                 pos.y.floor() as i32,
                 pos.z.floor() as i32,
             );
-            let lu = LU::new(
-                Some(light.sky()),
-                Some(light.r()),
-                Some(light.g()),
-                Some(light.b()),
-            );
-
-            let mut chunk = Chunk::new(0, 0, 0);
-            //Fill in the light for the chunk
-            for x in 0..=2 {
-                for y in 0..=2 {
-                    for z in 0..=2 {
-                        chunk.update_light(x, y, z, lu);
-                    }
-                }
-            }
-            chunk.set_block_relative(1, 1, 1, block);
-            let mut vert_data = vec![];
-            let adj_chunks = [None; 6];
-            add_block_vertices(&chunk, adj_chunks, (1, 1, 1), &mut vert_data);
-            add_block_vertices_transparent(&chunk, adj_chunks, (1, 1, 1), &mut vert_data);
-            add_block_vertices_fluid(&chunk, adj_chunks, (1, 1, 1), &mut vert_data);
-
+            let (vert_data, indices) = generate_block_vertex_data(block, light);
             if vert_data.is_empty() {
                 continue;
             }
@@ -248,8 +219,7 @@ pub fn display_dropped_items(gamestate: &Game) {
             let transform = get_block_dropped_transform(scale, dropped_item.entity.yaw);
 
             chunkshader.uniform_matrix4f("transform", &transform);
-            let face_count = vert_data.len() / (7 * 4);
-            let vao = ChunkVao::generate_new(&vert_data, &get_indices(face_count), 7);
+            let vao = ChunkVao::generate_new(&vert_data, &indices, 7);
             vao.draw();
             //Draw another item to indicate that this dropped item consists
             //of multiple items
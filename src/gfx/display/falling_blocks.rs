@@ -0,0 +1,60 @@
+use super::get_fog_color;
+use crate::{
+    game::Game,
+    gfx::{
+        buildchunk::generate_block_vertex_data,
+        chunktable::{set_dyn_light, set_fog, ChunkVao},
+        frustum::Frustum,
+    },
+    voxel::world::{get_simulation_dist, in_sim_range},
+};
+use cgmath::{Matrix4, SquareMatrix};
+
+//Displays blocks that are falling, the vertices of each block are generated
+//at (1, 1, 1) so they are offset by one block from the corner of the block
+pub fn display_falling_blocks(gamestate: &Game) {
+    let falling_blocks = &gamestate.entities.falling_blocks;
+    if falling_blocks.is_empty() {
+        return;
+    }
+
+    let frustum = Frustum::new(&gamestate.cam, gamestate.aspect);
+    let campos = gamestate.cam.position;
+    let center = gamestate.world.get_center();
+    let sim_dist = get_simulation_dist(&gamestate.world);
+    gamestate.textures.bind("blocks");
+    let shader = gamestate.shaders.use_program("droppedblock");
+    shader.uniform_matrix4f("persp", &gamestate.persp);
+    shader.uniform_matrix4f("view", &gamestate.cam.get_view());
+    shader.uniform_matrix4f("transform", &Matrix4::identity());
+    shader.uniform_float("skybrightness", gamestate.world.sky_brightness());
+    shader.uniform_vec3f("campos", campos.x, campos.y, campos.z);
+    set_dyn_light(gamestate, &shader);
+    set_fog(gamestate, &shader, get_fog_color(gamestate));
+
+    for falling_block in falling_blocks {
+        if !in_sim_range(center, falling_block.get_chunk(), sim_dist) {
+            continue;
+        }
+
+        if !frustum.intersects(&falling_block.entity.get_hitbox()) {
+            continue;
+        }
+
+        let pos = falling_block.entity.position;
+        let light = gamestate.world.get_light(
+            pos.x.floor() as i32,
+            pos.y.floor() as i32,
+            pos.z.floor() as i32,
+        );
+        let (vert_data, indices) = generate_block_vertex_data(falling_block.block, light);
+        if vert_data.is_empty() {
+            continue;
+        }
+
+        shader.uniform_vec3f("chunkpos", pos.x - 1.5, pos.y - 1.5, pos.z - 1.5);
+        let vao = ChunkVao::generate_new(&vert_data, &indices, 7);
+        vao.draw();
+        vao.delete();
+    }
+}
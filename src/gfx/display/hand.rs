@@ -1,5 +1,5 @@
 use super::{
-    get_sky_brightness, get_skycolor,
+    get_fog_color,
     inventory::{display_block_item, display_block_item_flat3d, ITEM_TEX_SCALE, ITEM_TEX_SIZE},
 };
 use crate::{
@@ -147,9 +147,9 @@ pub fn display_hand_item(gamestate: &Game) {
             quad3d.uniform_matrix4f("transform", &Matrix4::from_scale(0.75));
             quad3d.uniform_matrix4f("persp", &gamestate.persp);
             quad3d.uniform_vec3f("campos", 0.0, 0.0, 0.0);
-            set_fog(gamestate, &quad3d, get_skycolor(gamestate.world.time));
+            set_fog(gamestate, &quad3d, get_fog_color(gamestate));
 
-            let brightness = get_sky_brightness(gamestate.world.time);
+            let brightness = gamestate.world.sky_brightness();
             let daylight = (light.skylight() as f32 * brightness) as u16;
             let r = (light.r().max(daylight) as f32 / 15.0).max(item_r);
             let g = (light.g().max(daylight) as f32 / 15.0).max(item_g);
@@ -12,7 +12,10 @@ use crate::{
         },
         chunktable::ChunkVao,
     },
-    voxel::{Block, Chunk},
+    voxel::{
+        block_ids::{CHEST, FURNACE, LIT_FURNACE},
+        Block, Chunk,
+    },
 };
 use cgmath::{Deg, Matrix4, SquareMatrix, Vector3};
 
@@ -704,7 +707,7 @@ pub fn display_inventory_screen(gamestate: &Game, w: i32, h: i32, mousepos: (f32
     } else {
         match gamestate.player.opened_block_id {
             //Chest
-            37 => {
+            CHEST => {
                 display_inventory(
                     gamestate,
                     &gamestate.player.open_block_data.inventory,
@@ -715,7 +718,7 @@ pub fn display_inventory_screen(gamestate: &Game, w: i32, h: i32, mousepos: (f32
                 );
             }
             //Furnace
-            40 | 70 => {
+            FURNACE | LIT_FURNACE => {
                 let arrow_y = BOTTOM_Y + SLOT_SZ / 2.0 + STEP * 5.0 + SLOT_SZ;
                 display_arrow(gamestate, 0.0, arrow_y);
                 display_fire_outline(gamestate, -STEP, arrow_y);
@@ -0,0 +1,69 @@
+use super::get_fog_color;
+use crate::{
+    game::{physics::Hitbox, Game},
+    gfx::{
+        buildchunk::generate_block_vertex_data,
+        chunktable::{set_dyn_light, set_fog, ChunkVao},
+        frustum::Frustum,
+        nonvoxel::generate_nonvoxel_block_data,
+    },
+};
+use cgmath::{vec3, Matrix4, SquareMatrix};
+
+//Displays the blocks that are sliding into place after being moved by a
+//piston, the vertices of each block are generated at (1, 1, 1) so they are
+//offset by one block
+pub fn display_moving_blocks(gamestate: &Game) {
+    let moving_blocks = gamestate.world.moving_blocks();
+    if moving_blocks.is_empty() {
+        return;
+    }
+
+    let frustum = Frustum::new(&gamestate.cam, gamestate.aspect);
+    let campos = gamestate.cam.position;
+    gamestate.textures.bind("blocks");
+    for shaderid in ["droppedblock", "nonvoxel"] {
+        let shader = gamestate.shaders.use_program(shaderid);
+        shader.uniform_matrix4f("persp", &gamestate.persp);
+        shader.uniform_matrix4f("view", &gamestate.cam.get_view());
+        if shaderid == "droppedblock" {
+            shader.uniform_matrix4f("transform", &Matrix4::identity());
+        }
+        shader.uniform_float("skybrightness", gamestate.world.sky_brightness());
+        shader.uniform_vec3f("campos", campos.x, campos.y, campos.z);
+        set_dyn_light(gamestate, &shader);
+        set_fog(gamestate, &shader, get_fog_color(gamestate));
+
+        for moving in moving_blocks {
+            if moving.block.non_voxel_geometry() != (shaderid == "nonvoxel") {
+                continue;
+            }
+
+            let (x, y, z) = moving.position();
+            let hitbox = Hitbox::from_vecs(vec3(x + 0.5, y + 0.5, z + 0.5), vec3(1.0, 1.0, 1.0));
+            if !frustum.intersects(&hitbox) {
+                continue;
+            }
+
+            let light = gamestate.world.get_light(moving.x, moving.y, moving.z);
+            let (vert_data, indices) = if moving.block.non_voxel_geometry() {
+                generate_nonvoxel_block_data(moving.block, light)
+            } else {
+                generate_block_vertex_data(moving.block, light)
+            };
+            if vert_data.is_empty() {
+                continue;
+            }
+
+            shader.uniform_vec3f("chunkpos", x - 1.0, y - 1.0, z - 1.0);
+            let values_per_vert = if moving.block.non_voxel_geometry() {
+                8
+            } else {
+                7
+            };
+            let vao = ChunkVao::generate_new(&vert_data, &indices, values_per_vert);
+            vao.draw();
+            vao.delete();
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use super::buildchunk::{add_nonvoxel_vertices, ChunkData, Indices};
-use crate::voxel::{Chunk, CHUNK_SIZE_I32};
+use crate::voxel::{
+    light::{Light, LU},
+    Block, Chunk, CHUNK_SIZE_I32,
+};
 use std::collections::HashMap;
 
 fn slice_to_u64(bytes: &[u8]) -> u64 {
@@ -20,12 +23,38 @@ pub fn generate_non_voxel_vertex_data(chunk: &Chunk) -> (ChunkData, Indices, i32
     for x in 0..CHUNK_SIZE_I32 {
         for y in 0..CHUNK_SIZE_I32 {
             for z in 0..CHUNK_SIZE_I32 {
+                //Blocks that are being moved by a piston are drawn separately
+                if chunk.is_moving_relative(x as usize, y as usize, z as usize) {
+                    continue;
+                }
                 let pos = (x, y, z);
                 add_nonvoxel_vertices(chunk, pos, &mut vert_data, &mut cache);
             }
         }
     }
 
+    let (vertices_indexed, indices) = index_vertices(&vert_data);
+    (vertices_indexed, indices, 8)
+}
+
+//Generates the vertices for a single non voxel block at (1, 1, 1)
+pub fn generate_nonvoxel_block_data(block: Block, light: Light) -> (ChunkData, Indices) {
+    let lu = LU::new(
+        Some(light.sky()),
+        Some(light.r()),
+        Some(light.g()),
+        Some(light.b()),
+    );
+    let mut chunk = Chunk::new(0, 0, 0);
+    chunk.update_light(1, 1, 1, lu);
+    chunk.set_block_relative(1, 1, 1, block);
+    let mut vert_data = vec![];
+    add_nonvoxel_vertices(&chunk, (1, 1, 1), &mut vert_data, &mut HashMap::new());
+    index_vertices(&vert_data)
+}
+
+//Removes duplicate vertices
+fn index_vertices(vert_data: &ChunkData) -> (ChunkData, Indices) {
     let mut indexmap = HashMap::<u64, u32>::new();
     let mut vertices_indexed = Vec::with_capacity(vert_data.len());
     let mut indices = vec![];
@@ -41,5 +70,5 @@ pub fn generate_non_voxel_vertex_data(chunk: &Chunk) -> (ChunkData, Indices, i32
         }
     }
 
-    (vertices_indexed, indices, 8)
+    (vertices_indexed, indices)
 }
@@ -4,6 +4,7 @@ pub mod death_screen;
 pub mod debug;
 pub mod main_menu;
 pub mod pause_menu;
+pub mod schematic_menu;
 pub mod select_world_menu;
 pub mod settings_screen;
 
@@ -19,6 +20,7 @@ use egui_backend::{
 use egui_gl_glfw as egui_backend;
 pub use main_menu::run_main_menu;
 pub use pause_menu::run_pause_menu;
+pub use schematic_menu::run_schematic_menu;
 pub use select_world_menu::run_select_world_menu;
 pub use settings_screen::run_settings_menu;
 
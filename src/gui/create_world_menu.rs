@@ -1,17 +1,62 @@
 use super::{init_egui_input_state, menu_text, set_ui_gl_state, transparent_frame};
 use crate::game::{save, EventHandler, Game, GameMode};
 use crate::gfx;
-use crate::voxel::world::WorldGenType;
+use crate::voxel::world::{
+    block_update::finite_fluid::{FluidMode, ALL_FLUID_MODES},
+    border::{BorderShape, WorldBorder, DEFAULT_BORDER_RADIUS, MIN_BORDER_RADIUS},
+    gen_settings::{
+        flat_layers_to_string, parse_flat_layers, worldgen_presets, GenSettings, WorldGenPreset,
+        HEIGHT_SCALE_RANGE, MAX_OCTAVES, ORE_FREQUENCY_RANGE,
+    },
+    map_preview::{preview_map, MapArea},
+    terrain_generator::{default_generator, get_generator, terrain_generators, TerrainGenerator},
+};
 use egui_backend::egui::{self, vec2, Color32, Pos2};
 use egui_gl_glfw as egui_backend;
 use glfw::{Context, Glfw, PWindow};
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Instant;
+
+//Size of the map preview in pixels and the number of blocks per pixel
+const PREVIEW_SIZE: u32 = 128;
+const PREVIEW_SCALE: u32 = 4;
+//The preview is redrawn once the settings have not changed for this many
+//seconds so that dragging a slider does not redraw it every frame
+const PREVIEW_DELAY: f32 = 0.25;
+//Largest border that can be picked in the menu
+const MAX_MENU_BORDER_RADIUS: f32 = 100_000.0;
+
+//Seed, generator and settings that a preview was drawn with
+type PreviewKey = (u32, &'static str, GenSettings);
+
+//Live map of the world that is about to be created
+struct MapPreview {
+    //None if the generator can not draw a map
+    texture: Option<egui::TextureHandle>,
+    drawn: Option<PreviewKey>,
+    //Settings that are waiting to be drawn and when they last changed
+    pending: Option<(PreviewKey, Instant)>,
+}
 
 //State for the create world menu
 struct CreateWorldMenuState {
     world_name: String,
     seed: String,
-    gen_type: WorldGenType,
+    gen_type: &'static dyn TerrainGenerator,
+    gen_settings: GenSettings,
+    //Flat world layers as they are typed in the advanced settings
+    flat_layers: String,
+    //Problems with the advanced settings that stop the world from being
+    //created
+    settings_errors: Vec<String>,
+    //None if the world has no border
+    border_shape: Option<BorderShape>,
+    border_radius: f32,
+    fluid_mode: FluidMode,
+    //Used when the seed is left empty so that the created world matches the
+    //preview
+    random_seed: u32,
+    preview: MapPreview,
     game_mode: GameMode,
     create_world: bool,
     quit_to_menu: bool,
@@ -20,14 +65,66 @@ struct CreateWorldMenuState {
 //Initialize the create world menu state
 impl CreateWorldMenuState {
     fn new() -> Self {
-        Self {
+        let settings = GenSettings::default();
+        let mut menu_state = Self {
             world_name: "New World".to_string(),
             seed: "".to_string(),
-            gen_type: WorldGenType::DefaultGen,
+            gen_type: default_generator(),
+            flat_layers: flat_layers_to_string(&settings.flat_layers),
+            gen_settings: settings,
+            settings_errors: vec![],
+            border_shape: None,
+            border_radius: DEFAULT_BORDER_RADIUS,
+            fluid_mode: FluidMode::Classic,
+            random_seed: fastrand::u32(..),
+            preview: MapPreview {
+                texture: None,
+                drawn: None,
+                pending: None,
+            },
             game_mode: GameMode::Survival,
             create_world: false,
             quit_to_menu: false,
+        };
+        if let Some(preset) = worldgen_presets().first() {
+            menu_state.apply_preset(preset);
         }
+        menu_state
+    }
+
+    fn apply_preset(&mut self, preset: &WorldGenPreset) {
+        self.gen_type = get_generator(&preset.gen_type).unwrap_or_else(default_generator);
+        self.gen_settings = preset.settings.clone();
+        self.flat_layers = flat_layers_to_string(&preset.settings.flat_layers);
+        self.settings_errors.clear();
+    }
+
+    //Checks the advanced settings, returns false if there are any problems
+    //with them (they are listed under the settings)
+    fn check_settings(&mut self) -> bool {
+        self.settings_errors = self.gen_settings.validate();
+        if let Err(msg) = parse_flat_layers(&self.flat_layers) {
+            self.settings_errors.push(msg);
+        }
+        self.settings_errors.is_empty()
+    }
+
+    //A preset stays selected until any of its settings are changed
+    fn preset_selected(&self, preset: &WorldGenPreset) -> bool {
+        preset.gen_type == self.gen_type.name() && preset.settings == self.gen_settings
+    }
+
+    fn get_seed(&self) -> u32 {
+        if self.seed.is_empty() {
+            self.random_seed
+        } else {
+            convert_string_to_seed(self.seed.clone())
+        }
+    }
+
+    fn get_border(&self) -> Option<WorldBorder> {
+        self.border_shape
+            .map(|shape| WorldBorder::new(shape, self.border_radius))
     }
 
     fn should_quit(&self) -> bool {
@@ -49,13 +146,17 @@ fn convert_string_to_seed(seed: String) -> u32 {
 
 //For when the user wants to create a new world
 fn create_new_world(menu_state: &mut CreateWorldMenuState, gamestate: &mut Game) {
-    let seed = if !menu_state.seed.is_empty() {
-        convert_string_to_seed(menu_state.seed.clone())
-    } else {
-        fastrand::u32(..)
-    };
+    let seed = menu_state.get_seed();
     let range = gamestate.settings.get_range() as i32;
-    gamestate.generate_world(seed, range, menu_state.gen_type, menu_state.game_mode);
+    gamestate.generate_world(
+        seed,
+        range,
+        menu_state.gen_type,
+        menu_state.gen_settings.clone(),
+        menu_state.get_border(),
+        menu_state.game_mode,
+    );
+    gamestate.world.set_fluid_mode(menu_state.fluid_mode);
     gamestate.world.init_block_light();
     gamestate.world.init_sky_light();
 }
@@ -63,21 +164,147 @@ fn create_new_world(menu_state: &mut CreateWorldMenuState, gamestate: &mut Game)
 fn world_gen_option(
     ui: &mut egui::Ui,
     menu_state: &mut CreateWorldMenuState,
-    gen_type: WorldGenType,
+    gen_type: &'static dyn TerrainGenerator,
 ) {
-    let selected = menu_state.gen_type == gen_type;
-    let gen_type_str = match gen_type {
-        WorldGenType::Flat => "Flat",
-        WorldGenType::OldGen => "Old",
-        WorldGenType::DefaultGen => "Default",
-        WorldGenType::Skyblock => "Skyblock",
-    };
-    let text = menu_text(gen_type_str, 20.0, Color32::WHITE);
+    let selected = menu_state.gen_type.name() == gen_type.name();
+    let text = menu_text(gen_type.display_name(), 20.0, Color32::WHITE);
     if ui.radio(selected, text).clicked() {
         menu_state.gen_type = gen_type;
     }
 }
 
+fn settings_slider<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut T,
+    range: std::ops::RangeInclusive<T>,
+) {
+    let slider = egui::Slider::new(value, range).text(menu_text(label, 16.0, Color32::WHITE));
+    ui.add(slider);
+}
+
+//Advanced world generation settings, these are only used by the generators
+//that they apply to (the flat layers only change flat worlds)
+fn display_advanced_settings(ui: &mut egui::Ui, menu_state: &mut CreateWorldMenuState) {
+    ui.label(menu_text("Generator", 20.0, Color32::WHITE));
+    //Radio options for world generation
+    for gen_type in terrain_generators() {
+        world_gen_option(ui, menu_state, *gen_type);
+    }
+
+    ui.add_space(8.0);
+    let settings = &mut menu_state.gen_settings;
+    settings_slider(ui, "Sea level", &mut settings.sea_level, -64..=64);
+    settings_slider(ui, "Lava level", &mut settings.lava_level, -128..=0);
+    settings_slider(ui, "Bottom", &mut settings.bottom, -128..=0);
+    settings_slider(ui, "Octaves", &mut settings.octaves, 1..=MAX_OCTAVES);
+    settings_slider(ui, "Persistence", &mut settings.persistence, 0.0..=1.0);
+    settings_slider(
+        ui,
+        "Height scale",
+        &mut settings.height_scale,
+        HEIGHT_SCALE_RANGE,
+    );
+    settings_slider(
+        ui,
+        "Ore frequency",
+        &mut settings.ore_frequency,
+        ORE_FREQUENCY_RANGE,
+    );
+    settings_slider(ui, "Flat surface", &mut settings.flat_surface, -64..=64);
+
+    ui.label(menu_text(
+        "Flat layers (block/thickness|...)",
+        16.0,
+        Color32::WHITE,
+    ));
+    let flat_layers_edit = egui::TextEdit::singleline(&mut menu_state.flat_layers);
+    if ui.add(flat_layers_edit).changed() {
+        menu_state.settings_errors.clear();
+        match parse_flat_layers(&menu_state.flat_layers) {
+            Ok(layers) => menu_state.gen_settings.flat_layers = layers,
+            Err(msg) => menu_state.settings_errors.push(msg),
+        }
+    }
+
+    ui.add_space(8.0);
+    ui.label(menu_text("World border", 20.0, Color32::WHITE));
+    let border_options = [
+        (None, "None"),
+        (Some(BorderShape::Square), "Square"),
+        (Some(BorderShape::Circle), "Circle"),
+    ];
+    for (shape, name) in border_options {
+        let selected = menu_state.border_shape == shape;
+        let text = menu_text(name, 16.0, Color32::WHITE);
+        if ui.radio(selected, text).clicked() {
+            menu_state.border_shape = shape;
+        }
+    }
+    if menu_state.border_shape.is_some() {
+        let slider = egui::Slider::new(
+            &mut menu_state.border_radius,
+            MIN_BORDER_RADIUS..=MAX_MENU_BORDER_RADIUS,
+        )
+        .logarithmic(true)
+        .text(menu_text("Border radius", 16.0, Color32::WHITE));
+        ui.add(slider);
+    }
+
+    ui.add_space(8.0);
+    ui.label(menu_text("Fluids", 20.0, Color32::WHITE));
+    for mode in ALL_FLUID_MODES {
+        let selected = menu_state.fluid_mode == mode;
+        let text = menu_text(mode.display_name(), 16.0, Color32::WHITE);
+        if ui.radio(selected, text).clicked() {
+            menu_state.fluid_mode = mode;
+        }
+    }
+}
+
+//Redraws the map preview if the seed or settings have changed
+fn update_preview(ctx: &egui::Context, menu_state: &mut CreateWorldMenuState) {
+    let gen_type = menu_state.gen_type;
+    let key = (
+        menu_state.get_seed(),
+        gen_type.name(),
+        menu_state.gen_settings.clone(),
+    );
+    let valid = menu_state.gen_settings.validate().is_empty();
+    let preview = &mut menu_state.preview;
+    if preview.drawn.as_ref() == Some(&key) {
+        preview.pending = None;
+        return;
+    }
+
+    match &preview.pending {
+        Some((pending, changed)) if *pending == key => {
+            if changed.elapsed().as_secs_f32() < PREVIEW_DELAY {
+                return;
+            }
+        }
+        _ => {
+            preview.pending = Some((key, Instant::now()));
+            return;
+        }
+    }
+
+    preview.texture = None;
+    if valid {
+        let area = MapArea::centered(0, 0, PREVIEW_SIZE, PREVIEW_SCALE);
+        let map = preview_map(key.0, gen_type, &key.2, area);
+        //Generators without a surface leave the map transparent
+        if map.pixels.chunks(4).any(|pixel| pixel[3] > 0) {
+            let size = [map.width as usize, map.height as usize];
+            let image = egui::ColorImage::from_rgba_unmultiplied(size, &map.pixels);
+            let texture = ctx.load_texture("map_preview", image, egui::TextureOptions::NEAREST);
+            preview.texture = Some(texture);
+        }
+    }
+    preview.drawn = Some(key);
+    preview.pending = None;
+}
+
 //Display the create world gui
 fn display_create_world(
     ui: &mut egui::Ui,
@@ -95,11 +322,21 @@ fn display_create_world(
         ui.label(" ");
         ui.label(menu_text("World Generation", 24.0, Color32::WHITE));
 
-        //Radio options for world generation
-        world_gen_option(ui, menu_state, WorldGenType::DefaultGen);
-        world_gen_option(ui, menu_state, WorldGenType::OldGen);
-        world_gen_option(ui, menu_state, WorldGenType::Skyblock);
-        world_gen_option(ui, menu_state, WorldGenType::Flat);
+        //Radio options for world generation presets
+        for preset in worldgen_presets() {
+            let selected = menu_state.preset_selected(preset);
+            let text = menu_text(&preset.display_name, 20.0, Color32::WHITE);
+            if ui.radio(selected, text).clicked() {
+                menu_state.apply_preset(preset);
+            }
+        }
+
+        egui::CollapsingHeader::new(menu_text("Advanced", 20.0, Color32::WHITE))
+            .id_salt("advanced")
+            .show(ui, |ui| display_advanced_settings(ui, menu_state));
+        for msg in &menu_state.settings_errors {
+            ui.label(menu_text(msg, 16.0, Color32::RED));
+        }
 
         ui.add_space(8.0);
         ui.label(menu_text("Game Mode", 24.0, Color32::WHITE));
@@ -122,10 +359,24 @@ fn display_create_world(
             egui::TextEdit::singleline(&mut menu_state.seed).font(egui::TextStyle::Heading);
         ui.add(world_seed_edit);
 
+        ui.add_space(8.0);
+        update_preview(ui.ctx(), menu_state);
+        match &menu_state.preview.texture {
+            Some(texture) => {
+                let size = vec2(PREVIEW_SIZE as f32, PREVIEW_SIZE as f32) * 2.0;
+                ui.add(egui::Image::new(texture).fit_to_exact_size(size));
+            }
+            None => {
+                ui.label(menu_text("No preview", 16.0, Color32::GRAY));
+            }
+        }
+        ui.add_space(8.0);
+
         if ui
             .button(menu_text("Create", 24.0, Color32::WHITE))
             .clicked()
             && !menu_state.world_name.is_empty()
+            && menu_state.check_settings()
         {
             menu_state.create_world = true;
             let path = save::get_world_path(&menu_state.world_name);
@@ -28,12 +28,26 @@ fn debug_info(
         input_state.pixels_per_point,
     );
     let pixels_per_point = format!("ctx.pixels_per_point() = {}", ctx.pixels_per_point(),);
+    let biome = gamestate
+        .world
+        .get_biome(playerx.floor() as i32, playerz.floor() as i32);
+    let biome_text = format!("biome: {}", biome.name());
     let paused = format!("paused = {}", gamestate.is_paused());
     let fps_text = format!("{fps} FPS");
-    let chunk_updates = format!("chunk updates: {}", gamestate.world.get_chunk_updates());
+    let block_updates = format!(
+        "scheduled block updates: {}",
+        gamestate.world.scheduled_tick_count()
+    );
     let seed_text = format!("seed: {}", gamestate.world.get_seed());
     let days_passed = format!("days passed: {}", gamestate.world.days_passed);
     let time_text = format!("current time: {:.2}", gamestate.world.time);
+    let border_text = match gamestate.world.border() {
+        Some(border) => {
+            let distance = border.distance(playerx, playerz);
+            format!("distance to border: {distance:.1}")
+        }
+        None => "distance to border: no border".to_string(),
+    };
     let dropped_item_text = format!(
         "dropped items | total: {} | in world: {}",
         gamestate.entities.get_dropped_item_count(),
@@ -48,15 +62,17 @@ fn debug_info(
         .show(ctx, |ui| {
             ui.heading(debug_text("Debug info - press F3 to hide"));
             ui.heading(debug_text(&player_pos));
+            ui.heading(debug_text(&biome_text));
             ui.heading(debug_text(&native_pixels_per_point));
             ui.heading(debug_text(&pixels_per_point));
             ui.heading(debug_text(&paused));
-            ui.heading(debug_text(&chunk_updates));
+            ui.heading(debug_text(&block_updates));
             ui.heading(debug_text(&fps_text));
             ui.heading(debug_text(&seed_text));
             ui.heading(debug_text(&days_passed));
             ui.heading(debug_text(&time_text));
             ui.heading(debug_text(&dropped_item_text));
+            ui.heading(debug_text(&border_text));
         });
 }
 
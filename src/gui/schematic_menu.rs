@@ -0,0 +1,194 @@
+use super::menu_text;
+use crate::game::schematics::{SchematicMenuAction, SchematicTool};
+use crate::voxel::schematic::SchematicTransform;
+use egui_backend::egui::{self, Color32};
+use egui_gl_glfw as egui_backend;
+
+//Creates an egui frame that is grayed out
+fn schematic_frame() -> egui::Frame {
+    egui::Frame::new()
+        .fill(egui::Color32::from_rgba_unmultiplied(32, 32, 32, 200))
+        .inner_margin(egui::Margin::symmetric(16, 16))
+}
+
+fn corner_text(corner: Option<(i32, i32, i32)>) -> String {
+    match corner {
+        Some((x, y, z)) => format!("{x}, {y}, {z}"),
+        None => "not set".to_string(),
+    }
+}
+
+fn button(ui: &mut egui::Ui, text: &str) -> bool {
+    ui.button(menu_text(text, 24.0, Color32::WHITE)).clicked()
+}
+
+//Selection of the two corners and copying them
+fn display_selection(ui: &mut egui::Ui, tool: &SchematicTool) -> Option<SchematicMenuAction> {
+    let mut action = None;
+    ui.label(menu_text("Selection", 32.0, Color32::WHITE));
+    ui.label(menu_text(
+        "Look at a block before opening this menu to select it.",
+        16.0,
+        Color32::WHITE,
+    ));
+    ui.horizontal(|ui| {
+        if button(ui, "Set Corner 1") {
+            action = Some(SchematicMenuAction::SetCorner1);
+        }
+        ui.label(menu_text(&corner_text(tool.corner1), 20.0, Color32::WHITE));
+    });
+    ui.horizontal(|ui| {
+        if button(ui, "Set Corner 2") {
+            action = Some(SchematicMenuAction::SetCorner2);
+        }
+        ui.label(menu_text(&corner_text(tool.corner2), 20.0, Color32::WHITE));
+    });
+    if let Some((w, h, d)) = tool.selection_size() {
+        let size = format!("Size: {w} x {h} x {d}");
+        ui.label(menu_text(&size, 20.0, Color32::WHITE));
+    }
+    ui.horizontal(|ui| {
+        if button(ui, "Copy") {
+            action = Some(SchematicMenuAction::Copy);
+        }
+        if button(ui, "Clear") {
+            action = Some(SchematicMenuAction::ClearSelection);
+        }
+    });
+    action
+}
+
+//Saving and loading schematics
+fn display_files(ui: &mut egui::Ui, tool: &mut SchematicTool) -> Option<SchematicMenuAction> {
+    let mut action = None;
+    ui.label(menu_text("Schematics", 32.0, Color32::WHITE));
+    ui.horizontal(|ui| {
+        let name_edit = egui::TextEdit::singleline(&mut tool.name).font(egui::TextStyle::Heading);
+        ui.add(name_edit);
+        if button(ui, "Save") {
+            action = Some(SchematicMenuAction::Save);
+        }
+    });
+
+    if tool.saved.is_empty() {
+        ui.label(menu_text("No saved schematics.", 20.0, Color32::WHITE));
+    }
+    egui::ScrollArea::vertical()
+        .max_height(160.0)
+        .show(ui, |ui| {
+            for name in &tool.saved {
+                if ui
+                    .button(menu_text(&format!("Load \"{name}\""), 20.0, Color32::WHITE))
+                    .clicked()
+                {
+                    action = Some(SchematicMenuAction::Load(name.clone()));
+                }
+            }
+        });
+    action
+}
+
+//Rotation, mirroring and pasting
+fn display_paste(ui: &mut egui::Ui, tool: &mut SchematicTool) -> Option<SchematicMenuAction> {
+    let mut action = None;
+    ui.label(menu_text("Paste", 32.0, Color32::WHITE));
+    match tool.clipboard.as_ref().map(|s| s.size()) {
+        Some((w, h, d)) => {
+            let size = format!("Clipboard: {w} x {h} x {d}");
+            ui.label(menu_text(&size, 20.0, Color32::WHITE));
+        }
+        None => {
+            ui.label(menu_text("Clipboard is empty.", 20.0, Color32::WHITE));
+        }
+    }
+
+    let SchematicTransform { rotation, mirror } = tool.transform;
+    ui.horizontal(|ui| {
+        let rotation_text = format!("Rotation: {} degrees", rotation as u32 * 90);
+        if button(ui, &rotation_text) {
+            tool.transform = SchematicTransform::new(rotation + 1, mirror);
+        }
+        let mut mirror = mirror;
+        let mirror_text = menu_text("Mirror", 24.0, Color32::WHITE);
+        if ui.checkbox(&mut mirror, mirror_text).changed() {
+            tool.transform = SchematicTransform::new(rotation, mirror);
+        }
+    });
+    ui.label(menu_text(
+        "The schematic is pasted on top of the block you are looking at.",
+        16.0,
+        Color32::WHITE,
+    ));
+    if button(ui, "Paste") {
+        action = Some(SchematicMenuAction::Paste);
+    }
+    action
+}
+
+fn display_schematic_menu(
+    ui: &mut egui::Ui,
+    tool: &mut SchematicTool,
+) -> Option<SchematicMenuAction> {
+    ui.vertical_centered(|ui| {
+        ui.label(menu_text("Schematics", 48.0, Color32::WHITE));
+        ui.add_space(16.0);
+        if let Some((msg, is_error)) = &tool.status {
+            let col = if *is_error {
+                Color32::RED
+            } else {
+                Color32::WHITE
+            };
+            ui.label(menu_text(msg, 20.0, col));
+        }
+    });
+
+    ui.add_space(16.0);
+    let mut action = display_selection(ui, tool);
+    ui.add_space(16.0);
+    action = display_files(ui, tool).or(action);
+    ui.add_space(16.0);
+    action = display_paste(ui, tool).or(action);
+    ui.add_space(16.0);
+    if button(ui, "Close") {
+        action = Some(SchematicMenuAction::Close);
+    }
+
+    action
+}
+
+//Returns action chosen by the user
+//should be run in a main game loop
+pub fn run_schematic_menu(
+    ctx: &egui::Context,
+    input_state: &mut egui_backend::EguiInputState,
+    painter: &mut egui_backend::Painter,
+    tool: &mut SchematicTool,
+) -> Option<SchematicMenuAction> {
+    //Begin frame
+    ctx.begin_pass(input_state.input.take());
+
+    let mut action = None;
+    egui::CentralPanel::default()
+        .frame(schematic_frame())
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                action = display_schematic_menu(ui, tool);
+            });
+        });
+
+    //End frame
+    let egui::FullOutput {
+        platform_output: _,
+        textures_delta,
+        shapes,
+        pixels_per_point: _,
+        viewport_output: _,
+    } = ctx.end_pass();
+
+    //Display
+    let native_pixels_per_point = input_state.pixels_per_point;
+    let clipped_shapes = ctx.tessellate(shapes, native_pixels_per_point);
+    painter.paint_and_update_textures(native_pixels_per_point, &clipped_shapes, &textures_delta);
+
+    action
+}
@@ -1,5 +1,11 @@
 use super::{init_egui_input_state, menu_text, set_ui_gl_state, transparent_frame};
-use crate::game::{save::SAVE_PATH, EventHandler, Game};
+use crate::game::{
+    save::{
+        backup::{self, Backup, MAX_BACKUPS},
+        SAVE_PATH,
+    },
+    EventHandler, Game,
+};
 use crate::gfx;
 use egui_backend::egui::{self, vec2, Color32, Pos2};
 use egui_gl_glfw as egui_backend;
@@ -11,6 +17,9 @@ struct SelectWorldMenuState {
     quit_to_menu: bool,
     selected_world: String,
     to_delete: String,
+    to_restore: String,
+    backups: Vec<Backup>,
+    selected_backup: Option<usize>,
     worlds: Vec<String>,
 }
 
@@ -22,6 +31,9 @@ impl SelectWorldMenuState {
             quit_to_menu: false,
             selected_world: String::new(),
             to_delete: String::new(),
+            to_restore: String::new(),
+            backups: vec![],
+            selected_backup: None,
             worlds: vec![],
         }
     }
@@ -96,9 +108,23 @@ fn display_select_world(
             .clicked()
             && !menu_state.selected_world.is_empty()
         {
+            gamestate.wait_for_backup();
             menu_state.to_delete = menu_state.selected_world.clone();
         }
 
+        //Restore world from a backup
+        if ui
+            .button(menu_text("Restore Backup", 24.0, Color32::WHITE))
+            .clicked()
+            && !menu_state.selected_world.is_empty()
+        {
+            gamestate.wait_for_backup();
+            let path = SAVE_PATH.to_string() + menu_state.selected_world.as_str() + "/";
+            menu_state.backups = backup::list_backups(&backup::get_backup_dir(&path));
+            menu_state.selected_backup = None;
+            menu_state.to_restore = menu_state.selected_world.clone();
+        }
+
         if ui
             .button(menu_text("Main Menu", 24.0, Color32::WHITE))
             .clicked()
@@ -145,6 +171,9 @@ fn display_delete_world(ui: &mut egui::Ui, menu_state: &mut SelectWorldMenuState
                 eprintln!("Failed to delete: {path}");
                 eprintln!("{msg}");
             }
+            if let Err(msg) = backup::delete_backups(&path) {
+                eprintln!("E: {msg}");
+            }
             menu_state.to_delete = String::new();
             menu_state.selected_world = String::new();
             menu_state.get_world_list();
@@ -152,6 +181,67 @@ fn display_delete_world(ui: &mut egui::Ui, menu_state: &mut SelectWorldMenuState
     });
 }
 
+//Display the restore backup gui
+fn display_restore_world(ui: &mut egui::Ui, menu_state: &mut SelectWorldMenuState, h: i32) {
+    ui.vertical_centered(|ui| {
+        ui.label(" ");
+        ui.label(menu_text(
+            format!("Restore \"{}\" from a backup", menu_state.to_restore).as_str(),
+            32.0,
+            Color32::WHITE,
+        ));
+        ui.label(menu_text(
+            "The current state of the world will also be backed up.",
+            20.0,
+            Color32::WHITE,
+        ));
+        ui.label(" ");
+
+        if menu_state.backups.is_empty() {
+            ui.label(menu_text("No backups found.", 24.0, Color32::WHITE));
+        }
+
+        egui::ScrollArea::vertical()
+            .max_height(h as f32 - 320.0)
+            .show(ui, |ui| {
+                for (i, backup) in menu_state.backups.iter().enumerate() {
+                    ui.selectable_value(
+                        &mut menu_state.selected_backup,
+                        Some(i),
+                        menu_text(&backup.name(), 24.0, Color32::WHITE),
+                    );
+                }
+            });
+
+        ui.label(" ");
+        let selected = menu_state
+            .selected_backup
+            .and_then(|i| menu_state.backups.get(i));
+        if ui
+            .button(menu_text("Restore", 24.0, Color32::WHITE))
+            .clicked()
+        {
+            if let Some(selected) = selected {
+                let path = SAVE_PATH.to_string() + menu_state.to_restore.as_str() + "/";
+                let backup_dir = backup::get_backup_dir(&path);
+                eprintln!("Attempting to restore {path} from {}...", selected.path);
+                if let Err(msg) = backup::restore_backup(&path, &backup_dir, selected, MAX_BACKUPS)
+                {
+                    eprintln!("E: {msg}");
+                }
+                menu_state.to_restore = String::new();
+            }
+        }
+
+        if ui
+            .button(menu_text("Cancel", 24.0, Color32::WHITE))
+            .clicked()
+        {
+            menu_state.to_restore = String::new();
+        }
+    });
+}
+
 //Display the create world menu screen
 pub fn run_select_world_menu(
     gamestate: &mut Game,
@@ -196,10 +286,12 @@ pub fn run_select_world_menu(
             .fixed_pos(Pos2::new(32.0, 0.0))
             .frame(transparent_frame())
             .show(&ctx, |ui| {
-                if menu_state.to_delete.is_empty() {
-                    display_select_world(ui, &mut menu_state, gamestate, h);
-                } else {
+                if !menu_state.to_delete.is_empty() {
                     display_delete_world(ui, &mut menu_state, h);
+                } else if !menu_state.to_restore.is_empty() {
+                    display_restore_world(ui, &mut menu_state, h);
+                } else {
+                    display_select_world(ui, &mut menu_state, gamestate, h);
                 }
             });
 
@@ -1,16 +1,14 @@
 #![windows_subsystem = "windows"]
 
 mod assets;
-mod bin_data;
 mod game;
 mod gfx;
 mod gui;
-mod impfile;
-mod voxel;
 
 use game::{save, Game};
 use gui::main_menu::MainMenuOutput;
-use voxel::{flags::init_voxel_flags, World, CHUNK_SIZE_F32, EMPTY_BLOCK};
+use voxel::{block_registry::block_registry, World, CHUNK_SIZE_F32, EMPTY_BLOCK};
+use voxelworld_core::{bin_data, impfile, voxel};
 
 const BLOCK_MENU_PATH: &str = "assets/block_menu.impfile";
 const SETTINGS_PATH: &str = "settings.impfile";
@@ -21,6 +19,12 @@ fn main() {
     //run the test simulations and then exit the program, however, this option
     //only exists for testing purposes.
     voxel::world::block_update::run_test_simulations(&args);
+    //If the user provides the argument --gen-stats, generate part of a world
+    //and write statistics about it (ore, caves, trees) and then exit
+    voxel::world::gen_stats::run_gen_stats(&args);
+    //If the user provides the argument --check-assets, report any problems
+    //in the asset files and then exit the program
+    game::assets::run_check_assets(&args);
 
     //Attempt to create save directory
     save::create_save_dir();
@@ -29,8 +33,8 @@ fn main() {
     let (mut window, events) = game::init_window(&mut glfw);
     //Initialize gl
     gl::load_with(|s| window.get_proc_address(s) as *const _);
-    //Initialize voxel flags
-    init_voxel_flags();
+    //Load the block registry
+    block_registry();
     //Initialize game state
     let mut gamestate = Game::new();
     gamestate.init();
@@ -64,4 +68,5 @@ fn main() {
     }
 
     gamestate.settings.save(SETTINGS_PATH);
+    gamestate.wait_for_backup();
 }
@@ -0,0 +1,402 @@
+/*
+ * For saving data in a binary format
+ * */
+
+mod tests;
+
+use cgmath::vec3;
+use std::collections::HashMap;
+
+type Vec3 = cgmath::Vector3<f32>;
+
+pub struct ByteStream {
+    bytes: Vec<u8>,
+    index: usize,
+}
+
+impl ByteStream {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes, index: 0 }
+    }
+
+    pub fn get_byte(&mut self) -> Option<u8> {
+        if self.index >= self.bytes.len() {
+            return None;
+        }
+
+        let byte = self.bytes[self.index];
+        self.index += 1;
+        Some(byte)
+    }
+
+    //Returns the next `count` bytes, fails if there are not enough bytes left
+    //in the stream (the stream is not advanced in that case)
+    pub fn get_bytes(&mut self, count: usize) -> Result<&[u8], String> {
+        if count > self.remaining() {
+            return Err(format!(
+                "expected {count} bytes at offset {} but only {} remain",
+                self.index,
+                self.remaining()
+            ));
+        }
+        let bytes = &self.bytes[self.index..(self.index + count)];
+        self.index += count;
+        Ok(bytes)
+    }
+
+    pub fn get_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.get_bytes(N)?);
+        Ok(buf)
+    }
+
+    pub fn get_u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_be_bytes(self.get_array()?))
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.index)
+    }
+
+    pub fn offset(&self) -> usize {
+        self.index
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.index >= self.bytes.len()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataType {
+    Int(i64),
+    Float(f32),
+    Vec3(Vec3),
+    Str(String),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    //Each element is stored with its own type flag so lists can be mixed
+    List(Vec<DataType>),
+    Table(DataTable),
+}
+
+//Tables and lists can not be nested deeper than this, this prevents a
+//malicious file from overflowing the stack when it is decoded
+pub const MAX_DEPTH: usize = 32;
+
+impl DataType {
+    pub fn get_type_flag(&self) -> u8 {
+        match self {
+            Self::Int(_) => 1,
+            Self::Float(_) => 2,
+            Self::Vec3(_) => 3,
+            Self::Str(_) => 4,
+            Self::Bool(_) => 5,
+            Self::Bytes(_) => 6,
+            Self::List(_) => 7,
+            Self::Table(_) => 8,
+        }
+    }
+
+    //Writes the value without its type flag
+    fn write_payload(&self, bytes: &mut Vec<u8>) {
+        match self {
+            Self::Int(int) => bytes.extend(int.to_be_bytes()),
+            Self::Float(float) => bytes.extend(float.to_be_bytes()),
+            Self::Vec3(v) => {
+                bytes.extend(v.x.to_be_bytes());
+                bytes.extend(v.y.to_be_bytes());
+                bytes.extend(v.z.to_be_bytes());
+            }
+            Self::Str(str) => write_bytes(str.as_bytes(), bytes),
+            Self::Bool(b) => bytes.push(*b as u8),
+            Self::Bytes(buf) => write_bytes(buf, bytes),
+            Self::List(list) => {
+                bytes.extend((list.len() as u32).to_be_bytes());
+                for val in list {
+                    bytes.push(val.get_type_flag());
+                    val.write_payload(bytes);
+                }
+            }
+            Self::Table(table) => table.write_bytes(bytes),
+        }
+    }
+
+    //Reads the value for `type_flag`, `depth` is how deeply nested the value is
+    fn read_payload(stream: &mut ByteStream, type_flag: u8, depth: usize) -> Result<Self, String> {
+        match type_flag {
+            1 => Ok(Self::Int(i64::from_be_bytes(stream.get_array()?))),
+            2 => Ok(Self::Float(f32::from_be_bytes(stream.get_array()?))),
+            3 => {
+                let x = f32::from_be_bytes(stream.get_array()?);
+                let y = f32::from_be_bytes(stream.get_array()?);
+                let z = f32::from_be_bytes(stream.get_array()?);
+                Ok(Self::Vec3(vec3(x, y, z)))
+            }
+            4 => Ok(Self::Str(read_str(stream)?)),
+            5 => match stream.get_array::<1>()? {
+                [0] => Ok(Self::Bool(false)),
+                [1] => Ok(Self::Bool(true)),
+                [b] => Err(format!("invalid bool value {b}")),
+            },
+            6 => Ok(Self::Bytes(read_bytes(stream)?.to_vec())),
+            7 => {
+                check_depth(depth)?;
+                let count = read_count(stream)?;
+                let mut list = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let offset = stream.offset();
+                    let flag = stream.get_array::<1>()?[0];
+                    if flag == 0 {
+                        return Err(format!("invalid list element type at offset {offset}"));
+                    }
+                    list.push(Self::read_payload(stream, flag, depth + 1)?);
+                }
+                Ok(Self::List(list))
+            }
+            8 => {
+                check_depth(depth)?;
+                Ok(Self::Table(DataTable::read(stream, depth + 1)?))
+            }
+            _ => Err(format!("unknown type flag {type_flag}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataTable {
+    values: HashMap<String, DataType>,
+}
+
+#[allow(dead_code)]
+impl Default for DataTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataTable {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn add_int(&mut self, name: &str, val: i64) {
+        self.values.insert(name.to_string(), DataType::Int(val));
+    }
+
+    pub fn add_float(&mut self, name: &str, val: f32) {
+        self.values.insert(name.to_string(), DataType::Float(val));
+    }
+
+    pub fn add_vec3(&mut self, name: &str, val: Vec3) {
+        self.values.insert(name.to_string(), DataType::Vec3(val));
+    }
+
+    pub fn add_str(&mut self, name: &str, val: &str) {
+        self.values
+            .insert(name.to_string(), DataType::Str(val.to_string()));
+    }
+
+    pub fn add_bool(&mut self, name: &str, val: bool) {
+        self.values.insert(name.to_string(), DataType::Bool(val));
+    }
+
+    pub fn add_bytes(&mut self, name: &str, val: &[u8]) {
+        self.values
+            .insert(name.to_string(), DataType::Bytes(val.to_vec()));
+    }
+
+    pub fn add_list(&mut self, name: &str, val: Vec<DataType>) {
+        self.values.insert(name.to_string(), DataType::List(val));
+    }
+
+    pub fn add_table(&mut self, name: &str, val: DataTable) {
+        self.values.insert(name.to_string(), DataType::Table(val));
+    }
+
+    pub fn get_all_vals(&self) -> &HashMap<String, DataType> {
+        &self.values
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        let val = self.values.get(name)?;
+        if let DataType::Int(v) = val {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_float(&self, name: &str) -> Option<f32> {
+        let val = self.values.get(name)?;
+        if let DataType::Float(v) = val {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_vec3(&self, name: &str) -> Option<Vec3> {
+        let val = self.values.get(name)?;
+        if let DataType::Vec3(v) = val {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<String> {
+        let val = self.values.get(name)?;
+        if let DataType::Str(v) = val {
+            Some(v.to_string())
+        } else {
+            None
+        }
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        let val = self.values.get(name)?;
+        if let DataType::Bool(v) = val {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_bytes(&self, name: &str) -> Option<&[u8]> {
+        let val = self.values.get(name)?;
+        if let DataType::Bytes(v) = val {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_list(&self, name: &str) -> Option<&[DataType]> {
+        let val = self.values.get(name)?;
+        if let DataType::List(v) = val {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_table(&self, name: &str) -> Option<&DataTable> {
+        let val = self.values.get(name)?;
+        if let DataType::Table(v) = val {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        for (name, val) in &self.values {
+            bytes.push(val.get_type_flag());
+            write_bytes(name.as_bytes(), bytes);
+            val.write_payload(bytes);
+        }
+
+        //Stop
+        bytes.push(0);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_bytes(&mut bytes);
+        bytes
+    }
+
+    //Reads a table, each value is a type flag, name and payload and the table
+    //ends with a 0 type flag
+    fn read(stream: &mut ByteStream, depth: usize) -> Result<Self, String> {
+        let mut table = Self::new();
+        loop {
+            let type_flag = stream.get_array::<1>()?[0];
+            if type_flag == 0 {
+                return Ok(table);
+            }
+            let name = read_str(stream)?;
+            let val = DataType::read_payload(stream, type_flag, depth)?;
+            table.values.insert(name, val);
+        }
+    }
+
+    pub fn insert(&mut self, name: &str, val: DataType) {
+        self.values.insert(name.to_string(), val);
+    }
+}
+
+fn check_depth(depth: usize) -> Result<(), String> {
+    if depth >= MAX_DEPTH {
+        return Err(format!("data is nested deeper than {MAX_DEPTH} levels"));
+    }
+    Ok(())
+}
+
+//Writes a u32 length followed by the bytes
+fn write_bytes(buf: &[u8], bytes: &mut Vec<u8>) {
+    bytes.extend((buf.len() as u32).to_be_bytes());
+    bytes.extend(buf);
+}
+
+fn read_bytes(stream: &mut ByteStream) -> Result<&[u8], String> {
+    let len = stream.get_u32()? as usize;
+    stream.get_bytes(len)
+}
+
+fn read_str(stream: &mut ByteStream) -> Result<String, String> {
+    let offset = stream.offset();
+    let bytes = read_bytes(stream)?;
+    String::from_utf8(bytes.to_vec())
+        .map_err(|_| format!("invalid utf-8 string at offset {offset}"))
+}
+
+//Reads the number of elements in a list, every element takes up at least one
+//byte so a count larger than the rest of the stream must be corrupted (this
+//also stops a bad count from allocating a huge amount of memory)
+fn read_count(stream: &mut ByteStream) -> Result<usize, String> {
+    let offset = stream.offset();
+    let count = stream.get_u32()? as usize;
+    if count > stream.remaining() {
+        return Err(format!("invalid element count {count} at offset {offset}"));
+    }
+    Ok(count)
+}
+
+pub type ParsedData = HashMap<String, Vec<DataTable>>;
+
+//Decodes a sequence of named table lists (see `get_table_list_bytes`),
+//returns an error if the data is truncated or malformed
+pub fn decode_binary_data(stream: &mut ByteStream) -> Result<ParsedData, String> {
+    let mut tables = HashMap::new();
+
+    while !stream.at_end() {
+        let name = read_str(stream)?;
+        let count = read_count(stream)?;
+        let mut list = Vec::with_capacity(count);
+        for _ in 0..count {
+            list.push(DataTable::read(stream, 0)?);
+        }
+        tables.insert(name, list);
+    }
+
+    Ok(tables)
+}
+
+pub fn get_table_list_bytes(name: &str, tables: &[DataTable]) -> Vec<u8> {
+    let mut bytes = vec![];
+
+    if tables.is_empty() {
+        return bytes;
+    }
+
+    write_bytes(name.as_bytes(), &mut bytes);
+    bytes.extend((tables.len() as u32).to_be_bytes());
+    for data_table in tables {
+        data_table.write_bytes(&mut bytes);
+    }
+    bytes
+}
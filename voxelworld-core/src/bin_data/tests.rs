@@ -0,0 +1,272 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn test_bin_data1() {
+    //Test data
+    let mut table = DataTable::new();
+    table.add_int("a", 1);
+
+    //Convert data into bytes
+    let bytes = get_table_list_bytes("test", &[table]);
+    assert!(!bytes.is_empty());
+
+    //Parse the data
+    let mut stream = ByteStream::new(bytes);
+    let parsed = decode_binary_data(&mut stream).unwrap();
+
+    //Make sure it's not empty
+    assert_eq!(parsed.len(), 1);
+    assert!(parsed.contains_key("test"));
+
+    //Check if the data is correct
+    if let Some(tables) = parsed.get("test") {
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].get_int("a"), Some(1));
+    }
+}
+
+#[test]
+fn test_bin_data2() {
+    //Test data
+    let a = 0xff;
+    let b = 0.5;
+    let c = vec3(1.0, 1.0, 1.0);
+    let d = "hello world";
+    let mut table = DataTable::new();
+    table.add_int("a", a);
+    table.add_float("b", b);
+    table.add_vec3("c", c);
+    table.add_str("d", d);
+
+    //Convert data into bytes
+    let bytes = get_table_list_bytes("test", &[table]);
+    assert!(!bytes.is_empty());
+
+    //Parse the data
+    let mut stream = ByteStream::new(bytes);
+    let parsed = decode_binary_data(&mut stream).unwrap();
+
+    //Make sure it's not empty
+    assert_eq!(parsed.len(), 1);
+    assert!(parsed.contains_key("test"));
+
+    //Check if the data is correct
+    if let Some(tables) = parsed.get("test") {
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].get_int("a"), Some(a));
+        assert_eq!(tables[0].get_float("b"), Some(b));
+        assert_eq!(tables[0].get_vec3("c"), Some(c));
+        assert_eq!(tables[0].get_str("d"), Some(d.to_string()));
+    }
+}
+
+#[test]
+fn test_bin_data3() {
+    //Test data
+    let mut tables = vec![];
+    for i in 0..10 {
+        let mut table = DataTable::new();
+        table.add_int("foo", i);
+        table.add_str("bar", "blahblah");
+        tables.push(table);
+    }
+
+    //Convert data into bytes
+    let bytes = get_table_list_bytes("test", &tables);
+    assert!(!bytes.is_empty());
+
+    //Parse the data
+    let mut stream = ByteStream::new(bytes);
+    let parsed = decode_binary_data(&mut stream).unwrap();
+
+    //Make sure it's not empty
+    assert_eq!(parsed.len(), 1);
+    assert!(parsed.contains_key("test"));
+
+    //Check if the data is correct
+    if let Some(tables) = parsed.get("test") {
+        assert_eq!(tables.len(), 10);
+        for (i, table) in tables.iter().enumerate() {
+            assert_eq!(table.get_int("foo"), Some(i as i64));
+            assert_eq!(table.get_str("bar"), Some("blahblah".to_string()));
+        }
+    }
+}
+
+#[test]
+fn test_bin_data4() {
+    //Test data
+    let mut tables1 = vec![];
+    for i in 0..10 {
+        let mut table = DataTable::new();
+        table.add_int("foo", i);
+        table.add_str("bar", "blahblah");
+        tables1.push(table);
+    }
+
+    let mut tables2 = vec![];
+    for i in 0..5 {
+        let mut table = DataTable::new();
+        let i_f32 = i as f32;
+        table.add_float("fizz", i_f32);
+        table.add_vec3("buzz", vec3(i_f32, i_f32, i_f32));
+        tables2.push(table);
+    }
+
+    //Convert data into bytes
+    let mut bytes = vec![];
+    bytes.extend(get_table_list_bytes("foo", &tables1));
+    bytes.extend(get_table_list_bytes("bar", &tables2));
+
+    //Parse the data
+    let mut stream = ByteStream::new(bytes);
+    let parsed = decode_binary_data(&mut stream).unwrap();
+
+    //Make sure it's not empty
+    assert_eq!(parsed.len(), 2);
+    assert!(parsed.contains_key("foo"));
+    assert!(parsed.contains_key("bar"));
+
+    //Check if the data is correct
+    if let Some(tables) = parsed.get("foo") {
+        assert_eq!(tables.len(), 10);
+        for (i, table) in tables.iter().enumerate() {
+            assert_eq!(table.get_int("foo"), Some(i as i64));
+            assert_eq!(table.get_str("bar"), Some("blahblah".to_string()));
+        }
+    }
+
+    if let Some(tables) = parsed.get("bar") {
+        assert_eq!(tables.len(), 5);
+        for (i, table) in tables.iter().enumerate() {
+            let i_f32 = i as f32;
+            assert_eq!(table.get_float("fizz"), Some(i_f32));
+            assert_eq!(table.get_vec3("buzz"), Some(vec3(i_f32, i_f32, i_f32)));
+        }
+    }
+}
+
+//Table with every type of value, including nested lists and tables
+#[cfg(test)]
+fn nested_table() -> DataTable {
+    let mut inner = DataTable::new();
+    inner.add_bool("flag", true);
+    inner.add_list("empty", vec![]);
+    inner.add_table("empty_table", DataTable::new());
+
+    let mut table = DataTable::new();
+    table.add_int("a", -7);
+    table.add_float("b", 0.25);
+    table.add_vec3("c", vec3(1.0, -2.0, 3.0));
+    table.add_str("d", "hello");
+    table.add_bool("e", false);
+    table.add_bytes("f", &[0, 1, 2, 255]);
+    table.add_list(
+        "g",
+        vec![
+            DataType::Str("block,1,0,64".to_string()),
+            DataType::Int(3),
+            DataType::List(vec![DataType::Bool(true)]),
+            DataType::Table(inner.clone()),
+        ],
+    );
+    table.add_table("h", inner);
+    table
+}
+
+#[test]
+fn test_bin_data_nested() {
+    let tables = vec![nested_table(), DataTable::new(), nested_table()];
+    let bytes = get_table_list_bytes("test", &tables);
+    let mut stream = ByteStream::new(bytes);
+    let parsed = decode_binary_data(&mut stream).unwrap();
+    assert_eq!(parsed.get("test"), Some(&tables));
+
+    let table = &parsed["test"][0];
+    assert_eq!(table.get_bool("e"), Some(false));
+    assert_eq!(table.get_bytes("f"), Some([0, 1, 2, 255].as_slice()));
+    assert_eq!(table.get_list("g").map(|list| list.len()), Some(4));
+    assert_eq!(
+        table
+            .get_table("h")
+            .and_then(|inner| inner.get_bool("flag")),
+        Some(true)
+    );
+    //Wrong type
+    assert_eq!(table.get_bool("a"), None);
+}
+
+#[test]
+fn test_bin_data_malformed() {
+    let bytes = get_table_list_bytes("test", &[nested_table()]);
+
+    //Every truncation of valid data is rejected
+    for len in 1..bytes.len() {
+        let mut stream = ByteStream::new(bytes[..len].to_vec());
+        assert!(decode_binary_data(&mut stream).is_err(), "len = {len}");
+    }
+
+    //Invalid bool
+    let mut table = DataTable::new();
+    table.add_bool("a", true);
+    let mut bytes = get_table_list_bytes("test", &[table]);
+    let len = bytes.len();
+    bytes[len - 2] = 2;
+    assert!(decode_binary_data(&mut ByteStream::new(bytes)).is_err());
+
+    //Unknown type flag
+    let bytes = vec![0, 0, 0, 1, b'a', 0, 0, 0, 1, 9, 0, 0, 0, 0, 0];
+    assert!(decode_binary_data(&mut ByteStream::new(bytes)).is_err());
+
+    //Huge count
+    let bytes = vec![0, 0, 0, 1, b'a', 0xff, 0xff, 0xff, 0xff, 0];
+    assert!(decode_binary_data(&mut ByteStream::new(bytes)).is_err());
+
+    //Invalid utf-8
+    let bytes = vec![0, 0, 0, 1, 0xff, 0, 0, 0, 1, 0];
+    assert!(decode_binary_data(&mut ByteStream::new(bytes)).is_err());
+
+    //Nested too deeply
+    let mut val = DataType::Int(0);
+    for _ in 0..(MAX_DEPTH + 1) {
+        val = DataType::List(vec![val]);
+    }
+    let mut table = DataTable::new();
+    table.insert("a", val);
+    let bytes = get_table_list_bytes("test", &[table]);
+    assert!(decode_binary_data(&mut ByteStream::new(bytes)).is_err());
+
+    //Empty data is valid
+    assert!(decode_binary_data(&mut ByteStream::new(vec![]))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_bin_data_fuzz() {
+    //Decoding random data should never panic, most of it will be rejected
+    let mut rng = fastrand::Rng::with_seed(0x5eed);
+    for _ in 0..10000 {
+        let len = rng.usize(0..64);
+        let bytes: Vec<u8> = (0..len).map(|_| rng.u8(..)).collect();
+        let _ = decode_binary_data(&mut ByteStream::new(bytes));
+    }
+
+    //Randomly mutated valid data
+    let valid = get_table_list_bytes("test", &[nested_table(), nested_table()]);
+    for _ in 0..10000 {
+        let mut bytes = valid.clone();
+        for _ in 0..rng.usize(1..4) {
+            let i = rng.usize(0..bytes.len());
+            bytes[i] = rng.u8(..);
+        }
+        if let Ok(parsed) = decode_binary_data(&mut ByteStream::new(bytes)) {
+            //Anything that decodes must encode again without problems
+            for (name, tables) in parsed {
+                let bytes = get_table_list_bytes(&name, &tables);
+                assert!(decode_binary_data(&mut ByteStream::new(bytes)).is_ok());
+            }
+        }
+    }
+}
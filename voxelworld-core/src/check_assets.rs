@@ -0,0 +1,158 @@
+/*
+ * Validates the files in the assets directory. Every problem in every file is
+ * collected so that they can all be reported at once instead of the game
+ * quietly falling back to defaults when something is wrong.
+ * */
+
+use crate::{
+    crafting::{add_block_aliases, parse_item_alias_file, RecipeTable},
+    impfile::{parse_file_diagnostics, Diagnostic},
+    inventory::{loot::read_loot_tables, read_leftover_table},
+    voxel::{
+        block_registry::BlockRegistry,
+        world::{gen_config::read_worldgen_config, gen_settings::read_worldgen_presets},
+    },
+};
+use std::{collections::HashSet, path::Path};
+
+const BLOCKS_FILE: &str = "blocks.impfile";
+const ITEM_ALIASES_FILE: &str = "item_aliases.impfile";
+const RECIPES_FILE: &str = "recipes.impfile";
+const FURNACE_FILE: &str = "furnace.impfile";
+const LEFTOVERS_FILE: &str = "leftovers.impfile";
+const LOOT_TABLES_FILE: &str = "loot_tables.impfile";
+const WORLDGEN_FILE: &str = "worldgen.impfile";
+const WORLDGEN_PRESETS_FILE: &str = "worldgen_presets.impfile";
+
+//Checks a file in the assets directory, it is passed the path to the file
+pub type AssetCheck = fn(&str) -> Vec<Diagnostic>;
+
+//Returns every impfile in a directory (and its subdirectories)
+fn find_impfiles(dir: &Path, files: &mut Vec<String>) {
+    let Ok(dir_contents) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in dir_contents.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_impfiles(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "impfile") {
+            files.push(path.to_string_lossy().to_string());
+        }
+    }
+}
+
+//Checks the files in `assets_dir` (which should end in a '/') that are used
+//by voxelworld-core along with the files in `other_checks` (file name, check),
+//any other impfile is only checked for syntax errors
+pub fn check_assets(assets_dir: &str, other_checks: &[(&str, AssetCheck)]) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let path = |file: &str| assets_dir.to_string() + file;
+
+    let (registry, registry_diagnostics) =
+        BlockRegistry::load_diagnostics(&path(BLOCKS_FILE), &path(ITEM_ALIASES_FILE));
+    diagnostics.extend(registry_diagnostics);
+
+    //Errors in the item alias file were already reported by the registry
+    let (mut item_aliases, _) = parse_item_alias_file(&path(ITEM_ALIASES_FILE));
+    add_block_aliases(&mut item_aliases, &registry);
+    let mut recipe_table = RecipeTable::new();
+    diagnostics.extend(recipe_table.read_recipes(&path(RECIPES_FILE), &item_aliases));
+    diagnostics.extend(recipe_table.read_furnace(&path(FURNACE_FILE), &item_aliases));
+    let (_, leftover_diagnostics) = read_leftover_table(&path(LEFTOVERS_FILE), &item_aliases);
+    diagnostics.extend(leftover_diagnostics);
+    let (_, loot_diagnostics) = read_loot_tables(&path(LOOT_TABLES_FILE), &item_aliases);
+    diagnostics.extend(loot_diagnostics);
+    let (_, worldgen_diagnostics) = read_worldgen_config(&path(WORLDGEN_FILE));
+    diagnostics.extend(worldgen_diagnostics);
+    let (_, preset_diagnostics) = read_worldgen_presets(&path(WORLDGEN_PRESETS_FILE));
+    diagnostics.extend(preset_diagnostics);
+
+    let mut checked: HashSet<String> = [
+        BLOCKS_FILE,
+        ITEM_ALIASES_FILE,
+        RECIPES_FILE,
+        FURNACE_FILE,
+        LEFTOVERS_FILE,
+        LOOT_TABLES_FILE,
+        WORLDGEN_FILE,
+        WORLDGEN_PRESETS_FILE,
+    ]
+    .iter()
+    .map(|file| path(file))
+    .collect();
+    for (file, check) in other_checks {
+        diagnostics.extend(check(&path(file)));
+        checked.insert(path(file));
+    }
+
+    let mut files = vec![];
+    find_impfiles(Path::new(assets_dir), &mut files);
+    files.sort();
+    for file in files {
+        if !checked.contains(&file) {
+            let (_, syntax_diagnostics) = parse_file_diagnostics(&file);
+            diagnostics.extend(syntax_diagnostics);
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_assets() {
+        //The assets that ship with the game should not have any problems
+        let diagnostics = check_assets("../assets/", &[]);
+        for diagnostic in &diagnostics {
+            eprintln!("{diagnostic}");
+        }
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_check_assets_errors() {
+        let dir = std::env::temp_dir().join(format!("voxelworld_assets_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        let dir = dir.to_string_lossy().to_string() + "/";
+        for file in [
+            BLOCKS_FILE,
+            ITEM_ALIASES_FILE,
+            FURNACE_FILE,
+            LEFTOVERS_FILE,
+            LOOT_TABLES_FILE,
+            WORLDGEN_FILE,
+            WORLDGEN_PRESETS_FILE,
+        ] {
+            std::fs::copy("../assets/".to_string() + file, dir.clone() + file).unwrap();
+        }
+        let recipes = "\"a\" {\n\t\"items\" = \"log|nothing\";\n\t\"output\" = \"plank\";\n\t\"colour\" = \"red\";\n}\n";
+        std::fs::write(dir.clone() + RECIPES_FILE, recipes).unwrap();
+        std::fs::write(
+            dir.clone() + "sub/broken.impfile",
+            "\"a\" {\n\t\"b\" = \"c\"\n}\n",
+        )
+        .unwrap();
+
+        //Every problem in every file is reported
+        let diagnostics = check_assets(&dir, &[]);
+        let found: Vec<(String, usize, bool)> = diagnostics
+            .iter()
+            .map(|d| (d.path.replace(&dir, ""), d.line, d.is_error()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                //Unknown item, unknown key, then the syntax error
+                (RECIPES_FILE.to_string(), 2, true),
+                (RECIPES_FILE.to_string(), 4, false),
+                ("sub/broken.impfile".to_string(), 3, true),
+            ]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
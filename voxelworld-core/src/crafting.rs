@@ -5,9 +5,16 @@ use super::inventory::{
     Item,
 };
 use crate::{
-    game::crafting::tool_recipes::generate_tool_recipes,
-    impfile::{self, Entry},
-    voxel::{Block, FULL_BLOCK, SLAB, STAIR},
+    crafting::tool_recipes::generate_tool_recipes,
+    impfile::{
+        self,
+        from_entry::{load_file, print_diagnostics},
+        Diagnostic, EntryReader, FromEntry,
+    },
+    voxel::{
+        block_registry::{block_registry, BlockRegistry},
+        Block, FULL_BLOCK, SLAB, STAIR,
+    },
 };
 use std::collections::HashMap;
 
@@ -41,25 +48,57 @@ fn add_block_variants(aliases: &mut ItemAliases, name: &str, item: Item) {
     }
 }
 
-//Loads item aliases from impfile
-pub fn load_item_aliases(path: &str) -> ItemAliases {
+//Loads item aliases from impfile without adding any slab or stair variants
+pub fn parse_item_alias_file(path: &str) -> (ItemAliases, Vec<Diagnostic>) {
     let mut aliases = ItemAliases::new();
 
-    let entries = impfile::parse_file(path);
-    for e in entries {
-        let vars = e.get_all_vars();
-        for (name, val) in vars {
-            if let Ok(item) = string_to_item_err(&val) {
-                let reduced = reduce_amt(item);
-                aliases.insert(name.clone(), reduced);
-                add_block_variants(&mut aliases, &name, item);
+    let (entries, mut diagnostics) = impfile::parse_file_diagnostics(path);
+    for e in &entries {
+        let mut reader = EntryReader::new(e, path);
+        for (name, val) in reader.all_vars() {
+            match string_to_item_err(&val) {
+                Ok(item) => {
+                    aliases.insert(name.clone(), reduce_amt(item));
+                }
+                Err(_) => reader.error(&name, &format!("invalid item \"{val}\"")),
             }
         }
+        diagnostics.extend(reader.finish());
     }
 
+    (aliases, diagnostics)
+}
+
+//Adds the blocks in the block registry (aliased by their name) and the slab
+//and stair variants of each block
+pub fn add_block_aliases(aliases: &mut ItemAliases, registry: &BlockRegistry) {
+    aliases.extend(registry.block_aliases(false));
+
+    let items: Vec<(String, Item)> = aliases
+        .iter()
+        .map(|(name, item)| (name.clone(), *item))
+        .collect();
+    for (name, item) in items {
+        add_block_variants(aliases, &name, item);
+    }
+}
+
+//Loads item aliases from impfile, every block in the block registry is also
+//aliased by its name
+pub fn load_item_aliases(path: &str) -> ItemAliases {
+    let (mut aliases, diagnostics) = parse_item_alias_file(path);
+    print_diagnostics(&diagnostics);
+    add_block_aliases(&mut aliases, block_registry());
     aliases
 }
 
+fn parse_aliased_item(s: &str, item_aliases: &ItemAliases) -> Option<Item> {
+    item_aliases
+        .get(s)
+        .copied()
+        .or_else(|| string_to_item_err(s).ok())
+}
+
 pub struct Recipe {
     ingredients: Inventory,
     output: Item,
@@ -67,48 +106,58 @@ pub struct Recipe {
     shapeless: bool,
 }
 
-impl Recipe {
-    pub fn from_entry(entry: &impfile::Entry, item_aliases: &ItemAliases) -> Result<Self, ()> {
-        let w = entry.get_var("width").parse::<usize>().unwrap_or(1);
-        let h = entry.get_var("height").parse::<usize>().unwrap_or(1);
-        let parsed_ingredients: Vec<Item> = entry
-            .get_var("items")
-            .split("|")
-            .filter_map(|s| {
-                let aliased = item_aliases.get(s);
-                if aliased.is_some() {
-                    return aliased.cloned();
-                }
-                string_to_item_err(s).ok()
-            })
-            .map(reduce_amt)
-            .chain(std::iter::repeat(Item::Empty))
-            .take(w * h)
-            .collect();
-        let output_amt = entry.get_var("amt").parse().unwrap_or(1);
-        let output_str = entry.get_var("output");
-        let parsed_output = if let Some(item) = item_aliases.get(&output_str) {
-            *item
-        } else {
-            string_to_item_err(&output_str)?
+impl FromEntry for Recipe {
+    type Context = ItemAliases;
+
+    fn from_entry(reader: &mut EntryReader, item_aliases: &ItemAliases) -> Option<Self> {
+        let w = reader.get_or("width", 1usize);
+        let h = reader.get_or("height", 1usize);
+        if w == 0 || h == 0 {
+            reader.error("width", "recipes must be at least 1x1");
+            return None;
+        }
+
+        let items_str = reader.required::<String>("items")?;
+        let mut parsed_ingredients = vec![];
+        for s in items_str.split("|") {
+            match parse_aliased_item(s, item_aliases) {
+                Some(item) => parsed_ingredients.push(reduce_amt(item)),
+                None => reader.error("items", &format!("unknown item \"{s}\"")),
+            }
+        }
+        if parsed_ingredients.len() > w * h {
+            let msg = format!(
+                "{} items do not fit in a {w}x{h} grid",
+                parsed_ingredients.len()
+            );
+            reader.error("items", &msg);
+        }
+
+        let output_amt = reader.get_or("amt", 1);
+        let output_str = reader.required::<String>("output")?;
+        let Some(parsed_output) = parse_aliased_item(&output_str, item_aliases) else {
+            reader.error("output", &format!("unknown item \"{output_str}\""));
+            return None;
         };
         let multiplied_output = multiply_items(parsed_output, output_amt);
 
         let mut grid = Inventory::empty_with_sz(w, h);
-        for (i, item) in parsed_ingredients.iter().enumerate() {
+        for (i, item) in parsed_ingredients.iter().take(w * h).enumerate() {
             let ix = i % w;
             let iy = i / w;
             grid.set_item(ix, iy, *item);
         }
 
-        Ok(Self {
+        Some(Self {
             ingredients: grid,
-            reflect: entry.get_var("reflect").parse::<bool>().unwrap_or(false),
-            shapeless: entry.get_var("shapeless").parse::<bool>().unwrap_or(false),
+            reflect: reader.get_or("reflect", false),
+            shapeless: reader.get_or("shapeless", false),
             output: multiplied_output,
         })
     }
+}
 
+impl Recipe {
     fn check_match_pos(&self, crafting: &Inventory, x: usize, y: usize) -> bool {
         let w = self.ingredients.w();
         let h = self.ingredients.h();
@@ -237,15 +286,15 @@ fn generate_stair_recipe(block: Block) -> Recipe {
 
 //Generates wool recipes (for combining wool together with dye to color it)
 fn generate_wool_recipes() -> Vec<Recipe> {
-    const WHITE_WOOL_ID: u8 = 57;
-    const WHITE_DYE_ID: u8 = 96;
+    const WHITE_WOOL_ID: u16 = 57;
+    const WHITE_DYE_ID: u16 = 96;
     let mut wool_recipes: Vec<Recipe> = (1..=11)
         .map(|index| {
             let mut grid = Inventory::empty_with_sz(2, 1);
             //Wool
             grid.set_item(0, 0, Item::Block(Block::new_id(WHITE_WOOL_ID), 1));
-            let dye_id: u8 = WHITE_DYE_ID + index;
-            grid.set_item(1, 0, Item::Sprite(dye_id as u16, 1));
+            let dye_id: u16 = WHITE_DYE_ID + index;
+            grid.set_item(1, 0, Item::Sprite(dye_id, 1));
             let dyed_wool = Item::Block(Block::new_id(WHITE_WOOL_ID + index), 1);
             Recipe {
                 ingredients: grid,
@@ -260,7 +309,7 @@ fn generate_wool_recipes() -> Vec<Recipe> {
             let mut grid = Inventory::empty_with_sz(2, 1);
             //Wool
             grid.set_item(0, 0, Item::Block(Block::new_id(WHITE_WOOL_ID + index), 1));
-            grid.set_item(1, 0, Item::Sprite(WHITE_DYE_ID as u16, 1));
+            grid.set_item(1, 0, Item::Sprite(WHITE_DYE_ID, 1));
             Recipe {
                 ingredients: grid,
                 output: Item::Block(Block::new_id(WHITE_WOOL_ID), 1),
@@ -273,46 +322,37 @@ fn generate_wool_recipes() -> Vec<Recipe> {
     wool_recipes
 }
 
-fn get_fuel_from_entry(entry: Entry, item_aliases: &ItemAliases) -> Vec<(Item, f32)> {
-    entry
-        .get_all_vars()
-        .iter()
-        .filter_map(|(name, val)| {
-            let aliased = item_aliases.get(name);
-            if let Some(aliased) = aliased {
-                return Some((*aliased, val));
-            }
-            let item = string_to_item_err(name).ok()?;
-            Some((item, val))
-        })
-        .filter_map(|(item, val)| {
-            let fuel_amt = val.parse::<f32>().ok()?;
-            Some((item, fuel_amt))
-        })
-        .collect()
+fn get_fuel_from_entry(reader: &mut EntryReader, item_aliases: &ItemAliases) -> Vec<(Item, f32)> {
+    let mut fuel = vec![];
+    for (name, val) in reader.all_vars() {
+        let Some(item) = parse_aliased_item(&name, item_aliases) else {
+            reader.error(&name, &format!("unknown item \"{name}\""));
+            continue;
+        };
+        match val.parse::<f32>() {
+            Ok(fuel_amt) => fuel.push((item, fuel_amt)),
+            Err(_) => reader.error(&name, &format!("invalid fuel amount \"{val}\"")),
+        }
+    }
+    fuel
 }
 
-fn get_furnace_from_entry(entry: Entry, item_aliases: &ItemAliases) -> Vec<(Item, Item)> {
-    entry
-        .get_all_vars()
-        .iter()
-        .filter_map(|(name, val)| {
-            let aliased = item_aliases.get(name);
-            if let Some(aliased) = aliased {
-                return Some((*aliased, val));
-            }
-            let item = string_to_item_err(name).ok()?;
-            Some((item, val))
-        })
-        .filter_map(|(item, val)| {
-            let aliased = item_aliases.get(val);
-            if let Some(aliased) = aliased {
-                return Some((item, *aliased));
-            }
-            let output = string_to_item_err(val).ok()?;
-            Some((item, output))
-        })
-        .collect()
+fn get_furnace_from_entry(
+    reader: &mut EntryReader,
+    item_aliases: &ItemAliases,
+) -> Vec<(Item, Item)> {
+    let mut furnace = vec![];
+    for (name, val) in reader.all_vars() {
+        let Some(item) = parse_aliased_item(&name, item_aliases) else {
+            reader.error(&name, &format!("unknown item \"{name}\""));
+            continue;
+        };
+        match parse_aliased_item(&val, item_aliases) {
+            Some(output) => furnace.push((item, output)),
+            None => reader.error(&name, &format!("unknown item \"{val}\"")),
+        }
+    }
+    furnace
 }
 
 fn add_block_fuel_variants(block: Block, fuel_amt: f32, block_variants: &mut Vec<(Item, f32)>) {
@@ -354,6 +394,12 @@ fn add_block_fuel_variants(block: Block, fuel_amt: f32, block_variants: &mut Vec
     block_variants.push((Item::Block(stair, 1), 0.75 * fuel_amt));
 }
 
+impl Default for RecipeTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RecipeTable {
     pub fn new() -> Self {
         Self {
@@ -363,21 +409,34 @@ impl RecipeTable {
         }
     }
 
-    pub fn load_furnace(&mut self, item_alias_path: &str, recipe_path: &str) {
-        let item_aliases = load_item_aliases(item_alias_path);
-        let entries = impfile::parse_file(recipe_path);
-        for e in entries {
+    //Adds the fuel and smelting recipes in `recipe_path`, returns any
+    //problems found in the file
+    pub fn read_furnace(
+        &mut self,
+        recipe_path: &str,
+        item_aliases: &ItemAliases,
+    ) -> Vec<Diagnostic> {
+        let (entries, mut diagnostics) = impfile::parse_file_diagnostics(recipe_path);
+        for e in &entries {
+            let mut reader = EntryReader::new(e, recipe_path);
             match e.get_name().as_str() {
                 "fuel" => {
-                    let fuel = get_fuel_from_entry(e, &item_aliases);
+                    let fuel = get_fuel_from_entry(&mut reader, item_aliases);
                     self.fuel.extend(fuel);
                 }
                 "furnace" => {
-                    let furnace = get_furnace_from_entry(e, &item_aliases);
+                    let furnace = get_furnace_from_entry(&mut reader, item_aliases);
                     self.furnace_table.extend(furnace);
                 }
-                _ => {}
+                name => {
+                    reader.all_vars();
+                    reader.error(
+                        "",
+                        &format!("expected \"fuel\" or \"furnace\", found \"{name}\""),
+                    );
+                }
             }
+            diagnostics.extend(reader.finish());
         }
 
         let mut block_variants = vec![];
@@ -388,15 +447,30 @@ impl RecipeTable {
         }
         self.fuel.extend(block_variants);
 
+        diagnostics
+    }
+
+    pub fn load_furnace(&mut self, item_alias_path: &str, recipe_path: &str) {
+        let item_aliases = load_item_aliases(item_alias_path);
+        print_diagnostics(&self.read_furnace(recipe_path, &item_aliases));
         eprintln!("Loaded {} furnace recipes", self.furnace_table.len());
     }
 
+    //Reads the crafting recipes in `recipe_path` (without any of the auto
+    //generated recipes), returns any problems found in the file
+    pub fn read_recipes(
+        &mut self,
+        recipe_path: &str,
+        item_aliases: &ItemAliases,
+    ) -> Vec<Diagnostic> {
+        let (recipes, diagnostics) = load_file::<Recipe>(recipe_path, item_aliases);
+        self.recipes = recipes;
+        diagnostics
+    }
+
     pub fn load_recipes(&mut self, item_alias_path: &str, recipe_path: &str) {
         let item_aliases = load_item_aliases(item_alias_path);
-        self.recipes = impfile::parse_file(recipe_path)
-            .iter()
-            .filter_map(|e| Recipe::from_entry(e, &item_aliases).ok())
-            .collect();
+        print_diagnostics(&self.read_recipes(recipe_path, &item_aliases));
         let mut auto_generated_recipes = vec![];
         for item in item_aliases.values().copied() {
             if let Item::Block(block, _) = item {
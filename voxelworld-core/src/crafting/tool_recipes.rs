@@ -1,5 +1,5 @@
 use super::{ItemAliases, Recipe};
-use crate::game::inventory::{
+use crate::inventory::{
     string_to_item_err,
     tools::{ToolInfo, ToolMaterial, ToolType},
     Inventory, Item,
@@ -0,0 +1,352 @@
+pub mod from_entry;
+mod tests;
+
+pub use from_entry::{Diagnostic, EntryReader, FromEntry, FromValue, Severity};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+
+/*
+ * An entry, how an impfile is organized
+ * */
+pub struct Entry {
+    name: String,
+    variables: HashMap<String, String>,
+    //Line and column of the entry name in the file (0 if the entry was not
+    //read from a file)
+    line: usize,
+    col: usize,
+    //Line and column of each variable
+    var_positions: HashMap<String, (usize, usize)>,
+}
+
+pub type EntryList = Vec<Entry>;
+
+impl Entry {
+    pub fn new(entry_name: &str) -> Self {
+        Self {
+            name: entry_name.to_string(),
+            variables: HashMap::new(),
+            line: 0,
+            col: 0,
+            var_positions: HashMap::new(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_vec(entry_name: &str, vars: Vec<(String, String)>) -> Self {
+        let mut variable_map = HashMap::new();
+        for (name, value) in vars {
+            variable_map.insert(name, value);
+        }
+
+        Self {
+            name: entry_name.to_string(),
+            variables: variable_map,
+            line: 0,
+            col: 0,
+            var_positions: HashMap::new(),
+        }
+    }
+
+    //Returns the name of the entry
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    //Returns the line and column of the entry in the file it was read from
+    pub fn pos(&self) -> (usize, usize) {
+        (self.line, self.col)
+    }
+
+    //Returns the line and column of a variable, if the variable does not
+    //exist then the position of the entry is returned
+    pub fn var_pos(&self, var_name: &str) -> (usize, usize) {
+        self.var_positions
+            .get(var_name)
+            .copied()
+            .unwrap_or(self.pos())
+    }
+
+    //Returns a variable value if it exists
+    pub fn try_get_var(&self, var_name: &str) -> Option<&str> {
+        self.variables.get(var_name).map(|s| s.as_str())
+    }
+
+    //Returns a variable value, if a variable is not found "" is returned
+    pub fn get_var(&self, var_name: &str) -> String {
+        self.variables
+            .get(var_name)
+            .cloned()
+            .unwrap_or("".to_string())
+    }
+
+    pub fn add_string(&mut self, var_name: &str, s: &str) {
+        self.variables.insert(var_name.to_string(), s.to_string());
+    }
+
+    pub fn add_float(&mut self, var_name: &str, v: f32) {
+        self.variables.insert(var_name.to_string(), v.to_string());
+    }
+
+    pub fn add_bool(&mut self, var_name: &str, b: bool) {
+        self.variables.insert(var_name.to_string(), b.to_string());
+    }
+
+    pub fn add_integer(&mut self, var_name: &str, i: i64) {
+        self.variables.insert(var_name.to_string(), i.to_string());
+    }
+
+    //Converts the value into a formatted string, used for serializing to a file
+    pub fn to_impfile_string(&self) -> String {
+        let mut res = String::new();
+        //name
+        res.push('\"');
+        res.push_str(&self.name);
+        res.push_str("\" {\n");
+        //variables
+        for (var_name, val) in &self.variables {
+            res.push_str("\t\"");
+            res.push_str(var_name);
+            res.push_str("\" = \"");
+            res.push_str(val);
+            res.push_str("\";\n");
+        }
+        res.push('}');
+
+        res
+    }
+
+    //Returns a vector of variables: (name, value)
+    pub fn get_all_vars(&self) -> Vec<(String, String)> {
+        self.variables
+            .iter()
+            .map(|(name, val)| (name.to_string(), val.to_string()))
+            .collect()
+    }
+}
+
+//Writes a comment into a file
+pub fn write_comment(outfile: &mut File, comment_text: &str) {
+    let mut text = String::new();
+    for ch in comment_text.chars() {
+        if ch == '\n' {
+            let mut comment_text = String::new();
+            comment_text.push_str("# ");
+            comment_text.push_str(&text);
+            comment_text.push('\n');
+
+            let res = outfile.write_all(comment_text.as_bytes());
+            //Output any potential errors
+            if let Err(msg) = res {
+                eprintln!("E: {msg}");
+            }
+            text.clear();
+            continue;
+        }
+
+        text.push(ch);
+    }
+
+    let mut comment_text = String::new();
+    comment_text.push_str("# ");
+    comment_text.push_str(&text);
+    comment_text.push('\n');
+    let res = outfile.write_all(comment_text.as_bytes());
+    //Output any potential errors
+    if let Err(msg) = res {
+        eprintln!("E: {msg}");
+    }
+}
+
+//A character in a file along with its line and column (both start at 1)
+type FileChar = (char, usize, usize);
+
+//Syntax error: message, line, column
+type SyntaxError = (String, usize, usize);
+
+//Removes any comments from a string
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(index) => &line[..index],
+        None => line,
+    }
+}
+
+//Removes comments and any whitespace outside of quotes, the position of each
+//remaining character is kept so that errors can point to where they are
+fn strip_file(contents: &str) -> Result<Vec<FileChar>, SyntaxError> {
+    let mut file_chars = vec![];
+    for (line_num, line) in contents.lines().enumerate() {
+        let line = strip_comment(line);
+        let mut quote_count = 0;
+        for (col, ch) in line.chars().enumerate() {
+            if ch.is_whitespace() && quote_count % 2 == 0 {
+                continue;
+            }
+
+            if ch == '\"' {
+                quote_count += 1;
+            }
+
+            file_chars.push((ch, line_num + 1, col + 1));
+        }
+
+        if quote_count % 2 != 0 {
+            return Err(("Mismatched quotes".to_string(), line_num + 1, 1));
+        }
+    }
+    Ok(file_chars)
+}
+
+struct Parser {
+    chars: Vec<FileChar>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).map(|(ch, _, _)| *ch)
+    }
+
+    //Position of the next character (or the end of the last line)
+    fn pos(&self) -> (usize, usize) {
+        match self.chars.get(self.index) {
+            Some((_, line, col)) => (*line, *col),
+            None => self
+                .chars
+                .last()
+                .map(|(_, line, col)| (*line, col + 1))
+                .unwrap_or((1, 1)),
+        }
+    }
+
+    fn error(&self, msg: String) -> SyntaxError {
+        let (line, col) = self.pos();
+        (msg, line, col)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SyntaxError> {
+        match self.peek() {
+            Some(ch) if ch == expected => {
+                self.index += 1;
+                Ok(())
+            }
+            Some(ch) => Err(self.error(format!("Expected '{expected}', found '{ch}'"))),
+            None => Err(self.error(format!("Expected '{expected}', found end of file"))),
+        }
+    }
+
+    //Parses a string in quotes
+    fn parse_quoted(&mut self) -> Result<String, SyntaxError> {
+        self.expect('\"')?;
+        let mut s = String::new();
+        while let Some(ch) = self.peek() {
+            self.index += 1;
+            if ch == '\"' {
+                return Ok(s);
+            }
+            s.push(ch);
+        }
+        Err(self.error("Missing closing quote".to_string()))
+    }
+
+    //Returns Ok((name, value)) or Err(msg)
+    fn parse_variable(&mut self) -> Result<(String, String), SyntaxError> {
+        let (line, col) = self.pos();
+        let name = self.parse_quoted()?;
+        if name.is_empty() {
+            return Err(("Empty variable name".to_string(), line, col));
+        }
+        self.expect('=')?;
+        let value = self.parse_quoted()?;
+        self.expect(';')?;
+        Ok((name, value))
+    }
+
+    fn parse_entry(&mut self) -> Result<Entry, SyntaxError> {
+        let (line, col) = self.pos();
+        let name = self.parse_quoted()?;
+        //Empty name is invalid, return error
+        if name.is_empty() {
+            return Err(("Empty entry name".to_string(), line, col));
+        }
+        self.expect('{')?;
+
+        let mut entry = Entry::new(&name);
+        entry.line = line;
+        entry.col = col;
+        while self.peek() != Some('}') {
+            if self.peek().is_none() {
+                return Err(self.error(format!("Missing closing }} for '{name}'")));
+            }
+
+            let pos = self.pos();
+            let (var_name, value) = self
+                .parse_variable()
+                .map_err(|(msg, line, col)| (format!("Error in '{name}': {msg}"), line, col))?;
+            entry.var_positions.insert(var_name.clone(), pos);
+            entry.variables.insert(var_name, value);
+        }
+        self.index += 1;
+
+        Ok(entry)
+    }
+}
+
+//Parses the contents of an impfile, `path` is only used for error messages.
+//Parsing stops at the first syntax error, the entries before the error are
+//still returned.
+pub fn parse_str(contents: &str, path: &str) -> (EntryList, Vec<Diagnostic>) {
+    let mut entries = vec![];
+    let to_diagnostic = |(msg, line, col): SyntaxError| {
+        Diagnostic::error(path, line, col, &format!("Syntax error: {msg}"))
+    };
+
+    let file_chars = match strip_file(contents) {
+        Ok(file_chars) => file_chars,
+        Err(err) => return (entries, vec![to_diagnostic(err)]),
+    };
+
+    let mut parser = Parser {
+        chars: file_chars,
+        index: 0,
+    };
+    while parser.peek().is_some() {
+        match parser.parse_entry() {
+            Ok(entry) => entries.push(entry),
+            Err(err) => return (entries, vec![to_diagnostic(err)]),
+        }
+    }
+
+    (entries, vec![])
+}
+
+//Same as parse_file but returns any errors instead of printing them
+pub fn parse_file_diagnostics(path: &str) -> (EntryList, Vec<Diagnostic>) {
+    let mut file_contents = String::new();
+    match File::open(path) {
+        Ok(mut file) => {
+            if let Err(msg) = file.read_to_string(&mut file_contents) {
+                let msg = format!("Failed to read file: {msg}");
+                return (vec![], vec![Diagnostic::error(path, 0, 0, &msg)]);
+            }
+        }
+        Err(msg) => {
+            let msg = format!("Failed to open file: {msg}");
+            return (vec![], vec![Diagnostic::error(path, 0, 0, &msg)]);
+        }
+    }
+
+    parse_str(&file_contents, path)
+}
+
+pub fn parse_file(path: &str) -> EntryList {
+    let (entries, diagnostics) = parse_file_diagnostics(path);
+    if diagnostics.is_empty() {
+        eprintln!("read {} entries from {path}", entries.len());
+    }
+    from_entry::print_diagnostics(&diagnostics);
+    entries
+}
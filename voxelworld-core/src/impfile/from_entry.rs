@@ -0,0 +1,396 @@
+/*
+ * Typed access to impfile entries
+ *
+ * Loaders implement FromEntry and read variables through an EntryReader which
+ * converts them to the type that is wanted and keeps track of every problem
+ * it finds (along with its line and column) instead of falling back to a
+ * default value without saying anything. Once an entry is read, any variable
+ * that was never looked at is reported as an unknown key.
+ * */
+
+use super::{parse_file_diagnostics, Entry};
+use std::{collections::HashSet, fmt::Display, path::Path};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+//A problem found in an impfile
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: String,
+    //Line and column start at 1, 0 means that there is no position
+    pub line: usize,
+    pub col: usize,
+    pub msg: String,
+}
+
+impl Diagnostic {
+    pub fn error(path: &str, line: usize, col: usize, msg: &str) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: path.to_string(),
+            line,
+            col,
+            msg: msg.to_string(),
+        }
+    }
+
+    pub fn warning(path: &str, line: usize, col: usize, msg: &str) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(path, line, col, msg)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let prefix = match self.severity {
+            Severity::Error => "E",
+            Severity::Warning => "W",
+        };
+        if self.line == 0 {
+            write!(f, "{prefix}: {}: {}", self.path, self.msg)
+        } else {
+            write!(
+                f,
+                "{prefix}: {}:{}:{}: {}",
+                self.path, self.line, self.col, self.msg
+            )
+        }
+    }
+}
+
+pub fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        eprintln!("{diagnostic}");
+    }
+}
+
+//A type that a variable can be converted to
+pub trait FromValue: Sized {
+    //Used in error messages, ex: "expected an integer"
+    const EXPECTED: &'static str;
+    fn from_value(value: &str) -> Option<Self>;
+}
+
+impl FromValue for String {
+    const EXPECTED: &'static str = "a string";
+    fn from_value(value: &str) -> Option<Self> {
+        Some(value.to_string())
+    }
+}
+
+impl FromValue for bool {
+    const EXPECTED: &'static str = "true or false";
+    fn from_value(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+macro_rules! impl_from_value_num {
+    ($expected:literal, $($t:ty),*) => {
+        $(
+            impl FromValue for $t {
+                const EXPECTED: &'static str = $expected;
+                fn from_value(value: &str) -> Option<Self> {
+                    value.trim().parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_value_num!("an integer", u8, u16, u32, u64, usize, i32, i64);
+impl_from_value_num!("a number", f32, f64);
+
+//Comma separated list, an empty string is an empty list
+impl<T: FromValue> FromValue for Vec<T> {
+    const EXPECTED: &'static str = "a comma separated list";
+    fn from_value(value: &str) -> Option<Self> {
+        if value.is_empty() {
+            return Some(vec![]);
+        }
+        value.split(',').map(|v| T::from_value(v.trim())).collect()
+    }
+}
+
+pub struct EntryReader<'a> {
+    entry: &'a Entry,
+    path: &'a str,
+    //Variables that have been read
+    used: HashSet<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> EntryReader<'a> {
+    pub fn new(entry: &'a Entry, path: &'a str) -> Self {
+        Self {
+            entry,
+            path,
+            used: HashSet::new(),
+            diagnostics: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.entry.name
+    }
+
+    pub fn entry(&self) -> &Entry {
+        self.entry
+    }
+
+    //Reports an error at a variable (or at the entry if `var_name` does not
+    //exist in the entry)
+    pub fn error(&mut self, var_name: &str, msg: &str) {
+        let (line, col) = self.entry.var_pos(var_name);
+        let msg = format!("\'{}\': {msg}", self.entry.name);
+        self.diagnostics
+            .push(Diagnostic::error(self.path, line, col, &msg));
+    }
+
+    pub fn warning(&mut self, var_name: &str, msg: &str) {
+        let (line, col) = self.entry.var_pos(var_name);
+        let msg = format!("\'{}\': {msg}", self.entry.name);
+        self.diagnostics
+            .push(Diagnostic::warning(self.path, line, col, &msg));
+    }
+
+    //Returns the raw value of a variable and marks it as used
+    pub fn get_str(&mut self, var_name: &str) -> Option<&'a str> {
+        self.used.insert(var_name.to_string());
+        self.entry.try_get_var(var_name)
+    }
+
+    //Returns None if the variable does not exist, reports an error if the
+    //variable can not be converted
+    pub fn get<T: FromValue>(&mut self, var_name: &str) -> Option<T> {
+        let value = self.get_str(var_name)?;
+        let converted = T::from_value(value);
+        if converted.is_none() {
+            let msg = format!(
+                "\"{var_name}\" should be {}, found \"{value}\"",
+                T::EXPECTED
+            );
+            self.error(var_name, &msg);
+        }
+        converted
+    }
+
+    //Same as get but the variable also has to exist
+    pub fn required<T: FromValue>(&mut self, var_name: &str) -> Option<T> {
+        if self.entry.try_get_var(var_name).is_none() {
+            self.error(var_name, &format!("missing \"{var_name}\""));
+            return None;
+        }
+        self.get(var_name)
+    }
+
+    //Same as required but the variable is a path to a file that has to exist
+    pub fn required_path(&mut self, var_name: &str) -> Option<String> {
+        let path: String = self.required(var_name)?;
+        if !Path::new(&path).is_file() {
+            self.error(var_name, &format!("file \"{path}\" does not exist"));
+            return None;
+        }
+        Some(path)
+    }
+
+    //Returns `default` if the variable is missing or invalid
+    pub fn get_or<T: FromValue>(&mut self, var_name: &str, default: T) -> T {
+        self.get(var_name).unwrap_or(default)
+    }
+
+    //Returns a list of values separated by `separator`, every value that can
+    //not be converted is reported
+    pub fn get_list<T: FromValue>(&mut self, var_name: &str, separator: char) -> Option<Vec<T>> {
+        let value = self.get_str(var_name)?;
+        if value.is_empty() {
+            return Some(vec![]);
+        }
+
+        let mut list = vec![];
+        for v in value.split(separator) {
+            match T::from_value(v) {
+                Some(converted) => list.push(converted),
+                None => {
+                    let msg = format!("\"{var_name}\": \"{v}\" should be {}", T::EXPECTED);
+                    self.error(var_name, &msg);
+                }
+            }
+        }
+
+        if list.len() == value.split(separator).count() {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    //Returns all variables that start with `prefix` (sorted by name) and
+    //marks them as used
+    pub fn vars_with_prefix(&mut self, prefix: &str) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = self
+            .entry
+            .get_all_vars()
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .collect();
+        vars.sort();
+        for (name, _) in &vars {
+            self.used.insert(name.clone());
+        }
+        vars
+    }
+
+    //Returns every variable in the entry, for entries that are a table of
+    //names to values
+    pub fn all_vars(&mut self) -> Vec<(String, String)> {
+        self.vars_with_prefix("")
+    }
+
+    //Returns any problems found, this also reports variables that were not
+    //read as unknown
+    pub fn finish(mut self) -> Vec<Diagnostic> {
+        let mut unknown: Vec<(usize, usize, String)> = self
+            .entry
+            .variables
+            .keys()
+            .filter(|name| !self.used.contains(*name))
+            .map(|name| {
+                let (line, col) = self.entry.var_pos(name);
+                (line, col, name.clone())
+            })
+            .collect();
+        unknown.sort();
+        for (_, _, name) in unknown {
+            self.warning(&name, &format!("unknown key \"{name}\""));
+        }
+        self.diagnostics
+    }
+}
+
+pub trait FromEntry: Sized {
+    //Any extra data that is needed to read the entry (ex: item aliases)
+    type Context;
+
+    //Returns None if the entry is invalid, the problems should be reported
+    //through `reader`
+    fn from_entry(reader: &mut EntryReader, ctx: &Self::Context) -> Option<Self>;
+}
+
+//Reads every entry, entries that fail to be read are skipped
+pub fn from_entries<T: FromEntry>(
+    entries: &[Entry],
+    path: &str,
+    ctx: &T::Context,
+) -> (Vec<T>, Vec<Diagnostic>) {
+    let mut values = vec![];
+    let mut diagnostics = vec![];
+    for entry in entries {
+        let mut reader = EntryReader::new(entry, path);
+        if let Some(value) = T::from_entry(&mut reader, ctx) {
+            values.push(value);
+        }
+        diagnostics.extend(reader.finish());
+    }
+    (values, diagnostics)
+}
+
+//Parses a file and reads every entry in it
+pub fn load_file<T: FromEntry>(path: &str, ctx: &T::Context) -> (Vec<T>, Vec<Diagnostic>) {
+    let (entries, mut diagnostics) = parse_file_diagnostics(path);
+    let (values, entry_diagnostics) = from_entries(&entries, path, ctx);
+    diagnostics.extend(entry_diagnostics);
+    (values, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impfile::parse_str;
+
+    struct TestEntry {
+        count: u32,
+        scale: f32,
+        enabled: bool,
+        tags: Vec<String>,
+    }
+
+    impl FromEntry for TestEntry {
+        type Context = ();
+
+        fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+            let count = reader.required("count");
+            let scale = reader.get_or("scale", 1.0);
+            let enabled = reader.get_or("enabled", false);
+            let tags = reader.get_list("tags", '|').unwrap_or_default();
+            Some(Self {
+                count: count?,
+                scale,
+                enabled,
+                tags,
+            })
+        }
+    }
+
+    #[test]
+    fn test_from_entry() {
+        let contents = "\"a\" {\n\t\"count\" = \"3\";\n\t\"scale\" = \"0.5\";\n\t\"enabled\" = \"true\";\n\t\"tags\" = \"x|y\";\n}\n";
+        let (entries, diagnostics) = parse_str(contents, "test");
+        assert!(diagnostics.is_empty());
+        let (values, diagnostics) = from_entries::<TestEntry>(&entries, "test", &());
+        assert!(diagnostics.is_empty());
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].count, 3);
+        assert_eq!(values[0].scale, 0.5);
+        assert!(values[0].enabled);
+        assert_eq!(values[0].tags, vec!["x".to_string(), "y".to_string()]);
+    }
+
+    #[test]
+    fn test_from_entry_errors() {
+        let contents = "\"a\" {\n\t\"scale\" = \"big\";\n\t\"colour\" = \"red\";\n}\n";
+        let (entries, _) = parse_str(contents, "test");
+        let (values, diagnostics) = from_entries::<TestEntry>(&entries, "test", &());
+        assert!(values.is_empty());
+        //Missing count is reported at the entry, the rest at the variable
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics[0].is_error());
+        assert_eq!((diagnostics[0].line, diagnostics[0].col), (1, 1));
+        assert!(diagnostics[1].is_error());
+        assert_eq!((diagnostics[1].line, diagnostics[1].col), (2, 2));
+        assert_eq!(diagnostics[2].severity, Severity::Warning);
+        assert_eq!((diagnostics[2].line, diagnostics[2].col), (3, 2));
+        assert!(diagnostics[2].msg.contains("colour"));
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let contents = "\"a\" {\n\t\"x\" = \"1\";\n\t\"y\" == \"2\";\n}\n";
+        let (entries, diagnostics) = parse_str(contents, "test");
+        assert!(entries.is_empty());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].col), (3, 7));
+    }
+
+    #[test]
+    fn test_values() {
+        assert_eq!(u8::from_value("255"), Some(255));
+        assert_eq!(u8::from_value("256"), None);
+        assert_eq!(i32::from_value("-4"), Some(-4));
+        assert_eq!(bool::from_value("yes"), None);
+        assert_eq!(Vec::<u8>::from_value("1, 2,3"), Some(vec![1, 2, 3]));
+        assert_eq!(Vec::<u8>::from_value(""), Some(vec![]));
+        assert_eq!(Vec::<u8>::from_value("1,a"), None);
+    }
+}
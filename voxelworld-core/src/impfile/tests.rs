@@ -18,7 +18,7 @@ fn test_impfile1() {
 fn test_impfile2() {
     let entries = parse_file("test_impfiles/test2.impfile");
     assert_eq!(entries.len(), 3);
-    let expected = vec![
+    let expected = [
         Entry::from_vec(
             "foo",
             vec![
@@ -65,10 +65,9 @@ fn test_entry_to_string() {
     entry.add_string("foo", "bar");
     entry.add_string("fizz", "buzz");
 
-    let entry_str = strip_whitespace(&entry.to_impfile_string());
-    let mut entry_str_chars = entry_str.chars().peekable();
-
-    let entry2 = parse_entry(&mut entry_str_chars);
+    let (entries, diagnostics) = parse_str(&entry.to_impfile_string(), "test");
+    assert!(diagnostics.is_empty());
+    let entry2 = entries.into_iter().next().ok_or(());
     assert!(entry2.is_ok());
 
     if let Ok(e) = entry2 {
@@ -1,4 +1,5 @@
 pub mod food;
+pub mod loot;
 pub mod tools;
 
 use self::{
@@ -6,7 +7,16 @@ use self::{
     tools::string_to_tool_info,
 };
 use super::crafting::{load_item_aliases, ItemAliases};
-use crate::{impfile, voxel::Block};
+use crate::{
+    bin_data::{DataTable, DataType},
+    impfile::{self, from_entry::print_diagnostics, Diagnostic, EntryReader},
+    voxel::{
+        block_ids::{
+            BRICKS, DIRT, GLASS, GRASS, LAVA, LEAVES, LOG, PLANK, STONE, STONE_BRICKS, WATER,
+        },
+        Block, EMPTY_BLOCK,
+    },
+};
 use std::collections::HashMap;
 use tools::ToolInfo;
 
@@ -23,7 +33,7 @@ pub enum Item {
     //Atlas (or id), food info
     Food(u16, FoodInfo),
     //Block id
-    Bucket(u8),
+    Bucket(u16),
     Empty,
 }
 
@@ -124,7 +134,7 @@ pub fn string_to_item_err(s: &str) -> Result<Item, ()> {
     let tokens: Vec<String> = s.split(",").map(|s| s.to_string()).collect();
 
     if tokens.len() == 4 && tokens[0] == "block" {
-        let id = tokens[1].parse::<u8>().unwrap_or(1);
+        let id = tokens[1].parse::<u16>().unwrap_or(1);
         let geometry = tokens[2].parse::<u8>().unwrap_or(0);
         let amt = tokens[3].parse::<u8>().unwrap_or(1);
 
@@ -153,7 +163,7 @@ pub fn string_to_item_err(s: &str) -> Result<Item, ()> {
         let info = string_to_food_info(&tokens[2]).map_err(|_| ())?;
         Ok(Item::Food(id, info))
     } else if tokens.len() == 2 && tokens[0] == "bucket" {
-        let blockid = tokens[1].parse::<u8>().unwrap_or(0);
+        let blockid = tokens[1].parse::<u16>().unwrap_or(0);
         Ok(Item::Bucket(blockid))
     } else if tokens.len() == 1 && tokens[0] == "empty" {
         Ok(Item::Empty)
@@ -256,15 +266,15 @@ impl Hotbar {
 
     pub fn init_hotbar() -> Self {
         let mut hotbar = Self::empty_hotbar();
-        hotbar.items[0] = Item::Block(Block::new_id(1), 1);
-        hotbar.items[1] = Item::Block(Block::new_id(2), 1);
-        hotbar.items[2] = Item::Block(Block::new_id(4), 1);
-        hotbar.items[3] = Item::Block(Block::new_id(5), 1);
-        hotbar.items[4] = Item::Block(Block::new_id(6), 1);
-        hotbar.items[5] = Item::Block(Block::new_id(7), 1);
-        hotbar.items[6] = Item::Block(Block::new_id(8), 1);
-        hotbar.items[7] = Item::Block(Block::new_id(9), 1);
-        hotbar.items[8] = Item::Block(Block::new_id(10), 1);
+        hotbar.items[0] = Item::Block(Block::new_id(GRASS), 1);
+        hotbar.items[1] = Item::Block(Block::new_id(STONE), 1);
+        hotbar.items[2] = Item::Block(Block::new_id(DIRT), 1);
+        hotbar.items[3] = Item::Block(Block::new_id(BRICKS), 1);
+        hotbar.items[4] = Item::Block(Block::new_id(PLANK), 1);
+        hotbar.items[5] = Item::Block(Block::new_id(LEAVES), 1);
+        hotbar.items[6] = Item::Block(Block::new_id(LOG), 1);
+        hotbar.items[7] = Item::Block(Block::new_id(GLASS), 1);
+        hotbar.items[8] = Item::Block(Block::new_id(STONE_BRICKS), 1);
         hotbar
     }
 
@@ -384,6 +394,12 @@ pub struct Inventory {
     items: Vec<Item>,
 }
 
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Inventory {
     pub fn new() -> Self {
         Self {
@@ -477,6 +493,42 @@ impl Inventory {
         }
     }
 
+    //Stores the items as a list of strings (see `item_to_string`)
+    pub fn to_data_table(&self) -> DataTable {
+        let mut data_table = DataTable::new();
+        data_table.add_int("w", self.width as i64);
+        data_table.add_int("h", self.height as i64);
+        let items = self
+            .items
+            .iter()
+            .map(|item| DataType::Str(item_to_string(*item)))
+            .collect();
+        data_table.add_list("items", items);
+        data_table
+    }
+
+    pub fn from_data_table(data_table: &DataTable) -> Self {
+        let w = data_table.get_int("w").unwrap_or(1) as usize;
+        let h = data_table.get_int("h").unwrap_or(1) as usize;
+        let inventory_items = data_table
+            .get_list("items")
+            .unwrap_or(&[])
+            .iter()
+            .map(|item| match item {
+                DataType::Str(s) => string_to_item(s),
+                _ => Item::Empty,
+            })
+            .chain(std::iter::repeat(Item::Empty))
+            .take(w * h)
+            .collect();
+
+        Self {
+            width: w,
+            height: h,
+            items: inventory_items,
+        }
+    }
+
     pub fn w(&self) -> usize {
         self.width
     }
@@ -586,22 +638,37 @@ fn parse_aliased_items(s: &str, item_aliases: &ItemAliases) -> Result<Item, ()>
     string_to_item_err(s)
 }
 
-pub fn load_leftover_table(item_alias_path: &str, leftovers_path: &str) -> HashMap<String, Item> {
-    let entries = impfile::parse_file(leftovers_path);
-    let item_aliases = load_item_aliases(item_alias_path);
+//Reads the leftover table, returns any problems found in the file along with
+//the table
+pub fn read_leftover_table(
+    leftovers_path: &str,
+    item_aliases: &ItemAliases,
+) -> (HashMap<String, Item>, Vec<Diagnostic>) {
+    let (entries, mut diagnostics) = impfile::parse_file_diagnostics(leftovers_path);
     let mut leftovers_table = HashMap::new();
-    for e in entries {
-        for (name, value) in e.get_all_vars() {
-            let item = parse_aliased_items(&name, &item_aliases);
-            if item.is_err() {
+    for e in &entries {
+        let mut reader = EntryReader::new(e, leftovers_path);
+        for (name, value) in reader.all_vars() {
+            let Ok(item) = parse_aliased_items(&name, item_aliases) else {
+                reader.error(&name, &format!("unknown item \"{name}\""));
                 continue;
-            }
-            let item_str = item_to_string(item.unwrap_or(Item::Empty));
-            let leftover = parse_aliased_items(&value, &item_aliases).unwrap_or(Item::Empty);
-            leftovers_table.insert(item_str, leftover);
+            };
+            let Ok(leftover) = parse_aliased_items(&value, item_aliases) else {
+                reader.error(&name, &format!("unknown item \"{value}\""));
+                continue;
+            };
+            leftovers_table.insert(item_to_string(item), leftover);
         }
+        diagnostics.extend(reader.finish());
     }
 
+    (leftovers_table, diagnostics)
+}
+
+pub fn load_leftover_table(item_alias_path: &str, leftovers_path: &str) -> HashMap<String, Item> {
+    let item_aliases = load_item_aliases(item_alias_path);
+    let (leftovers_table, diagnostics) = read_leftover_table(leftovers_path, &item_aliases);
+    print_diagnostics(&diagnostics);
     leftovers_table
 }
 
@@ -609,9 +676,9 @@ pub const fn get_item_atlas_id(item: Item) -> u16 {
     match item {
         Item::Tool(id, _) | Item::Food(id, _) | Item::Sprite(id, _) => id,
         Item::Bucket(blockid) => match blockid {
-            0 => 16,
-            12 => 17,
-            13 => 18,
+            EMPTY_BLOCK => 16,
+            WATER => 17,
+            LAVA => 18,
             _ => 0,
         },
         _ => 0,
@@ -0,0 +1,249 @@
+/*
+ * Loot tables for the chests in generated structures
+ *
+ * Each entry in LOOT_TABLE_PATH is a loot table, the name of the entry is the
+ * name of the table. The tables are loaded the first time they are used, see
+ * that file for the format of each table.
+ * */
+
+use super::{multiply_items, parse_aliased_items, reduce_amt, Item};
+use crate::{
+    crafting::{load_item_aliases, ItemAliases},
+    impfile::{
+        from_entry::{load_file, print_diagnostics},
+        Diagnostic, EntryReader, FromEntry,
+    },
+    voxel::{block_registry::ITEM_ALIAS_PATH, tile_data::TileData},
+};
+use std::{collections::HashMap, sync::OnceLock};
+
+#[cfg(not(test))]
+pub const LOOT_TABLE_PATH: &str = "assets/loot_tables.impfile";
+//Tests are run from the voxelworld-core directory
+#[cfg(test)]
+pub const LOOT_TABLE_PATH: &str = "../assets/loot_tables.impfile";
+
+//Number of times to try to find an empty slot for an item
+const SLOT_TRIES: usize = 8;
+
+#[derive(Clone, Debug)]
+struct LootItem {
+    item: Item,
+    weight: u32,
+    min_amt: u8,
+    max_amt: u8,
+}
+
+//Parses "item/weight/min-max", the amount can also be a single number
+fn parse_loot_item(s: &str, item_aliases: &ItemAliases) -> Result<LootItem, String> {
+    //Items can contain '/' so the weight and amount are split off from the end
+    let mut parts = s.rsplitn(3, '/');
+    let (Some(amt), Some(weight), Some(item)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(format!("\"{s}\" should be item/weight/amount"));
+    };
+
+    let Ok(item) = parse_aliased_items(item, item_aliases) else {
+        return Err(format!("unknown item \"{item}\""));
+    };
+    let Ok(weight) = weight.parse::<u32>() else {
+        return Err(format!("invalid weight \"{weight}\""));
+    };
+    let (min_amt, max_amt) = amt.split_once('-').unwrap_or((amt, amt));
+    let (Ok(min_amt), Ok(max_amt)) = (min_amt.parse::<u8>(), max_amt.parse::<u8>()) else {
+        return Err(format!("invalid amount \"{amt}\""));
+    };
+    if min_amt == 0 || min_amt > max_amt {
+        return Err(format!("invalid amount \"{amt}\""));
+    }
+
+    Ok(LootItem {
+        item: reduce_amt(item),
+        weight,
+        min_amt,
+        max_amt,
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct LootTable {
+    pub name: String,
+    min_rolls: u32,
+    max_rolls: u32,
+    items: Vec<LootItem>,
+}
+
+impl FromEntry for LootTable {
+    type Context = ItemAliases;
+
+    fn from_entry(reader: &mut EntryReader, item_aliases: &ItemAliases) -> Option<Self> {
+        let items_str = reader.required::<String>("items")?;
+        let mut items = vec![];
+        for s in items_str.split('|') {
+            match parse_loot_item(s, item_aliases) {
+                Ok(loot_item) => items.push(loot_item),
+                Err(msg) => reader.error("items", &msg),
+            }
+        }
+
+        let rolls = reader.get_or("rolls", vec![1u32]);
+        let (min_rolls, max_rolls) = match rolls[..] {
+            [rolls] => (rolls, rolls),
+            [min_rolls, max_rolls] if min_rolls <= max_rolls => (min_rolls, max_rolls),
+            _ => {
+                reader.error("rolls", "\"rolls\" should be \"amount\" or \"min,max\"");
+                return None;
+            }
+        };
+
+        Some(Self {
+            name: reader.name().to_string(),
+            min_rolls,
+            max_rolls,
+            items,
+        })
+    }
+}
+
+impl LootTable {
+    //Picks a random item from the table
+    fn roll(&self, rng: &mut fastrand::Rng) -> Option<Item> {
+        let total: u32 = self.items.iter().map(|loot_item| loot_item.weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.u32(0..total);
+        for loot_item in &self.items {
+            if roll < loot_item.weight {
+                let amt = rng.u8(loot_item.min_amt..=loot_item.max_amt);
+                return Some(multiply_items(loot_item.item, amt));
+            }
+            roll -= loot_item.weight;
+        }
+        None
+    }
+
+    //Returns a chest that is filled with random items from the table, items
+    //are put in random slots and are dropped if no empty slot can be found
+    pub fn fill_chest(&self, rng: &mut fastrand::Rng) -> TileData {
+        let mut chest = TileData::new_chest();
+        let w = chest.inventory.w();
+        let h = chest.inventory.h();
+        let rolls = rng.u32(self.min_rolls..=self.max_rolls);
+        for _ in 0..rolls {
+            let Some(item) = self.roll(rng) else {
+                continue;
+            };
+
+            for _ in 0..SLOT_TRIES {
+                let x = rng.usize(0..w);
+                let y = rng.usize(0..h);
+                if chest.inventory.get_item(x, y).is_empty() {
+                    chest.inventory.set_item(x, y, item);
+                    break;
+                }
+            }
+        }
+        chest
+    }
+}
+
+pub type LootTables = HashMap<String, LootTable>;
+
+//Reads the loot tables, returns any problems found in the file along with the
+//tables
+pub fn read_loot_tables(path: &str, item_aliases: &ItemAliases) -> (LootTables, Vec<Diagnostic>) {
+    let (tables, diagnostics) = load_file::<LootTable>(path, item_aliases);
+    let tables = tables
+        .into_iter()
+        .map(|table| (table.name.clone(), table))
+        .collect();
+    (tables, diagnostics)
+}
+
+fn load_loot_tables(item_alias_path: &str, loot_table_path: &str) -> LootTables {
+    let item_aliases = load_item_aliases(item_alias_path);
+    let (tables, diagnostics) = read_loot_tables(loot_table_path, &item_aliases);
+    print_diagnostics(&diagnostics);
+    tables
+}
+
+static LOOT_TABLES: OnceLock<LootTables> = OnceLock::new();
+
+//Returns the loot tables, they are loaded the first time this is called
+pub fn loot_tables() -> &'static LootTables {
+    LOOT_TABLES.get_or_init(|| load_loot_tables(ITEM_ALIAS_PATH, LOOT_TABLE_PATH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        impfile::{from_entry::from_entries, parse_str},
+        inventory::item_to_string,
+    };
+
+    fn read_str(s: &str) -> (Vec<LootTable>, Vec<Diagnostic>) {
+        let item_aliases = load_item_aliases(ITEM_ALIAS_PATH);
+        let (entries, _) = parse_str(s, "test");
+        from_entries::<LootTable>(&entries, "test", &item_aliases)
+    }
+
+    #[test]
+    fn test_read_loot_table() {
+        let (tables, diagnostics) = read_str(
+            "\"a\" {\n\t\"rolls\" = \"2,5\";\n\t\"items\" = \"coal/3/1-4|diamond/1/1\";\n}\n",
+        );
+        assert!(diagnostics.is_empty());
+        let table = &tables[0];
+        assert_eq!(table.name, "a");
+        assert_eq!((table.min_rolls, table.max_rolls), (2, 5));
+        assert_eq!(table.items.len(), 2);
+        assert_eq!((table.items[0].min_amt, table.items[0].max_amt), (1, 4));
+        assert_eq!(table.items[1].weight, 1);
+
+        //Every bad item is reported
+        let (_, diagnostics) = read_str(
+            "\"a\" {\n\t\"rolls\" = \"5,2\";\n\t\"items\" = \"coal/3/1\";\n}\n\"b\" {\n\t\"items\" = \"nothing/1/1|coal/x/1|coal/1/0\";\n}\n",
+        );
+        assert_eq!(diagnostics.len(), 4);
+        assert!(diagnostics.iter().all(|d| d.is_error()));
+    }
+
+    #[test]
+    fn test_fill_chest() {
+        let (tables, _) =
+            read_str("\"a\" {\n\t\"rolls\" = \"4\";\n\t\"items\" = \"coal/1/2-3\";\n}\n");
+        let mut rng = fastrand::Rng::with_seed(1);
+        let chest = tables[0].fill_chest(&mut rng);
+        let mut total = 0;
+        let mut items = vec![];
+        for x in 0..chest.inventory.w() {
+            for y in 0..chest.inventory.h() {
+                let item = chest.inventory.get_item(x, y);
+                items.push(item_to_string(item));
+                if item.is_empty() {
+                    continue;
+                }
+                assert_eq!(item_to_string(reduce_amt(item)), "item,2,1");
+                total += item.amt();
+            }
+        }
+        assert!((4..=12).contains(&total));
+
+        //The same seed fills the chest with the same items
+        let chest2 = tables[0].fill_chest(&mut fastrand::Rng::with_seed(1));
+        for (i, item) in items.iter().enumerate() {
+            let x = i / chest2.inventory.h();
+            let y = i % chest2.inventory.h();
+            assert_eq!(&item_to_string(chest2.inventory.get_item(x, y)), item);
+        }
+    }
+
+    #[test]
+    fn test_load_loot_tables() {
+        for name in ["dungeon", "ruins", "village"] {
+            assert!(loot_tables().contains_key(name));
+        }
+    }
+}
@@ -0,0 +1,22 @@
+/*
+ * Everything in the game that does not need a window or OpenGL: blocks,
+ * world generation and simulation, saving/loading and items. This is shared
+ * by the game and by voxelworld-tool.
+ *
+ * This crate must not depend on any windowing or graphics crates, anything
+ * that needs to tell the renderer about changes to the world does so through
+ * queues that the game drains (see World::take_mesh_updates).
+ * */
+
+//A lot of the parsing functions only report that they failed, the caller
+//prints the error
+#![allow(clippy::result_unit_err)]
+
+pub mod bin_data;
+pub mod check_assets;
+pub mod crafting;
+pub mod impfile;
+pub mod inventory;
+pub mod physics;
+pub mod save;
+pub mod voxel;
@@ -1,4 +1,12 @@
 use crate::voxel::{
+    block_ids::{
+        BLUE_FLOWER, BLUE_FLOWER_SEEDS, BLUE_TORCH, BUTTON, COTTON, COTTON_1, COTTON_FLOWERS,
+        COTTON_SEED, DEAD_BUSH, DOOR, DOOR_TOP, FENCE, GATE, GROWING_BLUE_FLOWER,
+        GROWING_RED_FLOWER, GROWING_WHITE_FLOWER, GROWING_YELLOW_FLOWER, INVERTER, LADDER, LEVER,
+        MUSHROOM, PRESSURE_PLATE, RED_FLOWER, RED_FLOWER_SEEDS, SAPLING, SEEDS, SNOWY_SAPLING,
+        SUGARCANE, TALL_GRASS, TORCH, WHEAT, WHEAT_1, WHEAT_3, WHITE_FLOWER, WHITE_FLOWER_SEEDS,
+        YELLOW_FLOWER, YELLOW_FLOWER_SEEDS,
+    },
     orientation_to_normal, rotate_orientation, rotate_orientation_reverse, Block, World,
     EMPTY_BLOCK,
 };
@@ -94,7 +102,7 @@ impl Hitbox {
 
         let hitbox = match block.id {
             //Ladder
-            75 => {
+            LADDER => {
                 let norm = orientation_to_normal(block.orientation());
                 Some(Self::new(
                     fx - norm.x as f32 * 0.3,
@@ -106,11 +114,11 @@ impl Hitbox {
                 ))
             }
             //Fence
-            76 => Some(Self::new(fx, fy + 0.25, fz, 1.0, 1.5, 1.0)),
+            FENCE => Some(Self::new(fx, fy + 0.25, fz, 1.0, 1.5, 1.0)),
             //Gate
-            78 => Some(Self::new(fx, fy + 0.25, fz, 1.0, 1.5, 1.0)),
+            GATE => Some(Self::new(fx, fy + 0.25, fz, 1.0, 1.5, 1.0)),
             //Doors
-            79 | 81 => {
+            DOOR | DOOR_TOP => {
                 let norm = if block.reflection() == 0 {
                     orientation_to_normal(block.orientation())
                 } else {
@@ -181,32 +189,33 @@ impl Hitbox {
         let fz = z as f32 + 0.5;
         match block.id {
             //Ladder
-            75 => Self::from_block_orientation(x, y, z, 0.9, block),
+            LADDER => Self::from_block_orientation(x, y, z, 0.9, block),
             //Seeds (wheat, cotton, or flowers)
-            77 | 98 | 103 | 105 | 107 | 109 => {
+            SEEDS | COTTON_SEED | RED_FLOWER_SEEDS | YELLOW_FLOWER_SEEDS | BLUE_FLOWER_SEEDS
+            | WHITE_FLOWER_SEEDS => {
                 let mut bbox = Self::from_block_orientation(x, y, z, 0.9, block);
                 bbox.dimensions.x *= 0.8;
                 bbox.dimensions.z *= 0.8;
                 bbox
             }
             //Wheat
-            50..=52 => {
-                let sz = 1.0 / 16.0 * 2.0f32.powi(block.id as i32 - 50 + 1);
+            WHEAT_1..=WHEAT_3 => {
+                let sz = 1.0 / 16.0 * 2.0f32.powi(block.id as i32 - WHEAT_1 as i32 + 1);
                 let mut bbox = Self::from_block_orientation(x, y, z, 1.0 - sz, block);
                 bbox.dimensions.x *= 0.8;
                 bbox.dimensions.z *= 0.8;
                 bbox
             }
             //Cotton
-            99..=101 => {
-                let sz = 1.0 / 16.0 * 2.0f32.powi(block.id as i32 - 99 + 1) * 1.5;
+            COTTON_1..=COTTON_FLOWERS => {
+                let sz = 1.0 / 16.0 * 2.0f32.powi(block.id as i32 - COTTON_1 as i32 + 1) * 1.5;
                 let mut bbox = Self::from_block_orientation(x, y, z, 1.0 - sz, block);
                 bbox.dimensions.x *= sz.clamp(0.4, 0.8);
                 bbox.dimensions.z *= sz.clamp(0.4, 0.8);
                 bbox
             }
             //Fully grown wheat, sapling, grass, dead bush, cotton
-            47 | 49 | 53 | 90 | 92 | 102 => {
+            SAPLING | TALL_GRASS | WHEAT | DEAD_BUSH | SNOWY_SAPLING | COTTON => {
                 let sz = 15.0 / 16.0;
                 let mut bbox = Self::from_block_orientation(x, y, z, 1.0 - sz, block);
                 bbox.dimensions.x *= 0.8;
@@ -214,44 +223,68 @@ impl Hitbox {
                 bbox
             }
             //Mushroom, yellow flower, growing yellow flower
-            48 | 55 | 106 => {
+            MUSHROOM | YELLOW_FLOWER | GROWING_YELLOW_FLOWER => {
                 let mut bbox = Self::from_block_orientation(x, y, z, 0.5, block);
                 bbox.dimensions.x *= 0.3;
                 bbox.dimensions.z *= 0.3;
                 bbox
             }
             // Red and blue flower
-            54 | 56 => {
+            RED_FLOWER | BLUE_FLOWER => {
                 let mut bbox = Self::from_block_orientation(x, y, z, 0.2, block);
                 bbox.dimensions.x *= 0.3;
                 bbox.dimensions.z *= 0.3;
                 bbox
             }
             //Growing red and blue flowers
-            104 | 108 => {
+            GROWING_RED_FLOWER | GROWING_BLUE_FLOWER => {
                 let mut bbox = Self::from_block_orientation(x, y, z, 0.4, block);
                 bbox.dimensions.x *= 0.3;
                 bbox.dimensions.z *= 0.3;
                 bbox
             }
             //Growing white flower
-            110 => {
+            GROWING_WHITE_FLOWER => {
                 let mut bbox = Self::from_block_orientation(x, y, z, 0.5, block);
                 bbox.dimensions.x *= 0.5;
                 bbox.dimensions.z *= 0.5;
                 bbox
             }
             //White flower
-            111 => {
+            WHITE_FLOWER => {
                 let mut bbox = Self::from_block_orientation(x, y, z, 0.1, block);
                 bbox.dimensions.x *= 0.5;
                 bbox.dimensions.z *= 0.5;
                 bbox
             }
+            //Lever and button
+            LEVER | BUTTON => {
+                let sz = if block.id == LEVER {
+                    10.0 / 16.0
+                } else {
+                    14.0 / 16.0
+                };
+                let norm = orientation_to_normal(block.orientation());
+                let mut bbox = Self::from_block_orientation(x, y, z, sz, block);
+                if norm.x == 0 {
+                    bbox.dimensions.x *= 0.5;
+                }
+                if norm.y == 0 {
+                    bbox.dimensions.y *= 0.5;
+                }
+                if norm.z == 0 {
+                    bbox.dimensions.z *= 0.5;
+                }
+                bbox
+            }
+            //Pressure plate, wire, repeater, and inverter
+            PRESSURE_PLATE..=INVERTER => {
+                Self::from_block_orientation(x, y, z, 14.0 / 16.0, Block::new())
+            }
             //Sugar cane
-            69 => Self::new(fx, fy, fz, 0.8, 1.0, 0.8),
+            SUGARCANE => Self::new(fx, fy, fz, 0.8, 1.0, 0.8),
             //Torches
-            71..=74 => match block.orientation() {
+            TORCH..=BLUE_TORCH => match block.orientation() {
                 0 | 3 => {
                     let mut bbox = Self::from_block_orientation(x, y, z, 5.5 / 16.0, block);
                     bbox.dimensions.x *= 3.0 / 16.0;
@@ -273,9 +306,9 @@ impl Hitbox {
                 _ => Self::from_block(x, y, z),
             },
             //Fence
-            76 => Self::new(fx, fy, fz, 0.5, 1.0, 0.5),
+            FENCE => Self::new(fx, fy, fz, 0.5, 1.0, 0.5),
             //Gate
-            78 => match block.orientation() {
+            GATE => match block.orientation() {
                 1 | 4 => Self::new(fx, fy, fz, 0.5, 1.0, 1.0),
                 2 | 5 => Self::new(fx, fy, fz, 1.0, 1.0, 0.5),
                 _ => Self::new(fx, fy, fz, 1.0, 1.0, 1.0),
@@ -482,6 +515,77 @@ pub fn get_block_collision(world: &World, hitbox: &Hitbox) -> Option<Hitbox> {
     hit
 }
 
+//Returns how far `hitbox` needs to move in the direction `dir` so that it no
+//longer overlaps `pusher`, None if they do not overlap, `dir` should be one
+//of the six directions that a piston can push in
+pub fn push_distance(hitbox: &Hitbox, pusher: &Hitbox, dir: Vector3<i32>) -> Option<f32> {
+    if !hitbox.intersects(pusher) {
+        return None;
+    }
+
+    let dist = |axis: usize, sign: i32| {
+        let pusher_edge = pusher.position[axis] + pusher.dimensions[axis] / 2.0 * sign as f32;
+        let hitbox_edge = hitbox.position[axis] - hitbox.dimensions[axis] / 2.0 * sign as f32;
+        (pusher_edge - hitbox_edge) * sign as f32
+    };
+
+    let dist = if dir.x != 0 {
+        dist(0, dir.x.signum())
+    } else if dir.y != 0 {
+        dist(1, dir.y.signum())
+    } else if dir.z != 0 {
+        dist(2, dir.z.signum())
+    } else {
+        return None;
+    };
+
+    if dist <= 0.0 {
+        return None;
+    }
+    Some(dist)
+}
+
+//Returns how far `hitbox` can be pushed in the direction `dir` (at most
+//`dist`) before it runs into a block, blocks that are moving or that the
+//hitbox already overlaps do not stop it
+pub fn clamp_push(world: &World, hitbox: &Hitbox, dir: Vector3<i32>, dist: f32) -> f32 {
+    const STEP: f32 = 1.0 / 16.0;
+    let dir = Vector3::new(dir.x as f32, dir.y as f32, dir.z as f32);
+    let blocked = |moved: &Hitbox| {
+        let ix = moved.position.x.floor() as i32;
+        let iy = moved.position.y.floor() as i32;
+        let iz = moved.position.z.floor() as i32;
+        for x in (ix - 2)..=(ix + 2) {
+            for y in (iy - 2)..=(iy + 2) {
+                for z in (iz - 2)..=(iz + 2) {
+                    let block = world.get_block(x, y, z);
+                    if block.id == EMPTY_BLOCK || block.no_hitbox() || world.is_moving(x, y, z) {
+                        continue;
+                    }
+
+                    let composite_hitbox = Hitbox::from_block_data(x, y, z, block);
+                    let block_hitbox = composite_to_hitbox(composite_hitbox, moved);
+                    if moved.intersects(&block_hitbox) && !hitbox.intersects(&block_hitbox) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    };
+
+    let mut allowed = 0.0;
+    while allowed < dist {
+        let next = (allowed + STEP).min(dist);
+        let moved = Hitbox::from_vecs(hitbox.position + dir * next, hitbox.dimensions);
+        if blocked(&moved) {
+            break;
+        }
+        allowed = next;
+    }
+    allowed
+}
+
 pub fn least_dist(pos: Vector3<f32>, hitbox: &Hitbox) -> f32 {
     let maxpoint = hitbox.position + hitbox.dimensions / 2.0;
     let minpoint = hitbox.position - hitbox.dimensions / 2.0;
@@ -509,3 +613,42 @@ pub fn ray_intersects_box(pos: Vector3<f32>, dir: Vector3<f32>, hitbox: &Hitbox)
 
     dist <= 0.01
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_distance() {
+        let pusher = Hitbox::from_block(0, 0, 0);
+        let hitbox = Hitbox::new(1.2, 0.5, 0.5, 0.6, 1.8, 0.6);
+        let dist = push_distance(&hitbox, &pusher, Vector3::new(1, 0, 0)).unwrap();
+        assert!((dist - 0.1).abs() < 0.001);
+        let dist = push_distance(&hitbox, &pusher, Vector3::new(0, 1, 0)).unwrap();
+        assert!((dist - 1.4).abs() < 0.001);
+        let far = Hitbox::new(3.0, 0.5, 0.5, 0.6, 1.8, 0.6);
+        assert!(push_distance(&far, &pusher, Vector3::new(1, 0, 0)).is_none());
+    }
+
+    #[test]
+    fn test_clamp_push() {
+        use crate::voxel::{
+            block_ids::STONE,
+            world::{terrain_generator::FlatGenerator, GameMode},
+        };
+
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        for y in 8..14 {
+            world.set_block(2, y, 0, Block::new_id(STONE));
+        }
+        let hitbox = Hitbox::new(1.2, 11.0, 0.5, 0.6, 1.8, 0.6);
+        let dir = Vector3::new(1, 0, 0);
+        //The wall is 0.5 blocks away, the hitbox stops just short of it
+        let dist = clamp_push(&world, &hitbox, dir, 1.0);
+        assert!(dist > 0.4 && dist <= 0.5);
+        assert!((clamp_push(&world, &hitbox, dir, 0.1) - 0.1).abs() < 0.001);
+        //Nothing is in the way in the other direction
+        let dist = clamp_push(&world, &hitbox, Vector3::new(-1, 0, 0), 1.0);
+        assert!((dist - 1.0).abs() < 0.001);
+    }
+}
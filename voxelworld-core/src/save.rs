@@ -0,0 +1,117 @@
+pub mod backup;
+
+use std::{fs::File, io::Write, path::Path};
+
+pub const SAVE_PATH: &str = "saves/";
+pub const CHUNK_PATH: &str = "chunkdata/";
+pub const SCHEMATICS_PATH: &str = "schematics/";
+//Suffix for the temporary files created while saving
+pub const TEMP_SUFFIX: &str = ".tmp";
+
+//Makes sure a rename in `dir` is on disk, this is not supported on every
+//platform so errors are ignored
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+//Saves a file by writing it to a temporary file, syncing it to the disk and
+//then renaming it over `path`. This way a crash in the middle of saving can
+//never leave behind a truncated file, at worst the old file is kept.
+pub fn save_atomic<F>(path: &str, write: F) -> Result<(), String>
+where
+    F: FnOnce(&mut File) -> std::io::Result<()>,
+{
+    let temp_path = path.to_string() + TEMP_SUFFIX;
+    let res = File::create(&temp_path)
+        .and_then(|mut file| {
+            write(&mut file)?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp_path, path));
+
+    if let Err(msg) = res {
+        //Clean up the temporary file, it might not exist
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(format!("{path}: {msg}"));
+    }
+
+    if let Some(dir) = Path::new(path).parent() {
+        sync_dir(dir);
+    }
+
+    Ok(())
+}
+
+pub fn save_bytes_atomic(path: &str, bytes: &[u8]) -> Result<(), String> {
+    save_atomic(path, |file| file.write_all(bytes))
+}
+
+pub fn create_save_dir() {
+    if Path::new(SAVE_PATH).exists() {
+        return;
+    }
+
+    if let Err(msg) = std::fs::create_dir_all(SAVE_PATH) {
+        eprintln!("{msg}");
+        panic!("Failed to create save directory, killing program.");
+    }
+}
+
+pub fn get_world_path(world_name: &str) -> String {
+    let mut path = SAVE_PATH.to_string() + world_name.to_lowercase().as_str() + "/";
+
+    let mut id = 1i32;
+    while Path::new(&path).exists() {
+        path = SAVE_PATH.to_string()
+            + world_name.to_lowercase().as_str()
+            + "_"
+            + id.to_string().as_str()
+            + "/";
+        id += 1;
+    }
+
+    path
+}
+
+pub fn create_world_dir(world_path: &str) -> Result<(), String> {
+    if Path::new(world_path).exists() {
+        return Err("Path exists".to_string());
+    }
+
+    std::fs::create_dir_all(world_path).map_err(|e| e.to_string())?;
+    let chunk_path = world_path.to_string() + CHUNK_PATH;
+    std::fs::create_dir_all(chunk_path).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_atomic() {
+        let dir = std::env::temp_dir().join(format!("voxelworld_save_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.impfile").to_string_lossy().to_string();
+        save_bytes_atomic(&path, b"first").unwrap();
+        save_bytes_atomic(&path, b"second").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert!(!Path::new(&(path.clone() + TEMP_SUFFIX)).exists());
+
+        //A failed save should leave the original file untouched
+        let res = save_atomic(&path, |file| {
+            file.write_all(b"partial")?;
+            Err(std::io::Error::other("crash"))
+        });
+        assert!(res.is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert!(!Path::new(&(path.clone() + TEMP_SUFFIX)).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,368 @@
+/*
+ * World backups
+ *
+ * Each world gets a directory in BACKUP_PATH with the same name as the world
+ * and every backup is a full copy of the world directory named
+ * backup_<unix timestamp>. Only the newest `max_backups` are kept.
+ * The game backs a world up after it is closed, unless nothing changed since
+ * the newest backup.
+ * */
+
+use super::TEMP_SUFFIX;
+use std::{
+    io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const BACKUP_PATH: &str = "backups/";
+pub const MAX_BACKUPS: usize = 5;
+const BACKUP_PREFIX: &str = "backup_";
+
+pub struct Backup {
+    pub path: String,
+    pub timestamp: u64,
+    //Used to order backups created in the same second
+    id: u32,
+}
+
+impl Backup {
+    pub fn name(&self) -> String {
+        format_timestamp(self.timestamp)
+    }
+}
+
+//Converts days since 1970-01-01 into (year, month, day)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+//Formats a unix timestamp as "YYYY-MM-DD HH:MM:SS UTC"
+pub fn format_timestamp(timestamp: u64) -> String {
+    let secs = timestamp % 86400;
+    let (year, month, day) = civil_from_days((timestamp / 86400) as i64);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+fn world_name(world_path: &str) -> String {
+    Path::new(world_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+//Returns the directory that the backups of a world are stored in
+pub fn get_backup_dir(world_path: &str) -> String {
+    BACKUP_PATH.to_string() + world_name(world_path).as_str() + "/"
+}
+
+//Recursively copies a directory, skipping temporary files left over from
+//saves that did not finish
+fn copy_dir(src: &Path, dst: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)?.flatten() {
+        let name = entry.file_name();
+        if name.to_string_lossy().ends_with(TEMP_SUFFIX) {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &dst.join(&name))?;
+        } else {
+            std::fs::copy(&path, dst.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+//Returns true if both directories have the same files with the same
+//contents, temporary files are ignored
+fn same_contents(a: &Path, b: &Path) -> bool {
+    let list = |dir: &Path| -> io::Result<Vec<(std::ffi::OsString, bool)>> {
+        let mut entries = vec![];
+        for entry in std::fs::read_dir(dir)?.flatten() {
+            let name = entry.file_name();
+            if name.to_string_lossy().ends_with(TEMP_SUFFIX) {
+                continue;
+            }
+            entries.push((name, entry.path().is_dir()));
+        }
+        entries.sort();
+        Ok(entries)
+    };
+
+    let (Ok(entries_a), Ok(entries_b)) = (list(a), list(b)) else {
+        return false;
+    };
+    if entries_a != entries_b {
+        return false;
+    }
+
+    entries_a.iter().all(|(name, is_dir)| {
+        if *is_dir {
+            return same_contents(&a.join(name), &b.join(name));
+        }
+        match (std::fs::read(a.join(name)), std::fs::read(b.join(name))) {
+            (Ok(bytes_a), Ok(bytes_b)) => bytes_a == bytes_b,
+            _ => false,
+        }
+    })
+}
+
+//Returns (timestamp, id)
+fn parse_backup_name(name: &str) -> Option<(u64, u32)> {
+    let name = name.strip_prefix(BACKUP_PREFIX)?;
+    //Backups created in the same second have a number appended to them
+    let mut parts = name.split('_');
+    let timestamp = parts.next()?.parse().ok()?;
+    let id = match parts.next() {
+        Some(id) => id.parse().ok()?,
+        None => 0,
+    };
+    Some((timestamp, id))
+}
+
+//Returns the backups in `backup_dir`, newest first
+pub fn list_backups(backup_dir: &str) -> Vec<Backup> {
+    let mut backups = vec![];
+    if let Ok(dir_contents) = std::fs::read_dir(backup_dir) {
+        for entry in dir_contents.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !entry.path().is_dir() {
+                continue;
+            }
+            if let Some((timestamp, id)) = parse_backup_name(&name) {
+                backups.push(Backup {
+                    path: entry.path().to_string_lossy().to_string() + "/",
+                    timestamp,
+                    id,
+                });
+            }
+        }
+    }
+    backups.sort_by_key(|b| std::cmp::Reverse((b.timestamp, b.id)));
+    backups
+}
+
+//Removes the oldest backups so that only `max_backups` remain
+fn prune_backups(backup_dir: &str, max_backups: usize) {
+    for backup in list_backups(backup_dir).iter().skip(max_backups) {
+        if let Err(msg) = std::fs::remove_dir_all(&backup.path) {
+            eprintln!("E: Failed to remove old backup {}", backup.path);
+            eprintln!("{msg}");
+        }
+    }
+}
+
+//Creates a new backup of the world in `backup_dir`
+pub fn create_backup(
+    world_path: &str,
+    backup_dir: &str,
+    max_backups: usize,
+) -> Result<Backup, String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_secs())
+        .unwrap_or(0);
+
+    let mut path = format!("{backup_dir}{BACKUP_PREFIX}{timestamp}");
+    let mut id = 1;
+    while Path::new(&path).exists() {
+        path = format!("{backup_dir}{BACKUP_PREFIX}{timestamp}_{id}");
+        id += 1;
+    }
+
+    //Copy to a temporary directory first so that an unfinished backup
+    //never shows up in the backup list
+    let temp_path = path.clone() + TEMP_SUFFIX;
+    let res = copy_dir(Path::new(world_path), Path::new(&temp_path))
+        .and_then(|_| std::fs::rename(&temp_path, &path));
+    if let Err(msg) = res {
+        let _ = std::fs::remove_dir_all(&temp_path);
+        return Err(format!("Failed to back up {world_path}: {msg}"));
+    }
+
+    prune_backups(backup_dir, max_backups);
+    eprintln!("Created backup {path}");
+
+    Ok(Backup {
+        path: path + "/",
+        timestamp,
+        id: id - 1,
+    })
+}
+
+//Creates a new backup of the world unless it is the same as the newest
+//backup, returns None if no backup was needed
+pub fn backup_if_changed(
+    world_path: &str,
+    backup_dir: &str,
+    max_backups: usize,
+) -> Result<Option<Backup>, String> {
+    if let Some(newest) = list_backups(backup_dir).first() {
+        if same_contents(Path::new(world_path), Path::new(&newest.path)) {
+            return Ok(None);
+        }
+    }
+    create_backup(world_path, backup_dir, max_backups).map(Some)
+}
+
+//Removes all backups of a world, used when the world is deleted
+pub fn delete_backups(world_path: &str) -> Result<(), String> {
+    let backup_dir = get_backup_dir(world_path);
+    match std::fs::remove_dir_all(&backup_dir) {
+        Ok(()) => Ok(()),
+        Err(msg) if msg.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(msg) => Err(format!("Failed to delete {backup_dir}: {msg}")),
+    }
+}
+
+//Replaces the world with a backup, the current state of the world is backed
+//up first so that restoring can be undone
+pub fn restore_backup(
+    world_path: &str,
+    backup_dir: &str,
+    backup: &Backup,
+    max_backups: usize,
+) -> Result<(), String> {
+    let restoring = backup_dir.to_string() + ".restoring";
+    let replaced = backup_dir.to_string() + ".replaced";
+    //Clean up anything left over from a restore that did not finish
+    let _ = std::fs::remove_dir_all(&restoring);
+    let _ = std::fs::remove_dir_all(&replaced);
+
+    //Copy the backup first since creating a new backup might remove it
+    copy_dir(Path::new(&backup.path), Path::new(&restoring))
+        .map_err(|msg| format!("Failed to copy {}: {msg}", backup.path))?;
+    create_backup(world_path, backup_dir, max_backups)?;
+
+    let world_dir = world_path.trim_end_matches('/');
+    std::fs::rename(world_dir, &replaced)
+        .map_err(|msg| format!("Failed to move {world_dir}: {msg}"))?;
+    if let Err(msg) = std::fs::rename(&restoring, world_dir) {
+        //Put the world back
+        let _ = std::fs::rename(&replaced, world_dir);
+        return Err(format!("Failed to restore {}: {msg}", backup.path));
+    }
+
+    if let Err(msg) = std::fs::remove_dir_all(&replaced) {
+        eprintln!("E: Failed to remove {replaced}");
+        eprintln!("{msg}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("voxelworld_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.to_string_lossy().to_string() + "/"
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_timestamp(1700000000), "2023-11-14 22:13:20 UTC");
+    }
+
+    #[test]
+    fn test_parse_backup_name() {
+        assert_eq!(
+            parse_backup_name("backup_1700000000"),
+            Some((1700000000, 0))
+        );
+        assert_eq!(
+            parse_backup_name("backup_1700000000_2"),
+            Some((1700000000, 2))
+        );
+        assert_eq!(parse_backup_name("backup_1700000000.tmp"), None);
+        assert_eq!(parse_backup_name(".restoring"), None);
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = test_dir("backup");
+        let world_path = dir.clone() + "saves/world/";
+        let backup_dir = dir.clone() + "backups/world/";
+        std::fs::create_dir_all(world_path.clone() + "chunkdata").unwrap();
+        std::fs::write(world_path.clone() + "world.impfile", "old").unwrap();
+        std::fs::write(world_path.clone() + "chunkdata/region_0_0_0", "old").unwrap();
+        //Left over from a crash, should not be backed up
+        std::fs::write(world_path.clone() + "player.impfile.tmp", "").unwrap();
+
+        let backup = create_backup(&world_path, &backup_dir, 2).unwrap();
+        assert!(!Path::new(&(backup.path.clone() + "player.impfile.tmp")).exists());
+        let region = std::fs::read_to_string(backup.path.clone() + "chunkdata/region_0_0_0");
+        assert_eq!(region.unwrap(), "old");
+
+        std::fs::write(world_path.clone() + "world.impfile", "new").unwrap();
+        restore_backup(&world_path, &backup_dir, &backup, 2).unwrap();
+        let world = std::fs::read_to_string(world_path.clone() + "world.impfile");
+        assert_eq!(world.unwrap(), "old");
+
+        //The state before restoring should also have been backed up
+        let backups = list_backups(&backup_dir);
+        assert_eq!(backups.len(), 2);
+        let world = std::fs::read_to_string(backups[0].path.clone() + "world.impfile");
+        assert_eq!(world.unwrap(), "new");
+
+        //Only the newest backups are kept
+        for _ in 0..3 {
+            create_backup(&world_path, &backup_dir, 2).unwrap();
+        }
+        assert_eq!(list_backups(&backup_dir).len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backup_if_changed() {
+        let dir = test_dir("backup_if_changed");
+        let world_path = dir.clone() + "saves/world/";
+        let backup_dir = dir.clone() + "backups/world/";
+        std::fs::create_dir_all(world_path.clone() + "chunkdata").unwrap();
+        std::fs::write(world_path.clone() + "world.impfile", "old").unwrap();
+        std::fs::write(world_path.clone() + "chunkdata/region_0_0_0", "old").unwrap();
+
+        assert!(backup_if_changed(&world_path, &backup_dir, 5)
+            .unwrap()
+            .is_some());
+        //Nothing changed
+        std::fs::write(world_path.clone() + "world.impfile.tmp", "").unwrap();
+        assert!(backup_if_changed(&world_path, &backup_dir, 5)
+            .unwrap()
+            .is_none());
+        assert_eq!(list_backups(&backup_dir).len(), 1);
+
+        std::fs::write(world_path.clone() + "chunkdata/region_0_0_0", "new").unwrap();
+        assert!(backup_if_changed(&world_path, &backup_dir, 5)
+            .unwrap()
+            .is_some());
+        std::fs::write(world_path.clone() + "chunkdata/region_1_0_0", "new").unwrap();
+        assert!(backup_if_changed(&world_path, &backup_dir, 5)
+            .unwrap()
+            .is_some());
+        assert_eq!(list_backups(&backup_dir).len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
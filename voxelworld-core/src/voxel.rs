@@ -1,4 +1,6 @@
+pub mod block_ids;
 pub mod block_info;
+pub mod block_registry;
 pub mod build;
 pub mod chunk;
 pub mod coordinates;
@@ -6,13 +8,16 @@ pub mod flags;
 pub mod is_valid;
 pub mod light;
 pub mod region;
+pub mod schematic;
 pub mod tile_data;
 pub mod world;
 
+use self::block_ids::{GATE, SNOW};
+use self::block_registry::block_registry;
 pub use self::build::{destroy_block, place_block};
 pub use self::coordinates::{out_of_bounds, world_to_chunk_position, wrap_coord, ChunkPos};
 use self::flags::{
-    get_flag, CAN_ROTATE_FLAG, CAN_USE, CONNECT_FLAG, FLAT_ITEM, FLUID, FLUID_DESTRUCTIBLE,
+    CAN_ROTATE_FLAG, CAN_USE, CONNECT_FLAG, FALLS, FLAT_ITEM, FLUID, FLUID_DESTRUCTIBLE, IMMOVABLE,
     NON_VOXEL, NO_HITBOX, OPEN_INVENTORY, REPLACEABLE, ROTATE_Y_ONLY, TRANSPARENT_FLAG,
 };
 use self::light::LightSrc;
@@ -25,8 +30,8 @@ pub const CHUNK_SIZE_I32: i32 = CHUNK_SIZE as i32;
 pub const CHUNK_SIZE_F32: f32 = CHUNK_SIZE as f32;
 pub const CHUNK_LEN: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
 pub const CHUNK_LEN_U32: u32 = CHUNK_LEN as u32;
-pub const EMPTY_BLOCK: u8 = 0;
-pub const INDESTRUCTIBLE: u8 = 3;
+pub const EMPTY_BLOCK: u16 = 0;
+pub const INDESTRUCTIBLE: u16 = 3;
 
 //Block shapes
 pub const FULL_BLOCK: u8 = 0;
@@ -43,7 +48,7 @@ pub enum Axis {
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Block {
     //Block id
-    pub id: u8,
+    pub id: u16,
     //Represents geometry of the block
     //For liquid: 7 = still, 8 = flowing under
     //First 3 bits = shape
@@ -58,6 +63,12 @@ pub struct Block {
     pub geometry: u8,
 }
 
+impl Default for Block {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Block {
     //Create a new empty block
     pub fn new() -> Self {
@@ -65,7 +76,7 @@ impl Block {
     }
 
     //Create a new block with an id
-    pub fn new_id(blockid: u8) -> Self {
+    pub fn new_id(blockid: u16) -> Self {
         Self {
             id: blockid,
             geometry: 0,
@@ -73,7 +84,7 @@ impl Block {
     }
 
     //Create a new fluid block
-    pub fn new_fluid(blockid: u8) -> Self {
+    pub fn new_fluid(blockid: u16) -> Self {
         Self {
             id: blockid,
             geometry: 7,
@@ -81,7 +92,7 @@ impl Block {
     }
 
     //Create a new block with id and orientation
-    pub fn new_id_orientation(blockid: u8, block_orientation: u8) -> Self {
+    pub fn new_id_orientation(blockid: u16, block_orientation: u8) -> Self {
         Self {
             id: blockid,
             geometry: block_orientation,
@@ -125,106 +136,97 @@ impl Block {
 
     //Returns if the block is transparent
     pub fn transparent(&self) -> bool {
-        get_flag(self.id) & TRANSPARENT_FLAG != 0
+        block_registry().has_flag(self.id, TRANSPARENT_FLAG)
     }
 
     //Returns if the block can "connect" to the block next to it
     //(will not display any face if the block is transparent and the same
     //block is next to it)
     pub fn can_connect(&self) -> bool {
-        get_flag(self.id) & CONNECT_FLAG != 0
+        block_registry().has_flag(self.id, CONNECT_FLAG)
     }
 
     //Returns if the block can be rotated when placed
     pub fn can_rotate(&self) -> bool {
-        get_flag(self.id) & CAN_ROTATE_FLAG != 0
+        block_registry().has_flag(self.id, CAN_ROTATE_FLAG)
     }
 
     //Returns if the voxel has no hitbox
     pub fn no_hitbox(&self) -> bool {
         //Open gate
-        if self.id == 78 && self.reflection() == 1 {
+        if self.id == GATE && self.reflection() == 1 {
             return true;
         }
-        get_flag(self.id) & NO_HITBOX != 0
+        block_registry().has_flag(self.id, NO_HITBOX)
     }
 
     //Returns if the voxel is a fluid
     pub fn is_fluid(&self) -> bool {
-        get_flag(self.id) & FLUID != 0
+        block_registry().has_flag(self.id, FLUID)
     }
 
     //Returns if the voxel can rotate only about the y axis
     pub fn rotate_y_only(&self) -> bool {
-        get_flag(self.id) & ROTATE_Y_ONLY != 0
+        block_registry().has_flag(self.id, ROTATE_Y_ONLY)
     }
 
     //Returns if the voxel must be displayed as a flat item
     pub fn is_flat_item(&self) -> bool {
-        get_flag(self.id) & FLAT_ITEM != 0
+        block_registry().has_flag(self.id, FLAT_ITEM)
     }
 
     //Returns if the voxel can be destroyed by fluid
     pub fn fluid_destructibe(&self) -> bool {
         //Snow bottom slab
-        if self.id == 86 && self.shape() == SLAB && self.orientation() == 0 {
+        if self.id == SNOW && self.shape() == SLAB && self.orientation() == 0 {
             return true;
         }
-        get_flag(self.id) & FLUID_DESTRUCTIBLE != 0
+        block_registry().has_flag(self.id, FLUID_DESTRUCTIBLE)
     }
 
     //Returns if the voxel has a non-voxel geometry and needs to be handled uniquely
     pub fn non_voxel_geometry(&self) -> bool {
-        get_flag(self.id) & NON_VOXEL != 0
+        block_registry().has_flag(self.id, NON_VOXEL)
     }
 
     //Returns if the voxel can by replaced by player placing a block
     pub fn replaceable(&self) -> bool {
         //Snow bottom slab is replaceable
-        if self.id == 86 && self.shape() == SLAB && self.orientation() == 0 {
+        if self.id == SNOW && self.shape() == SLAB && self.orientation() == 0 {
             return true;
         }
-        get_flag(self.id) & REPLACEABLE != 0
+        block_registry().has_flag(self.id, REPLACEABLE)
     }
 
     //Returns if the voxel can be used (right clicked on)
     pub fn can_use(&self) -> bool {
-        get_flag(self.id) & CAN_USE != 0
+        block_registry().has_flag(self.id, CAN_USE)
+    }
+
+    //Returns if the voxel can not be moved by pistons
+    pub fn immovable(&self) -> bool {
+        block_registry().has_flag(self.id, IMMOVABLE)
+    }
+
+    //Returns if the voxel falls when there is nothing under it
+    pub fn falls(&self) -> bool {
+        if self.shape() != FULL_BLOCK {
+            return false;
+        }
+        block_registry().has_flag(self.id, FALLS)
     }
 
     pub fn open_inventory(&self) -> bool {
         if self.shape() != FULL_BLOCK {
             return false;
         }
-        get_flag(self.id) & OPEN_INVENTORY != 0
+        block_registry().has_flag(self.id, OPEN_INVENTORY)
     }
 
     //Returns Some(light_level) if the block emits light
     //Returns None otherwise
     pub fn light_src(&self) -> Option<LightSrc> {
-        match self.id {
-            //Lava
-            13 => Some(LightSrc::new(15, 8, 0)),
-            //Red ore
-            23 => Some(LightSrc::new(6, 0, 0)),
-            //Lit Furnace
-            70 => Some(LightSrc::new(15, 12, 0)),
-            //Normal Torch
-            71 => Some(LightSrc::new(15, 15, 15)),
-            //Red torch
-            72 => Some(LightSrc::new(15, 0, 0)),
-            //Green torch
-            73 => Some(LightSrc::new(0, 15, 0)),
-            //Blue torch
-            74 => Some(LightSrc::new(0, 3, 15)),
-            //Uranium
-            94 => Some(LightSrc::new(0, 8, 0)),
-            //Uranium block
-            95 => Some(LightSrc::new(0, 12, 0)),
-            //White flower
-            111 => Some(LightSrc::new(4, 4, 4)),
-            _ => None,
-        }
+        block_registry().light_src(self.id)
     }
 }
 
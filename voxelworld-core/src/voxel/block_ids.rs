@@ -0,0 +1,175 @@
+/*
+ * Ids of blocks that have behavior that is hard coded in the game (plant
+ * growth, fluids, doors, etc.). Everything else about these blocks is loaded
+ * from the block registry, which must keep these blocks at the same ids.
+ * */
+
+pub const GRASS: u16 = 1;
+pub const STONE: u16 = 2;
+pub const DIRT: u16 = 4;
+pub const BRICKS: u16 = 5;
+pub const PLANK: u16 = 6;
+pub const LEAVES: u16 = 7;
+pub const LOG: u16 = 8;
+pub const GLASS: u16 = 9;
+pub const STONE_BRICKS: u16 = 10;
+pub const SAND: u16 = 11;
+pub const WATER: u16 = 12;
+pub const LAVA: u16 = 13;
+pub const OBSIDIAN: u16 = 14;
+pub const MAGMA_STONE: u16 = 15;
+pub const COBBLESTONE: u16 = 16;
+pub const MOSS: u16 = 17;
+pub const COAL_ORE: u16 = 18;
+pub const IRON_ORE: u16 = 19;
+pub const GOLD_ORE: u16 = 20;
+pub const DIAMOND_ORE: u16 = 21;
+pub const RAINBOW_ORE: u16 = 22;
+pub const RED_ORE: u16 = 23;
+pub const SAND_BRICKS: u16 = 32;
+pub const CHEST: u16 = 37;
+pub const FURNACE: u16 = 40;
+pub const WET_FARMLAND: u16 = 43;
+pub const DRY_FARMLAND: u16 = 45;
+pub const SAPLING: u16 = 47;
+pub const MUSHROOM: u16 = 48;
+pub const TALL_GRASS: u16 = 49;
+pub const WHEAT_1: u16 = 50;
+pub const WHEAT_3: u16 = 52;
+pub const WHEAT: u16 = 53;
+pub const RED_FLOWER: u16 = 54;
+pub const YELLOW_FLOWER: u16 = 55;
+pub const BLUE_FLOWER: u16 = 56;
+pub const SUGARCANE: u16 = 69;
+pub const LIT_FURNACE: u16 = 70;
+pub const TORCH: u16 = 71;
+pub const BLUE_TORCH: u16 = 74;
+pub const LADDER: u16 = 75;
+pub const FENCE: u16 = 76;
+pub const SEEDS: u16 = 77;
+pub const GATE: u16 = 78;
+pub const DOOR: u16 = 79;
+pub const DOOR_TOP: u16 = 81;
+pub const ICE: u16 = 85;
+pub const SNOW: u16 = 86;
+pub const SNOWY_GRASS: u16 = 87;
+pub const CACTUS: u16 = 88;
+pub const DEAD_BUSH: u16 = 90;
+pub const SNOWY_LEAVES: u16 = 91;
+pub const SNOWY_SAPLING: u16 = 92;
+pub const CLAY: u16 = 93;
+pub const URANIUM_ORE: u16 = 94;
+pub const AQUA_ORE: u16 = 96;
+pub const COTTON_SEED: u16 = 98;
+pub const COTTON_1: u16 = 99;
+pub const COTTON_FLOWERS: u16 = 101;
+pub const COTTON: u16 = 102;
+pub const RED_FLOWER_SEEDS: u16 = 103;
+pub const GROWING_RED_FLOWER: u16 = 104;
+pub const YELLOW_FLOWER_SEEDS: u16 = 105;
+pub const GROWING_YELLOW_FLOWER: u16 = 106;
+pub const BLUE_FLOWER_SEEDS: u16 = 107;
+pub const GROWING_BLUE_FLOWER: u16 = 108;
+pub const WHITE_FLOWER_SEEDS: u16 = 109;
+pub const GROWING_WHITE_FLOWER: u16 = 110;
+pub const WHITE_FLOWER: u16 = 111;
+pub const UNDERWORLD_PORTAL: u16 = 112;
+pub const SKY_PORTAL: u16 = 113;
+pub const LEVER: u16 = 114;
+pub const BUTTON: u16 = 115;
+pub const PRESSURE_PLATE: u16 = 116;
+pub const WIRE: u16 = 117;
+pub const REPEATER: u16 = 118;
+pub const INVERTER: u16 = 119;
+pub const LAMP: u16 = 120;
+pub const LIT_LAMP: u16 = 121;
+pub const PISTON: u16 = 122;
+pub const STICKY_PISTON: u16 = 123;
+pub const PISTON_HEAD: u16 = 124;
+
+//(name in the block registry, id)
+pub const BUILTIN_BLOCKS: &[(&str, u16)] = &[
+    ("grass", GRASS),
+    ("stone", STONE),
+    ("dirt", DIRT),
+    ("bricks", BRICKS),
+    ("plank", PLANK),
+    ("leaves", LEAVES),
+    ("log", LOG),
+    ("glass", GLASS),
+    ("stone_bricks", STONE_BRICKS),
+    ("sand", SAND),
+    ("water", WATER),
+    ("lava", LAVA),
+    ("obsidian", OBSIDIAN),
+    ("magma_stone", MAGMA_STONE),
+    ("cobblestone", COBBLESTONE),
+    ("moss", MOSS),
+    ("coal_ore", COAL_ORE),
+    ("iron_ore", IRON_ORE),
+    ("gold_ore", GOLD_ORE),
+    ("diamond_ore", DIAMOND_ORE),
+    ("rainbow_ore", RAINBOW_ORE),
+    ("red_ore", RED_ORE),
+    ("sand_bricks", SAND_BRICKS),
+    ("chest", CHEST),
+    ("furnace", FURNACE),
+    ("wet_farmland", WET_FARMLAND),
+    ("dry_farmland", DRY_FARMLAND),
+    ("sapling", SAPLING),
+    ("mushroom", MUSHROOM),
+    ("tall_grass", TALL_GRASS),
+    ("wheat_1", WHEAT_1),
+    ("wheat_3", WHEAT_3),
+    ("wheat", WHEAT),
+    ("red_flower", RED_FLOWER),
+    ("yellow_flower", YELLOW_FLOWER),
+    ("blue_flower", BLUE_FLOWER),
+    ("sugarcane", SUGARCANE),
+    ("lit_furnace", LIT_FURNACE),
+    ("torch", TORCH),
+    ("blue_torch", BLUE_TORCH),
+    ("ladder", LADDER),
+    ("fence", FENCE),
+    ("seeds", SEEDS),
+    ("gate", GATE),
+    ("door", DOOR),
+    ("door_top", DOOR_TOP),
+    ("ice", ICE),
+    ("snow", SNOW),
+    ("snowy_grass", SNOWY_GRASS),
+    ("cactus", CACTUS),
+    ("dead_bush", DEAD_BUSH),
+    ("snowy_leaves", SNOWY_LEAVES),
+    ("snowy_sapling", SNOWY_SAPLING),
+    ("clay", CLAY),
+    ("uranium_ore", URANIUM_ORE),
+    ("aqua_ore", AQUA_ORE),
+    ("cotton_seed", COTTON_SEED),
+    ("cotton_1", COTTON_1),
+    ("cotton_flowers", COTTON_FLOWERS),
+    ("cotton", COTTON),
+    ("red_flower_seeds", RED_FLOWER_SEEDS),
+    ("growing_red_flower", GROWING_RED_FLOWER),
+    ("yellow_flower_seeds", YELLOW_FLOWER_SEEDS),
+    ("growing_yellow_flower", GROWING_YELLOW_FLOWER),
+    ("blue_flower_seeds", BLUE_FLOWER_SEEDS),
+    ("growing_blue_flower", GROWING_BLUE_FLOWER),
+    ("white_flower_seeds", WHITE_FLOWER_SEEDS),
+    ("growing_white_flower", GROWING_WHITE_FLOWER),
+    ("white_flower", WHITE_FLOWER),
+    ("underworld_portal", UNDERWORLD_PORTAL),
+    ("sky_portal", SKY_PORTAL),
+    ("lever", LEVER),
+    ("button", BUTTON),
+    ("pressure_plate", PRESSURE_PLATE),
+    ("wire", WIRE),
+    ("repeater", REPEATER),
+    ("inverter", INVERTER),
+    ("lamp", LAMP),
+    ("lit_lamp", LIT_LAMP),
+    ("piston", PISTON),
+    ("sticky_piston", STICKY_PISTON),
+    ("piston_head", PISTON_HEAD),
+    ("bedrock", super::INDESTRUCTIBLE),
+];
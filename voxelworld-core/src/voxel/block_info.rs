@@ -1,19 +1,11 @@
-use super::{Block, EMPTY_BLOCK, FULL_BLOCK};
+use super::{block_registry::block_registry, Block, FULL_BLOCK};
 use crate::{
-    game::{
-        crafting::{load_item_aliases, ItemAliases},
-        inventory::{
-            item_to_string, reduce_amt, string_to_item_err,
-            tools::{string_to_tool_type, ToolType},
-            Item,
-        },
-    },
-    impfile::{self, Entry},
+    crafting::ItemAliases,
+    impfile::EntryReader,
+    inventory::{item_to_string, reduce_amt, string_to_item_err, tools::ToolType, Item},
 };
 use std::collections::HashMap;
 
-pub type BlockInfoTable = HashMap<u8, BlockInfo>;
-
 #[derive(Clone)]
 pub struct BlockDrop {
     //item that will be dropped
@@ -137,13 +129,7 @@ impl BlockInfo {
         };
 
         let multiplier = match held_item {
-            Item::Tool(_, info) => {
-                if Some(info.tool_type) == self.preferred_tool {
-                    info.speed
-                } else {
-                    1.0
-                }
-            }
+            Item::Tool(_, info) if Some(info.tool_type) == self.preferred_tool => info.speed,
             _ => 1.0,
         };
 
@@ -165,82 +151,24 @@ impl BlockInfo {
     }
 }
 
-fn update_info<T>(table: &mut BlockInfoTable, id: u8, update_fn: T)
-where
-    T: Fn(&mut BlockInfo),
-{
-    if let Some(info) = table.get_mut(&id) {
-        update_fn(info);
-    } else {
-        let mut info = BlockInfo::default();
-        update_fn(&mut info);
-        table.insert(id, info);
-    }
-}
-
-//Updates block info based on a list of ids
-fn update_info_list<T>(table: &mut BlockInfoTable, ids: &[u8], update_fn: T)
-where
-    T: Fn(&mut BlockInfo),
-{
-    for id in ids.iter().copied() {
-        update_info(table, id, &update_fn);
-    }
-}
-
-fn parse_block_list(val: &str, item_aliases: &ItemAliases) -> Vec<u8> {
-    val.split(",")
-        .map(|s| parse_block_id(s, item_aliases))
-        .filter_map(|b| b.ok())
-        .collect()
-}
-
-fn update_break_time(entry: &Entry, table: &mut BlockInfoTable) {
-    let alias_path = entry.get_var("alias_path");
-    let item_aliases = if !alias_path.is_empty() {
-        load_item_aliases(&alias_path)
-    } else {
-        HashMap::new()
-    };
-
-    let vars = entry.get_all_vars();
-    for (name, val) in vars {
-        let break_time = name.parse::<f32>();
-        if let Ok(break_time) = break_time {
-            let blocks = parse_block_list(&val, &item_aliases);
-            update_info_list(table, &blocks, |info| {
-                info.break_time = break_time;
-            })
-        }
-    }
-}
-
-fn parse_item_str_aliased(s: &str, item_aliases: &ItemAliases) -> Result<Item, ()> {
+fn parse_item_str_aliased(s: &str, item_aliases: &ItemAliases) -> Result<Item, String> {
     //Prioritize item alias
     if let Some(item) = item_aliases.get(s) {
         return Ok(*item);
     }
-    string_to_item_err(s)
+    string_to_item_err(s).map_err(|_| format!("unknown item \"{s}\""))
 }
 
-fn parse_block_id(s: &str, item_aliases: &ItemAliases) -> Result<u8, ()> {
-    if let Ok(item) = parse_item_str_aliased(s, item_aliases) {
-        return match item {
-            Item::Block(block, _) => Ok(block.id),
-            _ => Err(()),
-        };
-    }
-    s.parse::<u8>().map_err(|_| ())
-}
-
-fn parse_weight(s: &str, item_aliases: &ItemAliases) -> Result<(Item, f32), ()> {
+fn parse_weight(s: &str, item_aliases: &ItemAliases) -> Result<(Item, f32), String> {
     let data: Vec<String> = s.split("/").map(|s| s.to_string()).collect();
     //data must only have 2 components (item and weight)
     if data.len() != 2 {
-        return Err(());
+        return Err(format!("\"{s}\" should be item/weight"));
     }
     let block_drop = parse_item_str_aliased(&data[0], item_aliases)?;
-    let weight = data[1].parse::<f32>().map_err(|_| ())?;
+    let weight = data[1]
+        .parse::<f32>()
+        .map_err(|_| format!("invalid weight \"{}\"", data[1]))?;
     Ok((block_drop, weight))
 }
 
@@ -248,7 +176,7 @@ fn parse_drops(
     held_str: &str,
     drop_list: &str,
     item_aliases: &ItemAliases,
-) -> Result<(Vec<String>, WeightTable), ()> {
+) -> Result<(Vec<String>, WeightTable), String> {
     let pickaxes = [
         "wood_pickaxe".to_string(),
         "stone_pickaxe".to_string(),
@@ -268,106 +196,53 @@ fn parse_drops(
             "diamond+" => pickaxes[4..].to_vec(),
             _ => vec![s.to_string()],
         })
-        .map(|s| parse_item_str_aliased(&s, item_aliases))
-        .filter_map(|parsed| parsed.ok())
-        .map(item_to_string)
-        .collect();
+        .map(|s| parse_item_str_aliased(&s, item_aliases).map(item_to_string))
+        .collect::<Result<_, _>>()?;
     let weight_table: WeightTable = drop_list
         .split("|")
-        .map(|s| parse_weight(s, item_aliases))
-        .filter_map(|weight| weight.ok())
-        .map(|(i, w)| BlockDrop { item: i, weight: w })
-        .collect();
+        .map(|s| parse_weight(s, item_aliases).map(|(i, w)| BlockDrop { item: i, weight: w }))
+        .collect::<Result<_, _>>()?;
     Ok((held, weight_table))
 }
 
-fn update_block_drops(entry: &Entry, table: &mut BlockInfoTable) {
-    let path = entry.get_var("path");
-    if path.is_empty() {
-        return;
-    }
-    let block_drops = impfile::parse_file(&path);
-    let alias_path = entry.get_var("alias_path");
-    if alias_path.is_empty() {
-        return;
-    }
-    let item_aliases = load_item_aliases(&alias_path);
-    for e in block_drops {
-        let block_id = parse_block_id(&e.get_name(), &item_aliases).unwrap_or(EMPTY_BLOCK);
-        //If it wasn't parsed, ignore it
-        //This should be the case if it is an empty block
-        if block_id == EMPTY_BLOCK {
-            continue;
-        }
+//Prefix of the variables in a block registry entry that list the drops of
+//the block, the rest of the variable name is the held item
+const DROP_PREFIX: &str = "drop:";
+
+//Returns None if the entry does not have any drops
+pub fn load_block_drops(
+    reader: &mut EntryReader,
+    item_aliases: &ItemAliases,
+) -> Option<HashMap<String, WeightTable>> {
+    let mut block_drops = HashMap::new();
+    for (name, val) in reader.vars_with_prefix(DROP_PREFIX) {
+        let held_str = &name[DROP_PREFIX.len()..];
         //Parse the drops based on the item the player is holding
-        let drops: Vec<(Vec<String>, WeightTable)> = e
-            .get_all_vars()
-            .iter()
-            .map(|(name, val)| parse_drops(name, val, &item_aliases))
-            .filter_map(|drops| drops.ok())
-            .collect();
-        for (held_items, weights) in drops {
-            update_info_list(table, &[block_id], |info| {
-                if let Some(block_drops) = &mut info.block_drops {
-                    for held in &held_items {
-                        block_drops.insert(held.clone(), weights.clone());
-                    }
-                } else {
-                    let mut block_drops = HashMap::new();
-                    for held in &held_items {
-                        block_drops.insert(held.clone(), weights.clone());
-                    }
-                    info.block_drops = Some(block_drops);
+        match parse_drops(held_str, &val, item_aliases) {
+            Ok((held_items, weights)) => {
+                for held in held_items {
+                    block_drops.insert(held, weights.clone());
                 }
-            });
+            }
+            Err(msg) => reader.error(&name, &msg),
         }
     }
-}
 
-fn update_preferred_tool(entry: &Entry, table: &mut BlockInfoTable) {
-    let alias_path = entry.get_var("alias_path");
-    let item_aliases = if !alias_path.is_empty() {
-        load_item_aliases(&alias_path)
+    if block_drops.is_empty() {
+        None
     } else {
-        HashMap::new()
-    };
-    for (blocks_str, tool_str) in entry.get_all_vars() {
-        let tool = string_to_tool_type(&tool_str);
-        if let Ok(tool) = tool {
-            let blocks = parse_block_list(&blocks_str, &item_aliases);
-            update_info_list(table, &blocks, |info| {
-                info.preferred_tool = Some(tool);
-            });
-        }
+        Some(block_drops)
     }
 }
 
-//id -> block info
-pub fn load_block_info(path: &str) -> BlockInfoTable {
-    let mut table = BlockInfoTable::new();
-
-    let entries = impfile::parse_file(path);
-
-    for e in entries {
-        match e.get_name().as_str() {
-            "break_time" => update_break_time(&e, &mut table),
-            "drops" => update_block_drops(&e, &mut table),
-            "tools" => update_preferred_tool(&e, &mut table),
-            _ => {}
-        }
-    }
-
-    table
-}
-
-pub fn get_drop(table: &BlockInfoTable, held_item: Item, block: Block) -> Item {
+pub fn get_drop(held_item: Item, block: Block) -> Item {
     //If it's a nonsolid block, then simply have it drop itself,
     //regardless of tool used
     if block.shape() != FULL_BLOCK {
         return block_to_item(block);
     }
 
-    match table.get(&block.id) {
+    match block_registry().info(block.id) {
         Some(info) => info.get_drop_item(held_item, block),
         _ => block_to_item(block),
     }
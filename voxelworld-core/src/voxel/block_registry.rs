@@ -0,0 +1,486 @@
+/*
+ * Block registry
+ *
+ * Stores everything about a block that is not hard coded: its name, flags,
+ * light, break time, preferred tool, drops, and how its faces are textured.
+ * The registry is loaded from BLOCK_REGISTRY_PATH the first time it is used,
+ * see that file for the format of each block entry.
+ * */
+
+use super::{
+    block_ids::BUILTIN_BLOCKS,
+    block_info::{load_block_drops, BlockInfo},
+    flags::flag_from_name,
+    light::LightSrc,
+    Block, EMPTY_BLOCK,
+};
+use crate::{
+    crafting::{parse_item_alias_file, ItemAliases},
+    impfile::{self, from_entry::print_diagnostics, Diagnostic, Entry, EntryReader},
+    inventory::{tools::string_to_tool_type, Item},
+};
+use std::{
+    collections::{hash_map, HashMap},
+    sync::OnceLock,
+};
+
+#[cfg(not(test))]
+pub const BLOCK_REGISTRY_PATH: &str = "assets/blocks.impfile";
+#[cfg(not(test))]
+pub const ITEM_ALIAS_PATH: &str = "assets/item_aliases.impfile";
+//Tests are run from the voxelworld-core directory
+#[cfg(test)]
+pub const BLOCK_REGISTRY_PATH: &str = "../assets/blocks.impfile";
+#[cfg(test)]
+pub const ITEM_ALIAS_PATH: &str = "../assets/item_aliases.impfile";
+//Entry in the registry file that defines groups of flags
+const FLAG_GROUPS: &str = "flag_groups";
+
+//How the faces of a block are textured, each value is an index into the
+//block texture atlas
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BlockModel {
+    //Every face uses the texture at the same index as the block id
+    #[default]
+    Cube,
+    //The sides use the block texture (or side_half for the sides of slabs)
+    Grass {
+        top: u8,
+        bottom: u8,
+        side_half: u8,
+    },
+    //The sides use the block texture and the ends use `end`
+    Log {
+        end: u8,
+        side_rotated: u8,
+    },
+    //The front uses the block texture
+    Furnace {
+        side: u8,
+        top: u8,
+    },
+    //The front uses the block texture and `open` is used for the front
+    //while the piston is extended
+    Piston {
+        side: u8,
+        back: u8,
+        open: u8,
+    },
+    //Transparent block with special textures for the sides of slabs
+    Glass {
+        slab_side1: u8,
+        slab_side2: u8,
+    },
+    Plant,
+}
+
+impl BlockModel {
+    fn parse(model: &str, textures: &str) -> Result<Self, String> {
+        let textures: Vec<u8> = if textures.is_empty() {
+            vec![]
+        } else {
+            textures
+                .split(',')
+                .map(|t| t.trim().parse::<u8>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("invalid textures \"{textures}\""))?
+        };
+
+        let expected = match model {
+            "" | "cube" | "plant" => 0,
+            "log" | "furnace" | "glass" => 2,
+            "grass" | "piston" => 3,
+            _ => return Err(format!("unknown model \"{model}\"")),
+        };
+        if textures.len() != expected {
+            return Err(format!(
+                "model \"{model}\" needs {expected} textures, found {}",
+                textures.len()
+            ));
+        }
+
+        Ok(match model {
+            "grass" => Self::Grass {
+                top: textures[0],
+                bottom: textures[1],
+                side_half: textures[2],
+            },
+            "log" => Self::Log {
+                end: textures[0],
+                side_rotated: textures[1],
+            },
+            "furnace" => Self::Furnace {
+                side: textures[0],
+                top: textures[1],
+            },
+            "piston" => Self::Piston {
+                side: textures[0],
+                back: textures[1],
+                open: textures[2],
+            },
+            "glass" => Self::Glass {
+                slab_side1: textures[0],
+                slab_side2: textures[1],
+            },
+            "plant" => Self::Plant,
+            _ => Self::Cube,
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct BlockDef {
+    pub name: String,
+    pub id: u16,
+    pub flags: u16,
+    pub light: Option<LightSrc>,
+    //Break time, preferred tool and drops
+    pub info: BlockInfo,
+    pub model: BlockModel,
+    //Hidden blocks do not get an item alias (growing plants, top of doors)
+    pub hidden: bool,
+}
+
+#[derive(Default)]
+pub struct BlockRegistry {
+    blocks: HashMap<u16, BlockDef>,
+    ids: HashMap<String, u16>,
+    //Indexed by block id, looking up flags needs to be fast since it is done
+    //for every block when building chunk meshes
+    flags: Vec<u16>,
+}
+
+fn parse_flags(flags_str: &str, groups: &HashMap<String, u16>) -> Result<u16, String> {
+    let mut flags = 0;
+    for name in flags_str.split(',').map(|s| s.trim()) {
+        if name.is_empty() {
+            continue;
+        }
+        let flag = flag_from_name(name)
+            .or_else(|| groups.get(name).copied())
+            .ok_or_else(|| format!("unknown flag \"{name}\""))?;
+        flags |= flag;
+    }
+    Ok(flags)
+}
+
+fn parse_light(light_str: &str) -> Result<Option<LightSrc>, String> {
+    if light_str.is_empty() {
+        return Ok(None);
+    }
+    let vals: Vec<u16> = light_str
+        .split(',')
+        .map(|s| s.trim().parse::<u16>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("invalid light \"{light_str}\""))?;
+    match vals[..] {
+        [r, g, b] if r <= 15 && g <= 15 && b <= 15 => Ok(Some(LightSrc::new(r, g, b))),
+        _ => Err(format!("invalid light \"{light_str}\"")),
+    }
+}
+
+//Parses everything except for the drops (those need the names of all of the
+//blocks to be known first)
+fn parse_block(reader: &mut EntryReader, groups: &HashMap<String, u16>) -> Option<BlockDef> {
+    let id: u16 = reader.required("id")?;
+    if id == EMPTY_BLOCK {
+        reader.error(
+            "id",
+            &format!("id {EMPTY_BLOCK} is reserved for empty blocks"),
+        );
+        return None;
+    }
+
+    let break_time = reader.get_or("break_time", 0.0);
+
+    let preferred_tool = match reader.get_str("tool") {
+        Some(tool_str) => match string_to_tool_type(tool_str) {
+            Ok(tool) => Some(tool),
+            Err(_) => {
+                reader.error("tool", &format!("invalid tool \"{tool_str}\""));
+                None
+            }
+        },
+        None => None,
+    };
+
+    let flags_str = reader.get_str("flags").unwrap_or("");
+    let flags = parse_flags(flags_str, groups)
+        .map_err(|msg| reader.error("flags", &msg))
+        .ok()?;
+    let light = parse_light(reader.get_str("light").unwrap_or(""))
+        .map_err(|msg| reader.error("light", &msg))
+        .ok()?;
+    let model_str = reader.get_str("model").unwrap_or("");
+    let textures_str = reader.get_str("textures").unwrap_or("");
+    let model = BlockModel::parse(model_str, textures_str)
+        .map_err(|msg| reader.error("model", &msg))
+        .ok()?;
+
+    Some(BlockDef {
+        name: reader.name().to_string(),
+        id,
+        flags,
+        light,
+        info: BlockInfo {
+            break_time,
+            preferred_tool,
+            block_drops: None,
+        },
+        model,
+        hidden: reader.get_or("hidden", false),
+    })
+}
+
+impl BlockRegistry {
+    //`item_aliases` is used to parse the drops of each block, `path` is only
+    //used for error messages
+    pub fn from_entries(
+        entries: &[Entry],
+        path: &str,
+        item_aliases: &ItemAliases,
+    ) -> (Self, Vec<Diagnostic>) {
+        let mut diagnostics = vec![];
+        let mut groups = HashMap::new();
+        for entry in entries.iter().filter(|e| e.get_name() == FLAG_GROUPS) {
+            let mut reader = EntryReader::new(entry, path);
+            for (name, flags_str) in reader.all_vars() {
+                match parse_flags(&flags_str, &HashMap::new()) {
+                    Ok(flags) => {
+                        groups.insert(name, flags);
+                    }
+                    Err(msg) => reader.error(&name, &format!("flag group {name}: {msg}")),
+                }
+            }
+            diagnostics.extend(reader.finish());
+        }
+
+        let mut registry = Self::default();
+        let mut block_readers = vec![];
+        for entry in entries.iter().filter(|e| e.get_name() != FLAG_GROUPS) {
+            let mut reader = EntryReader::new(entry, path);
+            let name = entry.get_name();
+            let Some(block) = parse_block(&mut reader, &groups) else {
+                //Mark everything as read so that only the error is reported
+                reader.all_vars();
+                diagnostics.extend(reader.finish());
+                continue;
+            };
+
+            let error = if let Some(other) = registry.blocks.get(&block.id) {
+                format!("id {} is already used by {}", block.id, other.name)
+            } else if let hash_map::Entry::Vacant(slot) = registry.ids.entry(name) {
+                slot.insert(block.id);
+                block_readers.push((block.id, reader));
+                registry.blocks.insert(block.id, block);
+                continue;
+            } else {
+                "block is defined more than once".to_string()
+            };
+            reader.error("id", &error);
+            reader.all_vars();
+            diagnostics.extend(reader.finish());
+        }
+
+        //Blocks can drop other blocks so these can only be parsed once all of
+        //the block names are known
+        let mut aliases = item_aliases.clone();
+        aliases.extend(registry.block_aliases(true));
+        for (id, mut reader) in block_readers {
+            let block_drops = load_block_drops(&mut reader, &aliases);
+            if let Some(block) = registry.blocks.get_mut(&id) {
+                block.info.block_drops = block_drops;
+            }
+            diagnostics.extend(reader.finish());
+        }
+
+        let max_id = registry.blocks.keys().copied().max().unwrap_or(0);
+        registry.flags = vec![0; max_id as usize + 1];
+        for block in registry.blocks.values() {
+            registry.flags[block.id as usize] = block.flags;
+        }
+
+        for (name, id) in BUILTIN_BLOCKS {
+            if registry.get_id(name) != Some(*id) {
+                let msg = format!("block registry must have \"{name}\" with id {id}");
+                diagnostics.push(Diagnostic::error(path, 0, 0, &msg));
+            }
+        }
+
+        (registry, diagnostics)
+    }
+
+    //Returns the registry along with any problems in the registry file
+    pub fn load_diagnostics(path: &str, item_alias_path: &str) -> (Self, Vec<Diagnostic>) {
+        let (entries, mut diagnostics) = impfile::parse_file_diagnostics(path);
+        let (item_aliases, alias_diagnostics) = parse_item_alias_file(item_alias_path);
+        diagnostics.extend(alias_diagnostics);
+        let (registry, registry_diagnostics) = Self::from_entries(&entries, path, &item_aliases);
+        diagnostics.extend(registry_diagnostics);
+        (registry, diagnostics)
+    }
+
+    pub fn load(path: &str, item_alias_path: &str) -> Self {
+        let (registry, diagnostics) = Self::load_diagnostics(path, item_alias_path);
+        print_diagnostics(&diagnostics);
+        eprintln!("Loaded {} blocks", registry.blocks.len());
+        registry
+    }
+
+    pub fn get(&self, id: u16) -> Option<&BlockDef> {
+        self.blocks.get(&id)
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<u16> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn flags(&self, id: u16) -> u16 {
+        self.flags.get(id as usize).copied().unwrap_or(0)
+    }
+
+    pub fn has_flag(&self, id: u16, flag: u16) -> bool {
+        self.flags(id) & flag != 0
+    }
+
+    pub fn light_src(&self, id: u16) -> Option<LightSrc> {
+        self.get(id).and_then(|block| block.light)
+    }
+
+    pub fn info(&self, id: u16) -> Option<&BlockInfo> {
+        self.get(id).map(|block| &block.info)
+    }
+
+    pub fn model(&self, id: u16) -> BlockModel {
+        self.get(id).map(|block| block.model).unwrap_or_default()
+    }
+
+    //Returns the name of each block along with the block as an item
+    pub fn block_aliases(&self, include_hidden: bool) -> ItemAliases {
+        self.blocks
+            .values()
+            .filter(|block| include_hidden || !block.hidden)
+            .map(|block| (block.name.clone(), Item::Block(Block::new_id(block.id), 1)))
+            .collect()
+    }
+}
+
+static BLOCK_REGISTRY: OnceLock<BlockRegistry> = OnceLock::new();
+
+//Returns the block registry, it is loaded the first time this is called
+pub fn block_registry() -> &'static BlockRegistry {
+    BLOCK_REGISTRY.get_or_init(|| BlockRegistry::load(BLOCK_REGISTRY_PATH, ITEM_ALIAS_PATH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{
+        block_ids::{GATE, LAVA, WATER},
+        flags::{FLUID, NO_HITBOX, TRANSPARENT_FLAG},
+    };
+
+    fn test_entries() -> Vec<Entry> {
+        vec![
+            Entry::from_vec(
+                FLAG_GROUPS,
+                vec![("liquid".to_string(), "transparent,fluid".to_string())],
+            ),
+            Entry::from_vec(
+                "stone",
+                vec![
+                    ("id".to_string(), "2".to_string()),
+                    ("break_time".to_string(), "10.0".to_string()),
+                    ("tool".to_string(), "pickaxe".to_string()),
+                    ("drop:empty".to_string(), "empty/1.0".to_string()),
+                    ("drop:pebble".to_string(), "pebble/1.0".to_string()),
+                ],
+            ),
+            Entry::from_vec(
+                "pebble",
+                vec![
+                    ("id".to_string(), "300".to_string()),
+                    ("flags".to_string(), "liquid,no_hitbox".to_string()),
+                    ("light".to_string(), "1,2,3".to_string()),
+                    ("model".to_string(), "log".to_string()),
+                    ("textures".to_string(), "4,5".to_string()),
+                    ("hidden".to_string(), "true".to_string()),
+                ],
+            ),
+            //Invalid blocks
+            Entry::from_vec("duplicate", vec![("id".to_string(), "2".to_string())]),
+            Entry::from_vec("empty", vec![("id".to_string(), "0".to_string())]),
+            Entry::from_vec(
+                "bad_flags",
+                vec![
+                    ("id".to_string(), "5".to_string()),
+                    ("flags".to_string(), "shiny".to_string()),
+                ],
+            ),
+            Entry::from_vec(
+                "bad_model",
+                vec![
+                    ("id".to_string(), "6".to_string()),
+                    ("model".to_string(), "grass".to_string()),
+                    ("textures".to_string(), "1,2".to_string()),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_registry() {
+        let aliases = ItemAliases::new();
+        let (registry, diagnostics) =
+            BlockRegistry::from_entries(&test_entries(), "test", &aliases);
+        //duplicate, empty, bad_flags, bad_model and every builtin block except
+        //for stone are missing
+        let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+        assert_eq!(errors, 4 + BUILTIN_BLOCKS.len() - 1);
+        assert_eq!(registry.blocks.len(), 2);
+        assert_eq!(registry.get_id("stone"), Some(2));
+        assert_eq!(registry.get_id("duplicate"), None);
+        assert_eq!(registry.get(2).unwrap().name, "stone");
+        assert!(registry.get(5).is_none());
+        assert!(registry.get(6).is_none());
+
+        assert_eq!(registry.flags(2), 0);
+        assert_eq!(registry.flags(300), TRANSPARENT_FLAG | FLUID | NO_HITBOX);
+        assert!(registry.has_flag(300, FLUID));
+        assert_eq!(registry.flags(u16::MAX), 0);
+
+        assert!(registry.light_src(2).is_none());
+        let light = registry.light_src(300).unwrap();
+        assert_eq!((light.r, light.g, light.b), (1, 2, 3));
+
+        assert_eq!(registry.model(2), BlockModel::Cube);
+        assert_eq!(
+            registry.model(300),
+            BlockModel::Log {
+                end: 4,
+                side_rotated: 5
+            }
+        );
+
+        let info = registry.info(2).unwrap();
+        assert_eq!(info.break_time, 10.0);
+        //Drops can refer to any block by name
+        let drops = info.block_drops.as_ref().unwrap();
+        assert_eq!(drops.len(), 2);
+
+        //Hidden blocks do not get an item alias
+        assert_eq!(registry.block_aliases(false).len(), 1);
+        assert_eq!(registry.block_aliases(true).len(), 2);
+    }
+
+    #[test]
+    fn test_block_registry_asset() {
+        let registry = block_registry();
+        for (name, id) in BUILTIN_BLOCKS {
+            assert_eq!(registry.get_id(name), Some(*id), "{name}");
+        }
+        assert!(registry.has_flag(WATER, FLUID));
+        assert!(registry.light_src(LAVA).is_some());
+        assert!(registry.get(GATE).unwrap().info.preferred_tool.is_some());
+    }
+}
@@ -1,14 +1,24 @@
+use super::block_ids::{
+    BUTTON, CHEST, DOOR, DOOR_TOP, FURNACE, GATE, LADDER, LEAVES, LEVER, LIT_FURNACE, SKY_PORTAL,
+    SNOWY_LEAVES, TORCH, UNDERWORLD_PORTAL,
+};
 use super::coordinates::f32coord_to_int;
 use super::is_valid::get_check_valid_fn;
 use super::{Axis, FULL_BLOCK, INDESTRUCTIBLE};
 use super::{Block, World, EMPTY_BLOCK};
-use crate::game::inventory::Item;
-use crate::game::physics::{composite_to_hitbox, ray_intersects_box, CompositeHitbox, Hitbox};
-use crate::game::player::Player;
+use crate::inventory::Item;
+use crate::physics::{composite_to_hitbox, ray_intersects_box, CompositeHitbox, Hitbox};
 use cgmath::{InnerSpace, Vector3};
 
 pub const BLOCK_REACH: f32 = 4.0;
 
+//Whoever is placing blocks in the world
+pub trait Builder {
+    fn held_item(&self) -> Item;
+    fn is_crouching(&self) -> bool;
+    fn get_hitbox(&self) -> Hitbox;
+}
+
 /*
  * These are functions related to placing/building in the world
  * */
@@ -251,19 +261,15 @@ pub fn destroy_block(
 
     match block.id {
         //Door
-        79 => {
-            if world.get_block(ix, iy + 1, iz).id == 81 {
-                world.set_block(ix, iy + 1, iz, Block::new_id(0));
-            }
+        DOOR if world.get_block(ix, iy + 1, iz).id == DOOR_TOP => {
+            world.set_block(ix, iy + 1, iz, Block::new());
         }
-        81 => {
-            if world.get_block(ix, iy - 1, iz).id == 79 {
-                world.set_block(ix, iy - 1, iz, Block::new_id(0));
-            }
+        DOOR_TOP if world.get_block(ix, iy - 1, iz).id == DOOR => {
+            world.set_block(ix, iy - 1, iz, Block::new());
         }
         _ => {}
     }
-    world.set_block(ix, iy, iz, Block::new_id(0));
+    world.set_block(ix, iy, iz, Block::new());
     if block.id != EMPTY_BLOCK {
         return Some((ix, iy, iz));
     }
@@ -290,19 +296,15 @@ pub fn destroy_block_suffocating(
 
         match block.id {
             //Door
-            79 => {
-                if world.get_block(ix, iy + 1, iz).id == 81 {
-                    world.set_block(ix, iy + 1, iz, Block::new_id(0));
-                }
+            DOOR if world.get_block(ix, iy + 1, iz).id == DOOR_TOP => {
+                world.set_block(ix, iy + 1, iz, Block::new());
             }
-            81 => {
-                if world.get_block(ix, iy - 1, iz).id == 79 {
-                    world.set_block(ix, iy - 1, iz, Block::new_id(0));
-                }
+            DOOR_TOP if world.get_block(ix, iy - 1, iz).id == DOOR => {
+                world.set_block(ix, iy - 1, iz, Block::new());
             }
             _ => {}
         }
-        world.set_block(ix, iy, iz, Block::new_id(0));
+        world.set_block(ix, iy, iz, Block::new());
         if block.id != EMPTY_BLOCK {
             return Some((ix, iy, iz));
         }
@@ -469,8 +471,8 @@ fn set_non_voxel_orientation(dir: Vector3<f32>, axis: Axis, block: &mut Block) {
     }
 
     let orientation = match block.id {
-        //Torches and ladder
-        71..=75 => set_torch_orientation(dir, axis),
+        //Torches, ladder, levers, and buttons
+        TORCH..=LADDER | LEVER | BUTTON => set_torch_orientation(dir, axis),
         _ => block.orientation(),
     };
     block.set_orientation(orientation);
@@ -478,7 +480,7 @@ fn set_non_voxel_orientation(dir: Vector3<f32>, axis: Axis, block: &mut Block) {
 
 fn place(
     world: &mut World,
-    player: &Player,
+    player: &impl Builder,
     ix: i32,
     iy: i32,
     iz: i32,
@@ -507,12 +509,12 @@ pub fn interact_with_block(
     pos: Vector3<f32>,
     dir: Vector3<f32>,
     world: &mut World,
-    player: &Player,
+    player: &impl Builder,
 ) -> Option<(i32, i32, i32)> {
     let (ix, iy, iz) = get_selected(pos, dir, world);
 
     let mut block;
-    if let Item::Block(blockdata, _) = player.hotbar.get_selected() {
+    if let Item::Block(blockdata, _) = player.held_item() {
         block = blockdata;
         if block.is_fluid() {
             block.geometry = 7;
@@ -524,10 +526,10 @@ pub fn interact_with_block(
         //to differentiate between naturally generated leaves and 'artificial'
         //leaves. This is kind of a hack but I don't want to come up with
         //a better solution.
-        if (block.id == 7 || block.id == 91) && block.geometry == 0 {
+        if (block.id == LEAVES || block.id == SNOWY_LEAVES) && block.geometry == 0 {
             block.set_orientation(1);
         }
-    } else if let Item::Bucket(blockid) = player.hotbar.get_selected() {
+    } else if let Item::Bucket(blockid) = player.held_item() {
         //Place fluid with a bucket
         if Block::new_id(blockid).is_fluid() {
             block = Block::new_fluid(blockid);
@@ -541,11 +543,16 @@ pub fn interact_with_block(
     let raycast_block = world.get_block(ix, iy, iz);
 
     if raycast_block.can_use() && !player.is_crouching() {
+        //Flip levers and press buttons
+        if world.use_switch(ix, iy, iz) {
+            return Some((ix, iy, iz));
+        }
+
         block = match raycast_block.id {
-            //Chest/Furnace
-            37 | 40 | 70 => raycast_block,
+            //Chest/Furnace/Portal
+            CHEST | FURNACE | LIT_FURNACE | UNDERWORLD_PORTAL | SKY_PORTAL => raycast_block,
             //Open gates/door
-            78 | 79 | 81 => {
+            GATE | DOOR | DOOR_TOP => {
                 let mut b = raycast_block;
                 let is_open = b.reflection();
                 if is_open == 1 {
@@ -559,14 +566,16 @@ pub fn interact_with_block(
         };
 
         match block.id {
-            //Chest/Furnace
-            37 | 40 | 70 => return Some((ix, iy, iz)),
+            //Chest/Furnace/Portal, these are handled by the game
+            CHEST | FURNACE | LIT_FURNACE | UNDERWORLD_PORTAL | SKY_PORTAL => {
+                return Some((ix, iy, iz))
+            }
             //Open door
-            79 => {
+            DOOR => {
                 let prev_block = world.get_block(ix, iy, iz);
                 let ret = if place(world, player, ix, iy, iz, block).is_some() {
                     let mut top = block;
-                    top.id = 81;
+                    top.id = DOOR_TOP;
                     place(world, player, ix, iy + 1, iz, top)
                 } else {
                     None
@@ -578,11 +587,11 @@ pub fn interact_with_block(
 
                 return ret;
             }
-            81 => {
+            DOOR_TOP => {
                 let prev_block = world.get_block(ix, iy, iz);
                 let ret = if place(world, player, ix, iy, iz, block).is_some() {
                     let mut bot = block;
-                    bot.id = 79;
+                    bot.id = DOOR;
                     place(world, player, ix, iy - 1, iz, bot)
                 } else {
                     None
@@ -606,7 +615,7 @@ pub fn place_block(
     pos: Vector3<f32>,
     dir: Vector3<f32>,
     world: &mut World,
-    player: &Player,
+    player: &impl Builder,
 ) -> Option<(i32, i32, i32)> {
     let (x, y, z, axis) = raycast(pos, dir, BLOCK_REACH, world, |block| {
         block.is_fluid() || block.id == EMPTY_BLOCK
@@ -624,7 +633,7 @@ pub fn place_block(
     let (mut ix, mut iy, mut iz) = get_selected(pos, dir, world);
 
     let mut block;
-    if let Item::Block(blockdata, _) = player.hotbar.get_selected() {
+    if let Item::Block(blockdata, _) = player.held_item() {
         block = blockdata;
         if block.is_fluid() {
             block.geometry = 7;
@@ -636,10 +645,10 @@ pub fn place_block(
         //to differentiate between naturally generated leaves and 'artificial'
         //leaves. This is kind of a hack but I don't want to come up with
         //a better solution.
-        if (block.id == 7 || block.id == 91) && block.geometry == 0 {
+        if (block.id == LEAVES || block.id == SNOWY_LEAVES) && block.geometry == 0 {
             block.set_orientation(1);
         }
-    } else if let Item::Bucket(blockid) = player.hotbar.get_selected() {
+    } else if let Item::Bucket(blockid) = player.held_item() {
         //Place fluid with a bucket
         if Block::new_id(blockid).is_fluid() {
             block = Block::new_fluid(blockid);
@@ -700,12 +709,12 @@ pub fn place_block(
         return None;
     }
 
-    if block.id == 79 {
+    if block.id == DOOR {
         //Place door
         let prev_block = world.get_block(ix, iy, iz);
         let ret = if place(world, player, ix, iy, iz, block).is_some() {
             let mut top = block;
-            top.id = 81;
+            top.id = DOOR_TOP;
             let top_replace = world.get_block(ix, iy + 1, iz);
             if top_replace.id != EMPTY_BLOCK
                 && !top_replace.is_fluid()
@@ -1,4 +1,5 @@
 mod light;
+pub mod palette;
 pub mod save;
 mod update_tile_data;
 
@@ -7,7 +8,8 @@ use super::{
     tile_data::TileData,
     world_to_chunk_position, Block, ChunkPos, CHUNK_SIZE, CHUNK_SIZE_I32, EMPTY_BLOCK,
 };
-use std::collections::HashMap;
+use palette::BlockPalette;
+use std::collections::{HashMap, HashSet};
 
 fn out_of_bounds(index_x: i32, index_y: i32, index_z: i32) -> bool {
     index_x < 0
@@ -30,7 +32,7 @@ pub struct Chunk {
     //Chunks are CHUNK_SIZE x CHUNK_SIZE x CHUNK_SIZE cubes
     //For convention, assume that x is left to right, z is
     //forward and backwards, and y is up and down
-    blocks: Vec<Block>,
+    blocks: BlockPalette,
     //Stores the light data for each block
     light: Vec<Light>,
     //Integer position of the chunk, this is corner that has the lowest value
@@ -40,33 +42,40 @@ pub struct Chunk {
     iy: i32,
     iz: i32,
     data: HashMap<(i32, i32, i32), TileData>,
+    //Positions of blocks that are waiting to be updated and the tick that
+    //they should be updated on
+    scheduled_ticks: HashMap<(i32, i32, i32), u64>,
+    //Blocks that are being moved by a piston, these are drawn separately
+    //while they slide into place so they are left out of the chunk mesh
+    moving: HashSet<(i32, i32, i32)>,
+    //Set for chunks loaded from regions that were saved before scheduled
+    //ticks were stored, every block that can change in them is scheduled
+    //once they are added to the world
+    unscheduled: bool,
 }
 
 impl Chunk {
     pub fn new(x: i32, y: i32, z: i32) -> Self {
         Self {
-            blocks: vec![],
+            blocks: BlockPalette::new(),
             light: vec![],
             ix: x,
             iy: y,
             iz: z,
             data: HashMap::new(),
+            scheduled_ticks: HashMap::new(),
+            moving: HashSet::new(),
+            unscheduled: false,
         }
     }
 
     pub fn is_empty(&self) -> bool {
-        for b in &self.blocks {
-            if b.id != EMPTY_BLOCK {
-                return false;
-            }
-        }
-
-        true
+        self.blocks.is_all_empty()
     }
 
     //Frees memory if the chunk is completely empty
     pub fn handle_empty(&mut self) {
-        if self.blocks.is_empty() {
+        if self.blocks.is_unallocated() {
             return;
         }
 
@@ -75,12 +84,19 @@ impl Chunk {
         }
     }
 
+    //Removes unused blocks from the palette, this reduces memory use for
+    //chunks that are made of only a few types of blocks
+    pub fn shrink(&mut self) {
+        self.blocks.shrink();
+    }
+
+    //Approximate number of bytes used to store the blocks in the chunk
+    pub fn block_memory_usage(&self) -> usize {
+        self.blocks.memory_usage()
+    }
+
     //Relative the position of the chunk (0 <= x, y, z < CHUNK_SIZE)
     pub fn get_block_relative(&self, x: usize, y: usize, z: usize) -> Block {
-        if self.blocks.is_empty() {
-            return Block::new();
-        }
-
         //Out of bounds, return 0
         if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
             return Block::new();
@@ -89,7 +105,7 @@ impl Chunk {
         let index_y = CHUNK_SIZE * CHUNK_SIZE * y;
         let index_z = CHUNK_SIZE * z;
         let index_x = x;
-        self.blocks[index_x + index_y + index_z]
+        self.blocks.get(index_x + index_y + index_z)
     }
 
     pub fn get_light_relative(&self, x: usize, y: usize, z: usize) -> Light {
@@ -125,16 +141,6 @@ impl Chunk {
     }
 
     pub fn set_block_relative(&mut self, x: usize, y: usize, z: usize, block: Block) {
-        if self.blocks.is_empty() && block.id != EMPTY_BLOCK {
-            self.blocks = vec![Block::new(); CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE];
-        } else if !self.blocks.is_empty() && block.id == EMPTY_BLOCK {
-            self.handle_empty();
-        }
-
-        if self.blocks.is_empty() && block.id == EMPTY_BLOCK {
-            return;
-        }
-
         //Out of bounds
         if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
             return;
@@ -143,7 +149,10 @@ impl Chunk {
         let index_y = CHUNK_SIZE * CHUNK_SIZE * y;
         let index_z = CHUNK_SIZE * z;
         let index_x = x;
-        self.blocks[index_x + index_y + index_z] = block;
+        self.blocks.set(index_x + index_y + index_z, block);
+        if block.id == EMPTY_BLOCK {
+            self.handle_empty();
+        }
     }
 
     //x, y, and z are absolute world positions
@@ -208,6 +217,94 @@ impl Chunk {
         self.data.get(&(x, y, z)).cloned()
     }
 
+    //Schedules the block at (x, y, z) to be updated on tick `due`, if the
+    //block is already scheduled then the earlier of the two ticks is kept
+    //If the position is not in the chunk, ignore it
+    pub fn schedule_tick(&mut self, x: i32, y: i32, z: i32, due: u64) {
+        let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+        if chunkx != self.ix || chunky != self.iy || chunkz != self.iz {
+            return;
+        }
+        let scheduled = self.scheduled_ticks.entry((x, y, z)).or_insert(due);
+        *scheduled = (*scheduled).min(due);
+    }
+
+    //Removes and returns the positions of the blocks that are due to be
+    //updated on or before `tick`
+    pub fn take_due_ticks(&mut self, tick: u64) -> Vec<(i32, i32, i32)> {
+        if self.scheduled_ticks.is_empty() {
+            return vec![];
+        }
+        let due: Vec<(i32, i32, i32)> = self
+            .scheduled_ticks
+            .iter()
+            .filter(|(_, due)| **due <= tick)
+            .map(|(pos, _)| *pos)
+            .collect();
+        for pos in &due {
+            self.scheduled_ticks.remove(pos);
+        }
+        due
+    }
+
+    //Removes the scheduled tick of the block at (x, y, z) and returns the
+    //tick it was due on
+    pub fn take_scheduled_tick(&mut self, x: i32, y: i32, z: i32) -> Option<u64> {
+        self.scheduled_ticks.remove(&(x, y, z))
+    }
+
+    pub fn scheduled_ticks(&self) -> impl Iterator<Item = ((i32, i32, i32), u64)> + '_ {
+        self.scheduled_ticks.iter().map(|(pos, due)| (*pos, *due))
+    }
+
+    pub fn scheduled_tick_count(&self) -> usize {
+        self.scheduled_ticks.len()
+    }
+
+    pub fn set_unscheduled(&mut self, unscheduled: bool) {
+        self.unscheduled = unscheduled;
+    }
+
+    pub fn is_unscheduled(&self) -> bool {
+        self.unscheduled
+    }
+
+    //Marks the block at (x, y, z) as being moved (or no longer being moved)
+    //If the position is not in the chunk, ignore it
+    pub fn set_moving(&mut self, x: i32, y: i32, z: i32, moving: bool) {
+        let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+        if chunkx != self.ix || chunky != self.iy || chunkz != self.iz {
+            return;
+        }
+        if moving {
+            self.moving.insert((x, y, z));
+        } else {
+            self.moving.remove(&(x, y, z));
+        }
+    }
+
+    //x, y, and z are relative to the chunk
+    pub fn is_moving_relative(&self, x: usize, y: usize, z: usize) -> bool {
+        if self.moving.is_empty() {
+            return false;
+        }
+        let pos = self.get_chunk_pos();
+        let x = x as i32 + pos.x * CHUNK_SIZE_I32;
+        let y = y as i32 + pos.y * CHUNK_SIZE_I32;
+        let z = z as i32 + pos.z * CHUNK_SIZE_I32;
+        self.moving.contains(&(x, y, z))
+    }
+
+    pub fn is_moving(&self, x: i32, y: i32, z: i32) -> bool {
+        self.moving.contains(&(x, y, z))
+    }
+
+    //Returns true if any block in the chunk might match `f`, this only
+    //checks the palette so it can return true for blocks that were removed
+    pub fn may_contain(&self, f: impl Fn(Block) -> bool) -> bool {
+        self.blocks.palette_any(f)
+    }
+
     pub fn get_chunk_pos(&self) -> ChunkPos {
         ChunkPos::new(self.ix, self.iy, self.iz)
     }
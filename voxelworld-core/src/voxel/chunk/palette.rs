@@ -0,0 +1,329 @@
+/*
+ * Block storage for a chunk
+ *
+ * Each chunk stores a palette of the distinct blocks in it and an array of
+ * indices into that palette packed into u64 words. Indices are `bits` wide
+ * (1, 2, 4, 8, or 16) and never cross a word boundary. A chunk with an empty
+ * palette is completely empty and a chunk with only a single block in its
+ * palette does not need to store any indices at all.
+ */
+
+use crate::voxel::{Block, CHUNK_SIZE, EMPTY_BLOCK};
+
+const BLOCK_COUNT: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+const WORD_BITS: usize = u64::BITS as usize;
+
+//Returns the smallest index size that can store `palette_len` entries
+fn bits_needed(palette_len: usize) -> usize {
+    let mut bits = 0;
+    while (1 << bits) < palette_len {
+        bits = if bits == 0 { 1 } else { bits * 2 };
+    }
+    bits
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BlockPalette {
+    palette: Vec<Block>,
+    //Number of bits used for each index, 0 if the palette has at most one entry
+    bits: usize,
+    data: Vec<u64>,
+}
+
+impl BlockPalette {
+    //Creates storage for a chunk that only contains empty blocks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //Creates the smallest palette that can store `blocks`, any block past
+    //BLOCK_COUNT is ignored and any missing blocks are empty
+    pub fn from_blocks<T: IntoIterator<Item = Block>>(blocks: T) -> Self {
+        let mut palette = vec![];
+        let mut indices = Vec::with_capacity(BLOCK_COUNT);
+        for block in blocks.into_iter().take(BLOCK_COUNT) {
+            let index = match palette.iter().position(|b| *b == block) {
+                Some(index) => index,
+                None => {
+                    palette.push(block);
+                    palette.len() - 1
+                }
+            };
+            indices.push(index);
+        }
+
+        if indices.len() < BLOCK_COUNT {
+            let empty = match palette.iter().position(|b| b.id == EMPTY_BLOCK) {
+                Some(index) => index,
+                None => {
+                    palette.push(Block::new());
+                    palette.len() - 1
+                }
+            };
+            indices.resize(BLOCK_COUNT, empty);
+        }
+
+        if palette.iter().all(|b| b.id == EMPTY_BLOCK) {
+            return Self::new();
+        }
+        let mut storage = Self {
+            palette,
+            bits: 0,
+            data: vec![],
+        };
+        storage.pack(&indices);
+        storage
+    }
+
+    fn entries_per_word(&self) -> usize {
+        WORD_BITS / self.bits
+    }
+
+    fn get_index(&self, i: usize) -> usize {
+        if self.bits == 0 {
+            return 0;
+        }
+        let per_word = self.entries_per_word();
+        let shift = (i % per_word) * self.bits;
+        let mask = (1u64 << self.bits) - 1;
+        ((self.data[i / per_word] >> shift) & mask) as usize
+    }
+
+    fn set_index(&mut self, i: usize, index: usize) {
+        if self.bits == 0 {
+            return;
+        }
+        let per_word = self.entries_per_word();
+        let shift = (i % per_word) * self.bits;
+        let mask = (1u64 << self.bits) - 1;
+        let word = &mut self.data[i / per_word];
+        *word &= !(mask << shift);
+        *word |= (index as u64) << shift;
+    }
+
+    fn indices(&self) -> Vec<usize> {
+        (0..BLOCK_COUNT).map(|i| self.get_index(i)).collect()
+    }
+
+    //Packs `indices` using the smallest index size that fits the palette
+    fn pack(&mut self, indices: &[usize]) {
+        self.bits = bits_needed(self.palette.len());
+        if self.bits == 0 {
+            self.data = vec![];
+            return;
+        }
+        self.data = vec![0; BLOCK_COUNT.div_ceil(self.entries_per_word())];
+        for (i, index) in indices.iter().enumerate() {
+            self.set_index(i, *index);
+        }
+    }
+
+    //Removes palette entries that are no longer used
+    fn compact(&mut self) {
+        let indices = self.indices();
+        let mut used = vec![false; self.palette.len()];
+        for index in &indices {
+            used[*index] = true;
+        }
+
+        if used.iter().all(|u| *u) {
+            return;
+        }
+
+        let mut remapped = vec![0; self.palette.len()];
+        let mut palette = vec![];
+        for (i, block) in self.palette.iter().enumerate() {
+            if used[i] {
+                remapped[i] = palette.len();
+                palette.push(*block);
+            }
+        }
+        self.palette = palette;
+        let indices: Vec<usize> = indices.iter().map(|index| remapped[*index]).collect();
+        self.pack(&indices);
+    }
+
+    //Adds a block to the palette and returns its index, the indices are
+    //repacked if they are too small to store the new palette index
+    fn add_to_palette(&mut self, block: Block) -> usize {
+        if self.palette.is_empty() {
+            //The chunk was empty, all of the indices are 0
+            self.palette.push(Block::new());
+        }
+
+        if bits_needed(self.palette.len() + 1) > self.bits {
+            self.compact();
+        }
+
+        self.palette.push(block);
+        if bits_needed(self.palette.len()) > self.bits {
+            let indices = self.indices();
+            self.pack(&indices);
+        }
+        self.palette.len() - 1
+    }
+
+    //i = x + z * CHUNK_SIZE + y * CHUNK_SIZE * CHUNK_SIZE
+    pub fn get(&self, i: usize) -> Block {
+        if self.palette.is_empty() || i >= BLOCK_COUNT {
+            return Block::new();
+        }
+        self.palette[self.get_index(i)]
+    }
+
+    pub fn set(&mut self, i: usize, block: Block) {
+        if i >= BLOCK_COUNT {
+            return;
+        }
+
+        if self.palette.is_empty() && block.id == EMPTY_BLOCK {
+            return;
+        }
+
+        let index = match self.palette.iter().position(|b| *b == block) {
+            Some(index) => index,
+            None => self.add_to_palette(block),
+        };
+        self.set_index(i, index);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Block> + '_ {
+        (0..BLOCK_COUNT).map(|i| self.get(i))
+    }
+
+    //Returns true if no memory is allocated for the blocks
+    pub fn is_unallocated(&self) -> bool {
+        self.palette.is_empty()
+    }
+
+    pub fn is_all_empty(&self) -> bool {
+        if self.palette.iter().all(|b| b.id == EMPTY_BLOCK) {
+            return true;
+        }
+        self.iter().all(|b| b.id == EMPTY_BLOCK)
+    }
+
+    //Returns true if any entry in the palette matches `f`, the palette can
+    //have entries that are no longer used by any block
+    pub fn palette_any(&self, f: impl Fn(Block) -> bool) -> bool {
+        self.palette.iter().any(|b| f(*b))
+    }
+
+    //Frees all memory used by the blocks, the chunk becomes empty
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    //Removes unused palette entries and shrinks the indices to the smallest
+    //size that can fit the palette, a chunk that is only made of a single
+    //block will not store any indices after this
+    pub fn shrink(&mut self) {
+        if self.is_all_empty() {
+            self.clear();
+            return;
+        }
+        self.compact();
+    }
+
+    //Approximate number of bytes used to store the blocks
+    pub fn memory_usage(&self) -> usize {
+        self.palette.capacity() * size_of::<Block>() + self.data.capacity() * size_of::<u64>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_needed() {
+        assert_eq!(bits_needed(0), 0);
+        assert_eq!(bits_needed(1), 0);
+        assert_eq!(bits_needed(2), 1);
+        assert_eq!(bits_needed(3), 2);
+        assert_eq!(bits_needed(16), 4);
+        assert_eq!(bits_needed(17), 8);
+        assert_eq!(bits_needed(257), 16);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut storage = BlockPalette::new();
+        storage.set(10, Block::new());
+        assert!(storage.is_unallocated());
+        assert!(storage.is_all_empty());
+        assert_eq!(storage.get(10), Block::new());
+        assert_eq!(storage.memory_usage(), 0);
+    }
+
+    #[test]
+    fn test_set_get() {
+        let mut storage = BlockPalette::new();
+        //Enough blocks to need 16 bit indices, with ids past 256
+        for i in 0..BLOCK_COUNT {
+            storage.set(
+                i,
+                Block::new_id_orientation((i % 300) as u16, (i % 7) as u8),
+            );
+        }
+        for i in 0..BLOCK_COUNT {
+            let expected = Block::new_id_orientation((i % 300) as u16, (i % 7) as u8);
+            assert_eq!(storage.get(i), expected);
+        }
+        assert_eq!(storage.bits, 16);
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut storage = BlockPalette::new();
+        storage.set(0, Block::new_id(1));
+        storage.set(0, Block::new_id(2));
+        storage.set(0, Block::new_id(3));
+        //Unused entries should be removed instead of growing the indices
+        storage.set(1, Block::new_id(4));
+        assert_eq!(storage.palette.len(), 3);
+        assert_eq!(storage.bits, 2);
+        assert_eq!(storage.get(0), Block::new_id(3));
+        assert_eq!(storage.get(1), Block::new_id(4));
+        assert_eq!(storage.get(2), Block::new());
+
+        storage.set(0, Block::new());
+        storage.set(1, Block::new());
+        assert!(storage.is_all_empty());
+        storage.shrink();
+        assert!(storage.is_unallocated());
+    }
+
+    #[test]
+    fn test_uniform() {
+        let stone = Block::new_id(2);
+        let storage = BlockPalette::from_blocks(std::iter::repeat_n(stone, BLOCK_COUNT));
+        assert_eq!(storage.palette.len(), 1);
+        assert!(storage.data.is_empty());
+        assert!(storage.iter().all(|b| b == stone));
+        //Storing each block individually would take much more memory
+        assert!(storage.memory_usage() < BLOCK_COUNT);
+
+        let mut storage = BlockPalette::new();
+        for i in 0..BLOCK_COUNT {
+            storage.set(i, stone);
+        }
+        storage.shrink();
+        assert_eq!(storage.palette.len(), 1);
+        assert!(storage.data.is_empty());
+        assert_eq!(storage.get(100), stone);
+    }
+
+    #[test]
+    fn test_from_blocks() {
+        let blocks = [Block::new_id(1), Block::new(), Block::new_id(300)];
+        let storage = BlockPalette::from_blocks(blocks);
+        assert_eq!(storage.get(0), Block::new_id(1));
+        assert_eq!(storage.get(1), Block::new());
+        assert_eq!(storage.get(2), Block::new_id(300));
+        assert_eq!(storage.get(BLOCK_COUNT - 1), Block::new());
+
+        let storage = BlockPalette::from_blocks([Block::new(); 8]);
+        assert!(storage.is_unallocated());
+    }
+}
@@ -1,9 +1,6 @@
-use super::Chunk;
-use crate::{
-    bin_data::DataTable,
-    voxel::{Block, CHUNK_SIZE},
-};
-use std::collections::HashMap;
+use super::{BlockPalette, Chunk};
+use crate::{bin_data::DataTable, voxel::Block};
+use std::collections::{HashMap, HashSet};
 
 impl Chunk {
     pub fn tiles_to_data_tables(&self) -> Vec<DataTable> {
@@ -19,38 +16,38 @@ impl Chunk {
             return Self::new(x, y, z);
         }
 
-        let mut chunk_blocks = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE);
-        for (count, block) in blocks {
-            for _ in 0..*count {
-                if chunk_blocks.len() >= CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE {
-                    break;
-                }
-                chunk_blocks.push(*block);
-            }
-        }
+        let chunk_blocks = blocks
+            .iter()
+            .flat_map(|(count, block)| std::iter::repeat_n(*block, *count as usize));
 
         Self {
-            blocks: chunk_blocks,
+            blocks: BlockPalette::from_blocks(chunk_blocks),
             light: vec![],
             ix: x,
             iy: y,
             iz: z,
             data: HashMap::new(),
+            scheduled_ticks: HashMap::new(),
+            moving: HashSet::new(),
+            unscheduled: false,
         }
     }
 
     pub fn rle_encode(&self) -> Vec<(u16, Block)> {
         let mut data = vec![];
+        if self.blocks.is_unallocated() {
+            return data;
+        }
 
         let mut count = 0u16;
         let mut block = Block::new();
-        for b in &self.blocks {
-            if *b != block {
+        for b in self.blocks.iter() {
+            if b != block {
                 if count != 0 {
                     data.push((count, block));
                 }
 
-                block = *b;
+                block = b;
                 count = 1;
                 continue;
             }
@@ -65,18 +62,20 @@ impl Chunk {
         data
     }
 
-    //Returns (len, counts, data)
+    //Returns (len, counts, data), data is the ids of the blocks followed by
+    //their geometry (all stored as u16)
     pub fn get_chunk_bytes(&self) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
         let rle = self.rle_encode();
         let mut counts = Vec::<u8>::with_capacity(rle.len() * size_of::<u16>());
-        let mut blockdata = Vec::<u8>::with_capacity(rle.len());
+        let mut ids = Vec::<u8>::with_capacity(rle.len() * size_of::<u16>());
+        let mut geometries = Vec::<u8>::with_capacity(rle.len() * size_of::<u16>());
         for (count, b) in rle {
-            let count_bytes = count.to_be_bytes();
-            counts.push(count_bytes[0]);
-            counts.push(count_bytes[1]);
-            blockdata.push(b.id);
-            blockdata.push(b.geometry);
+            counts.extend(count.to_be_bytes());
+            ids.extend(b.id.to_be_bytes());
+            geometries.extend((b.geometry as u16).to_be_bytes());
         }
+        let mut blockdata = ids;
+        blockdata.extend(geometries);
         let len = (counts.len() as u16 / size_of::<u16>() as u16).to_be_bytes();
 
         (len.to_vec(), counts, blockdata)
@@ -95,7 +94,7 @@ mod tests {
         let chunk2 = Chunk::from_rle(0, 0, 0, &rle);
         assert!(!rle.is_empty());
         for (i, b) in chunk2.blocks.iter().enumerate() {
-            assert_eq!(*b, testchunk.blocks[i]);
+            assert_eq!(b, testchunk.blocks.get(i));
         }
     }
 
@@ -105,7 +104,7 @@ mod tests {
         let rle = testchunk.rle_encode();
         assert_eq!(rle.len(), 0);
         let chunk2 = Chunk::from_rle(0, 0, 0, &rle);
-        assert_eq!(chunk2.blocks.len(), 0);
-        assert_eq!(testchunk.blocks.len(), chunk2.blocks.len());
+        assert!(chunk2.blocks.is_unallocated());
+        assert!(testchunk.blocks.is_unallocated());
     }
 }
@@ -1,10 +1,11 @@
 use super::Chunk;
 use crate::{
-    game::{
-        crafting::RecipeTable,
-        inventory::{items_match, merge_stacks, remove_amt_item, Item, MAX_STACK_SIZE},
+    crafting::RecipeTable,
+    inventory::{items_match, merge_stacks, remove_amt_item, Item, MAX_STACK_SIZE},
+    voxel::{
+        block_ids::{FURNACE, LIT_FURNACE},
+        tile_data::TileData,
     },
-    voxel::tile_data::TileData,
 };
 
 const SMELT_TIME: f32 = 8.0;
@@ -101,7 +102,7 @@ impl Chunk {
             let block = self.get_block(*x, *y, *z);
             match block.id {
                 //Furnace
-                40 | 70 => {
+                FURNACE | LIT_FURNACE => {
                     if let Some(updated) = update_furnace(tile_data, dt, recipes) {
                         updated_tile_data.push(((*x, *y, *z), updated));
                     }
@@ -121,20 +122,20 @@ impl Chunk {
 
             match block.id {
                 //Furnace
-                40 => {
+                FURNACE => {
                     let maxfuel = tile_data.get_float("maxfuel").unwrap_or(0.0);
                     if maxfuel > 0.0 {
                         block_updates.push((x, y, z));
-                        block.id = 70;
+                        block.id = LIT_FURNACE;
                         self.set_block(x, y, z, block);
                     }
                 }
                 //Lit furnace
-                70 => {
+                LIT_FURNACE => {
                     let maxfuel = tile_data.get_float("maxfuel").unwrap_or(0.0);
                     if maxfuel == 0.0 {
                         block_updates.push((x, y, z));
-                        block.id = 40;
+                        block.id = FURNACE;
                         self.set_block(x, y, z, block);
                     }
                 }
@@ -0,0 +1,39 @@
+pub const TRANSPARENT_FLAG: u16 = 1 << 0;
+pub const CONNECT_FLAG: u16 = 1 << 1;
+pub const CAN_ROTATE_FLAG: u16 = 1 << 2;
+pub const NO_HITBOX: u16 = 1 << 3;
+pub const FLUID: u16 = 1 << 4;
+pub const ROTATE_Y_ONLY: u16 = 1 << 5;
+pub const FLAT_ITEM: u16 = 1 << 6;
+pub const FLUID_DESTRUCTIBLE: u16 = 1 << 7;
+pub const NON_VOXEL: u16 = 1 << 8;
+pub const REPLACEABLE: u16 = 1 << 9;
+pub const CAN_USE: u16 = 1 << 10;
+pub const OPEN_INVENTORY: u16 = 1 << 11;
+pub const IMMOVABLE: u16 = 1 << 12;
+pub const FALLS: u16 = 1 << 13;
+
+//Names of the flags in the block registry
+const FLAG_NAMES: [(&str, u16); 14] = [
+    ("transparent", TRANSPARENT_FLAG),
+    ("connect", CONNECT_FLAG),
+    ("can_rotate", CAN_ROTATE_FLAG),
+    ("no_hitbox", NO_HITBOX),
+    ("fluid", FLUID),
+    ("rotate_y_only", ROTATE_Y_ONLY),
+    ("flat_item", FLAT_ITEM),
+    ("fluid_destructible", FLUID_DESTRUCTIBLE),
+    ("non_voxel", NON_VOXEL),
+    ("replaceable", REPLACEABLE),
+    ("can_use", CAN_USE),
+    ("open_inventory", OPEN_INVENTORY),
+    ("immovable", IMMOVABLE),
+    ("falls", FALLS),
+];
+
+pub fn flag_from_name(name: &str) -> Option<u16> {
+    FLAG_NAMES
+        .iter()
+        .find(|(flag_name, _)| *flag_name == name)
+        .map(|(_, flag)| *flag)
+}
@@ -0,0 +1,125 @@
+use super::block_ids::*;
+use super::{orientation_to_normal, World, EMPTY_BLOCK};
+
+fn check_below_valid(world: &World, x: i32, y: i32, z: i32, valid_blocks: &[u16]) -> bool {
+    let below = world.get_block(x, y - 1, z);
+    if below.shape() != 0 {
+        return false;
+    }
+    for block in valid_blocks {
+        if below.id == *block {
+            return true;
+        }
+    }
+    false
+}
+
+fn check_torch_valid(world: &World, x: i32, y: i32, z: i32) -> bool {
+    let block = world.get_block(x, y, z);
+    let dir = orientation_to_normal(block.orientation());
+    let adj = world.get_block(x - dir.x, y - dir.y, z - dir.z);
+    //Torches can be placed on top of fences
+    if dir.y != 0 && adj.id == FENCE {
+        return true;
+    }
+    !(adj.id == EMPTY_BLOCK || adj.transparent() || adj.shape() != 0)
+}
+
+fn check_door_valid(world: &World, x: i32, y: i32, z: i32) -> bool {
+    let below = world.get_block(x, y - 1, z);
+    !(below.id == EMPTY_BLOCK || below.is_fluid() || below.shape() != 0)
+}
+
+type ValidBlockFn = fn(&World, i32, i32, i32) -> bool;
+
+//Returns a function that checks if a block in a position (x, y, z) is valid
+//If None is returned, then we assume that this block can be placed anywhere
+pub fn get_check_valid_fn(block: u16) -> Option<ValidBlockFn> {
+    match block {
+        //Sapling and Grass
+        SAPLING | TALL_GRASS => {
+            Some(|world, x, y, z| check_below_valid(world, x, y, z, &[GRASS, DIRT, MOSS]))
+        }
+        //Flowers
+        RED_FLOWER..=BLUE_FLOWER
+        | GROWING_RED_FLOWER
+        | GROWING_YELLOW_FLOWER
+        | GROWING_BLUE_FLOWER => Some(|world, x, y, z| {
+            check_below_valid(
+                world,
+                x,
+                y,
+                z,
+                &[GRASS, DIRT, MOSS, WET_FARMLAND, DRY_FARMLAND],
+            )
+        }),
+        //Mushroom
+        MUSHROOM => Some(|world, x, y, z| {
+            let below = world.get_block(x, y - 1, z);
+            if below.shape() != 0 {
+                return false;
+            }
+            !below.transparent() && below.id != EMPTY_BLOCK
+        }),
+        //Wheat, cotton, and flower seeds
+        WHEAT_1..=WHEAT
+        | SEEDS
+        | COTTON_SEED
+        | RED_FLOWER_SEEDS
+        | YELLOW_FLOWER_SEEDS
+        | BLUE_FLOWER_SEEDS
+        | WHITE_FLOWER_SEEDS => {
+            Some(|world, x, y, z| check_below_valid(world, x, y, z, &[WET_FARMLAND, DRY_FARMLAND]))
+        }
+        //Sugar cane
+        SUGARCANE => Some(|world, x, y, z| {
+            check_below_valid(world, x, y, z, &[GRASS, DIRT, SAND, MOSS, SUGARCANE])
+        }),
+        //Torches and ladders
+        TORCH..=LADDER => Some(check_torch_valid),
+        //Door
+        DOOR => Some(check_door_valid),
+        //Levers and buttons can be attached to any side of a block
+        LEVER | BUTTON => Some(check_torch_valid),
+        //Signal blocks that need to be on top of a block
+        PRESSURE_PLATE | WIRE | REPEATER | INVERTER => Some(check_door_valid),
+        //Cactus
+        CACTUS => Some(|world, x, y, z| {
+            let shape = world.get_block(x, y, z).shape();
+            //Only apply to full blocks
+            check_below_valid(world, x, y, z, &[SAND, CACTUS]) || shape != 0
+        }),
+        //Dead bush
+        DEAD_BUSH => {
+            Some(|world, x, y, z| check_below_valid(world, x, y, z, &[GRASS, DIRT, SAND, MOSS]))
+        }
+        //Snowy sapling can be placed on snowy grass and snow
+        SNOWY_SAPLING => Some(|world, x, y, z| {
+            check_below_valid(world, x, y, z, &[GRASS, DIRT, MOSS, SNOW, SNOWY_GRASS])
+        }),
+        //Cotton
+        COTTON_1..=COTTON => Some(|world, x, y, z| {
+            check_below_valid(
+                world,
+                x,
+                y,
+                z,
+                &[GRASS, DIRT, MOSS, WET_FARMLAND, DRY_FARMLAND],
+            )
+        }),
+        //White flower
+        GROWING_WHITE_FLOWER | WHITE_FLOWER => Some(|world, x, y, z| {
+            let valid = [
+                GRASS,
+                DIRT,
+                MOSS,
+                WET_FARMLAND,
+                DRY_FARMLAND,
+                SNOW,
+                SNOWY_GRASS,
+            ];
+            check_below_valid(world, x, y, z, &valid)
+        }),
+        _ => None,
+    }
+}
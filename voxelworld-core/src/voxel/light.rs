@@ -1,4 +1,38 @@
-use super::{Block, Chunk, CHUNK_SIZE, CHUNK_SIZE_I32, EMPTY_BLOCK};
+use super::{
+    block_ids::{LEAVES, SNOWY_LEAVES},
+    Block, Chunk, CHUNK_SIZE, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
+
+//Fraction of a day that it takes for the sky to change from day to night
+pub const TRANSITION_TIME: f32 = 0.04;
+
+//Assumes 0.0 < t < 1.0
+pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    a * (1.0 - t) + b * t
+}
+
+pub fn get_sky_brightness(t: f32) -> f32 {
+    if t < TRANSITION_TIME {
+        lerp(0.0, 1.0, t / TRANSITION_TIME * 0.5 + 0.5)
+    } else if t > 1.0 - TRANSITION_TIME {
+        lerp(
+            0.0,
+            1.0,
+            (t - 1.0 + TRANSITION_TIME) / TRANSITION_TIME * 0.5,
+        )
+    } else if ((0.5 - TRANSITION_TIME)..(0.5 + TRANSITION_TIME)).contains(&t) {
+        lerp(
+            1.0,
+            0.0,
+            (t - 0.5 + TRANSITION_TIME) / TRANSITION_TIME * 0.5,
+        )
+    } else if (0.5..1.0).contains(&t) {
+        0.0
+    } else {
+        1.0
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Light {
@@ -160,7 +194,7 @@ pub struct SkyLightMap {
 //Returns whether sky light can pass through a block
 pub fn skylight_can_pass(block: Block) -> bool {
     //If it is a fluid or is leaves, then it blocks sky light
-    if block.is_fluid() || block.id == 7 || block.id == 91 {
+    if block.is_fluid() || block.id == LEAVES || block.id == SNOWY_LEAVES {
         return false;
     }
 
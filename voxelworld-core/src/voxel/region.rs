@@ -1,6 +1,8 @@
 use super::{tile_data::TileData, world_to_chunk_position, Chunk};
 use std::collections::HashMap;
 
+pub mod codec;
+pub mod format;
 pub mod load;
 pub mod save;
 
@@ -89,23 +91,35 @@ impl Region {
         );
     }
 
-    pub fn set_tile_data(&mut self, x: i32, y: i32, z: i32, tile_data: TileData) {
+    //Returns the chunk that the block at (x, y, z) is in, None if the block
+    //is not in the region or the chunk has not been generated
+    fn get_mut_block_chunk(&mut self, x: i32, y: i32, z: i32) -> Option<&mut Chunk> {
         let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
         let (startx, starty, startz) = regionpos_to_chunkpos(self.x, self.y, self.z);
         let ix = chunkx - startx;
         let iy = chunky - starty;
         let iz = chunkz - startz;
         if ix < 0 || iy < 0 || iz < 0 {
-            return;
+            return None;
         }
         if ix >= REGION_SIZE_I32 || iy >= REGION_SIZE_I32 || iz >= REGION_SIZE_I32 {
-            return;
+            return None;
         }
         let index = ix * REGION_SIZE_I32 * REGION_SIZE_I32 + iy * REGION_SIZE_I32 + iz;
-        if let Some(chunk) = &mut self.chunks[index as usize] {
+        self.chunks[index as usize].as_mut()
+    }
+
+    pub fn set_tile_data(&mut self, x: i32, y: i32, z: i32, tile_data: TileData) {
+        if let Some(chunk) = self.get_mut_block_chunk(x, y, z) {
             chunk.set_tile_data(x, y, z, Some(tile_data));
         }
     }
+
+    pub fn schedule_tick(&mut self, x: i32, y: i32, z: i32, due: u64) {
+        if let Some(chunk) = self.get_mut_block_chunk(x, y, z) {
+            chunk.schedule_tick(x, y, z, due);
+        }
+    }
 }
 
 pub fn get_region_chunks(region: &mut Region, chunks: &HashMap<(i32, i32, i32), Chunk>) {
@@ -0,0 +1,108 @@
+/*
+ * Codecs for the block data of a region file
+ *
+ * rle: the run length encoded chunk records (the original encoding)
+ * lz: the rle chunk records compressed with an LZ77 style compressor
+ * palette: each chunk is stored as a palette of blocks and a bit packed
+ *          list of indices into the palette
+ * */
+
+mod bench;
+mod lz;
+mod palette;
+
+pub use bench::benchmark_codecs;
+
+use super::{load::bytes_to_u16, load::region_from_bytes, Region};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RegionCodec {
+    Rle,
+    Lz,
+    Palette,
+}
+
+pub const ALL_CODECS: [RegionCodec; 3] = [RegionCodec::Rle, RegionCodec::Lz, RegionCodec::Palette];
+
+//Codec used for new worlds
+pub const DEFAULT_CODEC: RegionCodec = RegionCodec::Lz;
+
+impl RegionCodec {
+    //Stored in the world metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rle => "rle",
+            Self::Lz => "lz",
+            Self::Palette => "palette",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ALL_CODECS
+            .iter()
+            .copied()
+            .find(|codec| codec.name() == name)
+    }
+
+    //Stored in the region file
+    pub fn id(&self) -> u8 {
+        match self {
+            Self::Rle => 0,
+            Self::Lz => 1,
+            Self::Palette => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        ALL_CODECS.iter().copied().find(|codec| codec.id() == id)
+    }
+
+    pub fn encode_blocks(&self, region: &Region) -> Vec<u8> {
+        match self {
+            Self::Rle => region.get_block_bytes(),
+            Self::Lz => lz::compress(&region.get_block_bytes()),
+            Self::Palette => palette::encode(region),
+        }
+    }
+
+    //`wide_ids` should be false for region files that store u8 block ids
+    //(before version 3)
+    pub fn decode_blocks(
+        &self,
+        bytes: &[u8],
+        x: i32,
+        y: i32,
+        z: i32,
+        wide_ids: bool,
+    ) -> Result<Region, String> {
+        match self {
+            Self::Rle => Ok(region_from_bytes(&bytes_to_u16(bytes), x, y, z, wide_ids)),
+            Self::Lz => {
+                let block_bytes = lz::decompress(bytes)?;
+                Ok(region_from_bytes(
+                    &bytes_to_u16(&block_bytes),
+                    x,
+                    y,
+                    z,
+                    wide_ids,
+                ))
+            }
+            Self::Palette => palette::decode(bytes, x, y, z, wide_ids),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codec_names() {
+        for codec in ALL_CODECS {
+            assert_eq!(RegionCodec::from_name(codec.name()), Some(codec));
+            assert_eq!(RegionCodec::from_id(codec.id()), Some(codec));
+        }
+        assert_eq!(RegionCodec::from_name("zip"), None);
+        assert_eq!(RegionCodec::from_id(255), None);
+    }
+}
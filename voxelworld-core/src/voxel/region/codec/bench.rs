@@ -0,0 +1,85 @@
+use super::{RegionCodec, ALL_CODECS};
+use crate::voxel::{
+    region::{chunkpos_to_regionpos, get_region_chunks, Region},
+    world::{terrain_generator::DefaultGenerator, GameMode},
+    World,
+};
+use std::{collections::HashSet, time::Instant};
+
+const BENCH_SEED: u32 = 1234;
+const BENCH_RANGE: i32 = 5;
+const BENCH_ITERATIONS: u32 = 5;
+
+fn bench_codec(codec: RegionCodec, regions: &[Region]) -> String {
+    let mut total_size = 0;
+    let mut encode_time = 0.0;
+    let mut decode_time = 0.0;
+    for _ in 0..BENCH_ITERATIONS {
+        total_size = 0;
+        for region in regions {
+            let start = Instant::now();
+            let bytes = codec.encode_blocks(region);
+            encode_time += start.elapsed().as_secs_f64();
+            total_size += bytes.len();
+
+            let start = Instant::now();
+            if let Err(msg) = codec.decode_blocks(&bytes, region.x, region.y, region.z, true) {
+                eprintln!("E: {} failed to decode region: {msg}", codec.name());
+            }
+            decode_time += start.elapsed().as_secs_f64();
+        }
+    }
+
+    let encode_ms = encode_time * 1000.0 / BENCH_ITERATIONS as f64;
+    let decode_ms = decode_time * 1000.0 / BENCH_ITERATIONS as f64;
+    format!(
+        "{:<8} {:>10} bytes {:>10.3} ms encode {:>10.3} ms decode",
+        codec.name(),
+        total_size,
+        encode_ms,
+        decode_ms
+    )
+}
+
+//Generates some terrain and compares the size and speed of each region
+//codec, returns the results one line at a time
+pub fn benchmark_codecs() -> Vec<String> {
+    eprintln!("Generating world...");
+    let mut world = World::new(
+        BENCH_SEED,
+        BENCH_RANGE,
+        &DefaultGenerator,
+        GameMode::Creative,
+    );
+    world.generate_world();
+
+    let mut positions = HashSet::new();
+    for (x, y, z) in world.chunks.keys() {
+        positions.insert(chunkpos_to_regionpos(*x, *y, *z));
+    }
+    let regions: Vec<Region> = positions
+        .iter()
+        .map(|(x, y, z)| {
+            let mut region = Region::new(*x, *y, *z);
+            get_region_chunks(&mut region, &world.chunks);
+            region
+        })
+        .collect();
+
+    let mut results = vec![
+        format!(
+            "Region Codec Benchmark ({} regions, average of {BENCH_ITERATIONS} runs)",
+            regions.len()
+        ),
+        "------------------".to_string(),
+    ];
+    for codec in ALL_CODECS {
+        results.push(bench_codec(codec, &regions));
+    }
+    let block_memory: usize = world.chunks.values().map(|c| c.block_memory_usage()).sum();
+    results.push(format!(
+        "Chunk block storage: {block_memory} bytes for {} chunks",
+        world.chunks.len()
+    ));
+    results
+}
@@ -0,0 +1,161 @@
+/*
+ * Simple LZ77 style compressor
+ *
+ * The compressed data starts with the length of the uncompressed data (u32)
+ * followed by a list of tokens:
+ * 0b0nnnnnnn: literal run, the next n + 1 bytes are copied as is
+ * 0b1nnnnnnn: match, copy n + MIN_MATCH bytes starting `offset` bytes back
+ *             where offset is the u16 (big endian) that follows
+ * */
+
+use std::collections::HashMap;
+
+const MIN_MATCH: usize = 4;
+const MAX_MATCH: usize = 0x7f + MIN_MATCH;
+const MAX_LITERALS: usize = 0x80;
+const MAX_OFFSET: usize = u16::MAX as usize;
+
+fn hash_key(bytes: &[u8], index: usize) -> Option<u32> {
+    let key = bytes.get(index..(index + MIN_MATCH))?;
+    Some(u32::from_be_bytes([key[0], key[1], key[2], key[3]]))
+}
+
+fn flush_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for run in literals.chunks(MAX_LITERALS) {
+        out.push((run.len() - 1) as u8);
+        out.extend(run);
+    }
+}
+
+pub fn compress(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() / 2 + 4);
+    out.extend((bytes.len() as u32).to_be_bytes());
+
+    //Last position that each 4 byte sequence was seen at
+    let mut last_seen: HashMap<u32, usize> = HashMap::new();
+    let mut literal_start = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        let key = hash_key(bytes, index);
+        let candidate = key.and_then(|k| last_seen.insert(k, index));
+
+        let match_len = match candidate {
+            Some(start) if index - start <= MAX_OFFSET => bytes[index..]
+                .iter()
+                .zip(&bytes[start..])
+                .take(MAX_MATCH)
+                .take_while(|(a, b)| a == b)
+                .count(),
+            _ => 0,
+        };
+
+        if match_len < MIN_MATCH {
+            index += 1;
+            continue;
+        }
+
+        flush_literals(&mut out, &bytes[literal_start..index]);
+        let offset = (index - candidate.unwrap_or(index)) as u16;
+        out.push(0x80 | (match_len - MIN_MATCH) as u8);
+        out.extend(offset.to_be_bytes());
+        //Remember the positions inside of the match
+        for i in (index + 1)..(index + match_len) {
+            if let Some(k) = hash_key(bytes, i) {
+                last_seen.insert(k, i);
+            }
+        }
+        index += match_len;
+        literal_start = index;
+    }
+    flush_literals(&mut out, &bytes[literal_start..]);
+
+    out
+}
+
+pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let len_bytes = bytes.get(0..4).ok_or("missing uncompressed length")?;
+    let len = u32::from_be_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]);
+    //Do not trust the length too much when allocating
+    let mut out = Vec::with_capacity((len as usize).min(bytes.len() * 64));
+
+    let mut index = 4;
+    while index < bytes.len() {
+        let token = bytes[index] as usize;
+        index += 1;
+        if token & 0x80 == 0 {
+            let run = bytes
+                .get(index..(index + token + 1))
+                .ok_or("literal run is truncated")?;
+            out.extend(run);
+            index += token + 1;
+        } else {
+            let offset_bytes = bytes
+                .get(index..(index + 2))
+                .ok_or("match offset is truncated")?;
+            let offset = u16::from_be_bytes([offset_bytes[0], offset_bytes[1]]) as usize;
+            index += 2;
+            if offset == 0 || offset > out.len() {
+                return Err(format!("invalid match offset {offset}"));
+            }
+            //Matches can overlap with the bytes they produce so copy
+            //one byte at a time
+            let start = out.len() - offset;
+            for i in 0..((token & 0x7f) + MIN_MATCH) {
+                out.push(out[start + i]);
+            }
+        }
+
+        if out.len() > len as usize {
+            return Err(format!("decompressed data is longer than {len} bytes"));
+        }
+    }
+
+    if out.len() != len as usize {
+        return Err(format!(
+            "expected {len} bytes after decompression, got {}",
+            out.len()
+        ));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_round_trip(bytes: &[u8]) {
+        let compressed = compress(bytes);
+        assert_eq!(decompress(&compressed).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_lz_round_trip() {
+        check_round_trip(&[]);
+        check_round_trip(&[1]);
+        check_round_trip(&[1, 2, 3]);
+        check_round_trip(b"abcabcabcabcabcabcabcabcabcabc");
+        check_round_trip(&[0; 1000]);
+        let pseudo_random: Vec<u8> = (0..5000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+        check_round_trip(&pseudo_random);
+    }
+
+    #[test]
+    fn test_lz_compresses() {
+        let bytes = [7u8; 4096];
+        assert!(compress(&bytes).len() < 200);
+    }
+
+    #[test]
+    fn test_lz_invalid() {
+        assert!(decompress(&[]).is_err());
+        //Match before the start of the output
+        assert!(decompress(&[0, 0, 0, 4, 0x80, 0, 1]).is_err());
+        //Truncated literal run
+        assert!(decompress(&[0, 0, 0, 4, 3, 1, 2]).is_err());
+        //Wrong length
+        assert!(decompress(&[0, 0, 0, 5, 0, 1]).is_err());
+    }
+}
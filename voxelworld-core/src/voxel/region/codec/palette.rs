@@ -0,0 +1,247 @@
+/*
+ * Palette + bit packing encoder
+ *
+ * For every chunk in the region (in the same order as the RLE encoding):
+ * generated: u8 (0 = ungenerated, the rest of the chunk is skipped)
+ * palette length: u16
+ * palette: palette length entries, each one is a block id (u16) followed by
+ *          the block geometry (u8), before region format version 3 each
+ *          entry is a u16 (block id << 8 | geometry)
+ * bits per block: u8
+ * block count: u16
+ * indices: block count palette indices packed into bits per block bits each,
+ *          rounded up to the nearest byte
+ * */
+
+use super::super::{
+    load::{block_from_u16, make_block},
+    Region, REGION_SIZE, REGION_SIZE_I32,
+};
+use crate::voxel::{Block, Chunk};
+
+fn bits_needed(palette_len: usize) -> u8 {
+    let mut bits = 0;
+    while (1usize << bits) < palette_len {
+        bits += 1;
+    }
+    bits
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit: usize,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: vec![],
+            bit: 0,
+        }
+    }
+
+    fn write(&mut self, value: u16, bits: u8) {
+        for i in 0..bits {
+            if self.bit.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if (value >> i) & 1 == 1 {
+                let last = self.bytes.len() - 1;
+                self.bytes[last] |= 1 << (self.bit % 8);
+            }
+            self.bit += 1;
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    index: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, index: 0 }
+    }
+
+    fn read(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self.index + count;
+        let slice = self
+            .bytes
+            .get(self.index..end)
+            .ok_or("palette data is truncated")?;
+        self.index = end;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.read(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, String> {
+        let bytes = self.read(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+fn read_packed(packed: &[u8], index: usize, bits: u8) -> u16 {
+    if bits == 0 {
+        return 0;
+    }
+    //A value is at most 16 bits so it always fits in 3 bytes
+    let bit = index * bits as usize;
+    let byte = bit / 8;
+    let mut window = 0u32;
+    for i in 0..3 {
+        window |= (packed.get(byte + i).copied().unwrap_or(0) as u32) << (8 * i);
+    }
+    ((window >> (bit % 8)) & ((1u32 << bits) - 1)) as u16
+}
+
+fn encode_chunk(bytes: &mut Vec<u8>, chunk: &Chunk) {
+    let rle = chunk.rle_encode();
+    let mut palette: Vec<Block> = vec![];
+    for (_, block) in &rle {
+        if !palette.contains(block) {
+            palette.push(*block);
+        }
+    }
+
+    bytes.extend((palette.len() as u16).to_be_bytes());
+    for block in &palette {
+        bytes.extend(block.id.to_be_bytes());
+        bytes.push(block.geometry);
+    }
+    let bits = bits_needed(palette.len());
+    bytes.push(bits);
+    let count: u32 = rle.iter().map(|(count, _)| *count as u32).sum();
+    bytes.extend((count as u16).to_be_bytes());
+
+    let mut writer = BitWriter::new();
+    for (count, block) in &rle {
+        let index = palette.iter().position(|b| b == block).unwrap_or(0);
+        for _ in 0..*count {
+            writer.write(index as u16, bits);
+        }
+    }
+    bytes.extend(writer.bytes);
+}
+
+fn decode_chunk(
+    reader: &mut Reader,
+    x: i32,
+    y: i32,
+    z: i32,
+    wide_ids: bool,
+) -> Result<Chunk, String> {
+    let palette_len = reader.read_u16()? as usize;
+    let mut palette = Vec::with_capacity(palette_len);
+    for _ in 0..palette_len {
+        if wide_ids {
+            let id = reader.read_u16()?;
+            palette.push(make_block(id, reader.read_u8()?));
+        } else {
+            palette.push(block_from_u16(reader.read_u16()?));
+        }
+    }
+    let bits = reader.read_u8()?;
+    if bits > 16 {
+        return Err(format!("invalid bits per block {bits}"));
+    }
+    let count = reader.read_u16()? as usize;
+    let packed = reader.read((count * bits as usize).div_ceil(8))?;
+
+    if palette.is_empty() {
+        return Ok(Chunk::new(x, y, z));
+    }
+
+    let mut blocks: Vec<(u16, Block)> = vec![];
+    for i in 0..count {
+        let index = read_packed(packed, i, bits) as usize;
+        let block = *palette
+            .get(index)
+            .ok_or_else(|| format!("palette index {index} is out of bounds"))?;
+        match blocks.last_mut() {
+            Some((n, b)) if *b == block && *n < u16::MAX => *n += 1,
+            _ => blocks.push((1, block)),
+        }
+    }
+
+    Ok(Chunk::from_rle(x, y, z, &blocks))
+}
+
+pub fn encode(region: &Region) -> Vec<u8> {
+    let mut bytes = vec![];
+    for ix in 0..REGION_SIZE {
+        for iy in 0..REGION_SIZE {
+            for iz in 0..REGION_SIZE {
+                if let Some(chunk) = region.get_relative_chunk(ix, iy, iz) {
+                    bytes.push(1);
+                    encode_chunk(&mut bytes, chunk);
+                } else {
+                    bytes.push(0);
+                }
+            }
+        }
+    }
+    bytes
+}
+
+pub fn decode(bytes: &[u8], x: i32, y: i32, z: i32, wide_ids: bool) -> Result<Region, String> {
+    let mut region = Region::new(x, y, z);
+    let mut reader = Reader::new(bytes);
+    for ix in 0..REGION_SIZE_I32 {
+        for iy in 0..REGION_SIZE_I32 {
+            for iz in 0..REGION_SIZE_I32 {
+                if reader.read_u8()? == 0 {
+                    continue;
+                }
+
+                let chunkx = x * REGION_SIZE_I32 + ix;
+                let chunky = y * REGION_SIZE_I32 + iy;
+                let chunkz = z * REGION_SIZE_I32 + iz;
+                let chunk = decode_chunk(&mut reader, chunkx, chunky, chunkz, wide_ids)?;
+                region.set_chunk(chunkx, chunky, chunkz, Some(chunk));
+            }
+        }
+    }
+    Ok(region)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_needed() {
+        assert_eq!(bits_needed(0), 0);
+        assert_eq!(bits_needed(1), 0);
+        assert_eq!(bits_needed(2), 1);
+        assert_eq!(bits_needed(3), 2);
+        assert_eq!(bits_needed(16), 4);
+        assert_eq!(bits_needed(17), 5);
+    }
+
+    #[test]
+    fn test_palette_round_trip() {
+        let mut region = Region::new(0, -1, 0);
+        let mut chunk = Chunk::new(0, -4, 0);
+        for x in 0..16 {
+            for z in 0..16 {
+                //Ids past 256 need the wide palette entries
+                let id = ((x * 7 + z * 3) % 20) as u16 * 20 + 1;
+                chunk.set_block_relative(x, z % 3, z, Block::new_id_orientation(id, 2));
+            }
+        }
+        region.set_chunk(0, -4, 0, Some(chunk.clone()));
+        region.set_chunk(1, -3, 2, Some(Chunk::new(1, -3, 2)));
+        let bytes = encode(&region);
+        let decoded = decode(&bytes, 0, -1, 0, true).unwrap();
+        let decoded_chunk = decoded.get_relative_chunk(0, 0, 0).unwrap();
+        assert_eq!(decoded_chunk.rle_encode(), chunk.rle_encode());
+        assert!(decoded.get_relative_chunk(1, 1, 2).is_some());
+        assert_eq!(decoded.chunks.iter().flatten().count(), 2);
+        //Truncated data should fail to decode
+        assert!(decode(&bytes[..bytes.len() - 1], 0, -1, 0, true).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+/*
+ * Region file layout (version 1 and later):
+ *
+ * magic: 4 bytes ("VXRG")
+ * version: u16 (big endian)
+ * sections: a list of sections, each one is
+ *     length: u32 (big endian)
+ *     checksum: u32 (big endian, CRC-32 of the section bytes)
+ *     bytes: [u8; length]
+ *
+ * Version 1 has two sections: the block data followed by the tile data.
+ *
+ * Version 2 adds a byte after the header with the id of the codec used for
+ * the block data section (see codec.rs), the sections are the same as in
+ * version 1.
+ *
+ * Version 3 widens block ids from u8 to u16, the layout is the same as in
+ * version 2 but the block data uses the wide block encoding of each codec.
+ *
+ * Version 4 adds a third section after the tile data with the blocks that
+ * are scheduled to be updated, each one is x, y, z (i32, big endian) followed
+ * by the tick that the block is due to be updated on (u64, big endian).
+ *
+ * Version 0 is the original headerless format: a u32 length, the block data,
+ * then an optional u32 length followed by the tile data. Version 0 files are
+ * still readable and are upgraded to the current version when loaded.
+ */
+
+pub const REGION_MAGIC: [u8; 4] = *b"VXRG";
+pub const LEGACY_REGION_VERSION: u16 = 0;
+pub const REGION_FORMAT_VERSION: u16 = 4;
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            if crc & 1 == 1 {
+                crc = (crc >> 1) ^ 0xedb88320;
+            } else {
+                crc >>= 1;
+            }
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+//Standard CRC-32 (same as the one used by zlib/png)
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffffffffu32;
+    for b in bytes {
+        crc = CRC32_TABLE[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+//Returns the version of the region file along with the bytes that come
+//after the header, files without a header are version 0
+pub fn read_header(bytes: &[u8]) -> (u16, &[u8]) {
+    if bytes.len() < REGION_MAGIC.len() + size_of::<u16>() || bytes[0..4] != REGION_MAGIC {
+        return (LEGACY_REGION_VERSION, bytes);
+    }
+    let version = u16::from_be_bytes([bytes[4], bytes[5]]);
+    (version, &bytes[6..])
+}
+
+pub fn write_header(bytes: &mut Vec<u8>, version: u16) {
+    bytes.extend(REGION_MAGIC);
+    bytes.extend(version.to_be_bytes());
+}
+
+pub fn write_section(bytes: &mut Vec<u8>, section: &[u8]) {
+    bytes.extend((section.len() as u32).to_be_bytes());
+    bytes.extend(crc32(section).to_be_bytes());
+    bytes.extend(section);
+}
+
+fn read_u32(bytes: &[u8], index: usize) -> Option<u32> {
+    let end = index.checked_add(size_of::<u32>())?;
+    let slice = bytes.get(index..end)?;
+    Some(u32::from_be_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+//Reads a section starting at `index`, returns the section bytes and the
+//index of the byte after the section. Fails if the section is truncated or
+//if the checksum does not match.
+pub fn read_section(bytes: &[u8], index: usize) -> Result<(&[u8], usize), String> {
+    let len = read_u32(bytes, index).ok_or("section length is missing")? as usize;
+    let checksum = read_u32(bytes, index + 4).ok_or("section checksum is missing")?;
+    let start = index + 2 * size_of::<u32>();
+    let section = start
+        .checked_add(len)
+        .and_then(|end| bytes.get(start..end))
+        .ok_or("section is truncated")?;
+    let actual = crc32(section);
+    if actual != checksum {
+        return Err(format!(
+            "checksum mismatch (expected {checksum:08x}, got {actual:08x})"
+        ));
+    }
+    Ok((section, start + len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414fa339
+        );
+    }
+
+    #[test]
+    fn test_header() {
+        let mut bytes = vec![];
+        write_header(&mut bytes, REGION_FORMAT_VERSION);
+        bytes.extend([1, 2, 3]);
+        let (version, rest) = read_header(&bytes);
+        assert_eq!(version, REGION_FORMAT_VERSION);
+        assert_eq!(rest, &[1, 2, 3]);
+
+        //Legacy files do not have a header
+        let legacy = [0, 0, 0, 0];
+        let (version, rest) = read_header(&legacy);
+        assert_eq!(version, LEGACY_REGION_VERSION);
+        assert_eq!(rest, &legacy);
+    }
+
+    #[test]
+    fn test_section() {
+        let mut bytes = vec![];
+        write_section(&mut bytes, &[1, 2, 3, 4]);
+        write_section(&mut bytes, &[]);
+        let (section, index) = read_section(&bytes, 0).unwrap();
+        assert_eq!(section, &[1, 2, 3, 4]);
+        let (section, index) = read_section(&bytes, index).unwrap();
+        assert!(section.is_empty());
+        assert_eq!(index, bytes.len());
+        assert!(read_section(&bytes, index).is_err());
+    }
+
+    #[test]
+    fn test_section_corrupted() {
+        let mut bytes = vec![];
+        write_section(&mut bytes, &[1, 2, 3, 4]);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        assert!(read_section(&bytes, 0).is_err());
+        //Truncated
+        bytes.pop();
+        assert!(read_section(&bytes, 0).is_err());
+    }
+}
@@ -0,0 +1,522 @@
+use super::{
+    codec::RegionCodec,
+    format::{read_header, read_section, REGION_FORMAT_VERSION},
+    save::region_file_name,
+    Region, REGION_SIZE_I32,
+};
+use crate::{
+    bin_data::{decode_binary_data, ByteStream},
+    save::CHUNK_PATH,
+    voxel::{tile_data::TileData, Block, Chunk},
+};
+
+fn get_val<T: Copy>(data: &[T], index: usize) -> Option<T> {
+    if index >= data.len() {
+        None
+    } else {
+        Some(data[index])
+    }
+}
+
+pub(super) fn bytes_to_u16(bytes: &[u8]) -> Vec<u16> {
+    let mut vals = vec![0u16; bytes.len() / 2];
+    for (i, v) in vals.iter_mut().enumerate() {
+        let index = i * 2;
+        let a = get_val(bytes, index).unwrap_or(0);
+        let b = get_val(bytes, index + 1).unwrap_or(0);
+        *v = ((a as u16) << 8) | (b as u16);
+    }
+    vals
+}
+
+pub(super) fn make_block(id: u16, geometry: u8) -> Block {
+    let mut block = Block::new_id_orientation(id, geometry);
+    if block.is_fluid() && block.geometry == 0 {
+        block.geometry = 7;
+    }
+    block
+}
+
+//Before version 3, blocks are stored as (id << 8) | geometry
+pub(super) fn block_from_u16(block_data: u16) -> Block {
+    make_block(block_data >> 8, (block_data & 0xff) as u8)
+}
+
+//If `wide_ids` is true, each chunk record is
+//[len, ix, iy, iz, counts[len], ids[len], geometries[len]], otherwise it is
+//[len, ix, iy, iz, counts[len], blocks[len]] with u8 ids packed into blocks
+pub(super) fn region_from_bytes(data: &[u16], x: i32, y: i32, z: i32, wide_ids: bool) -> Region {
+    let mut region = Region::new(x, y, z);
+    if data.is_empty() {
+        return region;
+    }
+
+    let mut index = 0;
+    while index < data.len() {
+        let len = get_val(data, index).unwrap_or(0);
+        index += 1;
+        let ix = get_val(data, index).unwrap_or(0);
+        index += 1;
+        let iy = get_val(data, index).unwrap_or(0);
+        index += 1;
+        let iz = get_val(data, index).unwrap_or(0);
+        index += 1;
+        //Ungenerated
+        if len == 0xffff {
+            continue;
+        }
+
+        let len = len as usize;
+        let mut blocks = vec![];
+        let start = index;
+        for i in 0..len {
+            let count = get_val(data, start + i).unwrap_or(0);
+            let block = if wide_ids {
+                let id = get_val(data, start + len + i).unwrap_or(0);
+                let geometry = get_val(data, start + 2 * len + i).unwrap_or(0);
+                make_block(id, geometry as u8)
+            } else {
+                block_from_u16(get_val(data, start + len + i).unwrap_or(0))
+            };
+            blocks.push((count, block));
+        }
+        index += if wide_ids { 3 * len } else { 2 * len };
+
+        let chunkx = x * REGION_SIZE_I32 + ix as i32;
+        let chunky = y * REGION_SIZE_I32 + iy as i32;
+        let chunkz = z * REGION_SIZE_I32 + iz as i32;
+        let chunk = Chunk::from_rle(chunkx, chunky, chunkz, &blocks);
+        region.set_chunk(chunkx, chunky, chunkz, Some(chunk));
+    }
+
+    region
+}
+
+fn load_tile_data(region: &mut Region, tile_data_bytes: &[u8]) -> Result<(), String> {
+    let mut byte_stream = ByteStream::new(tile_data_bytes.to_vec());
+    let parsed = decode_binary_data(&mut byte_stream).map_err(|msg| format!("tile data: {msg}"))?;
+    if let Some(tile_data_list) = parsed.get("tile_data") {
+        tile_data_list
+            .iter()
+            .filter_map(TileData::from_data_table)
+            .for_each(|((x, y, z), tile_data)| {
+                region.set_tile_data(x, y, z, tile_data);
+            });
+    }
+    Ok(())
+}
+
+//Returns the bytes in [start, start + len), clamped to the end of the data
+fn get_slice(bytes: &[u8], start: usize, len: usize) -> &[u8] {
+    let start = start.min(bytes.len());
+    let end = start.saturating_add(len).min(bytes.len());
+    &bytes[start..end]
+}
+
+fn get_u32(bytes: &[u8], index: usize) -> u32 {
+    let mut u32_bytes = [0u8; size_of::<u32>()];
+    for (i, b) in get_slice(bytes, index, size_of::<u32>()).iter().enumerate() {
+        u32_bytes[i] = *b;
+    }
+    u32::from_be_bytes(u32_bytes)
+}
+
+//Version 0: no header or checksums, missing data is treated as zeros
+fn decode_v0(bytes: &[u8], x: i32, y: i32, z: i32) -> Result<Region, String> {
+    let sz = get_u32(bytes, 0) as usize;
+    let block_bytes = get_slice(bytes, size_of::<u32>(), sz);
+    let chunk_data = bytes_to_u16(block_bytes);
+    let mut region = region_from_bytes(&chunk_data, x, y, z, false);
+
+    let index = size_of::<u32>() + sz;
+    let sz = get_u32(bytes, index) as usize;
+    let tile_data_bytes = get_slice(bytes, index + size_of::<u32>(), sz);
+    load_tile_data(&mut region, tile_data_bytes)?;
+
+    Ok(region)
+}
+
+//Version 1: block data section followed by tile data section
+fn decode_v1(bytes: &[u8], x: i32, y: i32, z: i32) -> Result<Region, String> {
+    let (block_bytes, index) =
+        read_section(bytes, 0).map_err(|msg| format!("block data: {msg}"))?;
+    let (tile_data_bytes, _) =
+        read_section(bytes, index).map_err(|msg| format!("tile data: {msg}"))?;
+    let chunk_data = bytes_to_u16(block_bytes);
+    let mut region = region_from_bytes(&chunk_data, x, y, z, false);
+    load_tile_data(&mut region, tile_data_bytes)?;
+    Ok(region)
+}
+
+fn load_scheduled_ticks(region: &mut Region, bytes: &[u8]) -> Result<(), String> {
+    const RECORD_SIZE: usize = 3 * size_of::<i32>() + size_of::<u64>();
+    if !bytes.len().is_multiple_of(RECORD_SIZE) {
+        return Err("scheduled ticks: section is the wrong length".to_string());
+    }
+    for record in bytes.chunks_exact(RECORD_SIZE) {
+        let coord =
+            |i: usize| i32::from_be_bytes([record[i], record[i + 1], record[i + 2], record[i + 3]]);
+        let mut due = [0u8; size_of::<u64>()];
+        due.copy_from_slice(&record[12..]);
+        region.schedule_tick(coord(0), coord(4), coord(8), u64::from_be_bytes(due));
+    }
+    Ok(())
+}
+
+//Version 2: codec id (u8) followed by the block data section (encoded with
+//that codec) and the tile data section
+//Version 3: same as version 2 but block ids are stored as u16
+//Version 4: same as version 3 with a scheduled tick section at the end
+fn decode_with_codec(bytes: &[u8], x: i32, y: i32, z: i32, version: u16) -> Result<Region, String> {
+    let codec_id = *bytes.first().ok_or("codec is missing")?;
+    let codec =
+        RegionCodec::from_id(codec_id).ok_or_else(|| format!("unknown region codec {codec_id}"))?;
+    let (block_bytes, index) =
+        read_section(bytes, 1).map_err(|msg| format!("block data: {msg}"))?;
+    let (tile_data_bytes, index) =
+        read_section(bytes, index).map_err(|msg| format!("tile data: {msg}"))?;
+    let mut region = codec.decode_blocks(block_bytes, x, y, z, version >= 3)?;
+    load_tile_data(&mut region, tile_data_bytes)?;
+    if version >= 4 {
+        let (tick_bytes, _) =
+            read_section(bytes, index).map_err(|msg| format!("scheduled ticks: {msg}"))?;
+        load_scheduled_ticks(&mut region, tick_bytes)?;
+    }
+    Ok(region)
+}
+
+//Decodes the contents of a region file, returns the region along with the
+//version of the format it was stored in
+pub fn decode_region(bytes: &[u8], x: i32, y: i32, z: i32) -> Result<(Region, u16), String> {
+    let (version, data) = read_header(bytes);
+    let mut region = match version {
+        0 => decode_v0(data, x, y, z)?,
+        1 => decode_v1(data, x, y, z)?,
+        2..=4 => decode_with_codec(data, x, y, z, version)?,
+        _ => return Err(format!("unsupported region format version {version}")),
+    };
+    //Scheduled ticks were only saved starting with version 4
+    if version < 4 {
+        region
+            .chunks
+            .iter_mut()
+            .flatten()
+            .for_each(|chunk| chunk.set_unscheduled(true));
+    }
+    Ok((region, version))
+}
+
+//Region files that fail to decode are renamed with this suffix so that they
+//are never overwritten by a region that only has some of its chunks loaded
+pub const CORRUPT_SUFFIX: &str = ".corrupt";
+
+impl Region {
+    //Returns Ok(None) if the region file does not exist or was corrupted
+    //and `quarantine` is set (corrupted files are moved out of the way
+    //first), returns an error if the file exists but could not be read or
+    //moved, in that case the file should not be overwritten
+    //Files in an old format are upgraded in place with `codec`, None leaves
+    //them as they are (for tools that only read the world, these should
+    //also not set `quarantine` so that the files are left alone)
+    pub fn read_region(
+        world_dir_path: &str,
+        x: i32,
+        y: i32,
+        z: i32,
+        codec: Option<RegionCodec>,
+        quarantine: bool,
+    ) -> Result<Option<Self>, String> {
+        let path = world_dir_path.to_string() + CHUNK_PATH + region_file_name(x, y, z).as_str();
+
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("failed to read {path}: {e}")),
+        };
+        match decode_region(&bytes, x, y, z) {
+            Ok((region, version)) => {
+                //Upgrade old region files in place
+                if let Some(codec) = codec.filter(|_| version < REGION_FORMAT_VERSION) {
+                    if region.save_region(world_dir_path, codec).is_err() {
+                        eprintln!("E: Failed to upgrade region {x}, {y}, {z} (version {version})");
+                    }
+                }
+                Ok(Some(region))
+            }
+            Err(msg) if !quarantine => Err(format!("failed to decode {path}: {msg}")),
+            Err(msg) => {
+                eprintln!("E: Failed to load region {x}, {y}, {z}: {msg}");
+                let corrupt_path = path.clone() + CORRUPT_SUFFIX;
+                std::fs::rename(&path, &corrupt_path)
+                    .map_err(|e| format!("failed to move corrupted {path}: {e}"))?;
+                eprintln!("E: Moved corrupted region to {corrupt_path}");
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn load_region(
+        world_dir_path: &str,
+        x: i32,
+        y: i32,
+        z: i32,
+        codec: Option<RegionCodec>,
+        quarantine: bool,
+    ) -> Option<Self> {
+        match Self::read_region(world_dir_path, x, y, z, codec, quarantine) {
+            Ok(region) => region,
+            Err(msg) => {
+                eprintln!("E: {msg}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        inventory::Item,
+        voxel::region::{
+            codec::{ALL_CODECS, DEFAULT_CODEC},
+            format::{LEGACY_REGION_VERSION, REGION_MAGIC},
+            REGION_SIZE,
+        },
+    };
+
+    //Fixture files, one for each version of the region format, all of them
+    //store the region created by `fixture_region`
+    const FIXTURE_V0: &[u8] = include_bytes!("../../../test_regions/region_v0");
+    const FIXTURE_V1: &[u8] = include_bytes!("../../../test_regions/region_v1");
+    const FIXTURE_V2: &[u8] = include_bytes!("../../../test_regions/region_v2");
+    const FIXTURE_V3: &[u8] = include_bytes!("../../../test_regions/region_v3");
+    const FIXTURE_V4: &[u8] = include_bytes!("../../../test_regions/region_v4");
+
+    fn fixture_region() -> Region {
+        let mut region = Region::new(0, 0, 0);
+        let mut chunk = Chunk::new(0, 0, 0);
+        chunk.set_block_relative(0, 0, 0, Block::new_id(1));
+        chunk.set_block_relative(1, 2, 3, Block::new_id_orientation(5, 3));
+        chunk.set_block_relative(15, 15, 15, Block::new_id(2));
+        chunk.set_block_relative(4, 4, 4, Block::new_id(37));
+        let mut chest = TileData::new_chest();
+        chest
+            .inventory
+            .set_item(0, 0, Item::Block(Block::new_id(1), 10));
+        chunk.set_tile_data(4, 4, 4, Some(chest));
+        region.set_chunk(0, 0, 0, Some(chunk));
+        //Generated but empty chunk
+        region.set_chunk(1, 2, 3, Some(Chunk::new(1, 2, 3)));
+        region
+    }
+
+    //Scheduled ticks were added in version 4 so they are not in the fixture
+    //region for older versions
+    fn fixture_region_with_ticks() -> Region {
+        let mut region = fixture_region();
+        region.schedule_tick(1, 2, 3, 40);
+        region.schedule_tick(-1, 0, 0, 12);
+        region.schedule_tick(15, 15, 15, 7);
+        region
+    }
+
+    fn check_scheduled_ticks(region: &Region) {
+        let chunk = region.get_relative_chunk(0, 0, 0).unwrap();
+        let mut ticks: Vec<_> = chunk.scheduled_ticks().collect();
+        ticks.sort();
+        assert_eq!(ticks, vec![((1, 2, 3), 40), ((15, 15, 15), 7)]);
+    }
+
+    fn check_fixture(region: &Region) {
+        let chunk = region.get_relative_chunk(0, 0, 0).unwrap();
+        assert_eq!(chunk.get_block_relative(0, 0, 0), Block::new_id(1));
+        assert_eq!(
+            chunk.get_block_relative(1, 2, 3),
+            Block::new_id_orientation(5, 3)
+        );
+        assert_eq!(chunk.get_block_relative(15, 15, 15), Block::new_id(2));
+        assert_eq!(chunk.get_block_relative(4, 4, 4), Block::new_id(37));
+        assert_eq!(chunk.get_block_relative(8, 8, 8), Block::new());
+        let expected = fixture_region();
+        let expected_chunk = expected.get_relative_chunk(0, 0, 0).unwrap();
+        assert_eq!(chunk.rle_encode(), expected_chunk.rle_encode());
+        let chest = chunk.get_tile_data(4, 4, 4).unwrap();
+        let expected_chest = expected_chunk.get_tile_data(4, 4, 4).unwrap();
+        assert_eq!(
+            chest.inventory.items_to_string(),
+            expected_chest.inventory.items_to_string()
+        );
+
+        let empty = region.get_relative_chunk(1, 2, 3).unwrap();
+        assert!(empty.rle_encode().is_empty());
+
+        for (i, chunk) in region.chunks.iter().enumerate() {
+            let generated = i == 0 || i == REGION_SIZE * REGION_SIZE + 2 * REGION_SIZE + 3;
+            assert_eq!(chunk.is_some(), generated);
+        }
+    }
+
+    #[test]
+    fn test_load_v0_fixture() {
+        let (region, version) = decode_region(FIXTURE_V0, 0, 0, 0).unwrap();
+        assert_eq!(version, LEGACY_REGION_VERSION);
+        check_fixture(&region);
+    }
+
+    #[test]
+    fn test_load_v1_fixture() {
+        let (region, version) = decode_region(FIXTURE_V1, 0, 0, 0).unwrap();
+        assert_eq!(version, 1);
+        check_fixture(&region);
+    }
+
+    #[test]
+    fn test_load_v2_fixture() {
+        let (region, version) = decode_region(FIXTURE_V2, 0, 0, 0).unwrap();
+        assert_eq!(version, 2);
+        check_fixture(&region);
+    }
+
+    #[test]
+    fn test_load_v3_fixture() {
+        let (region, version) = decode_region(FIXTURE_V3, 0, 0, 0).unwrap();
+        assert_eq!(version, 3);
+        check_fixture(&region);
+        assert!(region
+            .chunks
+            .iter()
+            .flatten()
+            .all(|chunk| chunk.is_unscheduled()));
+    }
+
+    #[test]
+    fn test_load_v4_fixture() {
+        let (region, version) = decode_region(FIXTURE_V4, 0, 0, 0).unwrap();
+        assert_eq!(version, 4);
+        check_fixture(&region);
+        assert!(!region
+            .chunks
+            .iter()
+            .flatten()
+            .any(|chunk| chunk.is_unscheduled()));
+        check_scheduled_ticks(&region);
+    }
+
+    #[test]
+    fn test_round_trip_scheduled_ticks() {
+        for codec in ALL_CODECS {
+            let bytes = fixture_region_with_ticks().to_bytes(codec);
+            let (region, _) = decode_region(&bytes, 0, 0, 0).unwrap();
+            check_scheduled_ticks(&region);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for codec in ALL_CODECS {
+            let bytes = fixture_region().to_bytes(codec);
+            assert_eq!(bytes[0..4], REGION_MAGIC);
+            let (region, version) = decode_region(&bytes, 0, 0, 0).unwrap();
+            assert_eq!(version, REGION_FORMAT_VERSION);
+            check_fixture(&region);
+            //Tile data tables are hash maps so the byte order may differ
+            assert_eq!(region.to_bytes(codec).len(), bytes.len());
+        }
+    }
+
+    #[test]
+    fn test_round_trip_wide_ids() {
+        let mut region = fixture_region();
+        let mut chunk = region.get_relative_chunk(0, 0, 0).unwrap().clone();
+        chunk.set_block_relative(2, 0, 0, Block::new_id(300));
+        chunk.set_block_relative(3, 0, 0, Block::new_id_orientation(u16::MAX, 5));
+        region.set_chunk(0, 0, 0, Some(chunk));
+        for codec in ALL_CODECS {
+            let bytes = region.to_bytes(codec);
+            let (decoded, _) = decode_region(&bytes, 0, 0, 0).unwrap();
+            let chunk = decoded.get_relative_chunk(0, 0, 0).unwrap();
+            assert_eq!(chunk.get_block_relative(2, 0, 0), Block::new_id(300));
+            assert_eq!(
+                chunk.get_block_relative(3, 0, 0),
+                Block::new_id_orientation(u16::MAX, 5)
+            );
+            assert_eq!(chunk.get_block_relative(4, 4, 4), Block::new_id(37));
+        }
+    }
+
+    #[test]
+    fn test_upgrade() {
+        for fixture in [FIXTURE_V0, FIXTURE_V1, FIXTURE_V2, FIXTURE_V3, FIXTURE_V4] {
+            let (region, _) = decode_region(fixture, 0, 0, 0).unwrap();
+            let upgraded = region.to_bytes(DEFAULT_CODEC);
+            let (region, version) = decode_region(&upgraded, 0, 0, 0).unwrap();
+            assert_eq!(version, REGION_FORMAT_VERSION);
+            check_fixture(&region);
+        }
+    }
+
+    #[test]
+    fn test_load_corrupted() {
+        for codec in ALL_CODECS {
+            let mut bytes = fixture_region().to_bytes(codec);
+            let index = bytes.len() / 2;
+            bytes[index] ^= 0xff;
+            assert!(decode_region(&bytes, 0, 0, 0).is_err());
+
+            let bytes = fixture_region().to_bytes(codec);
+            assert!(decode_region(&bytes[..bytes.len() - 1], 0, 0, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_read_corrupted() {
+        let dir = std::env::temp_dir().join(format!("voxelworld_read_{}", std::process::id()));
+        let world_path = format!("{}/", dir.display());
+        let chunk_dir = world_path.clone() + CHUNK_PATH;
+        std::fs::create_dir_all(&chunk_dir).unwrap();
+        let mut bytes = fixture_region().to_bytes(DEFAULT_CODEC);
+        let index = bytes.len() / 2;
+        bytes[index] ^= 0xff;
+        let path = chunk_dir + region_file_name(0, 0, 0).as_str();
+        let corrupt_path = path.clone() + CORRUPT_SUFFIX;
+        std::fs::write(&path, &bytes).unwrap();
+
+        //Tools that only read the world leave the file where it is
+        assert!(Region::read_region(&world_path, 0, 0, 0, None, false).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), bytes);
+        assert!(!std::path::Path::new(&corrupt_path).exists());
+
+        //Loading the world to play it moves the file out of the way
+        let region = Region::read_region(&world_path, 0, 0, 0, Some(DEFAULT_CODEC), true);
+        assert!(region.unwrap().is_none());
+        assert!(!std::path::Path::new(&path).exists());
+        assert_eq!(std::fs::read(&corrupt_path).unwrap(), bytes);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_unknown_codec() {
+        let mut bytes = fixture_region().to_bytes(DEFAULT_CODEC);
+        bytes[6] = 255;
+        assert!(decode_region(&bytes, 0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_load_unsupported_version() {
+        let mut bytes = fixture_region().to_bytes(DEFAULT_CODEC);
+        bytes[4..6].copy_from_slice(&u16::MAX.to_be_bytes());
+        assert!(decode_region(&bytes, 0, 0, 0).is_err());
+    }
+
+    //Run with `cargo test write_region_fixture -- --ignored` to create the
+    //fixture for the current version after bumping REGION_FORMAT_VERSION
+    #[test]
+    #[ignore]
+    fn write_region_fixture() {
+        let path = format!("test_regions/region_v{REGION_FORMAT_VERSION}");
+        let bytes = fixture_region_with_ticks().to_bytes(DEFAULT_CODEC);
+        std::fs::write(path, bytes).unwrap();
+    }
+}
@@ -0,0 +1,214 @@
+use super::{
+    codec::RegionCodec,
+    format::{write_header, write_section, REGION_FORMAT_VERSION},
+    Region, REGION_SIZE,
+};
+use crate::{
+    bin_data::get_table_list_bytes,
+    save::{save_bytes_atomic, CHUNK_PATH},
+};
+use std::path::Path;
+
+pub fn region_file_name(x: i32, y: i32, z: i32) -> String {
+    format!("region_{x}_{y}_{z}")
+}
+
+//Inverse of region_file_name, returns None for anything that is not a region
+pub fn parse_region_file_name(name: &str) -> Option<(i32, i32, i32)> {
+    let coords: Vec<i32> = name
+        .strip_prefix("region_")?
+        .split('_')
+        .map(|c| c.parse::<i32>().ok())
+        .collect::<Option<Vec<i32>>>()?;
+    match coords[..] {
+        [x, y, z] => Some((x, y, z)),
+        _ => None,
+    }
+}
+
+impl Region {
+    fn get_chunk_data(&self, ix: usize, iy: usize, iz: usize) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        if let Some(chunk) = self.get_relative_chunk(ix, iy, iz) {
+            chunk.get_chunk_bytes()
+        } else {
+            (0xffffu16.to_be_bytes().to_vec(), vec![], vec![])
+        }
+    }
+
+    //Block data section: a list of RLE encoded chunks, each chunk is
+    //[len, ix, iy, iz, counts[len], ids[len], geometries[len]] (all u16)
+    pub(super) fn get_block_bytes(&self) -> Vec<u8> {
+        let mut block_bytes = vec![];
+        for ix in 0..REGION_SIZE {
+            for iy in 0..REGION_SIZE {
+                for iz in 0..REGION_SIZE {
+                    let (len, counts, data) = self.get_chunk_data(ix, iy, iz);
+                    block_bytes.extend(len);
+                    block_bytes.extend((ix as u16).to_be_bytes());
+                    block_bytes.extend((iy as u16).to_be_bytes());
+                    block_bytes.extend((iz as u16).to_be_bytes());
+                    block_bytes.extend(counts);
+                    block_bytes.extend(data);
+                }
+            }
+        }
+        block_bytes
+    }
+
+    fn get_tile_data_bytes(&self) -> Vec<u8> {
+        let mut tile_data = vec![];
+        for chunk in self.chunks.iter().flatten() {
+            tile_data.extend(chunk.tiles_to_data_tables());
+        }
+
+        if tile_data.is_empty() {
+            return vec![];
+        }
+
+        get_table_list_bytes("tile_data", &tile_data)
+    }
+
+    //Scheduled tick section: [x, y, z (i32), due (u64)] for each scheduled
+    //block, sorted so that saving the same region gives the same bytes
+    fn get_scheduled_tick_bytes(&self) -> Vec<u8> {
+        let mut ticks: Vec<((i32, i32, i32), u64)> = self
+            .chunks
+            .iter()
+            .flatten()
+            .flat_map(|chunk| chunk.scheduled_ticks())
+            .collect();
+        ticks.sort();
+
+        let mut bytes = vec![];
+        for ((x, y, z), due) in ticks {
+            bytes.extend(x.to_be_bytes());
+            bytes.extend(y.to_be_bytes());
+            bytes.extend(z.to_be_bytes());
+            bytes.extend(due.to_be_bytes());
+        }
+        bytes
+    }
+
+    //Encodes the region in the current region file format
+    pub fn to_bytes(&self, codec: RegionCodec) -> Vec<u8> {
+        let mut bytes = vec![];
+        write_header(&mut bytes, REGION_FORMAT_VERSION);
+        bytes.push(codec.id());
+        write_section(&mut bytes, &codec.encode_blocks(self));
+        write_section(&mut bytes, &self.get_tile_data_bytes());
+        write_section(&mut bytes, &self.get_scheduled_tick_bytes());
+        bytes
+    }
+
+    pub fn save_region(&self, worldpath: &str, codec: RegionCodec) -> Result<(), String> {
+        let chunk_dir_path = worldpath.to_string() + CHUNK_PATH;
+        let chunk_path = chunk_dir_path.clone() + region_file_name(self.x, self.y, self.z).as_str();
+        if !Path::new(&chunk_dir_path).exists() {
+            if let Err(msg) = std::fs::create_dir_all(&chunk_dir_path) {
+                eprintln!("E: Failed to create chunk dir");
+                eprintln!("{msg}");
+                return Err(chunk_path);
+            }
+        }
+
+        let bytes = self.to_bytes(codec);
+        if let Err(msg) = save_bytes_atomic(&chunk_path, &bytes) {
+            eprintln!("Error when saving {}, {}, {}", self.x, self.y, self.z);
+            eprintln!("E: {msg}");
+            return Err(chunk_path);
+        }
+
+        Ok(())
+    }
+}
+
+fn merge_regions(updated: &mut Region, original: &Region) {
+    for ix in 0..REGION_SIZE {
+        for iy in 0..REGION_SIZE {
+            for iz in 0..REGION_SIZE {
+                if updated.get_relative_chunk(ix, iy, iz).is_some() {
+                    continue;
+                }
+
+                if let Some(chunk) = original.get_relative_chunk(ix, iy, iz) {
+                    updated.set_relative_chunk(ix, iy, iz, Some(chunk.clone()));
+                }
+            }
+        }
+    }
+}
+
+pub fn serialize_region(
+    world_dir_path: &str,
+    region: &Region,
+    codec: RegionCodec,
+) -> Result<(), String> {
+    //Nothing to merge if every chunk in the region is loaded
+    if region.chunks.iter().all(|chunk| chunk.is_some()) {
+        return region.save_region(world_dir_path, codec);
+    }
+
+    let mut region_clone = region.clone();
+    let x = region.x;
+    let y = region.y;
+    let z = region.z;
+
+    //If the original file can not be read then the chunks that are not loaded
+    //would be lost by overwriting it
+    if let Some(original) = Region::read_region(world_dir_path, x, y, z, None, true)? {
+        merge_regions(&mut region_clone, &original);
+    }
+
+    region_clone.save_region(world_dir_path, codec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{
+        region::{codec::DEFAULT_CODEC, load::CORRUPT_SUFFIX},
+        Chunk,
+    };
+
+    #[test]
+    fn test_region_file_name() {
+        assert_eq!(
+            parse_region_file_name(&region_file_name(1, -2, 30)),
+            Some((1, -2, 30))
+        );
+        assert_eq!(parse_region_file_name("region_1_2"), None);
+        assert_eq!(parse_region_file_name("region_1_2_3_4"), None);
+        assert_eq!(parse_region_file_name("region_1_2_3.corrupt"), None);
+        assert_eq!(parse_region_file_name("region_1_2_3.tmp"), None);
+    }
+
+    #[test]
+    fn test_serialize_corrupted_region() {
+        let dir = std::env::temp_dir().join(format!("voxelworld_corrupt_{}", std::process::id()));
+        let world_path = format!("{}/", dir.display());
+        let chunk_dir = world_path.clone() + CHUNK_PATH;
+        std::fs::create_dir_all(&chunk_dir).unwrap();
+
+        let mut original = Region::new(0, 0, 0);
+        original.set_chunk(0, 0, 0, Some(Chunk::new(0, 0, 0)));
+        original.set_chunk(1, 0, 0, Some(Chunk::new(1, 0, 0)));
+        let mut bytes = original.to_bytes(DEFAULT_CODEC);
+        let index = bytes.len() / 2;
+        bytes[index] ^= 0xff;
+        let path = chunk_dir + region_file_name(0, 0, 0).as_str();
+        std::fs::write(&path, &bytes).unwrap();
+
+        //Saving a partially loaded region keeps the corrupted file around
+        //instead of overwriting the chunks that could not be loaded
+        let mut partial = Region::new(0, 0, 0);
+        partial.set_chunk(0, 0, 0, Some(Chunk::new(0, 0, 0)));
+        serialize_region(&world_path, &partial, DEFAULT_CODEC).unwrap();
+        let corrupt_path = path.clone() + CORRUPT_SUFFIX;
+        assert_eq!(std::fs::read(&corrupt_path).unwrap(), bytes);
+        let saved = Region::load_region(&world_path, 0, 0, 0, None, false).unwrap();
+        assert!(saved.get_relative_chunk(0, 0, 0).is_some());
+        assert!(saved.get_relative_chunk(1, 0, 0).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,568 @@
+/*
+ * Schematics are copies of an axis aligned box of blocks (along with any tile
+ * data in it, such as chest inventories) that can be saved to a file and
+ * pasted back into any world, this allows builds to be moved between worlds.
+ *
+ * Schematics are stored with bin_data in the `schematics/` directory of a
+ * save, see `Schematic::to_bytes` for the format.
+ * */
+
+use super::{
+    block_ids::FENCE,
+    tile_data::TileData,
+    world::{block_update::get_chunktable_updates, World},
+    Block, EMPTY_BLOCK, FULL_BLOCK,
+};
+use crate::{
+    bin_data::{decode_binary_data, get_table_list_bytes, ByteStream, DataTable},
+    save::{save_bytes_atomic, SCHEMATICS_PATH},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+pub const SCHEMATIC_EXTENSION: &str = ".schematic";
+const SCHEMATIC_VERSION: i64 = 1;
+//Schematics can not be larger than this along any axis
+pub const MAX_SCHEMATIC_SIZE: usize = 256;
+//Each block is stored as its id (u16) followed by its geometry (u8)
+const BYTES_PER_BLOCK: usize = 3;
+
+//Maps an orientation to the orientation after a quarter turn about the y axis
+//(+x -> +z -> -x -> -z), up and down are not changed
+const ROTATE_ORIENTATION: [u8; 6] = [0, 2, 4, 3, 5, 1];
+//Maps an orientation to the orientation after mirroring along the x axis
+const MIRROR_ORIENTATION: [u8; 6] = [0, 4, 2, 3, 1, 5];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SchematicTransform {
+    //Number of quarter turns about the y axis, each quarter turn maps +x to +z
+    pub rotation: u8,
+    //Mirror along the x axis (this is applied before the rotation)
+    pub mirror: bool,
+}
+
+impl SchematicTransform {
+    pub fn new(rotation: u8, mirror: bool) -> Self {
+        Self {
+            rotation: rotation % 4,
+            mirror,
+        }
+    }
+
+    //Returns the block with its orientation transformed, fluids use the
+    //orientation bits for their level and fences use the geometry to store
+    //which blocks they connect to so those are not changed (fences will be
+    //reconnected by block updates)
+    pub fn transform_block(&self, mut block: Block) -> Block {
+        if block.is_fluid() || block.id == FENCE {
+            return block;
+        }
+
+        let has_orientation =
+            block.can_rotate() || block.shape() != FULL_BLOCK || block.non_voxel_geometry();
+        let orientation = block.orientation();
+        if !has_orientation || orientation > 5 {
+            return block;
+        }
+
+        let mut orientation = if self.mirror {
+            MIRROR_ORIENTATION[orientation as usize]
+        } else {
+            orientation
+        };
+        for _ in 0..(self.rotation % 4) {
+            orientation = ROTATE_ORIENTATION[orientation as usize];
+        }
+        block.set_orientation(orientation);
+        block
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Schematic {
+    width: usize,
+    height: usize,
+    depth: usize,
+    //Stored in x, z, y order (x changes the fastest)
+    blocks: Vec<Block>,
+    //Positions are relative to the minimum corner of the schematic
+    tile_data: HashMap<(i32, i32, i32), TileData>,
+}
+
+impl Schematic {
+    //Creates a schematic filled with empty blocks
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+            blocks: vec![Block::new(); width * height * depth],
+            tile_data: HashMap::new(),
+        }
+    }
+
+    //Returns (width, height, depth)
+    pub fn size(&self) -> (usize, usize, usize) {
+        (self.width, self.height, self.depth)
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height || z >= self.depth {
+            return None;
+        }
+        Some(y * self.width * self.depth + z * self.width + x)
+    }
+
+    //Returns an empty block if the position is out of bounds
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> Block {
+        self.index(x, y, z)
+            .map(|i| self.blocks[i])
+            .unwrap_or_default()
+    }
+
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block: Block) {
+        if let Some(i) = self.index(x, y, z) {
+            self.blocks[i] = block;
+        }
+    }
+
+    pub fn get_tile_data(&self, x: usize, y: usize, z: usize) -> Option<&TileData> {
+        self.tile_data.get(&(x as i32, y as i32, z as i32))
+    }
+
+    //None clears the tile data at that position
+    pub fn set_tile_data(&mut self, x: usize, y: usize, z: usize, tile_data: Option<TileData>) {
+        if self.index(x, y, z).is_none() {
+            return;
+        }
+        let pos = (x as i32, y as i32, z as i32);
+        match tile_data {
+            Some(tile_data) => self.tile_data.insert(pos, tile_data),
+            None => self.tile_data.remove(&pos),
+        };
+    }
+
+    //Returns the number of blocks that are not empty
+    pub fn block_count(&self) -> usize {
+        self.blocks.iter().filter(|b| b.id != EMPTY_BLOCK).count()
+    }
+
+    //Returns a copy of the schematic that is mirrored and rotated
+    pub fn transformed(&self, transform: SchematicTransform) -> Self {
+        let (w, h, d) = if transform.rotation % 2 == 1 {
+            (self.depth, self.height, self.width)
+        } else {
+            (self.width, self.height, self.depth)
+        };
+
+        //Maps a position in this schematic to its position in the new one
+        let transform_pos = |x: usize, z: usize| {
+            let (mut x, mut z) = (x, z);
+            if transform.mirror {
+                x = self.width - 1 - x;
+            }
+            let (mut cur_w, mut cur_d) = (self.width, self.depth);
+            for _ in 0..(transform.rotation % 4) {
+                //(x, z) -> (-z, x)
+                (x, z) = (cur_d - 1 - z, x);
+                (cur_w, cur_d) = (cur_d, cur_w);
+            }
+            (x, z)
+        };
+
+        let mut transformed = Self::new(w, h, d);
+        for y in 0..self.height {
+            for z in 0..self.depth {
+                for x in 0..self.width {
+                    let (tx, tz) = transform_pos(x, z);
+                    let block = transform.transform_block(self.get_block(x, y, z));
+                    transformed.set_block(tx, y, tz, block);
+                }
+            }
+        }
+
+        for (&(x, y, z), tile_data) in &self.tile_data {
+            let (tx, tz) = transform_pos(x as usize, z as usize);
+            transformed.set_tile_data(tx, y as usize, tz, Some(tile_data.clone()));
+        }
+
+        transformed
+    }
+
+    //The schematic is stored as a "schematic" table list with a single table
+    //that has the version, dimensions and blocks (see `BYTES_PER_BLOCK`)
+    //followed by a "tile_data" table list
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut header = DataTable::new();
+        header.add_int("version", SCHEMATIC_VERSION);
+        header.add_int("width", self.width as i64);
+        header.add_int("height", self.height as i64);
+        header.add_int("depth", self.depth as i64);
+        let mut block_bytes = Vec::with_capacity(self.blocks.len() * BYTES_PER_BLOCK);
+        for block in &self.blocks {
+            block_bytes.extend(block.id.to_be_bytes());
+            block_bytes.push(block.geometry);
+        }
+        header.add_bytes("blocks", &block_bytes);
+
+        let tile_data: Vec<DataTable> = self
+            .tile_data
+            .iter()
+            .map(|((x, y, z), tile_data)| tile_data.to_data_table(*x, *y, *z))
+            .collect();
+
+        let mut bytes = get_table_list_bytes("schematic", &[header]);
+        bytes.extend(get_table_list_bytes("tile_data", &tile_data));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut stream = ByteStream::new(bytes.to_vec());
+        let parsed = decode_binary_data(&mut stream)?;
+        let header = parsed
+            .get("schematic")
+            .and_then(|tables| tables.first())
+            .ok_or("missing schematic header")?;
+
+        let version = header.get_int("version").ok_or("missing version")?;
+        if version > SCHEMATIC_VERSION {
+            return Err(format!("unsupported schematic version {version}"));
+        }
+        let get_dimension = |name: &str| -> Result<usize, String> {
+            let val = header.get_int(name).ok_or(format!("missing {name}"))?;
+            if val <= 0 || val as usize > MAX_SCHEMATIC_SIZE {
+                return Err(format!("invalid {name} {val}"));
+            }
+            Ok(val as usize)
+        };
+        let width = get_dimension("width")?;
+        let height = get_dimension("height")?;
+        let depth = get_dimension("depth")?;
+
+        let block_bytes = header.get_bytes("blocks").ok_or("missing blocks")?;
+        if block_bytes.len() != width * height * depth * BYTES_PER_BLOCK {
+            return Err(format!(
+                "expected {} blocks but found {} bytes",
+                width * height * depth,
+                block_bytes.len()
+            ));
+        }
+        let blocks = block_bytes
+            .chunks_exact(BYTES_PER_BLOCK)
+            .map(|b| Block::new_id_orientation(u16::from_be_bytes([b[0], b[1]]), b[2]))
+            .collect();
+
+        let mut schematic = Self {
+            width,
+            height,
+            depth,
+            blocks,
+            tile_data: HashMap::new(),
+        };
+        let tile_data_list = parsed.get("tile_data").map(|l| l.as_slice()).unwrap_or(&[]);
+        for ((x, y, z), tile_data) in tile_data_list.iter().filter_map(TileData::from_data_table) {
+            if x < 0 || y < 0 || z < 0 {
+                continue;
+            }
+            schematic.set_tile_data(x as usize, y as usize, z as usize, Some(tile_data));
+        }
+
+        Ok(schematic)
+    }
+}
+
+//Schematic names can only have letters, numbers, '-' and '_' so that they
+//can not escape the schematics directory
+pub fn valid_schematic_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub fn get_schematic_dir(world_path: &str) -> String {
+    world_path.to_string() + SCHEMATICS_PATH
+}
+
+pub fn get_schematic_path(world_path: &str, name: &str) -> Result<String, String> {
+    if !valid_schematic_name(name) {
+        return Err(format!("invalid schematic name \"{name}\""));
+    }
+    Ok(get_schematic_dir(world_path) + name + SCHEMATIC_EXTENSION)
+}
+
+//Returns the names of the schematics saved in a world, sorted alphabetically
+pub fn list_schematics(world_path: &str) -> Vec<String> {
+    let Ok(dir_contents) = std::fs::read_dir(get_schematic_dir(world_path)) else {
+        return vec![];
+    };
+
+    let mut names: Vec<String> = dir_contents
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let name = file_name.strip_suffix(SCHEMATIC_EXTENSION)?;
+            if valid_schematic_name(name) {
+                Some(name.to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+pub fn save_schematic(world_path: &str, name: &str, schematic: &Schematic) -> Result<(), String> {
+    let path = get_schematic_path(world_path, name)?;
+    let dir = get_schematic_dir(world_path);
+    if !Path::new(&dir).exists() {
+        std::fs::create_dir_all(&dir).map_err(|msg| format!("{dir}: {msg}"))?;
+    }
+    save_bytes_atomic(&path, &schematic.to_bytes())
+}
+
+pub fn load_schematic(world_path: &str, name: &str) -> Result<Schematic, String> {
+    let path = get_schematic_path(world_path, name)?;
+    let bytes = std::fs::read(&path).map_err(|msg| format!("{path}: {msg}"))?;
+    Schematic::from_bytes(&bytes).map_err(|msg| format!("{path}: {msg}"))
+}
+
+impl World {
+    //Copies the blocks in the box between two corners (inclusive) into a
+    //schematic, any blocks that are not loaded are copied as empty blocks
+    pub fn copy_schematic(
+        &self,
+        corner1: (i32, i32, i32),
+        corner2: (i32, i32, i32),
+    ) -> Result<Schematic, String> {
+        let (x1, y1, z1) = corner1;
+        let (x2, y2, z2) = corner2;
+        let (minx, miny, minz) = (x1.min(x2), y1.min(y2), z1.min(z2));
+        let width = x1.abs_diff(x2) as usize + 1;
+        let height = y1.abs_diff(y2) as usize + 1;
+        let depth = z1.abs_diff(z2) as usize + 1;
+        if width.max(height).max(depth) > MAX_SCHEMATIC_SIZE {
+            return Err(format!(
+                "selection is too large ({width}x{height}x{depth}), schematics can be at most {MAX_SCHEMATIC_SIZE} blocks along each axis"
+            ));
+        }
+
+        let mut schematic = Schematic::new(width, height, depth);
+        for y in 0..height {
+            for z in 0..depth {
+                for x in 0..width {
+                    let (wx, wy, wz) = (minx + x as i32, miny + y as i32, minz + z as i32);
+                    schematic.set_block(x, y, z, self.get_block(wx, wy, wz));
+                    schematic.set_tile_data(x, y, z, self.get_tile_data(wx, wy, wz));
+                }
+            }
+        }
+        Ok(schematic)
+    }
+
+    //Pastes a schematic with its minimum corner at `pos` after transforming
+    //it, every block in the box is replaced (including with empty blocks).
+    //Light is updated and the chunks that were changed are added to the mesh
+    //updates (see `take_mesh_updates`).
+    pub fn paste_schematic(
+        &mut self,
+        schematic: &Schematic,
+        pos: (i32, i32, i32),
+        transform: SchematicTransform,
+    ) {
+        let schematic = schematic.transformed(transform);
+        let (x, y, z) = pos;
+        let (width, height, depth) = schematic.size();
+        let mut update_mesh = HashSet::new();
+        let mut light_updates = vec![];
+        for iy in 0..height {
+            for iz in 0..depth {
+                for ix in 0..width {
+                    let (wx, wy, wz) = (x + ix as i32, y + iy as i32, z + iz as i32);
+                    let block = schematic.get_block(ix, iy, iz);
+                    let tile_data = schematic.get_tile_data(ix, iy, iz).cloned();
+                    if self.get_block(wx, wy, wz) == block
+                        && tile_data.is_none()
+                        && self.get_tile_data(wx, wy, wz).is_none()
+                    {
+                        continue;
+                    }
+                    get_chunktable_updates(wx, wy, wz, &mut update_mesh);
+                    self.set_block(wx, wy, wz, block);
+                    self.set_tile_data(wx, wy, wz, tile_data);
+                    light_updates.push((wx, wy, wz));
+                }
+            }
+        }
+
+        update_mesh.extend(self.update_block_light(&light_updates));
+        self.add_mesh_updates(update_mesh);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        inventory::{item_to_string, Item},
+        voxel::{
+            block_ids::{CHEST, LOG, STONE, WATER},
+            world::{terrain_generator::FlatGenerator, GameMode},
+            SLAB,
+        },
+    };
+
+    fn test_world() -> World {
+        World::new(0, 1, &FlatGenerator, GameMode::Creative)
+    }
+
+    fn stair(orientation: u8) -> Block {
+        let mut block = Block::new_id(STONE);
+        block.set_shape(2);
+        block.set_orientation(orientation);
+        block
+    }
+
+    #[test]
+    fn test_transform_block() {
+        let rotate = SchematicTransform::new(1, false);
+        let orientations: Vec<u8> = (0..6)
+            .map(|o| rotate.transform_block(stair(o)).orientation())
+            .collect();
+        assert_eq!(orientations, vec![0, 2, 4, 3, 5, 1]);
+
+        //Four quarter turns do nothing
+        for o in 0..6 {
+            let mut block = stair(o);
+            for _ in 0..4 {
+                block = rotate.transform_block(block);
+            }
+            assert_eq!(block, stair(o));
+        }
+
+        //Shape and reflection are kept
+        let mut block = stair(1);
+        block.set_reflection(1);
+        let transformed = SchematicTransform::new(0, true).transform_block(block);
+        assert_eq!(transformed.orientation(), 4);
+        assert_eq!(transformed.reflection(), 1);
+        assert_eq!(transformed.shape(), 2);
+
+        //Fluid levels are not orientations
+        let mut water = Block::new_id(WATER);
+        water.geometry = 4;
+        assert_eq!(rotate.transform_block(water), water);
+    }
+
+    #[test]
+    fn test_schematic_transform() {
+        let mut schematic = Schematic::new(3, 1, 2);
+        schematic.set_block(0, 0, 0, Block::new_id(LOG));
+        schematic.set_block(2, 0, 1, stair(1));
+        schematic.set_tile_data(2, 0, 1, Some(TileData::new_chest()));
+
+        let rotated = schematic.transformed(SchematicTransform::new(1, false));
+        assert_eq!(rotated.size(), (2, 1, 3));
+        //(x, z) -> (depth - 1 - z, x)
+        assert_eq!(rotated.get_block(1, 0, 0).id, LOG);
+        assert_eq!(rotated.get_block(0, 0, 2), stair(2));
+        assert!(rotated.get_tile_data(0, 0, 2).is_some());
+        assert_eq!(rotated.block_count(), 2);
+
+        let mirrored = schematic.transformed(SchematicTransform::new(0, true));
+        assert_eq!(mirrored.size(), (3, 1, 2));
+        assert_eq!(mirrored.get_block(2, 0, 0).id, LOG);
+        assert_eq!(mirrored.get_block(0, 0, 1), stair(4));
+
+        //Rotating all the way around gives back the same schematic
+        let mut turned = schematic.clone();
+        for _ in 0..4 {
+            turned = turned.transformed(SchematicTransform::new(1, false));
+        }
+        assert_eq!(turned.blocks, schematic.blocks);
+    }
+
+    #[test]
+    fn test_schematic_bytes() {
+        let mut schematic = Schematic::new(2, 3, 4);
+        schematic.set_block(1, 2, 3, stair(5));
+        let mut slab = Block::new_id(STONE);
+        slab.set_shape(SLAB);
+        schematic.set_block(0, 1, 0, slab);
+        let mut chest = TileData::new_chest();
+        chest.inventory.set_item(0, 0, Item::Sprite(3, 7));
+        schematic.set_tile_data(1, 0, 1, Some(chest));
+
+        let loaded = Schematic::from_bytes(&schematic.to_bytes()).unwrap();
+        assert_eq!(loaded.size(), (2, 3, 4));
+        assert_eq!(loaded.blocks, schematic.blocks);
+        let chest = loaded.get_tile_data(1, 0, 1).unwrap();
+        assert_eq!(item_to_string(chest.inventory.get_item(0, 0)), "item,3,7");
+
+        //Truncated and empty data is rejected
+        let bytes = schematic.to_bytes();
+        assert!(Schematic::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+        assert!(Schematic::from_bytes(&[]).is_err());
+    }
+
+    #[test]
+    fn test_copy_paste() {
+        let mut world = test_world();
+        world.set_block(0, 0, 0, Block::new_id(LOG));
+        world.set_block(1, 0, 0, stair(1));
+        world.set_block(1, 1, 2, Block::new_id(CHEST));
+        let mut chest = TileData::new_chest();
+        chest.inventory.set_item(1, 1, Item::Sprite(3, 7));
+        world.set_tile_data(1, 1, 2, Some(chest));
+
+        //Corners can be given in any order
+        let schematic = world.copy_schematic((1, 1, 2), (0, 0, 0)).unwrap();
+        assert_eq!(schematic.size(), (2, 2, 3));
+        assert_eq!(schematic.block_count(), 3);
+
+        //Paste across a chunk border with a half turn
+        world.paste_schematic(&schematic, (14, 0, 14), SchematicTransform::new(2, false));
+        assert_eq!(world.get_block(15, 0, 16).id, LOG);
+        assert_eq!(world.get_block(14, 0, 16), stair(4));
+        assert_eq!(world.get_block(14, 1, 14).id, CHEST);
+        let chest = world.get_tile_data(14, 1, 14).unwrap();
+        assert_eq!(item_to_string(chest.inventory.get_item(1, 1)), "item,3,7");
+        assert!(!world.take_mesh_updates().is_empty());
+
+        //Pasting replaces the old blocks and tile data
+        let empty = Schematic::new(2, 2, 3);
+        world.paste_schematic(&empty, (14, 0, 14), SchematicTransform::default());
+        assert_eq!(world.get_block(14, 1, 14).id, EMPTY_BLOCK);
+        assert!(world.get_tile_data(14, 1, 14).is_none());
+
+        let too_large = world.copy_schematic((0, 0, 0), (MAX_SCHEMATIC_SIZE as i32, 0, 0));
+        assert!(too_large.is_err());
+    }
+
+    #[test]
+    fn test_schematic_files() {
+        let world_path = std::env::temp_dir()
+            .join(format!("voxelworld_schematics_{}", std::process::id()))
+            .to_string_lossy()
+            .to_string()
+            + "/";
+        let _ = std::fs::remove_dir_all(&world_path);
+
+        let mut schematic = Schematic::new(1, 2, 1);
+        schematic.set_block(0, 1, 0, Block::new_id(LOG));
+        assert!(list_schematics(&world_path).is_empty());
+        save_schematic(&world_path, "house_2", &schematic).unwrap();
+        save_schematic(&world_path, "bridge", &schematic).unwrap();
+        assert_eq!(list_schematics(&world_path), vec!["bridge", "house_2"]);
+        let loaded = load_schematic(&world_path, "house_2").unwrap();
+        assert_eq!(loaded.blocks, schematic.blocks);
+
+        assert!(save_schematic(&world_path, "../escape", &schematic).is_err());
+        assert!(save_schematic(&world_path, "", &schematic).is_err());
+        assert!(load_schematic(&world_path, "missing").is_err());
+        std::fs::remove_dir_all(&world_path).unwrap();
+    }
+}
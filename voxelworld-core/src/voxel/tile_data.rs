@@ -5,7 +5,7 @@
 
 use crate::{
     bin_data::{DataTable, DataType},
-    game::inventory::{Inventory, Item},
+    inventory::{Inventory, Item},
 };
 use std::collections::HashMap;
 
@@ -15,6 +15,12 @@ pub struct TileData {
     pub values: HashMap<String, DataType>,
 }
 
+impl Default for TileData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TileData {
     pub fn new() -> Self {
         Self {
@@ -123,9 +129,7 @@ impl TileData {
         data_table.add_int("x", x as i64);
         data_table.add_int("y", y as i64);
         data_table.add_int("z", z as i64);
-        data_table.add_int("w", self.inventory.w() as i64);
-        data_table.add_int("h", self.inventory.h() as i64);
-        data_table.add_str("items", &self.inventory.items_to_string());
+        data_table.add_table("inventory", self.inventory.to_data_table());
         data_table
     }
 
@@ -135,16 +139,25 @@ impl TileData {
         let y = data_table.get_int("y")? as i32;
         let z = data_table.get_int("z")? as i32;
 
-        let w = data_table.get_int("w").unwrap_or(1) as usize;
-        let h = data_table.get_int("h").unwrap_or(1) as usize;
-        let items_str = data_table.get_str("items").unwrap_or("".to_string());
-        let inventory = Inventory::from_data(&items_str, w, h);
+        let inventory = match data_table.get_table("inventory") {
+            Some(inventory_table) => Inventory::from_data_table(inventory_table),
+            //Older worlds store the inventory as a string of items
+            None => {
+                let w = data_table.get_int("w").unwrap_or(1) as usize;
+                let h = data_table.get_int("h").unwrap_or(1) as usize;
+                let items_str = data_table.get_str("items").unwrap_or("".to_string());
+                Inventory::from_data(&items_str, w, h)
+            }
+        };
 
         let mut tile_data = Self::new();
         tile_data.inventory = inventory;
         for (name, val) in data_table.get_all_vals() {
             //Reserved values
-            if matches!(name.as_str(), "x" | "y" | "z" | "w" | "h" | "items") {
+            if matches!(
+                name.as_str(),
+                "x" | "y" | "z" | "w" | "h" | "items" | "inventory"
+            ) {
                 continue;
             }
             tile_data.values.insert(name.to_string(), val.clone());
@@ -169,3 +182,54 @@ impl TileData {
         self.values.remove(name);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        inventory::item_to_string,
+        voxel::{block_ids::DIRT, Block},
+    };
+
+    fn item_strings(tile_data: &TileData) -> Vec<String> {
+        tile_data
+            .get_items()
+            .into_iter()
+            .map(item_to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_tile_data_table() {
+        let mut tile_data = TileData::new_furance();
+        tile_data.inventory.set_item(0, 0, Item::Sprite(5, 3));
+        tile_data
+            .inventory
+            .set_item(2, 0, Item::Block(Block::new_id(DIRT), 10));
+        tile_data.set_float("fuel", 1.5);
+
+        let data_table = tile_data.to_data_table(1, -2, 3);
+        let ((x, y, z), loaded) = TileData::from_data_table(&data_table).unwrap();
+        assert_eq!((x, y, z), (1, -2, 3));
+        assert_eq!(item_strings(&loaded), item_strings(&tile_data));
+        assert_eq!(loaded.get_float("fuel"), Some(1.5));
+        assert!(!loaded.values.contains_key("inventory"));
+    }
+
+    #[test]
+    fn test_tile_data_table_legacy() {
+        //Older worlds store the inventory in the "w", "h" and "items" values
+        let mut data_table = DataTable::new();
+        data_table.add_int("x", 0);
+        data_table.add_int("y", 0);
+        data_table.add_int("z", 0);
+        data_table.add_int("w", 3);
+        data_table.add_int("h", 1);
+        data_table.add_str("items", "item,5,3|empty|block,4,0,10");
+        let (_, loaded) = TileData::from_data_table(&data_table).unwrap();
+        assert_eq!(item_to_string(loaded.get_furnace_fuel()), "item,5,3");
+        assert_eq!(item_to_string(loaded.get_furnace_input()), "empty");
+        assert_eq!(item_to_string(loaded.get_furnace_output()), "block,4,0,10");
+        assert!(loaded.values.is_empty());
+    }
+}
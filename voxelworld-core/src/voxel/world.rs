@@ -1,29 +1,56 @@
+pub mod biome;
 pub mod block_update;
+pub mod border;
 mod default_world;
+pub mod dimension;
 mod flat_world;
+pub mod gen_config;
 mod gen_more;
+mod gen_rng;
+pub mod gen_settings;
+pub mod gen_stats;
 pub mod light;
+pub mod map_preview;
 mod old_world;
 mod save;
+mod sky_realm;
 mod skyblock_world;
-use crate::{game::GameMode, gfx::display::get_sky_brightness};
+pub mod terrain_generator;
+mod underworld;
 
 use super::{
-    light::{Light, SkyLightMap, LU},
-    region::{chunkpos_to_regionpos, get_region_chunks, get_region_chunks_remove, Region},
+    block_ids::{CHEST, FURNACE, LIT_FURNACE},
+    light::{get_sky_brightness, Light, SkyLightMap, LU},
+    region::{
+        chunkpos_to_regionpos,
+        codec::{RegionCodec, DEFAULT_CODEC},
+        get_region_chunks, get_region_chunks_remove, Region,
+    },
     tile_data::TileData,
-    world_to_chunk_position, wrap_coord, Block, Chunk, CHUNK_SIZE_I32, FULL_BLOCK,
+    world_to_chunk_position, Block, Chunk, FULL_BLOCK,
 };
+use biome::Biome;
+use block_update::pistons::MovingBlock;
+use block_update::{finite_fluid::FluidMode, schedule_chunk};
+use border::WorldBorder;
+use dimension::{overworld, Dimension, PortalLinks, Sky};
+use gen_config::{worldgen_config, CaveConfig};
 use gen_more::LoadChunkQueue;
+use gen_settings::GenSettings;
 use noise::{Fbm, NoiseFn, Perlin, Simplex};
 use std::collections::{HashMap, HashSet};
+use terrain_generator::{OldGenerator, TerrainGenerator};
 
-pub const OCTAVES: usize = 5;
-pub const PERSISTENCE: f64 = 0.5;
 const DEFAULT_TIME: f32 = 0.04;
 const MINUTES_PER_DAY: f32 = 20.0;
 const DAY_NIGHT_SPEED: f32 = 1.0 / (MINUTES_PER_DAY * 60.0);
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    Creative,
+    Survival,
+}
+
 //Struct that contains information for generating the world
 pub struct WorldGenerator {
     pub terrain_generator: Fbm<Perlin>,
@@ -33,15 +60,22 @@ pub struct WorldGenerator {
     //For world generation 2.0
     pub elevation: Fbm<Perlin>,
     pub temperature: Perlin,
+    pub humidity: Perlin,
     pub mountain: Fbm<Simplex>,
+    pub caves: CaveConfig,
+    pub settings: GenSettings,
     world_seed: u32,
 }
 
 impl WorldGenerator {
     fn new(seed: u32) -> Self {
+        Self::with_settings(seed, GenSettings::default())
+    }
+
+    fn with_settings(seed: u32, settings: GenSettings) -> Self {
         let mut terrain_noise = Fbm::new(seed);
-        terrain_noise.octaves = OCTAVES;
-        terrain_noise.persistence = PERSISTENCE;
+        terrain_noise.octaves = settings.octaves;
+        terrain_noise.persistence = settings.persistence;
 
         let mut elevation_noise = Fbm::new(seed + 5);
         elevation_noise.octaves = 3;
@@ -58,7 +92,10 @@ impl WorldGenerator {
             steepness: Perlin::new(seed + 3),
             elevation: elevation_noise,
             temperature: Perlin::new(seed + 4),
+            humidity: Perlin::new(seed + 7),
             mountain: mountain_noise,
+            caves: worldgen_config().caves.clone(),
+            settings,
             world_seed: seed,
         }
     }
@@ -69,6 +106,12 @@ impl WorldGenerator {
         self.temperature.get(point) * 0.5 + 0.5
     }
 
+    pub fn get_humidity(&self, x: i32, z: i32) -> f64 {
+        let offset = (self.world_seed % 1920) as f64 / 960.0;
+        let point = [x as f64 / 400.0 + offset, z as f64 / 400.0 + offset];
+        (self.humidity.get(point) * 0.5 + 0.5).clamp(0.0, 1.0)
+    }
+
     pub fn get_base_elevation(&self, x: i32, z: i32) -> f64 {
         let point = [x as f64 / 512.0, z as f64 / 512.0];
         self.terrain_generator.get(point)
@@ -93,14 +136,6 @@ impl WorldGenerator {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub enum WorldGenType {
-    OldGen,
-    Flat,
-    DefaultGen,
-    Skyblock,
-}
-
 //World struct
 pub struct World {
     //This only stores chunks that are near to the player
@@ -117,15 +152,16 @@ pub struct World {
     pub chunk_cache: HashMap<(i32, i32, i32), Chunk>,
     world_generator: WorldGenerator,
     world_seed: u32,
-    pub gen_type: WorldGenType,
+    //Generator of the overworld, other dimensions have their own generators
+    pub gen_type: &'static dyn TerrainGenerator,
+    //Dimension that is loaded, only one dimension is loaded at a time
+    pub dimension: &'static Dimension,
     //World path
     pub path: String,
     //Block update timer
     block_update_timer: f32,
     random_update_timer: f32,
-    //Updating chunks
-    updating: HashSet<(i32, i32, i32)>,
-    in_update_range: HashSet<(i32, i32, i32)>,
+    //Number of block update ticks, scheduled block updates are due on a tick
     ticks: u64,
     //Day/night cycle
     pub time: f32, //A number between 0.0 and 1.0
@@ -144,8 +180,27 @@ pub struct World {
     //the neighbors of the chunks and just immediately putting this data in the
     //update queue will result in a lot of duplicate chunk updates that aren't needed.
     chunktable_update_list: HashSet<(i32, i32, i32)>,
+    //Chunks that were changed by block updates and need their meshes rebuilt
+    mesh_updates: HashSet<(i32, i32, i32)>,
     //World game mode
     pub game_mode: GameMode,
+    //Codec used for the block data when saving regions
+    pub region_codec: RegionCodec,
+    //Portals that have been traveled through, shared by every dimension
+    pub portal_links: PortalLinks,
+    //Horizontal limit of the world in overworld coordinates, None if the
+    //world is unbounded
+    border: Option<WorldBorder>,
+    //The tick that pressed buttons and pressure plates are released on,
+    //these are not saved so everything is released when the world is loaded
+    signal_timers: HashMap<(i32, i32, i32), u64>,
+    //Blocks that were moved by pistons and are still sliding into place
+    moving_blocks: Vec<MovingBlock>,
+    //Blocks that started falling on the last block update, these are not
+    //saved since the game turns them into entities right away
+    falling_blocks: Vec<((i32, i32, i32), Block)>,
+    //How fluids flow, set when the world is created
+    fluid_mode: FluidMode,
 }
 
 impl World {
@@ -160,13 +215,12 @@ impl World {
             centerz: 0,
             chunk_cache: HashMap::new(),
             world_generator: WorldGenerator::new(0),
-            gen_type: WorldGenType::OldGen,
+            gen_type: &OldGenerator,
+            dimension: overworld(),
             world_seed: 0,
             path: String::new(),
             block_update_timer: 0.0,
             random_update_timer: 0.0,
-            updating: HashSet::new(),
-            in_update_range: HashSet::new(),
             ticks: 0,
             time: DEFAULT_TIME,
             days_passed: 0,
@@ -174,12 +228,25 @@ impl World {
             removed_from_cache: vec![],
             to_load: LoadChunkQueue::new(),
             chunktable_update_list: HashSet::new(),
+            mesh_updates: HashSet::new(),
             game_mode: GameMode::Creative, //Default to creative mode
+            region_codec: DEFAULT_CODEC,
+            portal_links: PortalLinks::new(),
+            border: None,
+            signal_timers: HashMap::new(),
+            moving_blocks: vec![],
+            falling_blocks: vec![],
+            fluid_mode: FluidMode::Classic,
         }
     }
 
     //Create a new chunk from a chunk render distance (range)
-    pub fn new(seed: u32, chunk_range: i32, generation: WorldGenType, mode: GameMode) -> Self {
+    pub fn new(
+        seed: u32,
+        chunk_range: i32,
+        generation: &'static dyn TerrainGenerator,
+        mode: GameMode,
+    ) -> Self {
         //Create chunk list
         let mut chunklist = HashMap::new();
         for y in -chunk_range..=chunk_range {
@@ -200,12 +267,11 @@ impl World {
             chunk_cache: HashMap::new(),
             world_generator: WorldGenerator::new(seed),
             gen_type: generation,
+            dimension: overworld(),
             world_seed: seed,
             path: String::new(),
             block_update_timer: 0.0,
             random_update_timer: 0.0,
-            updating: HashSet::new(),
-            in_update_range: HashSet::new(),
             ticks: 0,
             time: DEFAULT_TIME,
             days_passed: 0,
@@ -213,7 +279,15 @@ impl World {
             removed_from_cache: vec![],
             to_load: LoadChunkQueue::new(),
             chunktable_update_list: HashSet::new(),
+            mesh_updates: HashSet::new(),
             game_mode: mode,
+            region_codec: DEFAULT_CODEC,
+            portal_links: PortalLinks::new(),
+            border: None,
+            signal_timers: HashMap::new(),
+            moving_blocks: vec![],
+            falling_blocks: vec![],
+            fluid_mode: FluidMode::Classic,
         }
     }
 
@@ -236,35 +310,11 @@ impl World {
     //does nothing if the position is out of range for the world
     pub fn set_block(&mut self, x: i32, y: i32, z: i32, block: Block) {
         let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
-
-        let ix = wrap_coord(x);
-        let iy = wrap_coord(y);
-        let iz = wrap_coord(z);
-        for dx in -1i32..=1 {
-            for dy in -1i32..=1 {
-                for dz in -1i32..=1 {
-                    if (dx == -1 && ix != 0) || (dx == 1 && ix != CHUNK_SIZE_I32 - 1) {
-                        continue;
-                    }
-
-                    if (dy == -1 && iy != 0) || (dy == 1 && iy != CHUNK_SIZE_I32 - 1) {
-                        continue;
-                    }
-
-                    if (dz == -1 && iz != 0) || (dz == 1 && iz != CHUNK_SIZE_I32 - 1) {
-                        continue;
-                    }
-
-                    self.updating
-                        .insert((chunkx + dx, chunky + dy, chunkz + dz));
-                }
-            }
-        }
-
         self.to_save.insert((chunkx, chunky, chunkz));
         let chunk = self.get_mut_chunk(chunkx, chunky, chunkz);
         if let Some(chunk) = chunk {
             chunk.set_block(x, y, z, block);
+            self.notify_neighbors(x, y, z);
         }
     }
 
@@ -292,7 +342,7 @@ impl World {
 
     pub fn get_client_light(&self, x: i32, y: i32, z: i32) -> (f32, f32, f32) {
         let light = self.get_light(x, y, z);
-        let skylight = light.skylight() as f32 / 15.0 * get_sky_brightness(self.time);
+        let skylight = light.skylight() as f32 / 15.0 * self.sky_brightness();
         let r = (light.r() as f32 / 15.0).max(skylight);
         let g = (light.g() as f32 / 15.0).max(skylight);
         let b = (light.b() as f32 / 15.0).max(skylight);
@@ -334,7 +384,7 @@ impl World {
         while !self.removed_from_cache.is_empty() && time_passed < 0.0005 {
             let region_to_save = self.removed_from_cache.last();
             if let Some(region_to_save) = region_to_save {
-                save::save_region(region_to_save, &self.path);
+                save::save_region(region_to_save, &self.data_path(), self.region_codec);
                 self.removed_from_cache.pop();
             }
             let now = std::time::Instant::now();
@@ -388,11 +438,56 @@ impl World {
 
     //Generate world
     pub fn generate_world(&mut self) {
-        match self.gen_type {
-            WorldGenType::OldGen => self.gen_old(),
-            WorldGenType::Flat => self.gen_flat(),
-            WorldGenType::DefaultGen => self.gen_default(),
-            WorldGenType::Skyblock => self.gen_skyblock(),
+        let generator = self.generator();
+        let mut columns = HashMap::new();
+        let border = self.border();
+        let due = self.ticks + 1;
+        for chunk in self.chunks.values_mut() {
+            let pos = chunk.get_chunk_pos();
+            //Chunks outside of the border are left empty
+            if border.is_some_and(|border| !border.contains_chunk(pos.x, pos.z)) {
+                continue;
+            }
+            let column = columns
+                .entry((pos.x, pos.z))
+                .or_insert_with(|| generator.column(pos.x, pos.z, &self.world_generator));
+            column.gen_chunk(chunk);
+            schedule_chunk(chunk, due);
+        }
+    }
+
+    //Returns the biome at (x, z)
+    pub fn get_biome(&self, x: i32, z: i32) -> Biome {
+        self.generator().get_biome(x, z, &self.world_generator)
+    }
+
+    //Returns the (x, z) position that players spawn at
+    pub fn spawn_point(&self) -> (f32, f32) {
+        self.generator().spawn_point(&self.world_generator)
+    }
+
+    //Returns the generator of the loaded dimension
+    pub fn generator(&self) -> &'static dyn TerrainGenerator {
+        self.dimension.generator.unwrap_or(self.gen_type)
+    }
+
+    //Should be called before any chunks are loaded, otherwise the loaded
+    //chunks would be saved in the new dimension
+    pub fn set_dimension(&mut self, dimension: &'static Dimension) {
+        self.dimension = dimension;
+    }
+
+    //Returns the directory that the chunks and entities of the loaded
+    //dimension are saved in
+    pub fn data_path(&self) -> String {
+        self.dimension.data_path(&self.path)
+    }
+
+    //Brightness of sky light, between 0.0 and 1.0
+    pub fn sky_brightness(&self) -> f32 {
+        match self.dimension.sky {
+            Sky::DayNight => get_sky_brightness(self.time),
+            Sky::Fixed { brightness, .. } => brightness,
         }
     }
 
@@ -401,6 +496,16 @@ impl World {
         self.world_seed
     }
 
+    pub fn gen_settings(&self) -> &GenSettings {
+        &self.world_generator.settings
+    }
+
+    //Should be called before any chunks are generated, otherwise the chunks
+    //that were already generated will not match the new settings
+    pub fn set_gen_settings(&mut self, settings: GenSettings) {
+        self.world_generator = WorldGenerator::with_settings(self.world_seed, settings);
+    }
+
     //Returns adjacent chunks
     pub fn get_adjacent(&self, chunk: &Chunk) -> [Option<&Chunk>; 6] {
         let pos = chunk.get_chunk_pos();
@@ -414,11 +519,6 @@ impl World {
         ]
     }
 
-    //Returns how many chunks are updating
-    pub fn get_chunk_updates(&self) -> usize {
-        self.updating.len()
-    }
-
     //Updates day night cycle
     pub fn update_daynight(&mut self, dt: f32) {
         self.time += dt * DAY_NIGHT_SPEED;
@@ -468,12 +568,10 @@ impl World {
             return;
         }
         match block.id {
-            //Chest
-            37 => {
+            CHEST => {
                 self.set_tile_data(x, y, z, Some(TileData::new_chest()));
             }
-            //Furnace
-            40 | 70 => {
+            FURNACE | LIT_FURNACE => {
                 self.set_tile_data(x, y, z, Some(TileData::new_furance()));
             }
             _ => {}
@@ -483,10 +581,7 @@ impl World {
     //Returns the coordinates for the bottom of the world
     //beyond this point you can not place any blocks
     pub fn bottom(&self) -> i32 {
-        match self.gen_type {
-            WorldGenType::Flat | WorldGenType::OldGen | WorldGenType::DefaultGen => -128,
-            WorldGenType::Skyblock => -32,
-        }
+        self.generator().bottom()
     }
 }
 
@@ -0,0 +1,346 @@
+/*
+ * Biomes are picked from the climate at a position (temperature, humidity
+ * and the terrain/mountain height) and decide the surface blocks, trees,
+ * plants and the sky tint of that position.
+ *
+ * The biome names are stable and can be used by other systems to look up
+ * biomes, see `World::get_biome` for looking up the biome at a position.
+ * */
+
+use super::{
+    default_world::{get_climate, is_beach, is_mountain},
+    WorldGenerator,
+};
+use crate::voxel::{block_ids::*, Block};
+
+//Plants are picked by rolling a number in 0..PLANT_ROLL, if the roll is past
+//the total weight of the plant table then no plant is generated
+pub const PLANT_ROLL: i32 = 80;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Biome {
+    Ocean,
+    FrozenOcean,
+    Beach,
+    Plains,
+    Forest,
+    Swamp,
+    Taiga,
+    SnowyTundra,
+    Savanna,
+    Desert,
+    Badlands,
+    Mountains,
+}
+
+pub const ALL_BIOMES: [Biome; 12] = [
+    Biome::Ocean,
+    Biome::FrozenOcean,
+    Biome::Beach,
+    Biome::Plains,
+    Biome::Forest,
+    Biome::Swamp,
+    Biome::Taiga,
+    Biome::SnowyTundra,
+    Biome::Savanna,
+    Biome::Desert,
+    Biome::Badlands,
+    Biome::Mountains,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TreeType {
+    Oak,
+    SnowyOak,
+    Spruce,
+    Acacia,
+    Cactus,
+}
+
+//Temperature and humidity are between 0 and 100
+#[derive(Clone, Copy, Debug)]
+pub struct Climate {
+    pub temperature: i32,
+    pub humidity: i32,
+    pub terrain_height: i32,
+    pub mountain_height: i32,
+    pub sea_level: i32,
+}
+
+impl Biome {
+    pub fn from_climate(climate: &Climate) -> Self {
+        let Climate {
+            temperature,
+            humidity,
+            terrain_height,
+            mountain_height,
+            sea_level,
+        } = *climate;
+
+        if is_mountain(mountain_height, terrain_height) {
+            return Self::Mountains;
+        }
+
+        let height = terrain_height.max(mountain_height);
+        if height <= sea_level - 3 {
+            if temperature < 25 {
+                return Self::FrozenOcean;
+            }
+            return Self::Ocean;
+        }
+
+        if is_beach(temperature, height, sea_level) {
+            return Self::Beach;
+        }
+
+        if temperature > 75 {
+            if humidity < 30 {
+                Self::Badlands
+            } else if humidity > 70 {
+                Self::Savanna
+            } else {
+                Self::Desert
+            }
+        } else if temperature < 25 {
+            if humidity > 50 {
+                Self::Taiga
+            } else {
+                Self::SnowyTundra
+            }
+        } else if humidity > 65 && height <= sea_level + 6 {
+            Self::Swamp
+        } else if humidity > 50 {
+            Self::Forest
+        } else if temperature > 60 && humidity < 35 {
+            Self::Savanna
+        } else {
+            Self::Plains
+        }
+    }
+
+    //Stable name of the biome
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ocean => "ocean",
+            Self::FrozenOcean => "frozen_ocean",
+            Self::Beach => "beach",
+            Self::Plains => "plains",
+            Self::Forest => "forest",
+            Self::Swamp => "swamp",
+            Self::Taiga => "taiga",
+            Self::SnowyTundra => "snowy_tundra",
+            Self::Savanna => "savanna",
+            Self::Desert => "desert",
+            Self::Badlands => "badlands",
+            Self::Mountains => "mountains",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ALL_BIOMES
+            .iter()
+            .copied()
+            .find(|biome| biome.name() == name)
+    }
+
+    //Top block of the terrain, `height` is the height of the terrain
+    pub fn surface_block(&self, height: i32, sea_level: i32) -> Block {
+        match self {
+            Self::Ocean | Self::FrozenOcean | Self::Beach | Self::Desert | Self::Badlands => {
+                Block::new_id(SAND)
+            }
+            Self::Mountains => Block::new_id(STONE),
+            _ if height < sea_level => Block::new_id(DIRT),
+            Self::Taiga | Self::SnowyTundra => Block::new_id(SNOWY_GRASS),
+            //Moss in the wetter parts of swamps
+            Self::Swamp if height <= sea_level + 1 => Block::new_id(MOSS),
+            _ => Block::new_id(GRASS),
+        }
+    }
+
+    //Blocks right beneath the surface layer
+    pub fn under_block(&self) -> Block {
+        match self {
+            Self::Desert => Block::new_id(SAND),
+            Self::Badlands => Block::new_id(CLAY),
+            Self::Mountains => Block::new_id(STONE),
+            _ => Block::new_id(DIRT),
+        }
+    }
+
+    //Water at sea level freezes into ice
+    pub fn freezes_water(&self) -> bool {
+        matches!(self, Self::FrozenOcean | Self::Taiga | Self::SnowyTundra)
+    }
+
+    //Snow slabs are placed on top of the surface
+    pub fn has_snow(&self) -> bool {
+        matches!(self, Self::Taiga | Self::SnowyTundra)
+    }
+
+    //Returns the tree type and n where 1 in n trees are generated
+    pub fn tree(&self) -> Option<(TreeType, usize)> {
+        match self {
+            Self::Plains => Some((TreeType::Oak, 3)),
+            Self::Forest => Some((TreeType::Oak, 1)),
+            Self::Swamp => Some((TreeType::Oak, 2)),
+            Self::Taiga => Some((TreeType::Spruce, 1)),
+            Self::SnowyTundra => Some((TreeType::SnowyOak, 3)),
+            Self::Savanna => Some((TreeType::Acacia, 3)),
+            Self::Desert => Some((TreeType::Cactus, 3)),
+            Self::Badlands => Some((TreeType::Cactus, 6)),
+            Self::Ocean | Self::FrozenOcean | Self::Beach | Self::Mountains => None,
+        }
+    }
+
+    //Returns a list of (block id, weight), see `PLANT_ROLL`
+    pub fn plants(&self) -> &'static [(u16, i32)] {
+        match self {
+            Self::Plains => &[
+                (TALL_GRASS, 50),
+                (RED_FLOWER, 8),
+                (YELLOW_FLOWER, 8),
+                (BLUE_FLOWER, 4),
+                (MUSHROOM, 2),
+            ],
+            Self::Forest => &[
+                (TALL_GRASS, 40),
+                (RED_FLOWER, 4),
+                (YELLOW_FLOWER, 4),
+                (BLUE_FLOWER, 4),
+                (MUSHROOM, 6),
+            ],
+            Self::Swamp => &[(TALL_GRASS, 30), (MUSHROOM, 10), (BLUE_FLOWER, 6)],
+            Self::Savanna => &[(TALL_GRASS, 50), (YELLOW_FLOWER, 4), (COTTON, 4)],
+            Self::Taiga => &[(TALL_GRASS, 10), (MUSHROOM, 10), (WHITE_FLOWER, 5)],
+            Self::SnowyTundra => &[(MUSHROOM, 10), (WHITE_FLOWER, 5)],
+            Self::Desert => &[(DEAD_BUSH, 20), (MUSHROOM, 1)],
+            Self::Badlands => &[(DEAD_BUSH, 30)],
+            Self::Ocean | Self::FrozenOcean | Self::Beach | Self::Mountains => &[],
+        }
+    }
+
+    //Returns the plant for a roll in 0..PLANT_ROLL
+    pub fn pick_plant(&self, roll: i32) -> Option<u16> {
+        let mut total = 0;
+        for (id, weight) in self.plants() {
+            total += weight;
+            if roll < total {
+                return Some(*id);
+            }
+        }
+        None
+    }
+
+    //Multiplied with the sky and fog color
+    pub fn sky_tint(&self) -> (f32, f32, f32) {
+        match self {
+            Self::Ocean => (0.9, 0.95, 1.05),
+            Self::FrozenOcean => (0.9, 0.95, 1.1),
+            Self::Forest => (0.95, 1.0, 0.95),
+            Self::Swamp => (0.75, 0.85, 0.7),
+            Self::Taiga => (0.9, 0.95, 1.05),
+            Self::SnowyTundra => (0.95, 1.0, 1.1),
+            Self::Savanna | Self::Desert => (1.1, 1.0, 0.85),
+            Self::Badlands => (1.15, 0.9, 0.75),
+            Self::Beach | Self::Plains | Self::Mountains => (1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl WorldGenerator {
+    //Returns the biome at (x, z)
+    pub fn get_biome(&self, x: i32, z: i32) -> Biome {
+        Biome::from_climate(&get_climate(x, z, self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn climate(temperature: i32, humidity: i32, height: i32) -> Climate {
+        Climate {
+            temperature,
+            humidity,
+            terrain_height: height,
+            mountain_height: -80,
+            sea_level: 0,
+        }
+    }
+
+    #[test]
+    fn test_biome_names() {
+        let mut names = HashSet::new();
+        for biome in ALL_BIOMES {
+            assert!(names.insert(biome.name()));
+            assert_eq!(Biome::from_name(biome.name()), Some(biome));
+        }
+        assert_eq!(Biome::from_name("nether"), None);
+    }
+
+    #[test]
+    fn test_biome_from_climate() {
+        let biomes = [
+            (climate(20, 50, 16), Biome::SnowyTundra),
+            (climate(20, 60, 16), Biome::Taiga),
+            (climate(80, 50, 16), Biome::Desert),
+            (climate(80, 20, 16), Biome::Badlands),
+            (climate(80, 80, 16), Biome::Savanna),
+            (climate(50, 80, 4), Biome::Swamp),
+            (climate(50, 80, 16), Biome::Forest),
+            (climate(50, 40, 16), Biome::Plains),
+            (climate(50, 40, -10), Biome::Ocean),
+            (climate(10, 40, -10), Biome::FrozenOcean),
+            (climate(70, 40, 0), Biome::Beach),
+        ];
+        for (climate, biome) in biomes {
+            assert_eq!(Biome::from_climate(&climate), biome);
+        }
+
+        //Raising the sea level floods the land
+        let flooded = Climate {
+            sea_level: 24,
+            ..climate(50, 40, 16)
+        };
+        assert_eq!(Biome::from_climate(&flooded), Biome::Ocean);
+
+        let mountain = Climate {
+            temperature: 50,
+            humidity: 50,
+            terrain_height: 8,
+            mountain_height: 60,
+            sea_level: 0,
+        };
+        assert_eq!(Biome::from_climate(&mountain), Biome::Mountains);
+    }
+
+    #[test]
+    fn test_plant_tables() {
+        for biome in ALL_BIOMES {
+            let total: i32 = biome.plants().iter().map(|(_, weight)| weight).sum();
+            assert!(total <= PLANT_ROLL);
+            assert_eq!(biome.pick_plant(PLANT_ROLL), None);
+            if let Some((id, _)) = biome.plants().first() {
+                assert_eq!(biome.pick_plant(0), Some(*id));
+            }
+        }
+    }
+
+    #[test]
+    fn test_biome_lookup_is_stable() {
+        let world_generator = WorldGenerator::new(42);
+        let other = WorldGenerator::new(42);
+        let mut found = HashSet::new();
+        for x in -64..64 {
+            for z in -64..64 {
+                let biome = world_generator.get_biome(x * 32, z * 32);
+                assert_eq!(biome, other.get_biome(x * 32, z * 32));
+                found.insert(biome);
+            }
+        }
+        //A large enough area should have more than one biome
+        assert!(found.len() > 1);
+    }
+}
@@ -0,0 +1,719 @@
+pub mod finite_fluid;
+pub mod pistons;
+pub mod rand_block_update;
+pub mod signals;
+mod simulations;
+pub mod update_tile_data;
+
+use super::World;
+use crate::voxel::{
+    block_ids::{
+        BLUE_FLOWER, BUTTON, CACTUS, COTTON_SEED, DEAD_BUSH, DIRT, DOOR, DOOR_TOP, DRY_FARMLAND,
+        FENCE, GATE, GROWING_BLUE_FLOWER, INVERTER, LADDER, LAMP, LAVA, LEVER, LIT_LAMP, OBSIDIAN,
+        PISTON, PISTON_HEAD, PRESSURE_PLATE, REPEATER, SAPLING, SEEDS, SNOWY_SAPLING,
+        STICKY_PISTON, STONE, SUGARCANE, TORCH, WATER, WET_FARMLAND, WIRE,
+    },
+    is_valid::get_check_valid_fn,
+    world_to_chunk_position, wrap_coord, Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
+use finite_fluid::FluidMode;
+use pistons::is_piston;
+pub use simulations::run_test_simulations;
+use std::collections::{HashMap, HashSet};
+
+pub const BLOCK_UPDATE_INTERVAL: f32 = 0.2;
+const ADJ: [(i32, i32, i32); 4] = [(1, 0, 0), (0, 0, 1), (-1, 0, 0), (0, 0, -1)];
+//Blocks that are notified when a block changes, these are the blocks next to
+//it and the blocks to the side of the blocks above and below it (fluids check
+//the block under the blocks next to them and wires connect diagonally)
+const NOTIFY: [(i32, i32, i32); 15] = [
+    (0, 0, 0),
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+    (1, 1, 0),
+    (-1, 1, 0),
+    (0, 1, 1),
+    (0, 1, -1),
+    (1, -1, 0),
+    (-1, -1, 0),
+    (0, -1, 1),
+    (0, -1, -1),
+];
+
+type UpdateList = HashMap<(i32, i32, i32), Block>;
+type UpdateFn = fn(&World, i32, i32, i32, &mut UpdateList);
+
+pub fn get_chunktable_updates(x: i32, y: i32, z: i32, update_mesh: &mut HashSet<(i32, i32, i32)>) {
+    let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+    let ix = wrap_coord(x);
+    let iy = wrap_coord(y);
+    let iz = wrap_coord(z);
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            for dz in -1..=1 {
+                if (dx == -1 && ix != 0) || (dx == 1 && ix != CHUNK_SIZE_I32 - 1) {
+                    continue;
+                }
+
+                if (dy == -1 && iy != 0) || (dy == 1 && iy != CHUNK_SIZE_I32 - 1) {
+                    continue;
+                }
+
+                if (dz == -1 && iz != 0) || (dz == 1 && iz != CHUNK_SIZE_I32 - 1) {
+                    continue;
+                }
+
+                update_mesh.insert((chunkx + dx, chunky + dy, chunkz + dz));
+            }
+        }
+    }
+}
+
+fn add_water_tile(x: i32, y: i32, z: i32, level: u8, id: u16, to_update: &mut UpdateList) {
+    let mut water = Block::new_fluid(id);
+    water.geometry = level;
+
+    if water.geometry == 0 || water.geometry > 8 {
+        water.id = EMPTY_BLOCK;
+    }
+
+    if let Some(tile) = to_update.get(&(x, y, z)) {
+        if !tile.is_fluid() {
+            return;
+        }
+
+        if tile.id == water.id && tile.geometry < water.geometry && tile.geometry != 7 {
+            to_update.insert((x, y, z), water);
+        }
+    } else {
+        if water.geometry == 0 {
+            to_update.insert((x, y, z), Block::new());
+        }
+        to_update.insert((x, y, z), water);
+    }
+}
+
+//Returns true if updated
+fn update_fluid(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList, decrease: u8) {
+    let block = world.get_block(x, y, z);
+    let below = world.get_block(x, y - 1, z);
+    let level = block.geometry.min(7);
+
+    //Check for adjacent tiles and see if they allow for this block to exist
+    if block.geometry < 7 {
+        let mut count = 0;
+        let mut maxval = 0;
+        let mut next_to_fall = false;
+        for (dx, dy, dz) in ADJ {
+            let (posx, posy, posz) = (x + dx, y + dy, z + dz);
+            let block2 = world.get_block(posx, posy, posz);
+            if block2.id != block.id {
+                continue;
+            }
+
+            let underblock = world.get_block(posx, posy - 1, posz);
+            if (underblock.id == EMPTY_BLOCK || underblock.id == block.id) && block2.geometry != 7 {
+                continue;
+            }
+
+            if block2.geometry == 8 {
+                next_to_fall = true;
+                continue;
+            }
+
+            if block2.geometry > maxval {
+                maxval = block2.geometry;
+                count = 0;
+            }
+
+            if block2.geometry == maxval {
+                count += 1;
+            }
+        }
+
+        if maxval > 1 && (below.id == EMPTY_BLOCK || below.id == block.id) {
+            add_water_tile(x, y, z, 1, block.id, to_update);
+            if below.geometry != 7 {
+                add_water_tile(x, y - 1, z, 8, block.id, to_update);
+            }
+            return;
+        } else if maxval == 7 && count > 1 && decrease == 1 {
+            add_water_tile(x, y, z, 7, block.id, to_update);
+            return;
+        } else if next_to_fall && maxval < 7 {
+            add_water_tile(x, y, z, 7 - decrease, block.id, to_update);
+        } else if maxval <= 1 {
+            add_water_tile(x, y, z, 0, block.id, to_update);
+            return;
+        } else if maxval <= level {
+            add_water_tile(x, y, z, maxval - decrease, block.id, to_update);
+            return;
+        }
+    } else if block.geometry == 8 && world.get_block(x, y + 1, z).id != block.id {
+        add_water_tile(x, y, z, 7 - decrease, block.id, to_update);
+        return;
+    }
+
+    //Flow down
+    if (below.id == EMPTY_BLOCK || below.id == block.id || below.fluid_destructibe()) && level > 0 {
+        if below.geometry != 7 {
+            add_water_tile(x, y - 1, z, 8, block.id, to_update);
+        }
+        if block.geometry != 7 {
+            return;
+        }
+    }
+
+    if level <= decrease {
+        return;
+    }
+
+    //Flow to the sides
+    for (dx, dy, dz) in ADJ {
+        let (posx, posy, posz) = (x + dx, y + dy, z + dz);
+        let adjacent = world.get_block(posx, posy, posz);
+        if adjacent.id == EMPTY_BLOCK
+            || (adjacent.id == block.id && adjacent.geometry < block.geometry - 1)
+            || adjacent.fluid_destructibe()
+        {
+            let underblock = world.get_block(posx, posy - 1, posz);
+            let blocklevel = if underblock.id == block.id || underblock.id == EMPTY_BLOCK {
+                1.min(level)
+            } else if level <= 7 {
+                level - decrease
+            } else {
+                0
+            };
+
+            if level == 0 {
+                continue;
+            }
+
+            add_water_tile(posx, posy, posz, blocklevel, block.id, to_update);
+        }
+    }
+}
+
+//Returns true if updated
+fn water_to_stone(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) -> bool {
+    //Is the block above lava?
+    if world.get_block(x, y + 1, z).id == LAVA {
+        //then turn to stone
+        to_update.insert((x, y, z), Block::new_id(STONE));
+        return true;
+    }
+    false
+}
+
+//Returns true if updated
+fn freeze_lava(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) -> bool {
+    let block = world.get_block(x, y, z);
+    let new_block = if block.geometry == 7 {
+        //Obsidian
+        Block::new_id(OBSIDIAN)
+    } else {
+        //Stone
+        Block::new_id(STONE)
+    };
+
+    //If water is above or to the side, then freeze the lava
+    if world.get_block(x, y + 1, z).id == WATER {
+        to_update.insert((x, y, z), new_block);
+        return true;
+    }
+
+    for (dx, dy, dz) in ADJ {
+        if world.get_block(x + dx, y + dy, z + dz).id == WATER {
+            to_update.insert((x, y, z), new_block);
+            return true;
+        }
+    }
+
+    false
+}
+
+fn update_water(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let updated = water_to_stone(world, x, y, z, to_update);
+    if updated {
+        return;
+    }
+    update_fluid(world, x, y, z, to_update, 1);
+}
+
+fn update_lava(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let updated = freeze_lava(world, x, y, z, to_update);
+    if updated {
+        return;
+    }
+    let mut update_list = UpdateList::new();
+    update_fluid(world, x, y, z, &mut update_list, 2);
+    if world.ticks.is_multiple_of(5) {
+        for (pos, block) in update_list {
+            let (px, py, pz) = pos;
+            add_water_tile(px, py, pz, block.geometry, block.id, to_update);
+        }
+    } else if !update_list.is_empty() {
+        let mut updated = false;
+        for ((x, y, z), block) in update_list {
+            if world.get_block(x, y, z) != block {
+                updated = true;
+            }
+        }
+
+        if !updated {
+            return;
+        }
+
+        let mut block2 = world.get_block(x, y, z);
+        block2.geometry |= 1 << 7;
+        to_update.insert((x, y, z), block2);
+    }
+}
+
+fn update_farmland(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let above = world.get_block(x, y + 1, z);
+    if above.transparent() || above.id == EMPTY_BLOCK {
+        return;
+    }
+    to_update.insert((x, y, z), Block::new_id(DIRT));
+}
+
+fn update_plant(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let id = world.get_block(x, y, z).id;
+    if let Some(check_valid) = get_check_valid_fn(id) {
+        if check_valid(world, x, y, z) {
+            return;
+        }
+        to_update.insert((x, y, z), Block::new_id(EMPTY_BLOCK));
+    }
+}
+
+//Connect fences
+fn update_fence(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let mut block = world.get_block(x, y, z);
+
+    let geometry = block.geometry;
+    block.geometry = 0;
+    ADJ.iter()
+        .map(|(dx, dy, dz)| (x + dx, y + dy, z + dz))
+        .map(|(x, y, z)| world.get_block(x, y, z))
+        .enumerate()
+        .for_each(|(i, b)| {
+            if b.id == EMPTY_BLOCK {
+                return;
+            }
+            if b.shape() != 0 {
+                return;
+            }
+            if b.transparent() && b.id != block.id && b.id != GATE {
+                return;
+            }
+            block.geometry |= 1 << i;
+        });
+
+    if block.geometry == geometry {
+        return;
+    }
+
+    to_update.insert((x, y, z), block);
+}
+
+//Blocks that fall are removed from the world and are added back as an entity
+//by the game (see World::take_falling_blocks)
+fn update_falling(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let below = world.get_block(x, y - 1, z);
+    if below.id != EMPTY_BLOCK && !below.is_fluid() && !below.fluid_destructibe() {
+        return;
+    }
+    to_update.insert((x, y, z), Block::new());
+}
+
+//Meant to be called when player breaks ice
+//Checks if the block below is non-empty, if that's the case, then spawn water
+//where the ice was broken
+pub fn break_ice(world: &mut World, x: i32, y: i32, z: i32) {
+    let below = world.get_block(x, y - 1, z);
+    //Ignore if below is an empty block
+    if below.id == EMPTY_BLOCK {
+        return;
+    }
+    let water = Block::new_fluid(WATER);
+    world.set_block(x, y, z, water);
+}
+
+//Returns the function that updates a block when it is ticked, None if the
+//block never changes on its own
+fn get_update_fn(block: Block) -> Option<UpdateFn> {
+    if block.shape() != 0 {
+        return None;
+    }
+
+    if block.falls() {
+        return Some(update_falling);
+    }
+
+    match block.id {
+        //Water
+        WATER => Some(update_water),
+        //Lava
+        LAVA => Some(update_lava),
+        //Farmland
+        WET_FARMLAND | DRY_FARMLAND => Some(update_farmland),
+        //Plants, torches, ladders
+        SAPLING..=BLUE_FLOWER
+        | SUGARCANE
+        | TORCH..=LADDER
+        | SEEDS
+        | CACTUS
+        | DEAD_BUSH
+        | SNOWY_SAPLING
+        | COTTON_SEED..=GROWING_BLUE_FLOWER => Some(update_plant),
+        //Fence
+        FENCE => Some(update_fence),
+        //Signals
+        LEVER => Some(signals::update_lever),
+        BUTTON | PRESSURE_PLATE => Some(signals::update_pressable),
+        WIRE => Some(signals::update_wire),
+        REPEATER | INVERTER => Some(signals::update_repeater),
+        LAMP | LIT_LAMP => Some(signals::update_lamp),
+        DOOR | DOOR_TOP | GATE => Some(signals::update_door),
+        //Pistons
+        PISTON | STICKY_PISTON => Some(pistons::update_piston),
+        PISTON_HEAD => Some(pistons::update_piston_head),
+        _ => None,
+    }
+}
+
+//Schedules an update on tick `due` for every block in the chunk that can
+//change, this is only done for chunks that were just generated or that were
+//loaded from a region saved before scheduled ticks were stored so that they
+//settle, other chunks keep the ticks that were saved with them
+pub(super) fn schedule_chunk(chunk: &mut Chunk, due: u64) {
+    chunk.set_unscheduled(false);
+    if !chunk.may_contain(|block| get_update_fn(block).is_some()) {
+        return;
+    }
+
+    let pos = chunk.get_chunk_pos();
+    let startx = pos.x * CHUNK_SIZE_I32;
+    let starty = pos.y * CHUNK_SIZE_I32;
+    let startz = pos.z * CHUNK_SIZE_I32;
+    for x in startx..(startx + CHUNK_SIZE_I32) {
+        for y in starty..(starty + CHUNK_SIZE_I32) {
+            for z in startz..(startz + CHUNK_SIZE_I32) {
+                let block = chunk.get_block(x, y, z);
+                //Blocks that fall only start falling once a block next to
+                //them changes so that generated sand stays in place
+                if get_update_fn(block).is_some() && !block.falls() {
+                    chunk.schedule_tick(x, y, z, due);
+                }
+            }
+        }
+    }
+}
+
+impl World {
+    //Schedules the block at (x, y, z) to be updated `delay` ticks from now,
+    //does nothing if the chunk is not loaded
+    pub fn schedule_tick(&mut self, x: i32, y: i32, z: i32, delay: u64) {
+        let due = self.ticks + delay;
+        let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+        if let Some(chunk) = self.get_mut_chunk(chunkx, chunky, chunkz) {
+            chunk.schedule_tick(x, y, z, due);
+        }
+    }
+
+    //Removes the scheduled tick of the block at (x, y, z) and returns the
+    //tick it was due on
+    fn take_scheduled_tick(&mut self, x: i32, y: i32, z: i32) -> Option<u64> {
+        let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+        self.get_mut_chunk(chunkx, chunky, chunkz)?
+            .take_scheduled_tick(x, y, z)
+    }
+
+    //Called whenever a block changes, schedules an update on the next tick
+    //for the blocks around it that can change
+    pub(super) fn notify_neighbors(&mut self, x: i32, y: i32, z: i32) {
+        for (dx, dy, dz) in NOTIFY {
+            let (posx, posy, posz) = (x + dx, y + dy, z + dz);
+            if get_update_fn(self.get_block(posx, posy, posz)).is_none() {
+                continue;
+            }
+            self.schedule_tick(posx, posy, posz, 1);
+        }
+    }
+
+    //Returns a vec of destroyed blocks
+    //Vec<(block, x, y, z)>
+    pub fn update_blocks(&mut self, dt: f32, chunk_sim_dist: i32) -> Vec<((i32, i32, i32), Block)> {
+        self.block_update_timer += dt;
+        if self.block_update_timer <= BLOCK_UPDATE_INTERVAL {
+            return vec![];
+        }
+
+        self.ticks += 1;
+        self.block_update_timer = 0.0;
+
+        //Only blocks that were scheduled for this tick are updated
+        let mut due = vec![];
+        for x in (self.centerx - chunk_sim_dist)..=(self.centerx + chunk_sim_dist) {
+            for y in (self.centery - chunk_sim_dist)..=(self.centery + chunk_sim_dist) {
+                for z in (self.centerz - chunk_sim_dist)..=(self.centerz + chunk_sim_dist) {
+                    if let Some(chunk) = self.chunks.get_mut(&(x, y, z)) {
+                        due.extend(chunk.take_due_ticks(self.ticks));
+                    }
+                }
+            }
+        }
+        //Update the blocks in the same order every time
+        due.sort();
+
+        let mut light_updates = vec![];
+        let mut destroyed = vec![];
+        let mut update_mesh = HashSet::<(i32, i32, i32)>::new();
+
+        //Finite fluids move their volume directly in the world so they are
+        //updated one at a time before every other block
+        if self.fluid_mode == FluidMode::Finite {
+            let fluids: Vec<_> = due
+                .iter()
+                .copied()
+                .filter(|&(x, y, z)| self.get_block(x, y, z).is_fluid())
+                .collect();
+            for (x, y, z) in self.update_finite_fluids(&fluids, &mut destroyed) {
+                get_chunktable_updates(x, y, z, &mut update_mesh);
+                light_updates.push((x, y, z));
+            }
+        }
+
+        let mut to_update = UpdateList::new();
+        //Wires are updated a whole network at a time, each network only
+        //needs to be updated once no matter how many of its wires are due
+        let mut updated_wires = HashSet::new();
+        for (x, y, z) in due {
+            let block = self.get_block(x, y, z);
+            if self.fluid_mode == FluidMode::Finite && block.is_fluid() {
+                continue;
+            }
+
+            if block.id == WIRE {
+                if !updated_wires.contains(&(x, y, z)) {
+                    let network = signals::update_wire_network(self, x, y, z, &mut to_update);
+                    updated_wires.extend(network);
+                }
+                continue;
+            }
+
+            if let Some(update) = get_update_fn(block) {
+                update(self, x, y, z, &mut to_update);
+            }
+        }
+
+        let mut piston_changes = vec![];
+        for ((x, y, z), block) in to_update {
+            if self.get_block(x, y, z) == block {
+                continue;
+            }
+
+            //The block is waiting to change, check it again on the next tick
+            if block.geometry & (1 << 7) != 0 && block.is_fluid() {
+                self.schedule_tick(x, y, z, 1);
+                continue;
+            }
+
+            let prev_block = self.get_block(x, y, z);
+            //Pistons are extended and retracted after every other block is
+            //updated since they move the blocks around them
+            if is_piston(block.id) && prev_block.id == block.id {
+                piston_changes.push(((x, y, z), block.reflection()));
+                continue;
+            }
+
+            if prev_block.falls() && block.id == EMPTY_BLOCK {
+                self.falling_blocks.push(((x, y, z), prev_block));
+            } else if block.is_fluid() || block.id == EMPTY_BLOCK {
+                destroyed.push(((x, y, z), prev_block))
+            }
+
+            get_chunktable_updates(x, y, z, &mut update_mesh);
+            self.set_block(x, y, z, block);
+            light_updates.push((x, y, z));
+        }
+
+        piston_changes.sort();
+        for ((x, y, z), extended) in piston_changes {
+            let changed = if extended == 1 {
+                self.extend_piston(x, y, z, &mut destroyed)
+            } else {
+                self.retract_piston(x, y, z)
+            };
+            for (posx, posy, posz) in changed {
+                get_chunktable_updates(posx, posy, posz, &mut update_mesh);
+                light_updates.push((posx, posy, posz));
+            }
+        }
+
+        update_mesh.extend(self.update_block_light(&light_updates));
+        self.mesh_updates.extend(update_mesh);
+        self.update_signal_timers();
+
+        destroyed
+    }
+
+    //Returns the blocks that started falling since the last time this was
+    //called, the game turns them into falling block entities
+    pub fn take_falling_blocks(&mut self) -> Vec<((i32, i32, i32), Block)> {
+        std::mem::take(&mut self.falling_blocks)
+    }
+
+    //Returns the number of blocks that are waiting to be updated in the
+    //loaded chunks
+    pub fn scheduled_tick_count(&self) -> usize {
+        self.chunks
+            .values()
+            .map(|chunk| chunk.scheduled_tick_count())
+            .sum()
+    }
+
+    //Returns the chunks that were changed by block updates since the last
+    //time this was called
+    pub fn take_mesh_updates(&mut self) -> HashSet<(i32, i32, i32)> {
+        std::mem::take(&mut self.mesh_updates)
+    }
+
+    //Adds chunks that need to have their meshes rebuilt, for changes made
+    //outside of block updates
+    pub fn add_mesh_updates(&mut self, chunks: impl IntoIterator<Item = (i32, i32, i32)>) {
+        self.mesh_updates.extend(chunks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::block_ids::SAND;
+    use crate::voxel::region::Region;
+    use crate::voxel::world::{terrain_generator::FlatGenerator, GameMode};
+
+    fn tick(world: &mut World, count: usize) {
+        for _ in 0..count {
+            world.update_blocks(BLOCK_UPDATE_INTERVAL * 1.5, 1);
+        }
+    }
+
+    fn water_world() -> World {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        for x in -3..=3 {
+            for z in -3..=3 {
+                world.set_block(x, 4, z, Block::new_id(STONE));
+            }
+        }
+        world.set_block(0, 5, 0, Block::new_fluid(WATER));
+        world
+    }
+
+    #[test]
+    fn test_scheduled_water_flow() {
+        let mut world = water_world();
+        assert!(world.scheduled_tick_count() > 0);
+        tick(&mut world, 2);
+        assert_eq!(world.get_block(1, 5, 0).id, WATER);
+        assert_eq!(world.get_block(0, 5, -1).id, WATER);
+        //Once the water stops flowing, nothing is left to update
+        tick(&mut world, 200);
+        assert_eq!(world.scheduled_tick_count(), 0);
+        assert_eq!(world.get_block(3, 5, 0).id, WATER);
+    }
+
+    #[test]
+    fn test_neighbor_notification() {
+        let mut world = water_world();
+        tick(&mut world, 200);
+        assert_eq!(world.scheduled_tick_count(), 0);
+        //Removing the source schedules the water next to it, which then
+        //dries up
+        world.set_block(0, 5, 0, Block::new());
+        assert!(world.scheduled_tick_count() > 0);
+        tick(&mut world, 200);
+        assert_ne!(world.get_block(1, 5, 0).id, WATER);
+        assert_eq!(world.scheduled_tick_count(), 0);
+    }
+
+    #[test]
+    fn test_schedule_tick() {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        world.schedule_tick(0, 8, 0, 3);
+        world.schedule_tick(0, 8, 0, 5);
+        let chunk = world.get_chunk(0, 0, 0).unwrap();
+        assert_eq!(
+            chunk.scheduled_ticks().collect::<Vec<_>>(),
+            vec![((0, 8, 0), 3)]
+        );
+        tick(&mut world, 2);
+        assert_eq!(world.scheduled_tick_count(), 1);
+        tick(&mut world, 1);
+        assert_eq!(world.scheduled_tick_count(), 0);
+    }
+
+    #[test]
+    fn test_still_lake_leaving_sim_range() {
+        let mut world = water_world();
+        tick(&mut world, 200);
+        assert_eq!(world.scheduled_tick_count(), 0);
+        //Moving away from the lake and back does not update it again
+        for centerx in [3, 0, -3, 0] {
+            world.centerx = centerx;
+            tick(&mut world, 2);
+            assert_eq!(world.scheduled_tick_count(), 0);
+        }
+    }
+
+    #[test]
+    fn test_old_region_chunk() {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        tick(&mut world, 5);
+        assert_eq!(world.scheduled_tick_count(), 0);
+        //Simulate a chunk that is loaded later from a region that was saved
+        //before scheduled ticks were stored
+        let mut chunk = world.chunks.remove(&(1, 0, 0)).unwrap();
+        let x = CHUNK_SIZE_I32;
+        for z in -1..=1 {
+            chunk.set_block(x, 4, z, Block::new_id(STONE));
+            chunk.set_block(x + 1, 4, z, Block::new_id(STONE));
+        }
+        chunk.set_block(x, 5, 0, Block::new_fluid(WATER));
+        chunk.set_unscheduled(true);
+        let mut region = Region::new(0, 0, 0);
+        region.set_chunk(1, 0, 0, Some(chunk));
+        world.add_region(region);
+        assert!(!world.get_chunk(1, 0, 0).unwrap().is_unscheduled());
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(x + 1, 5, 0).id, WATER);
+    }
+
+    #[test]
+    fn test_falling_blocks() {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        world.set_block(0, 4, 0, Block::new_id(STONE));
+        world.set_block(0, 5, 0, Block::new_id(SAND));
+        world.set_block(0, 6, 0, Block::new_id(SAND));
+        tick(&mut world, 5);
+        assert!(world.take_falling_blocks().is_empty());
+        //Only the bottom block has nothing under it on the first tick, the
+        //block above it falls on the next tick
+        world.set_block(0, 4, 0, Block::new());
+        tick(&mut world, 1);
+        assert_eq!(world.get_block(0, 5, 0).id, EMPTY_BLOCK);
+        assert_eq!(
+            world.take_falling_blocks(),
+            vec![((0, 5, 0), Block::new_id(SAND))]
+        );
+        tick(&mut world, 1);
+        assert_eq!(world.take_falling_blocks().len(), 1);
+        assert_eq!(world.get_block(0, 6, 0).id, EMPTY_BLOCK);
+    }
+}
@@ -0,0 +1,395 @@
+/*
+ * Finite fluids are an alternative to the default fluid simulation where
+ * fluids do not have sources that never run out. Instead every fluid block
+ * holds a volume (stored in its geometry, 1 - 7 where 7 is a full block) and
+ * the volume only ever moves between blocks so a pool can be drained or
+ * pumped somewhere else.
+ *
+ * Fluid blocks that are scheduled on a tick are updated one at a time in a
+ * fixed order and each update moves volume directly in the world so that no
+ * volume is lost when two blocks flow into the same space.
+ * */
+
+use super::ADJ;
+use crate::voxel::{
+    block_ids::{LAVA, OBSIDIAN, STONE, WATER},
+    world_to_chunk_position, Block, World, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
+use std::collections::{HashSet, VecDeque};
+
+//Volume of a full fluid block, this matches the geometry of a fluid source
+//in the default simulation so fluids generated with the world are full
+pub const MAX_VOLUME: u8 = 7;
+//Lava only flows on every LAVA_FLOW_TICKS ticks
+const LAVA_FLOW_TICKS: u64 = 5;
+//Maximum number of blocks in a layer of fluid that are searched when leveling
+//out the layer
+const LEVEL_SEARCH_LIMIT: usize = 128;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum FluidMode {
+    //Fluid sources never run out and flowing fluids dry up without them
+    #[default]
+    Classic,
+    //Fluids carry a volume that is conserved as they flow
+    Finite,
+}
+
+pub const ALL_FLUID_MODES: [FluidMode; 2] = [FluidMode::Classic, FluidMode::Finite];
+
+impl FluidMode {
+    //Stored in the world metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Finite => "finite",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ALL_FLUID_MODES
+            .iter()
+            .copied()
+            .find(|mode| mode.name() == name)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Finite => "Finite",
+        }
+    }
+}
+
+//Returns the volume of fluid `id` at the block, 0 if it is not that fluid
+pub fn volume(block: Block, id: u16) -> u8 {
+    if block.id != id {
+        return 0;
+    }
+    //Blocks from the default simulation can be flowing down (8) or waiting
+    //to change (bit 7 is set), both count as full
+    let level = block.geometry & !(1 << 7);
+    if level == 0 || level > MAX_VOLUME {
+        MAX_VOLUME
+    } else {
+        level
+    }
+}
+
+fn fluid_block(id: u16, volume: u8) -> Block {
+    if volume == 0 {
+        return Block::new();
+    }
+    let mut block = Block::new_fluid(id);
+    block.geometry = volume.min(MAX_VOLUME);
+    block
+}
+
+impl World {
+    //Returns true if fluid `id` can flow into (x, y, z), fluids never flow
+    //into chunks that are not loaded since the volume would be lost
+    fn can_hold(&self, x: i32, y: i32, z: i32, id: u16) -> bool {
+        let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+        if self.get_chunk(chunkx, chunky, chunkz).is_none() {
+            return false;
+        }
+        let block = self.get_block(x, y, z);
+        block.id == EMPTY_BLOCK || block.id == id || block.fluid_destructibe()
+    }
+
+    //Sets the volume of fluid at (x, y, z), blocks that are destroyed by
+    //the fluid are added to `destroyed`
+    fn set_volume(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        id: u16,
+        volume: u8,
+        destroyed: &mut Vec<((i32, i32, i32), Block)>,
+    ) {
+        let prev = self.get_block(x, y, z);
+        if prev.id != id && prev.id != EMPTY_BLOCK {
+            destroyed.push(((x, y, z), prev));
+        }
+        self.set_block(x, y, z, fluid_block(id, volume));
+    }
+
+    //Moves `amount` of fluid from `from` to `to`
+    fn move_volume(
+        &mut self,
+        from: (i32, i32, i32),
+        to: (i32, i32, i32),
+        id: u16,
+        amount: u8,
+        changed: &mut Vec<(i32, i32, i32)>,
+        destroyed: &mut Vec<((i32, i32, i32), Block)>,
+    ) {
+        let (fx, fy, fz) = from;
+        let (tx, ty, tz) = to;
+        let from_volume = volume(self.get_block(fx, fy, fz), id);
+        let to_volume = volume(self.get_block(tx, ty, tz), id);
+        self.set_volume(fx, fy, fz, id, from_volume - amount, destroyed);
+        self.set_volume(tx, ty, tz, id, to_volume + amount, destroyed);
+        changed.push(from);
+        changed.push(to);
+    }
+
+    //Lava next to water turns into obsidian if it is full and stone
+    //otherwise, returns true if the lava was turned into stone
+    fn freeze_finite_lava(&mut self, x: i32, y: i32, z: i32) -> bool {
+        let next_to_water = ADJ
+            .iter()
+            .chain(&[(0, 1, 0)])
+            .any(|(dx, dy, dz)| self.get_block(x + dx, y + dy, z + dz).id == WATER);
+        if !next_to_water {
+            return false;
+        }
+        let lava = self.get_block(x, y, z);
+        let id = if volume(lava, LAVA) == MAX_VOLUME {
+            OBSIDIAN
+        } else {
+            STONE
+        };
+        self.set_block(x, y, z, Block::new_id(id));
+        true
+    }
+
+    //Updates a single fluid block, returns the positions of the blocks that
+    //were changed
+    fn update_finite_fluid(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        destroyed: &mut Vec<((i32, i32, i32), Block)>,
+    ) -> Vec<(i32, i32, i32)> {
+        let block = self.get_block(x, y, z);
+        let id = block.id;
+        let mut changed = vec![];
+        if id == LAVA {
+            if self.freeze_finite_lava(x, y, z) {
+                changed.push((x, y, z));
+                return changed;
+            }
+
+            //Check again on the next tick that lava can flow on
+            let wait = (LAVA_FLOW_TICKS - self.ticks % LAVA_FLOW_TICKS) % LAVA_FLOW_TICKS;
+            if wait > 0 {
+                self.schedule_tick(x, y, z, wait);
+                return changed;
+            }
+        }
+
+        let mut level = volume(block, id);
+        //Normalize blocks left over from the default simulation
+        if block.geometry != level {
+            self.set_block(x, y, z, fluid_block(id, level));
+            changed.push((x, y, z));
+        }
+
+        //Flow down as much as possible
+        if self.can_hold(x, y - 1, z, id) {
+            let below = volume(self.get_block(x, y - 1, z), id);
+            let amount = level.min(MAX_VOLUME - below);
+            if amount > 0 {
+                self.move_volume(
+                    (x, y, z),
+                    (x, y - 1, z),
+                    id,
+                    amount,
+                    &mut changed,
+                    destroyed,
+                );
+                level -= amount;
+            }
+        }
+
+        //Spread out to the sides one unit at a time, a block that has
+        //nothing under it takes fluid even if this block only has one unit
+        //left so that fluids drain off of ledges
+        for (dx, dy, dz) in ADJ {
+            if level == 0 {
+                break;
+            }
+
+            let (posx, posy, posz) = (x + dx, y + dy, z + dz);
+            if !self.can_hold(posx, posy, posz, id) {
+                continue;
+            }
+
+            let adj = volume(self.get_block(posx, posy, posz), id);
+            let falls = adj == 0
+                && self.can_hold(posx, posy - 1, posz, id)
+                && volume(self.get_block(posx, posy - 1, posz), id) < MAX_VOLUME;
+            if level >= adj + 2 || (falls && level > 0) {
+                self.move_volume(
+                    (x, y, z),
+                    (posx, posy, posz),
+                    id,
+                    1,
+                    &mut changed,
+                    destroyed,
+                );
+                level -= 1;
+            }
+        }
+
+        //Level out the rest of the layer that this block is connected to,
+        //without this fluids would pile up into mounds since neighbors that
+        //are only one unit apart never flow into each other
+        if let Some((pos, lowest)) = self.find_lowest_in_layer(x, y, z, id) {
+            if level >= lowest + 2 {
+                self.move_volume((x, y, z), pos, id, 1, &mut changed, destroyed);
+            }
+        }
+
+        changed
+    }
+
+    //Searches the layer of fluid connected to (x, y, z) for the block with
+    //the least fluid in it, blocks next to the layer that the fluid could
+    //flow into count as having no fluid
+    fn find_lowest_in_layer(
+        &self,
+        x: i32,
+        y: i32,
+        z: i32,
+        id: u16,
+    ) -> Option<((i32, i32, i32), u8)> {
+        let mut lowest: Option<((i32, i32, i32), u8)> = None;
+        let mut visited = HashSet::from([(x, y, z)]);
+        let mut queue = VecDeque::from([(x, y, z)]);
+        while let Some((posx, posy, posz)) = queue.pop_front() {
+            if visited.len() > LEVEL_SEARCH_LIMIT {
+                break;
+            }
+
+            for (dx, dy, dz) in ADJ {
+                let adj_pos = (posx + dx, posy + dy, posz + dz);
+                let (adjx, adjy, adjz) = adj_pos;
+                if visited.contains(&adj_pos) || !self.can_hold(adjx, adjy, adjz, id) {
+                    continue;
+                }
+                visited.insert(adj_pos);
+
+                let adj = volume(self.get_block(adjx, adjy, adjz), id);
+                if lowest.is_none_or(|(_, vol)| adj < vol) {
+                    lowest = Some((adj_pos, adj));
+                }
+
+                //Only search through blocks that have fluid in them
+                if adj > 0 {
+                    queue.push_back(adj_pos);
+                }
+            }
+        }
+        lowest
+    }
+
+    //Updates fluids that are due on this tick, returns the positions of
+    //the blocks that were changed
+    pub(super) fn update_finite_fluids(
+        &mut self,
+        due: &[(i32, i32, i32)],
+        destroyed: &mut Vec<((i32, i32, i32), Block)>,
+    ) -> Vec<(i32, i32, i32)> {
+        let mut changed = vec![];
+        for &(x, y, z) in due {
+            if !self.get_block(x, y, z).is_fluid() {
+                continue;
+            }
+            changed.extend(self.update_finite_fluid(x, y, z, destroyed));
+        }
+        changed
+    }
+
+    //Removes a bucket's worth of fluid (a full block), starting with the
+    //block at (x, y, z) and taking the rest from the fluid around it so that
+    //a lake can be drained even after it has spread out. Returns the blocks
+    //that were changed, or None if there is not enough fluid nearby in which
+    //case nothing is removed
+    pub fn take_bucket(&mut self, x: i32, y: i32, z: i32) -> Option<Vec<(i32, i32, i32)>> {
+        let block = self.get_block(x, y, z);
+        if !block.is_fluid() {
+            return None;
+        }
+
+        let id = block.id;
+        let mut taken = vec![];
+        let mut total = 0;
+        let mut visited = HashSet::from([(x, y, z)]);
+        let mut queue = VecDeque::from([(x, y, z)]);
+        while let Some((posx, posy, posz)) = queue.pop_front() {
+            let level = volume(self.get_block(posx, posy, posz), id);
+            if level > 0 {
+                let amount = level.min(MAX_VOLUME - total);
+                taken.push(((posx, posy, posz), level - amount));
+                total += amount;
+            }
+
+            if total == MAX_VOLUME {
+                break;
+            }
+
+            if visited.len() > LEVEL_SEARCH_LIMIT {
+                continue;
+            }
+
+            //Search along the layer and up through the fluid above it
+            for (dx, dy, dz) in ADJ.iter().chain(&[(0, 1, 0)]) {
+                let adj_pos = (posx + dx, posy + dy, posz + dz);
+                let (adjx, adjy, adjz) = adj_pos;
+                if visited.contains(&adj_pos) || !self.can_hold(adjx, adjy, adjz, id) {
+                    continue;
+                }
+                if *dy == 1 && volume(self.get_block(adjx, adjy, adjz), id) == 0 {
+                    continue;
+                }
+                visited.insert(adj_pos);
+                queue.push_back(adj_pos);
+            }
+        }
+
+        if total < MAX_VOLUME {
+            return None;
+        }
+
+        for &((posx, posy, posz), level) in &taken {
+            self.set_block(posx, posy, posz, fluid_block(id, level));
+        }
+        Some(taken.into_iter().map(|(pos, _)| pos).collect())
+    }
+
+    //Returns the total volume of fluid `id` in the loaded chunks
+    pub fn total_fluid_volume(&self, id: u16) -> u64 {
+        self.chunks
+            .iter()
+            .filter(|(_, chunk)| chunk.may_contain(|block| block.id == id))
+            .map(|((chunkx, chunky, chunkz), chunk)| {
+                let startx = chunkx * CHUNK_SIZE_I32;
+                let starty = chunky * CHUNK_SIZE_I32;
+                let startz = chunkz * CHUNK_SIZE_I32;
+                let mut total = 0;
+                for x in startx..(startx + CHUNK_SIZE_I32) {
+                    for y in starty..(starty + CHUNK_SIZE_I32) {
+                        for z in startz..(startz + CHUNK_SIZE_I32) {
+                            total += volume(chunk.get_block(x, y, z), id) as u64;
+                        }
+                    }
+                }
+                total
+            })
+            .sum()
+    }
+
+    pub fn fluid_mode(&self) -> FluidMode {
+        self.fluid_mode
+    }
+
+    //Should be set when the world is created, changing the mode of a world
+    //with fluids in it leaves the fluids as they are
+    pub fn set_fluid_mode(&mut self, mode: FluidMode) {
+        self.fluid_mode = mode;
+    }
+}
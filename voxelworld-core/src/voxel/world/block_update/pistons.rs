@@ -0,0 +1,492 @@
+/*
+ * Pistons push the blocks in front of them when they are powered and
+ * sticky pistons also pull the block in front of their head back when they
+ * are no longer powered.
+ *
+ * Pistons use the orientation of their geometry for the direction that
+ * they push in and the reflection bit is set while they are extended. The
+ * head of a piston uses the same orientation as the piston and has its
+ * reflection bit set if it belongs to a sticky piston.
+ *
+ * Blocks are moved right away in the world, the moved blocks are also added
+ * to a list of moving blocks so that they can be drawn sliding into place.
+ * */
+
+use super::{signals::signal_strength, UpdateList, BLOCK_UPDATE_INTERVAL};
+use crate::voxel::{
+    block_ids::{PISTON, PISTON_HEAD, STICKY_PISTON},
+    orientation_to_normal, world_to_chunk_position, Block, World, EMPTY_BLOCK,
+};
+
+//Maximum number of blocks that a piston can push
+pub const PUSH_LIMIT: usize = 12;
+//How many seconds it takes for a moved block to slide into place
+pub const MOVE_TIME: f32 = BLOCK_UPDATE_INTERVAL;
+//Number of ticks a blocked piston waits before it tries to extend again,
+//the blocks in its way might be too far away to notify it when they change
+const RETRY_DELAY: u64 = 4;
+
+//A block that was moved by a piston and is still sliding into place
+#[derive(Clone, Copy, Debug)]
+pub struct MovingBlock {
+    pub block: Block,
+    //Position that the block is moving to
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    //Direction that the block is moving in
+    pub dir: (i32, i32, i32),
+    //Goes from 0.0 to 1.0
+    pub progress: f32,
+}
+
+impl MovingBlock {
+    //Returns the position of the corner of the block with the lowest x, y,
+    //and z coordinates
+    pub fn position(&self) -> (f32, f32, f32) {
+        let (dx, dy, dz) = self.dir;
+        let t = 1.0 - self.progress.clamp(0.0, 1.0);
+        (
+            self.x as f32 - dx as f32 * t,
+            self.y as f32 - dy as f32 * t,
+            self.z as f32 - dz as f32 * t,
+        )
+    }
+}
+
+//Blocks that are moved by a piston and blocks that break when pushed
+struct PushList {
+    moved: Vec<(i32, i32, i32)>,
+    broken: Vec<(i32, i32, i32)>,
+}
+
+pub fn is_piston(id: u16) -> bool {
+    id == PISTON || id == STICKY_PISTON
+}
+
+pub fn piston_facing(block: Block) -> (i32, i32, i32) {
+    let normal = orientation_to_normal(block.orientation());
+    (normal.x, normal.y, normal.z)
+}
+
+//Extended pistons can not be moved since that would separate them from
+//their head
+fn can_move(block: Block) -> bool {
+    let extended = is_piston(block.id) && block.reflection() == 1;
+    !block.immovable() && !extended
+}
+
+//Blocks that are not full voxels are broken and dropped instead of being
+//moved
+fn breaks_when_pushed(block: Block) -> bool {
+    block.non_voxel_geometry()
+        || block.replaceable()
+        || block.fluid_destructibe()
+        || block.no_hitbox()
+}
+
+//Returns None if the piston at (x, y, z) can not push in the direction `dir`
+fn get_push_list(world: &World, x: i32, y: i32, z: i32, dir: (i32, i32, i32)) -> Option<PushList> {
+    let (dx, dy, dz) = dir;
+    let mut list = PushList {
+        moved: vec![],
+        broken: vec![],
+    };
+    let (mut posx, mut posy, mut posz) = (x + dx, y + dy, z + dz);
+    loop {
+        //Do not push blocks into chunks that are not loaded
+        let chunkpos = world_to_chunk_position(posx, posy, posz);
+        world.get_chunk(chunkpos.0, chunkpos.1, chunkpos.2)?;
+
+        let block = world.get_block(posx, posy, posz);
+        if block.id == EMPTY_BLOCK || block.is_fluid() {
+            break;
+        }
+
+        if !can_move(block) {
+            return None;
+        }
+
+        if breaks_when_pushed(block) {
+            list.broken.push((posx, posy, posz));
+            break;
+        }
+
+        if list.moved.len() >= PUSH_LIMIT {
+            return None;
+        }
+        list.moved.push((posx, posy, posz));
+        (posx, posy, posz) = (posx + dx, posy + dy, posz + dz);
+    }
+    Some(list)
+}
+
+//Returns true if the block in front of the piston is its head
+fn has_head(world: &World, x: i32, y: i32, z: i32, block: Block) -> bool {
+    let (dx, dy, dz) = piston_facing(block);
+    let head = world.get_block(x + dx, y + dy, z + dz);
+    head.id == PISTON_HEAD && head.orientation() == block.orientation()
+}
+
+pub fn update_piston(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let mut block = world.get_block(x, y, z);
+    let extended = block.reflection() == 1;
+    let powered = signal_strength(world, x, y, z) > 0;
+    //The head was broken, retract without pulling anything
+    if extended && !has_head(world, x, y, z, block) {
+        block.set_reflection(0);
+        to_update.insert((x, y, z), block);
+        return;
+    }
+
+    if extended == powered {
+        return;
+    }
+
+    //Whether the piston can actually extend is checked when it extends
+    block.set_reflection(powered as u8);
+    to_update.insert((x, y, z), block);
+}
+
+//Piston heads are removed if they are no longer attached to a piston
+pub fn update_piston_head(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let head = world.get_block(x, y, z);
+    let (dx, dy, dz) = piston_facing(head);
+    let (px, py, pz) = (x - dx, y - dy, z - dz);
+    let piston = world.get_block(px, py, pz);
+    if is_piston(piston.id)
+        && piston.reflection() == 1
+        && piston.orientation() == head.orientation()
+    {
+        return;
+    }
+    to_update.insert((x, y, z), Block::new_id(EMPTY_BLOCK));
+}
+
+impl World {
+    //Moves the block, its tile data and its scheduled tick from (x, y, z) in
+    //the direction `dir`, the destination is overwritten
+    fn move_block(&mut self, x: i32, y: i32, z: i32, dir: (i32, i32, i32)) {
+        let (dx, dy, dz) = dir;
+        let (tx, ty, tz) = (x + dx, y + dy, z + dz);
+        let block = self.get_block(x, y, z);
+        let tile_data = self.get_tile_data(x, y, z);
+        let due = self.take_scheduled_tick(x, y, z);
+        self.set_tile_data(x, y, z, None);
+        self.set_block(x, y, z, Block::new());
+        self.stop_moving(x, y, z);
+        self.set_block(tx, ty, tz, block);
+        self.set_tile_data(tx, ty, tz, tile_data);
+        self.start_moving(block, tx, ty, tz, dir);
+        if let Some(due) = due {
+            self.schedule_tick(tx, ty, tz, due.saturating_sub(self.ticks));
+        }
+    }
+
+    //Extends the piston at (x, y, z), returns the positions of the blocks
+    //that were changed and adds the blocks that were broken to `destroyed`
+    pub(super) fn extend_piston(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        destroyed: &mut Vec<((i32, i32, i32), Block)>,
+    ) -> Vec<(i32, i32, i32)> {
+        let mut piston = self.get_block(x, y, z);
+        if !is_piston(piston.id) || piston.reflection() == 1 {
+            return vec![];
+        }
+        let dir = piston_facing(piston);
+        let Some(list) = get_push_list(self, x, y, z, dir) else {
+            self.schedule_tick(x, y, z, RETRY_DELAY);
+            return vec![];
+        };
+
+        let (dx, dy, dz) = dir;
+        let mut changed = vec![(x, y, z)];
+        for (bx, by, bz) in list.broken {
+            destroyed.push(((bx, by, bz), self.get_block(bx, by, bz)));
+            self.set_tile_data(bx, by, bz, None);
+            self.set_block(bx, by, bz, Block::new());
+            changed.push((bx, by, bz));
+        }
+
+        //Start with the block that is furthest from the piston so that
+        //every block moves into an empty space
+        for &(bx, by, bz) in list.moved.iter().rev() {
+            self.move_block(bx, by, bz, dir);
+            changed.push((bx + dx, by + dy, bz + dz));
+        }
+
+        let (hx, hy, hz) = (x + dx, y + dy, z + dz);
+        let mut head = Block::new_id_orientation(PISTON_HEAD, piston.orientation());
+        head.set_reflection((piston.id == STICKY_PISTON) as u8);
+        self.set_block(hx, hy, hz, head);
+        self.start_moving(head, hx, hy, hz, dir);
+        changed.push((hx, hy, hz));
+
+        piston.set_reflection(1);
+        self.set_block(x, y, z, piston);
+        changed
+    }
+
+    //Retracts the piston at (x, y, z), returns the positions of the blocks
+    //that were changed
+    pub(super) fn retract_piston(&mut self, x: i32, y: i32, z: i32) -> Vec<(i32, i32, i32)> {
+        let mut piston = self.get_block(x, y, z);
+        if !is_piston(piston.id) || piston.reflection() == 0 {
+            return vec![];
+        }
+
+        let (dx, dy, dz) = piston_facing(piston);
+        let (hx, hy, hz) = (x + dx, y + dy, z + dz);
+        let mut changed = vec![(x, y, z)];
+        let head = has_head(self, x, y, z, piston);
+        if head {
+            self.set_block(hx, hy, hz, Block::new());
+            changed.push((hx, hy, hz));
+        }
+        piston.set_reflection(0);
+        self.set_block(x, y, z, piston);
+
+        //Pistons with a broken head retract without pulling anything
+        if !head || piston.id != STICKY_PISTON || self.get_block(hx, hy, hz).id != EMPTY_BLOCK {
+            return changed;
+        }
+
+        let (px, py, pz) = (hx + dx, hy + dy, hz + dz);
+        let pulled = self.get_block(px, py, pz);
+        if pulled.id == EMPTY_BLOCK
+            || pulled.is_fluid()
+            || !can_move(pulled)
+            || breaks_when_pushed(pulled)
+        {
+            return changed;
+        }
+        self.move_block(px, py, pz, (-dx, -dy, -dz));
+        changed.push((px, py, pz));
+        changed.push((hx, hy, hz));
+        changed
+    }
+
+    fn start_moving(&mut self, block: Block, x: i32, y: i32, z: i32, dir: (i32, i32, i32)) {
+        self.stop_moving(x, y, z);
+        let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+        if let Some(chunk) = self.get_mut_chunk(chunkx, chunky, chunkz) {
+            chunk.set_moving(x, y, z, true);
+        }
+        self.moving_blocks.push(MovingBlock {
+            block,
+            x,
+            y,
+            z,
+            dir,
+            progress: 0.0,
+        });
+    }
+
+    fn stop_moving(&mut self, x: i32, y: i32, z: i32) {
+        let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+        if let Some(chunk) = self.get_mut_chunk(chunkx, chunky, chunkz) {
+            chunk.set_moving(x, y, z, false);
+        }
+        self.moving_blocks
+            .retain(|moving| (moving.x, moving.y, moving.z) != (x, y, z));
+    }
+
+    //Should be called every frame, blocks that have finished moving are
+    //added back into the chunk meshes
+    pub fn update_moving_blocks(&mut self, dt: f32) {
+        if self.moving_blocks.is_empty() {
+            return;
+        }
+
+        let mut finished = vec![];
+        for moving in &mut self.moving_blocks {
+            moving.progress += dt / MOVE_TIME;
+            if moving.progress >= 1.0 {
+                finished.push((moving.x, moving.y, moving.z));
+            }
+        }
+
+        for (x, y, z) in finished {
+            self.stop_moving(x, y, z);
+            let mut update_mesh = std::collections::HashSet::new();
+            super::get_chunktable_updates(x, y, z, &mut update_mesh);
+            self.mesh_updates.extend(update_mesh);
+        }
+    }
+
+    pub fn moving_blocks(&self) -> &[MovingBlock] {
+        &self.moving_blocks
+    }
+
+    //Returns true if the block at (x, y, z) is still sliding into place
+    pub fn is_moving(&self, x: i32, y: i32, z: i32) -> bool {
+        let (chunkx, chunky, chunkz) = world_to_chunk_position(x, y, z);
+        self.get_chunk(chunkx, chunky, chunkz)
+            .is_some_and(|chunk| chunk.is_moving(x, y, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        inventory::Item,
+        voxel::{
+            block_ids::{CHEST, LEVER, OBSIDIAN, STONE, TORCH},
+            world::{terrain_generator::FlatGenerator, GameMode},
+            INDESTRUCTIBLE,
+        },
+    };
+
+    fn tick(world: &mut World, count: usize) {
+        for _ in 0..count {
+            world.update_blocks(BLOCK_UPDATE_INTERVAL * 1.5, 1);
+        }
+    }
+
+    //A world with a piston at (0, 1, 0) facing +x and a lever behind it
+    fn piston_world(id: u16) -> World {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        for x in -16..16 {
+            for z in -16..16 {
+                world.set_block(x, 0, z, Block::new_id(STONE));
+            }
+        }
+        world.set_block(0, 1, 0, Block::new_id_orientation(id, 1));
+        world.set_block(-1, 1, 0, Block::new_id_orientation(LEVER, 0));
+        tick(&mut world, 2);
+        world
+    }
+
+    #[test]
+    fn test_push_chest() {
+        let mut world = piston_world(PISTON);
+        world.set_block(1, 1, 0, Block::new_id(CHEST));
+        world.init_tile_data(1, 1, 0);
+        let mut chest = world.get_tile_data(1, 1, 0).unwrap();
+        chest
+            .inventory
+            .set_item(0, 0, Item::Block(Block::new_id(STONE), 7));
+        world.set_tile_data(1, 1, 0, Some(chest));
+        world.set_block(2, 1, 0, Block::new_id(TORCH));
+
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 1);
+        assert_eq!(world.get_block(1, 1, 0).id, PISTON_HEAD);
+        assert_eq!(world.get_block(2, 1, 0).id, CHEST);
+        assert!(world.get_tile_data(1, 1, 0).is_none());
+        let moved = world.get_tile_data(2, 1, 0).unwrap();
+        assert!(matches!(
+            moved.inventory.get_item(0, 0),
+            Item::Block(block, 7) if block.id == STONE
+        ));
+        assert!(world
+            .moving_blocks()
+            .iter()
+            .any(|moving| (moving.x, moving.y, moving.z) == (2, 1, 0)));
+        world.update_moving_blocks(MOVE_TIME);
+        assert!(world.moving_blocks().is_empty());
+
+        //A regular piston leaves the block behind
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 0);
+        assert_eq!(world.get_block(1, 1, 0).id, EMPTY_BLOCK);
+        assert_eq!(world.get_block(2, 1, 0).id, CHEST);
+    }
+
+    #[test]
+    fn test_push_limit_and_immovable() {
+        let mut world = piston_world(PISTON);
+        for x in 1..=(PUSH_LIMIT as i32 + 1) {
+            world.set_block(x, 1, 0, Block::new_id(STONE));
+        }
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 0);
+
+        //The end of the line is too far away to notify the piston so it
+        //tries again on its own
+        world.set_block(PUSH_LIMIT as i32 + 1, 1, 0, Block::new());
+        tick(&mut world, RETRY_DELAY as usize + 1);
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 1);
+        assert_eq!(world.get_block(PUSH_LIMIT as i32 + 1, 1, 0).id, STONE);
+
+        for id in [INDESTRUCTIBLE, OBSIDIAN] {
+            let mut world = piston_world(PISTON);
+            world.set_block(1, 1, 0, Block::new_id(STONE));
+            world.set_block(2, 1, 0, Block::new_id(id));
+            world.use_switch(-1, 1, 0);
+            tick(&mut world, 3);
+            assert_eq!(world.get_block(0, 1, 0).reflection(), 0);
+            assert_eq!(world.get_block(1, 1, 0).id, STONE);
+        }
+    }
+
+    #[test]
+    fn test_push_scheduled_tick() {
+        let mut world = piston_world(PISTON);
+        world.set_block(1, 1, 0, Block::new_id(STONE));
+        world.schedule_tick(1, 1, 0, 50);
+        let due = world.ticks + 50;
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(2, 1, 0).id, STONE);
+        let ticks: Vec<_> = world
+            .get_chunk(0, 0, 0)
+            .unwrap()
+            .scheduled_ticks()
+            .collect();
+        assert!(ticks.contains(&((2, 1, 0), due)));
+        assert!(!ticks.contains(&((1, 1, 0), due)));
+    }
+
+    #[test]
+    fn test_sticky_pull() {
+        let mut world = piston_world(STICKY_PISTON);
+        world.set_block(1, 1, 0, Block::new_id(STONE));
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(2, 1, 0).id, STONE);
+        assert_eq!(world.get_block(1, 1, 0).reflection(), 1);
+
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 0);
+        assert_eq!(world.get_block(1, 1, 0).id, STONE);
+        assert_eq!(world.get_block(2, 1, 0).id, EMPTY_BLOCK);
+    }
+
+    #[test]
+    fn test_broken_head() {
+        let mut world = piston_world(PISTON);
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(1, 1, 0).id, PISTON_HEAD);
+        //Breaking the piston removes the head
+        world.set_block(0, 1, 0, Block::new());
+        tick(&mut world, 2);
+        assert_eq!(world.get_block(1, 1, 0).id, EMPTY_BLOCK);
+    }
+
+    #[test]
+    fn test_sticky_broken_head() {
+        let mut world = piston_world(STICKY_PISTON);
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(1, 1, 0).id, PISTON_HEAD);
+        //Breaking the head retracts the piston without pulling the block in
+        //front of where the head was
+        world.set_block(2, 1, 0, Block::new_id(STONE));
+        world.set_block(1, 1, 0, Block::new());
+        world.use_switch(-1, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 0);
+        assert_eq!(world.get_block(1, 1, 0).id, EMPTY_BLOCK);
+        assert_eq!(world.get_block(2, 1, 0).id, STONE);
+    }
+}
@@ -1,7 +1,13 @@
 use super::{get_chunktable_updates, UpdateList, World, CHUNK_SIZE_I32};
-use crate::{
-    gfx::ChunkTables,
-    voxel::{Block, EMPTY_BLOCK},
+use crate::voxel::{
+    block_ids::{
+        BLUE_FLOWER, BLUE_FLOWER_SEEDS, CACTUS, COTTON_FLOWERS, COTTON_SEED, DIRT, DRY_FARMLAND,
+        GLASS, GRASS, GROWING_BLUE_FLOWER, GROWING_RED_FLOWER, GROWING_WHITE_FLOWER,
+        GROWING_YELLOW_FLOWER, LEAVES, LOG, RED_FLOWER, RED_FLOWER_SEEDS, SAPLING, SEEDS,
+        SNOWY_LEAVES, SNOWY_SAPLING, SUGARCANE, WATER, WET_FARMLAND, WHEAT_1, WHEAT_3,
+        WHITE_FLOWER_SEEDS, YELLOW_FLOWER, YELLOW_FLOWER_SEEDS,
+    },
+    Block, EMPTY_BLOCK,
 };
 use std::collections::HashSet;
 
@@ -9,14 +15,14 @@ pub const RANDOM_UPDATE_INTERVAL: f32 = 0.25;
 
 //This function was technically created for growing wheat but can also be used
 //for other crops as well (such as cotton/flowers)
-fn grow_wheat(world: &World, x: i32, y: i32, z: i32, id: u8, to_update: &mut UpdateList) {
+fn grow_wheat(world: &World, x: i32, y: i32, z: i32, id: u16, to_update: &mut UpdateList) {
     let below = world.get_block(x, y - 1, z);
-    let skip_dry_farmland = below.id == 45 && fastrand::i32(0..12) < 11;
-    let skip_wet_farmland = below.id == 43 && fastrand::i32(0..10) < 7;
+    let skip_dry_farmland = below.id == DRY_FARMLAND && fastrand::i32(0..12) < 11;
+    let skip_wet_farmland = below.id == WET_FARMLAND && fastrand::i32(0..10) < 7;
     if skip_wet_farmland || skip_dry_farmland {
         return;
     }
-    if !(below.id == 43 || below.id == 45) {
+    if !(below.id == WET_FARMLAND || below.id == DRY_FARMLAND) {
         return;
     }
     to_update.insert((x, y, z), Block::new_id(id + 1));
@@ -31,15 +37,15 @@ fn update_grass(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateLis
         return;
     }
     //Ignore logs
-    if above.id == 8 {
+    if above.id == LOG {
         return;
     }
     //Ignore water that isn't a full block
-    if above.id == 12 && above.geometry < 7 {
+    if above.id == WATER && above.geometry < 7 {
         return;
     }
     //If a non-trasparent block is above the block, then have the grass die
-    to_update.insert((x, y, z), Block::new_id(4));
+    to_update.insert((x, y, z), Block::new_id(DIRT));
 }
 
 fn update_dirt(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
@@ -63,8 +69,8 @@ fn update_dirt(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList
                 }
                 let block = world.get_block(x + dx, y + dy, z + dz);
 
-                if block.id == 1 && fastrand::bool() {
-                    to_update.insert((x, y, z), Block::new_id(1));
+                if block.id == GRASS && fastrand::bool() {
+                    to_update.insert((x, y, z), Block::new_id(GRASS));
                     return;
                 }
             }
@@ -75,21 +81,21 @@ fn update_dirt(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList
 //Convert dry farmland that is near water into wet farmland
 fn update_dry_farmland(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
     let above = world.get_block(x, y + 1, z);
-    if above.id == 12 {
-        to_update.insert((x, y, z), Block::new_id(43));
+    if above.id == WATER {
+        to_update.insert((x, y, z), Block::new_id(WET_FARMLAND));
         return;
     }
 
     for dx in -4..=4 {
         for dz in -4..=4 {
             let block = world.get_block(x + dx, y, z + dz);
-            if block.id == 12 {
-                to_update.insert((x, y, z), Block::new_id(43));
+            if block.id == WATER {
+                to_update.insert((x, y, z), Block::new_id(WET_FARMLAND));
                 return;
             }
             let block = world.get_block(x + dx, y - 1, z + dz);
-            if block.id == 12 {
-                to_update.insert((x, y, z), Block::new_id(43));
+            if block.id == WATER {
+                to_update.insert((x, y, z), Block::new_id(WET_FARMLAND));
                 return;
             }
         }
@@ -99,24 +105,24 @@ fn update_dry_farmland(world: &World, x: i32, y: i32, z: i32, to_update: &mut Up
 //Convert wet farmland that is far away from water into dry farmland
 fn update_wet_farmland(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
     let above = world.get_block(x, y + 1, z);
-    if above.id == 12 {
+    if above.id == WATER {
         return;
     }
 
     for dx in -4..=4 {
         for dz in -4..=4 {
             let block = world.get_block(x + dx, y, z + dz);
-            if block.id == 12 {
+            if block.id == WATER {
                 return;
             }
             let block = world.get_block(x + dx, y - 1, z + dz);
-            if block.id == 12 {
+            if block.id == WATER {
                 return;
             }
         }
     }
 
-    to_update.insert((x, y, z), Block::new_id(45));
+    to_update.insert((x, y, z), Block::new_id(DRY_FARMLAND));
 }
 
 //Have sugar cane grow
@@ -130,21 +136,21 @@ fn grow_sugarcane(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateL
     }
 
     let mut dy = -1;
-    while dy > -2 && world.get_block(x, y + dy, z).id == 69 {
+    while dy > -2 && world.get_block(x, y + dy, z).id == SUGARCANE {
         dy -= 1;
     }
 
     //If the sugar cane is floating or too tall, then do not update it
     let block = world.get_block(x, y + dy, z);
-    if block.id == EMPTY_BLOCK || block.id == 69 {
+    if block.id == EMPTY_BLOCK || block.id == SUGARCANE {
         return;
     }
 
     //Check if the sugar cane is bordering water
     const ADJ: [(i32, i32); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
     for (dx, dz) in ADJ {
-        if world.get_block(x + dx, y + dy, z + dz).id == 12 {
-            to_update.insert((x, y + 1, z), Block::new_id(69));
+        if world.get_block(x + dx, y + dy, z + dz).id == WATER {
+            to_update.insert((x, y + 1, z), Block::new_id(SUGARCANE));
             return;
         }
     }
@@ -177,9 +183,9 @@ fn grow_plant(world: &World, x: i32, y: i32, z: i32, prob_perc: u32, to_update:
 //Have sapling grow
 fn sapling_replaceable(block: Block) -> bool {
     block.id == EMPTY_BLOCK
-        || (block.transparent() && !block.is_fluid() && block.id != 9)
-        || block.id == 8
-        || block.id == 91
+        || (block.transparent() && !block.is_fluid() && block.id != GLASS)
+        || block.id == LOG
+        || block.id == SNOWY_LEAVES
 }
 
 fn sapling_place_leaves(
@@ -188,7 +194,7 @@ fn sapling_place_leaves(
     x: i32,
     y: i32,
     z: i32,
-    leaf_id: u8,
+    leaf_id: u16,
 ) {
     if world.get_block(x, y, z).id != EMPTY_BLOCK {
         return;
@@ -202,7 +208,7 @@ fn grow_leaves(
     starty: i32,
     xyz: (i32, i32, i32),
     height: i32,
-    leaf_id: u8,
+    leaf_id: u16,
 ) {
     let (x, y, z) = xyz;
     if y == starty + height {
@@ -226,7 +232,7 @@ fn grow_leaves(
     }
 }
 
-fn grow_sapling(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList, leaf_id: u8) {
+fn grow_sapling(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList, leaf_id: u16) {
     if fastrand::i32(0..12) != 0 {
         return;
     }
@@ -251,7 +257,7 @@ fn grow_sapling(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateLis
         grow_leaves(world, to_update, y, (x, vy, z), height, leaf_id);
     }
     for vy in y..(y + height) {
-        to_update.insert((x, vy, z), Block::new_id(8));
+        to_update.insert((x, vy, z), Block::new_id(LOG));
     }
 }
 
@@ -265,7 +271,7 @@ fn leaf_decay(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList)
         for vy in (y - 3)..=(y + 3) {
             for vz in (z - 3)..=(z + 3) {
                 //If within 3 blocks of a log, ignore
-                if world.get_block(vx, vy, vz).id == 8 {
+                if world.get_block(vx, vy, vz).id == LOG {
                     return;
                 }
             }
@@ -309,49 +315,55 @@ impl World {
                 }
                 match block.id {
                     //Grass
-                    1 => update_grass(self, x, y, z, to_update),
+                    GRASS => update_grass(self, x, y, z, to_update),
                     //Dirt
-                    4 => update_dirt(self, x, y, z, to_update),
+                    DIRT => update_dirt(self, x, y, z, to_update),
                     //Leaves
-                    7 | 91 => leaf_decay(self, x, y, z, to_update),
+                    LEAVES | SNOWY_LEAVES => leaf_decay(self, x, y, z, to_update),
                     //Wet farmland
-                    43 => update_wet_farmland(self, x, y, z, to_update),
+                    WET_FARMLAND => update_wet_farmland(self, x, y, z, to_update),
                     //Dry farmland
-                    45 => update_dry_farmland(self, x, y, z, to_update),
+                    DRY_FARMLAND => update_dry_farmland(self, x, y, z, to_update),
                     //Sapling
-                    47 => grow_sapling(self, x, y, z, to_update, 7),
+                    SAPLING => grow_sapling(self, x, y, z, to_update, LEAVES),
                     //Growing wheat, cotton, flowers
-                    50..=52 | 98..=101 | 103 | 105 | 107 => {
-                        grow_wheat(self, x, y, z, block.id, to_update)
-                    }
+                    WHEAT_1..=WHEAT_3
+                    | COTTON_SEED..=COTTON_FLOWERS
+                    | RED_FLOWER_SEEDS
+                    | YELLOW_FLOWER_SEEDS
+                    | BLUE_FLOWER_SEEDS => grow_wheat(self, x, y, z, block.id, to_update),
                     //Red flower
-                    104 => grow_wheat(self, x, y, z, 54 - 1, to_update),
+                    GROWING_RED_FLOWER => grow_wheat(self, x, y, z, RED_FLOWER - 1, to_update),
                     //Yellow flower
-                    106 => grow_wheat(self, x, y, z, 55 - 1, to_update),
+                    GROWING_YELLOW_FLOWER => {
+                        grow_wheat(self, x, y, z, YELLOW_FLOWER - 1, to_update)
+                    }
                     //Blue flower
-                    108 => grow_wheat(self, x, y, z, 56 - 1, to_update),
+                    GROWING_BLUE_FLOWER => grow_wheat(self, x, y, z, BLUE_FLOWER - 1, to_update),
                     //White flower
-                    109 | 110 => grow_wheat(self, x, y, z, block.id, to_update),
+                    WHITE_FLOWER_SEEDS | GROWING_WHITE_FLOWER => {
+                        grow_wheat(self, x, y, z, block.id, to_update)
+                    }
                     //Sugar cane
-                    69 => grow_sugarcane(self, x, y, z, to_update),
+                    SUGARCANE => grow_sugarcane(self, x, y, z, to_update),
                     //Grow cactus
-                    88 => grow_plant(self, x, y, z, 20, to_update),
+                    CACTUS => grow_plant(self, x, y, z, 20, to_update),
                     //Seeds
-                    77 => grow_wheat(self, x, y, z, 50 - 1, to_update),
+                    SEEDS => grow_wheat(self, x, y, z, WHEAT_1 - 1, to_update),
                     //Snow sapling
-                    92 => grow_sapling(self, x, y, z, to_update, 91),
+                    SNOWY_SAPLING => grow_sapling(self, x, y, z, to_update, SNOWY_LEAVES),
                     _ => {}
                 }
             });
     }
 
-    //If chunktables is None, we do not update any chunktable
+    //If update_light is false, we do not update the light or any chunk meshes
     //Returns a vec of destroyed blocks
     //Vec<(block, x, y, z)>
     pub fn rand_block_update(
         &mut self,
         dt: f32,
-        chunktables: Option<&mut ChunkTables>,
+        update_light: bool,
         chunk_sim_dist: i32,
     ) -> Vec<((i32, i32, i32), Block)> {
         self.random_update_timer -= dt;
@@ -387,14 +399,9 @@ impl World {
             get_chunktable_updates(x, y, z, &mut update_mesh);
         }
 
-        if chunktables.is_some() {
+        if update_light {
             update_mesh.extend(self.update_block_light(&light_updates));
-        }
-
-        if let Some(chunktables) = chunktables {
-            for (x, y, z) in update_mesh {
-                chunktables.update_table(self, x, y, z);
-            }
+            self.mesh_updates.extend(update_mesh);
         }
 
         destroyed
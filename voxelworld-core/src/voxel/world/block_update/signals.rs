@@ -0,0 +1,504 @@
+/*
+ * Signals allow players to build mechanisms. Power sources (levers, buttons,
+ * and pressure plates) send a signal through wires to consumers (doors,
+ * gates, and lamps). The signal loses one level of strength for every wire
+ * that it passes through, repeaters bring it back to full strength and
+ * inverters output a signal only when their input is off.
+ *
+ * The state of a signal block is stored in its geometry:
+ * wire: the lower 4 bits are the strength of the signal (0 - 15)
+ * lever, button, pressure plate, repeater, inverter: the reflection bit is
+ * set if the block is outputting a signal
+ *
+ * Levers and buttons use the orientation for the face they are attached to
+ * and repeaters and inverters use it for the direction that they output in.
+ *
+ * Signals are propagated with the scheduled block updates, a whole network
+ * of connected wires is updated at once while every other component takes
+ * one tick to react to a change in its input.
+ * */
+
+use super::UpdateList;
+use crate::voxel::{
+    block_ids::{
+        BUTTON, DOOR, DOOR_TOP, GATE, INVERTER, LAMP, LEVER, LIT_LAMP, PRESSURE_PLATE, REPEATER,
+        WIRE,
+    },
+    is_valid::get_check_valid_fn,
+    orientation_to_normal, Block, World, EMPTY_BLOCK,
+};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+pub const MAX_SIGNAL: u8 = 15;
+//How many ticks a button stays pressed
+pub const BUTTON_TICKS: u64 = 5;
+//How many ticks a pressure plate stays pressed after nothing is on it
+pub const PLATE_TICKS: u64 = 2;
+//Maximum number of wires that are updated at once, this prevents one huge
+//network from freezing the game
+const MAX_NETWORK_SIZE: usize = 4096;
+
+const FACES: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+//Wires connect to the wires next to them and can also go up or down one
+//block at a time
+const WIRE_CONNECTIONS: [(i32, i32, i32); 14] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+    (1, 1, 0),
+    (-1, 1, 0),
+    (0, 1, 1),
+    (0, 1, -1),
+    (1, -1, 0),
+    (-1, -1, 0),
+    (0, -1, 1),
+    (0, -1, -1),
+];
+
+//Returns true if the block is part of a circuit (it can output a signal or
+//carry one)
+pub fn is_signal_component(id: u16) -> bool {
+    matches!(id, LEVER..=INVERTER)
+}
+
+pub fn wire_strength(block: Block) -> u8 {
+    block.geometry & 15
+}
+
+fn set_wire_strength(block: &mut Block, strength: u8) {
+    block.geometry = (block.geometry & !15) | strength.min(MAX_SIGNAL);
+}
+
+//Returns the strength of the signal that the block at (x, y, z) outputs in
+//the direction (dx, dy, dz)
+fn output(world: &World, x: i32, y: i32, z: i32, dir: (i32, i32, i32)) -> u8 {
+    let block = world.get_block(x, y, z);
+    match block.id {
+        LEVER | BUTTON | PRESSURE_PLATE if block.reflection() == 1 => MAX_SIGNAL,
+        WIRE => wire_strength(block),
+        REPEATER | INVERTER if block.reflection() == 1 => {
+            let facing = orientation_to_normal(block.orientation());
+            if (facing.x, facing.y, facing.z) == dir {
+                MAX_SIGNAL
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    }
+}
+
+//Returns the strength of the strongest signal going into (x, y, z)
+pub fn signal_strength(world: &World, x: i32, y: i32, z: i32) -> u8 {
+    FACES
+        .iter()
+        .map(|(dx, dy, dz)| output(world, x + dx, y + dy, z + dz, (-dx, -dy, -dz)))
+        .max()
+        .unwrap_or(0)
+}
+
+//Returns true if there is anything next to (x, y, z) that can send it a
+//signal, consumers ignore signals if they are not connected to a circuit so
+//that they can still be used by hand
+fn next_to_circuit(world: &World, x: i32, y: i32, z: i32) -> bool {
+    FACES
+        .iter()
+        .map(|(dx, dy, dz)| world.get_block(x + dx, y + dy, z + dz))
+        .any(|block| is_signal_component(block.id))
+}
+
+//Removes the block if it is no longer attached to anything,
+//returns false if it was removed
+fn check_attached(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) -> bool {
+    let id = world.get_block(x, y, z).id;
+    if let Some(check_valid) = get_check_valid_fn(id) {
+        if !check_valid(world, x, y, z) {
+            to_update.insert((x, y, z), Block::new_id(EMPTY_BLOCK));
+            return false;
+        }
+    }
+    true
+}
+
+//Returns the tick that a button or pressure plate is released on
+fn release_tick(world: &World, x: i32, y: i32, z: i32) -> u64 {
+    world.signal_timers.get(&(x, y, z)).copied().unwrap_or(0)
+}
+
+//Levers only need to check if they are still attached, they are flipped
+//by the player
+pub fn update_lever(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    check_attached(world, x, y, z, to_update);
+}
+
+//Buttons and pressure plates
+pub fn update_pressable(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    if !check_attached(world, x, y, z, to_update) {
+        return;
+    }
+
+    let mut block = world.get_block(x, y, z);
+    if block.reflection() == 0 || world.ticks < release_tick(world, x, y, z) {
+        return;
+    }
+    block.set_reflection(0);
+    to_update.insert((x, y, z), block);
+}
+
+//Finds every wire connected to (x, y, z), returns them in the order that
+//they were found
+fn get_wire_network(world: &World, x: i32, y: i32, z: i32) -> Vec<(i32, i32, i32)> {
+    let mut network = vec![(x, y, z)];
+    let mut visited = HashSet::from([(x, y, z)]);
+    let mut i = 0;
+    while i < network.len() && network.len() < MAX_NETWORK_SIZE {
+        let (wx, wy, wz) = network[i];
+        for (dx, dy, dz) in WIRE_CONNECTIONS {
+            let pos = (wx + dx, wy + dy, wz + dz);
+            if visited.contains(&pos) {
+                continue;
+            }
+            let (px, py, pz) = pos;
+            if world.get_block(px, py, pz).id != WIRE {
+                continue;
+            }
+            visited.insert(pos);
+            network.push(pos);
+        }
+        i += 1;
+    }
+    network
+}
+
+pub fn update_wire(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    update_wire_network(world, x, y, z, to_update);
+}
+
+//Recalculates the signal strength of every wire in the network that the
+//wire at (x, y, z) is in, the strength of a wire is the strongest signal
+//that reaches it minus the number of wires the signal passed through.
+//Returns the wires in the network so that they are not updated again on
+//the same tick.
+pub fn update_wire_network(
+    world: &World,
+    x: i32,
+    y: i32,
+    z: i32,
+    to_update: &mut UpdateList,
+) -> Vec<(i32, i32, i32)> {
+    if !check_attached(world, x, y, z, to_update) {
+        return vec![];
+    }
+
+    let network = get_wire_network(world, x, y, z);
+    let mut strengths = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for &(wx, wy, wz) in &network {
+        let input = FACES
+            .iter()
+            .filter(|(dx, dy, dz)| world.get_block(wx + dx, wy + dy, wz + dz).id != WIRE)
+            .map(|(dx, dy, dz)| output(world, wx + dx, wy + dy, wz + dz, (-dx, -dy, -dz)))
+            .max()
+            .unwrap_or(0);
+        strengths.insert((wx, wy, wz), input);
+        if input > 0 {
+            queue.push((input, (wx, wy, wz)));
+        }
+    }
+
+    //Spread the strongest signals first so that a wire never has to be
+    //visited again after a weaker signal reached it
+    while let Some((strength, (wx, wy, wz))) = queue.pop() {
+        if strengths.get(&(wx, wy, wz)).copied().unwrap_or(0) > strength {
+            continue;
+        }
+
+        for (dx, dy, dz) in WIRE_CONNECTIONS {
+            let pos = (wx + dx, wy + dy, wz + dz);
+            let Some(current) = strengths.get_mut(&pos) else {
+                continue;
+            };
+            if *current + 1 >= strength {
+                continue;
+            }
+            *current = strength - 1;
+            queue.push((strength - 1, pos));
+        }
+    }
+
+    for &(wx, wy, wz) in &network {
+        let mut block = world.get_block(wx, wy, wz);
+        let strength = strengths.get(&(wx, wy, wz)).copied().unwrap_or(0);
+        if wire_strength(block) == strength {
+            continue;
+        }
+        set_wire_strength(&mut block, strength);
+        to_update.insert((wx, wy, wz), block);
+    }
+    network
+}
+
+//Repeaters output a full strength signal if there is a signal going into
+//their back, inverters output a signal if there is no signal going in
+pub fn update_repeater(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    if !check_attached(world, x, y, z, to_update) {
+        return;
+    }
+
+    let mut block = world.get_block(x, y, z);
+    let facing = orientation_to_normal(block.orientation());
+    let (bx, by, bz) = (x - facing.x, y - facing.y, z - facing.z);
+    let input = output(world, bx, by, bz, (facing.x, facing.y, facing.z)) > 0;
+    let on = if block.id == INVERTER { !input } else { input };
+    if (block.reflection() == 1) == on {
+        return;
+    }
+    block.set_reflection(on as u8);
+    to_update.insert((x, y, z), block);
+}
+
+pub fn update_lamp(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let mut block = world.get_block(x, y, z);
+    let id = if signal_strength(world, x, y, z) > 0 {
+        LIT_LAMP
+    } else {
+        LAMP
+    };
+    if block.id == id {
+        return;
+    }
+    block.id = id;
+    to_update.insert((x, y, z), block);
+}
+
+//Doors and gates are open while they are powered
+pub fn update_door(world: &World, x: i32, y: i32, z: i32, to_update: &mut UpdateList) {
+    let block = world.get_block(x, y, z);
+    //Every block that is part of the door
+    let parts = match block.id {
+        DOOR if world.get_block(x, y + 1, z).id == DOOR_TOP => vec![(x, y, z), (x, y + 1, z)],
+        DOOR_TOP if world.get_block(x, y - 1, z).id == DOOR => vec![(x, y - 1, z), (x, y, z)],
+        GATE => vec![(x, y, z)],
+        _ => return,
+    };
+
+    if !parts
+        .iter()
+        .any(|&(px, py, pz)| next_to_circuit(world, px, py, pz))
+    {
+        return;
+    }
+
+    let open = parts
+        .iter()
+        .any(|&(px, py, pz)| signal_strength(world, px, py, pz) > 0);
+    if (block.reflection() == 1) == open {
+        return;
+    }
+
+    for (px, py, pz) in parts {
+        let mut part = world.get_block(px, py, pz);
+        part.set_reflection(open as u8);
+        to_update.insert((px, py, pz), part);
+    }
+}
+
+impl World {
+    //Returns true if a signal is going into (x, y, z)
+    pub fn is_powered(&self, x: i32, y: i32, z: i32) -> bool {
+        signal_strength(self, x, y, z) > 0
+    }
+
+    //Flips a lever or presses a button, returns false if the block at
+    //(x, y, z) is neither
+    pub fn use_switch(&mut self, x: i32, y: i32, z: i32) -> bool {
+        let mut block = self.get_block(x, y, z);
+        match block.id {
+            LEVER => block.set_reflection(1 - block.reflection()),
+            BUTTON => {
+                block.set_reflection(1);
+                self.signal_timers
+                    .insert((x, y, z), self.ticks + BUTTON_TICKS);
+                self.schedule_tick(x, y, z, BUTTON_TICKS);
+            }
+            _ => return false,
+        }
+        self.set_block(x, y, z, block);
+        true
+    }
+
+    //Should be called every frame for anything that is standing in the block
+    //(x, y, z), does nothing if there is no pressure plate there
+    pub fn press_plate(&mut self, x: i32, y: i32, z: i32) {
+        let mut block = self.get_block(x, y, z);
+        if block.id != PRESSURE_PLATE {
+            return;
+        }
+
+        self.signal_timers
+            .insert((x, y, z), self.ticks + PLATE_TICKS);
+        self.schedule_tick(x, y, z, PLATE_TICKS);
+        if block.reflection() == 1 {
+            return;
+        }
+        block.set_reflection(1);
+        self.set_block(x, y, z, block);
+        let mut update_mesh = HashSet::new();
+        super::get_chunktable_updates(x, y, z, &mut update_mesh);
+        self.mesh_updates.extend(update_mesh);
+    }
+
+    //Removes the timers of buttons and pressure plates that were released
+    //and schedules an update for the rest on the tick they are released, a
+    //block can only have one scheduled update so an earlier update (from a
+    //neighbor changing) replaces the one for the release
+    pub(super) fn update_signal_timers(&mut self) {
+        let ticks = self.ticks;
+        self.signal_timers.retain(|_, release| *release > ticks);
+        let timers: Vec<_> = self
+            .signal_timers
+            .iter()
+            .map(|(&pos, &release)| (pos, release))
+            .collect();
+        for ((x, y, z), release) in timers {
+            self.schedule_tick(x, y, z, release - ticks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{
+        block_ids::STONE,
+        world::{block_update::BLOCK_UPDATE_INTERVAL, terrain_generator::FlatGenerator, GameMode},
+    };
+
+    fn tick(world: &mut World, count: usize) {
+        for _ in 0..count {
+            world.update_blocks(BLOCK_UPDATE_INTERVAL * 1.5, 1);
+        }
+    }
+
+    //A world with a floor of stone at y = 0
+    fn circuit_world() -> World {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        for x in -16..16 {
+            for z in -16..16 {
+                world.set_block(x, 0, z, Block::new_id(STONE));
+            }
+        }
+        tick(&mut world, 2);
+        world
+    }
+
+    #[test]
+    fn test_wire_decay() {
+        let mut world = circuit_world();
+        world.set_block(0, 1, 0, Block::new_id_orientation(LEVER, 0));
+        for x in 1..=15 {
+            world.set_block(x, 1, 0, Block::new_id(WIRE));
+        }
+        world.set_block(2, 1, 1, Block::new_id(LAMP));
+        tick(&mut world, 3);
+        assert_eq!(wire_strength(world.get_block(1, 1, 0)), 0);
+        assert_eq!(world.get_block(2, 1, 1).id, LAMP);
+
+        assert!(world.use_switch(0, 1, 0));
+        tick(&mut world, 3);
+        for x in 1..=15 {
+            let strength = wire_strength(world.get_block(x, 1, 0));
+            assert_eq!(strength, MAX_SIGNAL + 1 - x as u8);
+        }
+        assert_eq!(world.get_block(2, 1, 1).id, LIT_LAMP);
+
+        //Turn the lever back off
+        world.use_switch(0, 1, 0);
+        tick(&mut world, 3);
+        assert_eq!(wire_strength(world.get_block(8, 1, 0)), 0);
+        assert_eq!(world.get_block(2, 1, 1).id, LAMP);
+    }
+
+    #[test]
+    fn test_repeater_and_inverter() {
+        let mut world = circuit_world();
+        world.set_block(0, 1, 0, Block::new_id_orientation(LEVER, 0));
+        world.set_block(1, 1, 0, Block::new_id(WIRE));
+        //Repeater facing +x
+        world.set_block(2, 1, 0, Block::new_id_orientation(REPEATER, 1));
+        world.set_block(3, 1, 0, Block::new_id(WIRE));
+        //Inverter facing +x
+        world.set_block(4, 1, 0, Block::new_id_orientation(INVERTER, 1));
+        world.set_block(5, 1, 0, Block::new_id(WIRE));
+        tick(&mut world, 8);
+        assert_eq!(world.get_block(2, 1, 0).reflection(), 0);
+        assert_eq!(wire_strength(world.get_block(5, 1, 0)), MAX_SIGNAL);
+
+        world.use_switch(0, 1, 0);
+        tick(&mut world, 8);
+        assert_eq!(world.get_block(2, 1, 0).reflection(), 1);
+        assert_eq!(wire_strength(world.get_block(3, 1, 0)), MAX_SIGNAL);
+        assert_eq!(world.get_block(4, 1, 0).reflection(), 0);
+        assert_eq!(wire_strength(world.get_block(5, 1, 0)), 0);
+    }
+
+    #[test]
+    fn test_button_release() {
+        let mut world = circuit_world();
+        world.set_block(0, 1, 0, Block::new_id_orientation(BUTTON, 0));
+        world.set_block(1, 1, 0, Block::new_id(WIRE));
+        tick(&mut world, 2);
+        assert!(world.use_switch(0, 1, 0));
+        tick(&mut world, 2);
+        assert_eq!(wire_strength(world.get_block(1, 1, 0)), MAX_SIGNAL);
+        tick(&mut world, BUTTON_TICKS as usize + 2);
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 0);
+        assert_eq!(wire_strength(world.get_block(1, 1, 0)), 0);
+        assert!(world.signal_timers.is_empty());
+    }
+
+    #[test]
+    fn test_plate_opens_door() {
+        let mut world = circuit_world();
+        world.set_block(0, 1, 0, Block::new_id(PRESSURE_PLATE));
+        world.set_block(1, 1, 0, Block::new_id(WIRE));
+        world.set_block(2, 1, 0, Block::new_id_orientation(DOOR, 2));
+        world.set_block(2, 2, 0, Block::new_id_orientation(DOOR_TOP, 2));
+        tick(&mut world, 2);
+
+        //Something stands on the plate for a few ticks
+        for _ in 0..4 {
+            world.press_plate(0, 1, 0);
+            tick(&mut world, 1);
+        }
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 1);
+        assert_eq!(world.get_block(2, 1, 0).reflection(), 1);
+        assert_eq!(world.get_block(2, 2, 0).reflection(), 1);
+
+        tick(&mut world, PLATE_TICKS as usize + 3);
+        assert_eq!(world.get_block(0, 1, 0).reflection(), 0);
+        assert_eq!(world.get_block(2, 1, 0).reflection(), 0);
+    }
+
+    #[test]
+    fn test_broken_support() {
+        let mut world = circuit_world();
+        world.set_block(0, 1, 0, Block::new_id(WIRE));
+        tick(&mut world, 2);
+        world.set_block(0, 0, 0, Block::new());
+        let destroyed = world.update_blocks(BLOCK_UPDATE_INTERVAL * 1.5, 1);
+        assert_eq!(world.get_block(0, 1, 0).id, EMPTY_BLOCK);
+        assert_eq!(destroyed.len(), 1);
+    }
+}
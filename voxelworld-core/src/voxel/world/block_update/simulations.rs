@@ -0,0 +1,388 @@
+use super::{
+    finite_fluid::FluidMode, rand_block_update::RANDOM_UPDATE_INTERVAL, BLOCK_UPDATE_INTERVAL,
+};
+use crate::voxel::{
+    block_ids::{
+        BLUE_FLOWER, BLUE_FLOWER_SEEDS, CACTUS, COTTON, COTTON_SEED, DRY_FARMLAND, GRASS, LOG,
+        RED_FLOWER, RED_FLOWER_SEEDS, SAND, SAPLING, SEEDS, SNOWY_SAPLING, STONE, SUGARCANE, WATER,
+        WET_FARMLAND, WHEAT, WHITE_FLOWER, WHITE_FLOWER_SEEDS, YELLOW_FLOWER, YELLOW_FLOWER_SEEDS,
+    },
+    world::{terrain_generator::FlatGenerator, GameMode},
+    Block, World, EMPTY_BLOCK,
+};
+
+/*
+ * Test simulations for testing purposes
+ * Each simulation takes in the number of iterations to run and returns
+ * the average amount of time it takes in minutes
+ * */
+
+fn simulate_sugarcane_growth(iterations: i32) -> String {
+    eprintln!("SUGAR CANE GROWTH SIMULATION");
+    let mut total = 0.0f32;
+    for i in 0..iterations {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        let mut total_time = 0.0;
+        for x in 0..16 {
+            world.set_block(x, 1, 0, Block::new_id(GRASS));
+            world.set_block(x, 2, 0, Block::new_id(SUGARCANE));
+            world.set_block(x, 1, 1, Block::new_fluid(WATER));
+        }
+        let mut done = false;
+        while !done {
+            world.rand_block_update(RANDOM_UPDATE_INTERVAL, false, 0);
+            total_time += RANDOM_UPDATE_INTERVAL;
+            done = true;
+            for x in 0..16 {
+                let block = world.get_block(x, 4, 0);
+                assert_eq!(world.get_block(x, 5, 0).id, EMPTY_BLOCK);
+                if block.id != SUGARCANE {
+                    done = false;
+                }
+            }
+        }
+        let minutes = total_time / 60.0;
+        total += minutes;
+        eprintln!(
+            "({} / {iterations}) took {total_time} s ({minutes} min) to grow all sugarcane",
+            i + 1
+        );
+    }
+    format!(
+        "Average time to grow all sugarcane: {}",
+        total / iterations as f32
+    )
+}
+
+fn simulate_cactus_growth(iterations: i32) -> String {
+    eprintln!("CACTUS GROWTH SIMULATION");
+    let mut total = 0.0f32;
+    for i in 0..iterations {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        let mut total_time = 0.0;
+        for x in 0..9 {
+            for z in 0..9 {
+                world.set_block(x, 1, z, Block::new_id(SAND));
+                world.set_block(x, 2, z, Block::new_id(CACTUS));
+            }
+        }
+        let mut done = false;
+        while !done {
+            world.rand_block_update(RANDOM_UPDATE_INTERVAL, false, 0);
+            total_time += RANDOM_UPDATE_INTERVAL;
+            done = true;
+            for x in 0..9 {
+                for z in 0..9 {
+                    let block = world.get_block(x, 4, z);
+                    assert_eq!(world.get_block(x, 5, 0).id, EMPTY_BLOCK);
+                    if block.id != CACTUS {
+                        done = false;
+                    }
+                }
+            }
+        }
+        let minutes = total_time / 60.0;
+        total += minutes;
+        eprintln!(
+            "({} / {iterations}) took {total_time} s ({minutes} min) to grow all cacti",
+            i + 1
+        );
+    }
+    format!(
+        "Average time to grow all cacti: {} min",
+        total / iterations as f32
+    )
+}
+
+fn simulate_sapling_growth(iterations: i32) -> String {
+    eprintln!("SAPLING GROWTH SIMULATION");
+    let mut total = 0.0f32;
+    for i in 0..iterations {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        let mut total_time = 0.0;
+        world.set_block(0, 2, 0, Block::new_id(SAPLING));
+        world.set_block(0, 1, 0, Block::new_id(GRASS));
+        let mut done = false;
+        while !done {
+            world.rand_block_update(RANDOM_UPDATE_INTERVAL, false, 0);
+            total_time += RANDOM_UPDATE_INTERVAL;
+            done = world.get_block(0, 2, 0).id == LOG;
+        }
+        let minutes = total_time / 60.0;
+        total += minutes;
+        eprintln!(
+            "({} / {iterations}) took {total_time} s ({minutes} min) to grow sapling",
+            i + 1
+        );
+    }
+    format!(
+        "Average time to grow all saplings: {} min",
+        total / iterations as f32
+    )
+}
+
+fn simulate_snow_sapling_growth(iterations: i32) -> String {
+    eprintln!("SNOW SAPLING GROWTH SIMULATION");
+    let mut total = 0.0f32;
+    for i in 0..iterations {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        let mut total_time = 0.0;
+        world.set_block(0, 2, 0, Block::new_id(SNOWY_SAPLING));
+        world.set_block(0, 1, 0, Block::new_id(GRASS));
+        let mut done = false;
+        while !done {
+            world.rand_block_update(RANDOM_UPDATE_INTERVAL, false, 0);
+            total_time += RANDOM_UPDATE_INTERVAL;
+            done = world.get_block(0, 2, 0).id == LOG;
+        }
+        let minutes = total_time / 60.0;
+        total += minutes;
+        eprintln!(
+            "({} / {iterations}) took {total_time} s ({minutes} min) to grow sapling",
+            i + 1
+        );
+    }
+    format!(
+        "Average time to grow all snow saplings: {}",
+        total / iterations as f32
+    )
+}
+
+fn simulate_crop_growth(
+    iterations: i32,
+    crop_name: &str,
+    seed_id: u16,
+    crop_id: u16,
+    farmland_id: u16,
+) -> String {
+    eprintln!("{} GROWTH SIMULATION", crop_name.to_uppercase());
+    let mut total = 0.0f32;
+    for i in 0..iterations {
+        let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+        let mut total_time = 0.0;
+        for x in 0..4 {
+            for z in 0..4 {
+                world.set_block(x, 0, z, Block::new_id(seed_id));
+                world.set_block(x, -1, z, Block::new_id(farmland_id));
+            }
+        }
+        let mut done = false;
+        while !done {
+            world.rand_block_update(RANDOM_UPDATE_INTERVAL, false, 0);
+            total_time += RANDOM_UPDATE_INTERVAL;
+            done = true;
+            for x in 0..4 {
+                for z in 0..4 {
+                    let block = world.get_block(x, 0, z);
+                    if block.id != crop_id {
+                        done = false;
+                    }
+                }
+            }
+        }
+        let minutes = total_time / 60.0;
+        total += minutes;
+        eprintln!(
+            "({} / {iterations}) took {total_time} s ({minutes} min) to grow all {crop_name}",
+            i + 1
+        );
+    }
+    format!(
+        "Average time to grow all {crop_name}: {} min",
+        total / iterations as f32
+    )
+}
+
+//Drops a cube of water into a stone basin with finite fluids and runs block
+//updates until the water stops moving, checking that no water is lost or
+//created on every tick. Returns the world once it has settled and the number
+//of ticks it took
+fn simulate_finite_fluid_settling() -> (World, u32) {
+    const MAX_TICKS: u32 = 10000;
+    let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+    world.set_fluid_mode(FluidMode::Finite);
+    for x in -5..=5 {
+        for z in -5..=5 {
+            world.set_block(x, 0, z, Block::new_id(STONE));
+            if x.abs() == 5 || z.abs() == 5 {
+                for y in 1..=3 {
+                    world.set_block(x, y, z, Block::new_id(STONE));
+                }
+            }
+        }
+    }
+    for x in -1..=1 {
+        for y in 5..=7 {
+            for z in -1..=1 {
+                world.set_block(x, y, z, Block::new_fluid(WATER));
+            }
+        }
+    }
+
+    let total = world.total_fluid_volume(WATER);
+    let mut ticks = 0;
+    while world.scheduled_tick_count() > 0 && ticks < MAX_TICKS {
+        world.update_blocks(BLOCK_UPDATE_INTERVAL * 1.5, 1);
+        ticks += 1;
+        assert_eq!(world.total_fluid_volume(WATER), total);
+    }
+    assert!(ticks < MAX_TICKS, "finite water never settled");
+    (world, ticks)
+}
+
+fn simulate_finite_fluid(iterations: i32) -> String {
+    eprintln!("FINITE FLUID SIMULATION");
+    let mut total = 0;
+    for i in 0..iterations {
+        let (world, ticks) = simulate_finite_fluid_settling();
+        total += ticks;
+        eprintln!(
+            "({} / {iterations}) took {ticks} ticks for {} units of water to settle",
+            i + 1,
+            world.total_fluid_volume(WATER)
+        );
+    }
+    format!(
+        "Average ticks for finite water to settle: {}",
+        total as f32 / iterations as f32
+    )
+}
+
+pub fn run_test_simulations(args: &[String]) {
+    if !args.contains(&"--run-test-sims".to_string()) {
+        return;
+    }
+    //Run simulations and then quit the program
+    let results = vec![
+        simulate_sapling_growth(100),
+        simulate_sugarcane_growth(100),
+        simulate_crop_growth(100, "wheat", SEEDS, WHEAT, WET_FARMLAND),
+        simulate_crop_growth(100, "wheat (slow)", SEEDS, WHEAT, DRY_FARMLAND),
+        simulate_cactus_growth(100),
+        simulate_snow_sapling_growth(100),
+        simulate_crop_growth(100, "cotton", COTTON_SEED, COTTON, WET_FARMLAND),
+        simulate_crop_growth(100, "cotton (slow)", COTTON_SEED, COTTON, DRY_FARMLAND),
+        simulate_crop_growth(
+            100,
+            "red flowers",
+            RED_FLOWER_SEEDS,
+            RED_FLOWER,
+            WET_FARMLAND,
+        ),
+        simulate_crop_growth(
+            100,
+            "red flowers (slow)",
+            RED_FLOWER_SEEDS,
+            RED_FLOWER,
+            DRY_FARMLAND,
+        ),
+        simulate_crop_growth(
+            100,
+            "yellow flowers",
+            YELLOW_FLOWER_SEEDS,
+            YELLOW_FLOWER,
+            WET_FARMLAND,
+        ),
+        simulate_crop_growth(
+            100,
+            "yellow flowers (slow)",
+            YELLOW_FLOWER_SEEDS,
+            YELLOW_FLOWER,
+            DRY_FARMLAND,
+        ),
+        simulate_crop_growth(
+            100,
+            "blue flowers",
+            BLUE_FLOWER_SEEDS,
+            BLUE_FLOWER,
+            WET_FARMLAND,
+        ),
+        simulate_crop_growth(
+            100,
+            "blue flowers (slow)",
+            BLUE_FLOWER_SEEDS,
+            BLUE_FLOWER,
+            DRY_FARMLAND,
+        ),
+        simulate_crop_growth(
+            100,
+            "white flowers",
+            WHITE_FLOWER_SEEDS,
+            WHITE_FLOWER,
+            WET_FARMLAND,
+        ),
+        simulate_crop_growth(
+            100,
+            "white flowers (slow)",
+            WHITE_FLOWER_SEEDS,
+            WHITE_FLOWER,
+            DRY_FARMLAND,
+        ),
+        simulate_finite_fluid(10),
+    ];
+    //Output results
+    eprintln!();
+    eprintln!("Simulation Results");
+    eprintln!("------------------");
+    for line in results {
+        eprintln!("{line}");
+    }
+    //Exit program once all simulations are completed
+    std::process::exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::finite_fluid::volume;
+    use super::*;
+
+    #[test]
+    fn test_finite_fluid_simulation() {
+        let (world, ticks) = simulate_finite_fluid_settling();
+        //27 full blocks of water
+        assert_eq!(world.total_fluid_volume(WATER), 27 * 7);
+        //All of the water drained into the basin and leveled out, 189 units
+        //over 81 blocks is between 2 and 3 units in each block
+        for x in -4..=4 {
+            for z in -4..=4 {
+                let level = volume(world.get_block(x, 1, z), WATER);
+                assert!((2..=3).contains(&level));
+                for y in 2..=7 {
+                    assert_eq!(world.get_block(x, y, z).id, EMPTY_BLOCK);
+                }
+            }
+        }
+
+        //Running the simulation again gives the same result
+        let (world2, ticks2) = simulate_finite_fluid_settling();
+        assert_eq!(ticks, ticks2);
+        for x in -5..=5 {
+            for y in 0..=7 {
+                for z in -5..=5 {
+                    assert_eq!(world.get_block(x, y, z), world2.get_block(x, y, z));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_finite_fluid_drain() {
+        let (mut world, _) = simulate_finite_fluid_settling();
+        //The water is spread out too thin to fill a bucket from one block
+        //but scooping up whatever is left eventually empties the basin
+        for i in 0..27 {
+            let (x, z) = (-4..=4)
+                .flat_map(|x| (-4..=4).map(move |z| (x, z)))
+                .find(|&(x, z)| world.get_block(x, 1, z).is_fluid())
+                .expect("basin should still have water");
+            assert!(world.take_bucket(x, 1, z).is_some());
+            assert_eq!(world.total_fluid_volume(WATER), (26 - i) * 7);
+            while world.scheduled_tick_count() > 0 {
+                world.update_blocks(BLOCK_UPDATE_INTERVAL * 1.5, 1);
+            }
+            assert_eq!(world.total_fluid_volume(WATER), (26 - i) * 7);
+        }
+        assert_eq!(world.total_fluid_volume(WATER), 0);
+        assert!(world.take_bucket(0, 1, 0).is_none());
+    }
+}
@@ -1,5 +1,4 @@
-use crate::{game::crafting::RecipeTable, gfx::ChunkTables, voxel::World};
-use std::collections::HashSet;
+use crate::{crafting::RecipeTable, voxel::World};
 
 impl World {
     fn update_chunk_tile_data(
@@ -16,13 +15,7 @@ impl World {
         vec![]
     }
 
-    pub fn update_tile_data(
-        &mut self,
-        dt: f32,
-        chunk_sim_dist: i32,
-        recipes: &RecipeTable,
-        chunktables: &mut ChunkTables,
-    ) {
+    pub fn update_tile_data(&mut self, dt: f32, chunk_sim_dist: i32, recipes: &RecipeTable) {
         let mut block_updates = vec![];
         for x in (self.centerx - chunk_sim_dist)..=(self.centerx + chunk_sim_dist) {
             for y in (self.centery - chunk_sim_dist)..=(self.centery + chunk_sim_dist) {
@@ -32,10 +25,7 @@ impl World {
             }
         }
 
-        let mut update_mesh = HashSet::<(i32, i32, i32)>::new();
-        update_mesh.extend(self.update_block_light(&block_updates));
-        for (x, y, z) in update_mesh {
-            chunktables.update_table(self, x, y, z);
-        }
+        let update_mesh = self.update_block_light(&block_updates);
+        self.mesh_updates.extend(update_mesh);
     }
 }
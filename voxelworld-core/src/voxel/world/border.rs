@@ -0,0 +1,230 @@
+/*
+ * World borders limit how far out a world goes horizontally, the border is
+ * stored in the world metadata in overworld coordinates and is scaled down
+ * for dimensions that are smaller than the overworld.
+ *
+ * Chunks that are entirely outside of the border are never generated and
+ * players and dropped items can not cross it.
+ * */
+
+use super::World;
+use crate::{impfile::Entry, voxel::CHUNK_SIZE_I32};
+
+pub const MIN_BORDER_RADIUS: f32 = 16.0;
+pub const MAX_BORDER_RADIUS: f32 = 1_000_000.0;
+pub const DEFAULT_BORDER_RADIUS: f32 = 1024.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BorderShape {
+    Square,
+    Circle,
+}
+
+impl BorderShape {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Square => "square",
+            Self::Circle => "circle",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "square" => Some(Self::Square),
+            "circle" => Some(Self::Circle),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldBorder {
+    pub shape: BorderShape,
+    //Center of the border
+    pub x: f32,
+    pub z: f32,
+    //For squares this is half of the width of the square
+    pub radius: f32,
+}
+
+impl WorldBorder {
+    pub fn new(shape: BorderShape, radius: f32) -> Self {
+        Self {
+            shape,
+            x: 0.0,
+            z: 0.0,
+            radius: radius.clamp(MIN_BORDER_RADIUS, MAX_BORDER_RADIUS),
+        }
+    }
+
+    //Returns the border shrunk by `scale`, used for dimensions where one
+    //block is worth `scale` overworld blocks
+    pub fn scaled(&self, scale: f32) -> Self {
+        Self {
+            shape: self.shape,
+            x: self.x / scale,
+            z: self.z / scale,
+            radius: self.radius / scale,
+        }
+    }
+
+    //Returns how far (x, z) is from the border, this is negative if the
+    //position is outside of the border
+    pub fn distance(&self, x: f32, z: f32) -> f32 {
+        let dx = x - self.x;
+        let dz = z - self.z;
+        match self.shape {
+            BorderShape::Square => self.radius - dx.abs().max(dz.abs()),
+            BorderShape::Circle => self.radius - (dx * dx + dz * dz).sqrt(),
+        }
+    }
+
+    pub fn contains(&self, x: f32, z: f32) -> bool {
+        self.distance(x, z) >= 0.0
+    }
+
+    //Moves (x, z) to the closest position where a box that extends `size`
+    //blocks out from (x, z) is entirely inside of the border
+    pub fn clamp(&self, x: f32, z: f32, size: f32) -> (f32, f32) {
+        let r = (self.radius - size).max(0.0);
+        match self.shape {
+            BorderShape::Square => (
+                x.clamp(self.x - r, self.x + r),
+                z.clamp(self.z - r, self.z + r),
+            ),
+            BorderShape::Circle => {
+                //The corners of the box are the furthest out
+                let r = (self.radius - size * std::f32::consts::SQRT_2).max(0.0);
+                let dx = x - self.x;
+                let dz = z - self.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                if dist <= r {
+                    return (x, z);
+                }
+                (self.x + dx / dist * r, self.z + dz / dist * r)
+            }
+        }
+    }
+
+    //Returns true if any part of the chunk column (chunkx, chunkz) is inside
+    //of the border
+    pub fn contains_chunk(&self, chunkx: i32, chunkz: i32) -> bool {
+        let minx = (chunkx * CHUNK_SIZE_I32) as f32;
+        let minz = (chunkz * CHUNK_SIZE_I32) as f32;
+        let maxx = minx + CHUNK_SIZE_I32 as f32;
+        let maxz = minz + CHUNK_SIZE_I32 as f32;
+        //Closest point in the chunk to the center of the border
+        let x = self.x.clamp(minx, maxx);
+        let z = self.z.clamp(minz, maxz);
+        self.distance(x, z) > 0.0
+    }
+
+    pub fn add_to_entry(&self, entry: &mut Entry) {
+        entry.add_string("border_shape", self.shape.name());
+        entry.add_float("border_radius", self.radius);
+        entry.add_float("border_x", self.x);
+        entry.add_float("border_z", self.z);
+    }
+
+    //Returns None if the entry does not have a border (worlds from before
+    //borders were added have no border)
+    pub fn from_entry(entry: &Entry) -> Option<Self> {
+        let shape = BorderShape::from_name(entry.try_get_var("border_shape")?)?;
+        let float = |name: &str| entry.try_get_var(name)?.parse::<f32>().ok();
+        let mut border = Self::new(shape, float("border_radius")?);
+        border.x = float("border_x").unwrap_or(0.0);
+        border.z = float("border_z").unwrap_or(0.0);
+        Some(border)
+    }
+}
+
+impl World {
+    //Returns the border of the loaded dimension
+    pub fn border(&self) -> Option<WorldBorder> {
+        self.border
+            .map(|border| border.scaled(self.dimension.scale))
+    }
+
+    //Should be called before any chunks are generated, otherwise chunks
+    //outside of the border might already have been generated
+    pub fn set_border(&mut self, border: Option<WorldBorder>) {
+        self.border = border;
+    }
+
+    //Returns true if the chunk column (chunkx, chunkz) should be generated
+    pub fn column_in_border(&self, chunkx: i32, chunkz: i32) -> bool {
+        self.border()
+            .is_none_or(|border| border.contains_chunk(chunkx, chunkz))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::world::{terrain_generator::default_generator, GameMode};
+
+    #[test]
+    fn test_border_distance() {
+        let square = WorldBorder::new(BorderShape::Square, 100.0);
+        assert_eq!(square.distance(0.0, 0.0), 100.0);
+        assert_eq!(square.distance(90.0, -50.0), 10.0);
+        assert!(square.contains(99.0, 99.0));
+        assert!(!square.contains(101.0, 0.0));
+
+        let circle = WorldBorder::new(BorderShape::Circle, 100.0);
+        assert_eq!(circle.distance(60.0, 80.0), 0.0);
+        assert!(!circle.contains(99.0, 99.0));
+        assert!(circle.contains(-70.0, 70.0));
+    }
+
+    #[test]
+    fn test_border_clamp() {
+        let square = WorldBorder::new(BorderShape::Square, 100.0);
+        assert_eq!(square.clamp(10.0, 20.0, 0.5), (10.0, 20.0));
+        assert_eq!(square.clamp(150.0, -120.0, 0.5), (99.5, -99.5));
+
+        let circle = WorldBorder::new(BorderShape::Circle, 100.0);
+        let (x, z) = circle.clamp(300.0, 400.0, 0.0);
+        assert!((x - 60.0).abs() < 0.001 && (z - 80.0).abs() < 0.001);
+        let (x, z) = circle.clamp(0.0, 200.0, 1.0);
+        assert_eq!(x, 0.0);
+        assert!(circle.contains(x + 1.0, z + 1.0) && circle.contains(x - 1.0, z + 1.0));
+    }
+
+    #[test]
+    fn test_border_chunks() {
+        let square = WorldBorder::new(BorderShape::Square, 32.0);
+        assert!(square.contains_chunk(0, 0));
+        assert!(square.contains_chunk(-2, 1));
+        assert!(!square.contains_chunk(2, 0));
+        assert!(!square.contains_chunk(-3, 0));
+
+        let circle = WorldBorder::new(BorderShape::Circle, 32.0);
+        assert!(circle.contains_chunk(1, 1));
+        //The corner of the chunk closest to the center is outside
+        assert!(!circle.contains_chunk(2, 2));
+        assert!(!square.scaled(2.0).contains_chunk(1, 0));
+    }
+
+    #[test]
+    fn test_border_entry() {
+        let mut border = WorldBorder::new(BorderShape::Circle, 500.0);
+        border.x = 12.5;
+        border.z = -40.0;
+        let mut entry = Entry::new("world");
+        border.add_to_entry(&mut entry);
+        assert_eq!(WorldBorder::from_entry(&entry), Some(border));
+        assert_eq!(WorldBorder::from_entry(&Entry::new("world")), None);
+    }
+
+    #[test]
+    fn test_border_generation() {
+        let mut world = World::new(1234, 0, default_generator(), GameMode::Creative);
+        world.chunks.clear();
+        world.set_border(Some(WorldBorder::new(BorderShape::Square, 32.0)));
+        world.add_chunk(0, -3, 0);
+        world.add_chunk(4, -3, 0);
+        assert!(!world.chunks[&(0, -3, 0)].is_empty());
+        assert!(world.chunks[&(4, -3, 0)].is_empty());
+    }
+}
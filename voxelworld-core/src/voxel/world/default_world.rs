@@ -0,0 +1,465 @@
+/*
+ * Default world generation
+ * */
+
+mod carvers;
+mod gen_trees;
+mod ore;
+mod plants;
+mod structures;
+mod terrain;
+
+use self::{
+    carvers::{carve_caves, get_carve_spheres, CarveSphere},
+    gen_trees::get_tree_gen_info,
+    ore::{generate_aqua_ore, generate_clay, generate_magma_blocks, generate_ore},
+    plants::{generate_plants, generate_sugarcane, get_plant_positions, get_water_adjacent},
+    structures::{get_column_structures, StructurePiece},
+    terrain::{get_height, get_humidity, get_mountain, get_temperature},
+};
+use std::collections::HashMap;
+
+use super::{
+    biome::{Biome, Climate},
+    gen_rng::RngStream,
+    gen_settings::GenSettings,
+    terrain_generator::{ColumnGenerator, Surface, TerrainGenerator},
+    WorldGenerator,
+};
+use crate::voxel::CHUNK_SIZE_I32;
+use crate::voxel::{
+    block_ids::{ICE, LAVA, SAND, SNOW, STONE, WATER},
+    Block, Chunk, EMPTY_BLOCK, INDESTRUCTIBLE,
+};
+use gen_trees::generate_trees;
+use terrain::{add_to_heightmap, is_noise_cave, HeightMap};
+
+//Heights of the highest hills when the height scale is 1.0
+const MAX_HEIGHT: f64 = 95.0;
+//Beaches are at most this far above sea level
+const SAND_HEIGHT: i32 = 1;
+
+struct GenInfoTable {
+    heightmap: HeightMap,
+    mountain: HeightMap,
+    biomes: HashMap<(i32, i32), Vec<Biome>>,
+    tree_positions: HashMap<(i32, i32), Vec<(i32, i32)>>,
+    tree_heights: HashMap<(i32, i32), Vec<i32>>,
+    plant_positions: HashMap<(i32, i32), Vec<(i32, i32)>>,
+    sugarcane_positions: HashMap<(i32, i32), Vec<(i32, i32)>>,
+    structures: HashMap<(i32, i32), Vec<StructurePiece>>,
+    carvers: HashMap<(i32, i32), Vec<CarveSphere>>,
+}
+
+struct GenInfo<'a> {
+    heights: &'a [i32],
+    mountain: &'a [i32],
+    biomes: &'a [Biome],
+    tree_positions: &'a [(i32, i32)],
+    tree_heights: &'a [i32],
+    plant_positions: &'a [(i32, i32)],
+    sugarcane_positions: &'a [(i32, i32)],
+    structures: &'a [StructurePiece],
+    carvers: &'a [CarveSphere],
+}
+
+impl GenInfoTable {
+    fn new() -> Self {
+        Self {
+            heightmap: HeightMap::new(),
+            mountain: HeightMap::new(),
+            biomes: HashMap::new(),
+            tree_positions: HashMap::new(),
+            tree_heights: HashMap::new(),
+            plant_positions: HashMap::new(),
+            sugarcane_positions: HashMap::new(),
+            structures: HashMap::new(),
+            carvers: HashMap::new(),
+        }
+    }
+
+    fn add_heights(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        add_to_heightmap(x, z, &mut self.heightmap, world_generator, get_height);
+        add_to_heightmap(x, z, &mut self.mountain, world_generator, get_mountain);
+    }
+
+    //Assumes that the heights at (x, z) have been generated
+    fn add_biomes(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        if self.biomes.contains_key(&(x, z)) {
+            return;
+        }
+        let (Some(heights), Some(mountain)) =
+            (self.heightmap.get(&(x, z)), self.mountain.get(&(x, z)))
+        else {
+            return;
+        };
+        let posx = x * CHUNK_SIZE_I32;
+        let posz = z * CHUNK_SIZE_I32;
+        let mut biomes = vec![Biome::Plains; heights.len()];
+        for (i, biome) in biomes.iter_mut().enumerate() {
+            let blockx = posx + i as i32 % CHUNK_SIZE_I32;
+            let blockz = posz + i as i32 / CHUNK_SIZE_I32;
+            let climate = Climate {
+                temperature: get_temperature(blockx, blockz, world_generator),
+                humidity: get_humidity(blockx, blockz, world_generator),
+                terrain_height: heights[i],
+                mountain_height: mountain[i],
+                sea_level: world_generator.settings.sea_level,
+            };
+            *biome = Biome::from_climate(&climate);
+        }
+        self.biomes.insert((x, z), biomes);
+    }
+
+    fn add_trees(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        if self.tree_positions.contains_key(&(x, z)) {
+            return;
+        }
+
+        let (tree_pos, tree_h) = get_tree_gen_info(x, z, world_generator);
+        self.tree_positions.insert((x, z), tree_pos);
+        self.tree_heights.insert((x, z), tree_h);
+    }
+
+    fn add_plants(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        if self.plant_positions.contains_key(&(x, z)) {
+            return;
+        }
+        let plants = get_plant_positions(x, z, world_generator);
+        self.plant_positions.insert((x, z), plants);
+    }
+
+    //Assumes that (x, z) has been generated
+    fn add_sugarcane(&mut self, x: i32, z: i32, sea_level: i32) {
+        if self.sugarcane_positions.contains_key(&(x, z)) {
+            return;
+        }
+        let heights = self.heightmap.get(&(x, z));
+        if let Some(heights) = heights {
+            let water_adjacent = get_water_adjacent(x, z, heights, sea_level);
+            self.sugarcane_positions.insert((x, z), water_adjacent);
+        }
+    }
+
+    fn add_structures(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        if self.structures.contains_key(&(x, z)) {
+            return;
+        }
+        let structures = get_column_structures(x, z, world_generator);
+        self.structures.insert((x, z), structures);
+    }
+
+    fn add_carvers(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        if self.carvers.contains_key(&(x, z)) {
+            return;
+        }
+        let carvers = get_carve_spheres(x, z, world_generator);
+        self.carvers.insert((x, z), carvers);
+    }
+
+    fn get(&self, x: i32, z: i32) -> Option<GenInfo<'_>> {
+        let h = self.heightmap.get(&(x, z))?;
+        let mountain_h = self.mountain.get(&(x, z))?;
+        let biomes = self.biomes.get(&(x, z))?;
+        let trees = self.tree_positions.get(&(x, z))?;
+        let tree_h = self.tree_heights.get(&(x, z))?;
+        let plants = self.plant_positions.get(&(x, z))?;
+        let sugarcane = self.sugarcane_positions.get(&(x, z))?;
+        let structures = self.structures.get(&(x, z))?;
+        let carvers = self.carvers.get(&(x, z))?;
+
+        Some(GenInfo {
+            heights: h,
+            mountain: mountain_h,
+            biomes,
+            tree_positions: trees,
+            tree_heights: tree_h,
+            plant_positions: plants,
+            sugarcane_positions: sugarcane,
+            structures,
+            carvers,
+        })
+    }
+}
+
+pub fn is_mountain(mountain_h: i32, terrain_h: i32) -> bool {
+    mountain_h - 8 > terrain_h && mountain_h > 10 + terrain_h.max(-16).abs() / 4
+}
+
+fn get_surface_block(biome: Biome, mountain_h: i32, terrain_h: i32, sea_level: i32) -> Block {
+    if is_mountain(mountain_h, terrain_h) {
+        if mountain_h < (terrain_h + 42).min(50.max(terrain_h + 16)) || mountain_h < 32 {
+            //Stone at lower heights
+            return Block::new_id(STONE);
+        } else {
+            //Snow
+            return Block::new_id(SNOW);
+        }
+    }
+
+    biome.surface_block(terrain_h.max(mountain_h), sea_level)
+}
+
+//Returns the climate at (x, z)
+pub fn get_climate(x: i32, z: i32, world_generator: &WorldGenerator) -> Climate {
+    Climate {
+        temperature: get_temperature(x, z, world_generator),
+        humidity: get_humidity(x, z, world_generator),
+        terrain_height: get_height(x, z, world_generator),
+        mountain_height: get_mountain(x, z, world_generator),
+        sea_level: world_generator.settings.sea_level,
+    }
+}
+
+//Returns true if the chunk at y is above the highest terrain or below the
+//bottom of the world
+fn out_of_bounds(y: i32, settings: &GenSettings) -> bool {
+    let top = ((MAX_HEIGHT * settings.height_scale) as i32).max(settings.sea_level);
+    let bottom = settings.bottom.div_euclid(CHUNK_SIZE_I32);
+    !(bottom..=top.div_euclid(CHUNK_SIZE_I32)).contains(&y)
+}
+
+pub fn is_beach(temperature: i32, height: i32, sea_level: i32) -> bool {
+    ((12..18).contains(&temperature)
+        || (30..45).contains(&temperature)
+        || (50..55).contains(&temperature)
+        || temperature > 65)
+        && height <= sea_level + SAND_HEIGHT
+}
+
+fn gen_chunk(chunk: &mut Chunk, gen_info: GenInfo, world_generator: &WorldGenerator) {
+    let chunkpos = chunk.get_chunk_pos();
+    let posx = chunkpos.x * CHUNK_SIZE_I32;
+    let posy = chunkpos.y * CHUNK_SIZE_I32;
+    let posz = chunkpos.z * CHUNK_SIZE_I32;
+
+    let settings = &world_generator.settings;
+    if out_of_bounds(chunkpos.y, settings) {
+        return;
+    }
+
+    let pos = (chunkpos.x, chunkpos.y, chunkpos.z);
+    let mut bedrock_rng = world_generator.position_rng(RngStream::Bedrock, pos);
+    let mut ore_rng = world_generator.position_rng(RngStream::Ore, pos);
+    let mut clay_rng = world_generator.position_rng(RngStream::Clay, pos);
+
+    for x in posx..(posx + CHUNK_SIZE_I32) {
+        for z in posz..(posz + CHUNK_SIZE_I32) {
+            let index = ((z - posz) * CHUNK_SIZE_I32 + (x - posx)) as usize;
+            let terrain_h = gen_info.heights[index];
+            let mountain_h = gen_info.mountain[index];
+            let height = terrain_h.max(mountain_h);
+            let h = (height + 1).max(settings.sea_level + 1);
+
+            if (h + 1).max(mountain_h + 1) < posy {
+                continue;
+            }
+
+            let biome = gen_info.biomes[index];
+
+            for y in posy..(posy + CHUNK_SIZE_I32).min((h + 1).max(mountain_h + 1)) {
+                let bottom = settings.bottom;
+                let indestructible = (y == bottom)
+                    || (y == bottom + 1 && bedrock_rng.i32(0..4) < 2)
+                    || (y == bottom + 2 && bedrock_rng.i32(0..6) == 0);
+                if indestructible {
+                    //Bottom of the world
+                    chunk.set_block(x, y, z, Block::new_id(INDESTRUCTIBLE));
+                    continue;
+                }
+
+                if y > bottom && y <= settings.lava_level {
+                    chunk.set_block(x, y, z, Block::new_fluid(LAVA));
+                }
+
+                if y <= settings.sea_level && y > height {
+                    chunk.set_block(x, y, z, Block::new_fluid(WATER));
+                    if y == settings.sea_level && biome.freezes_water() {
+                        //Ice on top of water if we are in a cold biome
+                        chunk.set_block(x, y, z, Block::new_id(ICE));
+                    }
+                }
+
+                //Sand
+                if y > height - 4 && y <= height && height <= settings.sea_level - 3 {
+                    chunk.set_block(x, y, z, Block::new_id(SAND));
+                    continue;
+                }
+
+                //Beaches
+                if biome == Biome::Beach && y > height - 4 && y <= height {
+                    chunk.set_block(x, y, z, Block::new_id(SAND));
+                    continue;
+                }
+
+                //Generate noise caves
+                if world_generator.caves.noise_caves
+                    && is_noise_cave(x, y, z, &world_generator.noise_cave_generator)
+                {
+                    continue;
+                }
+
+                if y == height + 1 && biome.has_snow() && chunk.get_block(x, y, z).id == EMPTY_BLOCK
+                {
+                    //Snow slabs in cold biomes
+                    let mut snow_slab = Block::new_id(SNOW);
+                    snow_slab.set_shape(1);
+                    chunk.set_block(x, y, z, snow_slab);
+                } else if y == height {
+                    let surface_block =
+                        get_surface_block(biome, mountain_h, terrain_h, settings.sea_level);
+                    chunk.set_block(x, y, z, surface_block);
+                } else if y > height - 4 && y < height {
+                    let under_block = if is_mountain(mountain_h, terrain_h) {
+                        Block::new_id(STONE)
+                    } else {
+                        biome.under_block()
+                    };
+                    chunk.set_block(x, y, z, under_block);
+                } else if y < height && y > bottom {
+                    //Stone
+                    chunk.set_block(x, y, z, Block::new_id(STONE));
+                }
+            }
+        }
+    }
+
+    //Carve tunnels and ravines
+    let surface: Vec<i32> = gen_info
+        .heights
+        .iter()
+        .zip(gen_info.mountain)
+        .map(|(terrain_h, mountain_h)| *terrain_h.max(mountain_h))
+        .collect();
+    carve_caves(chunk, gen_info.carvers, &surface, settings);
+
+    //Generate ore
+    for x in posx..(posx + CHUNK_SIZE_I32) {
+        for z in posz..(posz + CHUNK_SIZE_I32) {
+            let index = ((z - posz) * CHUNK_SIZE_I32 + (x - posx)) as usize;
+            let terrain_h = gen_info.heights[index];
+            let mountain_h = gen_info.mountain[index];
+            let height = terrain_h.max(mountain_h);
+
+            if height < posy {
+                continue;
+            }
+
+            let h = height + 1;
+            for y in posy..(posy + CHUNK_SIZE_I32).min(h) {
+                generate_ore(chunk, (x, y, z), &mut ore_rng, settings);
+                generate_magma_blocks(chunk, (x, y, z), &mut ore_rng, settings);
+                if y == terrain_h || y == terrain_h - 1 {
+                    generate_clay(chunk, (x, y, z), &mut clay_rng, settings.sea_level);
+                    generate_aqua_ore(chunk, (x, y, z), &mut clay_rng, settings.sea_level);
+                }
+            }
+        }
+    }
+
+    //Generate trees
+    generate_trees(
+        chunk,
+        gen_info.tree_positions,
+        gen_info.tree_heights,
+        world_generator,
+    );
+    //Plants and sugar cane can end up in any chunk of the column so they use
+    //the same numbers in every chunk of the column
+    let mut plant_rng = world_generator.column_rng(RngStream::Plants, pos.0, pos.2);
+    generate_plants(
+        chunk,
+        gen_info.plant_positions,
+        &mut plant_rng,
+        world_generator,
+    );
+    let mut sugarcane_rng = world_generator.column_rng(RngStream::Sugarcane, pos.0, pos.2);
+    generate_sugarcane(
+        chunk,
+        gen_info.sugarcane_positions,
+        &mut sugarcane_rng,
+        settings.sea_level,
+    );
+    //Generate structures
+    for piece in gen_info.structures {
+        piece.place(chunk, world_generator);
+    }
+    //Chunks deep underground are mostly stone
+    chunk.shrink();
+}
+
+//Generation info for a single column
+struct ColumnInfo<'a> {
+    gen_info_table: GenInfoTable,
+    world_generator: &'a WorldGenerator,
+}
+
+impl ColumnGenerator for ColumnInfo<'_> {
+    fn gen_chunk(&self, chunk: &mut Chunk) {
+        let pos = chunk.get_chunk_pos();
+        if let Some(gen_info) = self.gen_info_table.get(pos.x, pos.z) {
+            gen_chunk(chunk, gen_info, self.world_generator);
+        }
+    }
+}
+
+pub struct DefaultGenerator;
+
+impl TerrainGenerator for DefaultGenerator {
+    fn name(&self) -> &'static str {
+        "default2"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Default"
+    }
+
+    fn column<'a>(
+        &self,
+        x: i32,
+        z: i32,
+        world_generator: &'a WorldGenerator,
+    ) -> Box<dyn ColumnGenerator + 'a> {
+        let mut gen_info_table = GenInfoTable::new();
+        gen_info_table.add_heights(x, z, world_generator);
+        gen_info_table.add_biomes(x, z, world_generator);
+        gen_info_table.add_trees(x, z, world_generator);
+        gen_info_table.add_plants(x, z, world_generator);
+        gen_info_table.add_sugarcane(x, z, world_generator.settings.sea_level);
+        gen_info_table.add_structures(x, z, world_generator);
+        gen_info_table.add_carvers(x, z, world_generator);
+        Box::new(ColumnInfo {
+            gen_info_table,
+            world_generator,
+        })
+    }
+
+    fn get_biome(&self, x: i32, z: i32, world_generator: &WorldGenerator) -> Biome {
+        world_generator.get_biome(x, z)
+    }
+
+    fn surface(&self, x: i32, z: i32, world_generator: &WorldGenerator) -> Option<Surface> {
+        let climate = get_climate(x, z, world_generator);
+        let biome = Biome::from_climate(&climate);
+        let (terrain_h, mountain_h) = (climate.terrain_height, climate.mountain_height);
+        let height = terrain_h.max(mountain_h);
+        let sea_level = climate.sea_level;
+        let block = if height <= sea_level - 3 {
+            Block::new_id(SAND)
+        } else {
+            get_surface_block(biome, mountain_h, terrain_h, sea_level)
+        };
+        let cover = if height >= sea_level {
+            None
+        } else if biome.freezes_water() {
+            Some((Block::new_id(ICE), sea_level))
+        } else {
+            Some((Block::new_fluid(WATER), sea_level))
+        };
+        Some(Surface {
+            height,
+            block,
+            cover,
+        })
+    }
+}
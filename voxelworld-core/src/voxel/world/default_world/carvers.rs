@@ -0,0 +1,351 @@
+/*
+ * Cave carvers
+ *
+ * Tunnels and ravines are random walks that carve spheres (tall ellipsoids
+ * for ravines) out of the terrain. Any chunk can start a cave system and the
+ * system is seeded only from the world seed and the position of the chunk
+ * that it starts in, so each column finds every system that starts within
+ * CARVER_RANGE chunks of it and carves the parts that pass through it. This
+ * means that a cave is carved the same way in every chunk it crosses no
+ * matter which chunk is generated first.
+ *
+ * Carved blocks at or below the lava level become lava and some cave systems
+ * are aquifers that are flooded with water up to a level below sea level.
+ * */
+
+use crate::voxel::{
+    block_ids::{LAVA, SNOW, WATER},
+    world::{
+        gen_config::CaveConfig, gen_rng::RngStream, gen_settings::GenSettings, WorldGenerator,
+    },
+    Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK, INDESTRUCTIBLE,
+};
+use std::f32::consts::{PI, TAU};
+
+//Distance in chunks that a cave system can reach from the chunk it starts in
+const CARVER_RANGE: i32 = 8;
+//Tunnels are cut off once they are this far away (in blocks) from the center
+//of the chunk that they started in
+const MAX_REACH: f32 = (CARVER_RANGE * CHUNK_SIZE_I32 - CHUNK_SIZE_I32 / 2) as f32;
+//Tunnels that branch off of another tunnel are this much smaller
+const BRANCH_SCALE: f32 = 0.6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CarveSphere {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub radius: f32,
+    //Vertical radius
+    pub height: f32,
+    //Carved blocks at or below this level are filled with water
+    pub water_level: Option<i32>,
+}
+
+impl CarveSphere {
+    fn intersects_column(&self, chunkx: i32, chunkz: i32) -> bool {
+        let minx = (chunkx * CHUNK_SIZE_I32) as f32;
+        let minz = (chunkz * CHUNK_SIZE_I32) as f32;
+        let maxx = minx + CHUNK_SIZE_I32 as f32;
+        let maxz = minz + CHUNK_SIZE_I32 as f32;
+        self.x + self.radius >= minx
+            && self.x - self.radius <= maxx
+            && self.z + self.radius >= minz
+            && self.z - self.radius <= maxz
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Tunnel {
+    x: f32,
+    y: f32,
+    z: f32,
+    yaw: f32,
+    pitch: f32,
+    radius: f32,
+    length: u32,
+    //Vertical radius / horizontal radius
+    height_scale: f32,
+    water_level: Option<i32>,
+    can_branch: bool,
+}
+
+fn random_in(rng: &mut fastrand::Rng, (min, max): (f32, f32)) -> f32 {
+    min + rng.f32() * (max - min)
+}
+
+fn carve_tunnel(
+    mut tunnel: Tunnel,
+    origin: (f32, f32),
+    config: &CaveConfig,
+    rng: &mut fastrand::Rng,
+    spheres: &mut Vec<CarveSphere>,
+) {
+    let is_ravine = tunnel.height_scale > 1.0;
+    let branch_step = if tunnel.can_branch && tunnel.length >= 8 && rng.f32() < config.branch_chance
+    {
+        Some(rng.u32((tunnel.length / 4)..(tunnel.length * 3 / 4)))
+    } else {
+        None
+    };
+
+    let mut yaw_change = 0.0;
+    let mut pitch_change = 0.0;
+    for step in 0..tunnel.length {
+        let t = step as f32 / tunnel.length as f32;
+        //Tunnels are widest in the middle
+        let radius = 1.0 + tunnel.radius * (t * PI).sin();
+        let height = radius * tunnel.height_scale;
+
+        tunnel.x += tunnel.yaw.cos() * tunnel.pitch.cos();
+        tunnel.y += tunnel.pitch.sin();
+        tunnel.z += tunnel.yaw.sin() * tunnel.pitch.cos();
+
+        //Ravines stay flat while tunnels can slope up and down
+        tunnel.pitch *= if is_ravine { 0.7 } else { 0.92 };
+        tunnel.pitch += pitch_change * 0.1;
+        tunnel.yaw += yaw_change * 0.1;
+        pitch_change = pitch_change * 0.9 + (rng.f32() - rng.f32()) * rng.f32() * 2.0;
+        let turn = if is_ravine { 1.0 } else { 4.0 };
+        yaw_change = yaw_change * 0.75 + (rng.f32() - rng.f32()) * rng.f32() * turn;
+
+        if branch_step == Some(step) {
+            for side in [-1.0, 1.0] {
+                let branch = Tunnel {
+                    yaw: tunnel.yaw + side * PI / 2.0,
+                    pitch: tunnel.pitch / 3.0,
+                    radius: tunnel.radius * BRANCH_SCALE,
+                    length: tunnel.length - step,
+                    can_branch: false,
+                    ..tunnel
+                };
+                carve_tunnel(branch, origin, config, rng, spheres);
+            }
+            return;
+        }
+
+        let reach = (tunnel.x - origin.0).abs().max((tunnel.z - origin.1).abs()) + radius;
+        if reach > MAX_REACH {
+            return;
+        }
+
+        //Skip some spheres so that the walls are not completely smooth
+        if rng.u32(0..4) == 0 {
+            continue;
+        }
+
+        spheres.push(CarveSphere {
+            x: tunnel.x,
+            y: tunnel.y,
+            z: tunnel.z,
+            radius,
+            height,
+            water_level: tunnel.water_level,
+        });
+    }
+}
+
+//Carves the cave systems that start in the chunk column at (chunkx, chunkz)
+fn gen_cave_systems(
+    chunkx: i32,
+    chunkz: i32,
+    world_generator: &WorldGenerator,
+    spheres: &mut Vec<CarveSphere>,
+) {
+    let config = &world_generator.caves;
+    let mut rng = world_generator.column_rng(RngStream::Caves, chunkx, chunkz);
+    let posx = (chunkx * CHUNK_SIZE_I32) as f32;
+    let posz = (chunkz * CHUNK_SIZE_I32) as f32;
+    let origin = (posx + 8.0, posz + 8.0);
+    let (min_y, max_y) = config.tunnel_height;
+    let sea_level = world_generator.settings.sea_level;
+
+    if rng.f32() < config.tunnel_chance {
+        let water_level = if rng.f32() < config.aquifer_chance {
+            Some(rng.i32(min_y.min(sea_level - 1)..sea_level))
+        } else {
+            None
+        };
+
+        let x = posx + rng.f32() * CHUNK_SIZE_I32 as f32;
+        let y = rng.i32(min_y..=max_y) as f32;
+        let z = posz + rng.f32() * CHUNK_SIZE_I32 as f32;
+        let (min_tunnels, max_tunnels) = config.tunnels;
+        let (min_length, max_length) = config.tunnel_length;
+        for _ in 0..rng.u32(min_tunnels..=max_tunnels) {
+            let tunnel = Tunnel {
+                x,
+                y,
+                z,
+                yaw: rng.f32() * TAU,
+                pitch: (rng.f32() - 0.5) * 0.5,
+                radius: random_in(&mut rng, config.tunnel_radius),
+                length: rng.u32(min_length..=max_length),
+                height_scale: 1.0,
+                water_level,
+                can_branch: true,
+            };
+            carve_tunnel(tunnel, origin, config, &mut rng, spheres);
+        }
+    }
+
+    if rng.f32() < config.ravine_chance {
+        let (min_length, max_length) = config.ravine_length;
+        let ravine = Tunnel {
+            x: posx + rng.f32() * CHUNK_SIZE_I32 as f32,
+            y: rng.i32(min_y..=max_y) as f32,
+            z: posz + rng.f32() * CHUNK_SIZE_I32 as f32,
+            yaw: rng.f32() * TAU,
+            pitch: (rng.f32() - 0.5) * 0.25,
+            radius: random_in(&mut rng, config.ravine_radius),
+            length: rng.u32(min_length..=max_length),
+            height_scale: config.ravine_depth,
+            water_level: None,
+            can_branch: false,
+        };
+        carve_tunnel(ravine, origin, config, &mut rng, spheres);
+    }
+}
+
+//Returns every sphere that carves out part of the column at (chunkx, chunkz)
+pub fn get_carve_spheres(
+    chunkx: i32,
+    chunkz: i32,
+    world_generator: &WorldGenerator,
+) -> Vec<CarveSphere> {
+    let mut spheres = vec![];
+    for x in (chunkx - CARVER_RANGE)..=(chunkx + CARVER_RANGE) {
+        for z in (chunkz - CARVER_RANGE)..=(chunkz + CARVER_RANGE) {
+            let mut system = vec![];
+            gen_cave_systems(x, z, world_generator, &mut system);
+            spheres.extend(
+                system
+                    .into_iter()
+                    .filter(|sphere| sphere.intersects_column(chunkx, chunkz)),
+            );
+        }
+    }
+    spheres
+}
+
+//Carves the spheres out of the chunk, `heights` is the height of the terrain
+//in the column of the chunk (indexed by z * CHUNK_SIZE + x)
+pub fn carve_caves(
+    chunk: &mut Chunk,
+    spheres: &[CarveSphere],
+    heights: &[i32],
+    settings: &GenSettings,
+) {
+    let chunkpos = chunk.get_chunk_pos();
+    let posx = chunkpos.x * CHUNK_SIZE_I32;
+    let posy = chunkpos.y * CHUNK_SIZE_I32;
+    let posz = chunkpos.z * CHUNK_SIZE_I32;
+
+    for sphere in spheres {
+        let minx = ((sphere.x - sphere.radius).floor() as i32).max(posx);
+        let maxx = ((sphere.x + sphere.radius).ceil() as i32).min(posx + CHUNK_SIZE_I32 - 1);
+        let miny = ((sphere.y - sphere.height).floor() as i32).max(posy);
+        let maxy = ((sphere.y + sphere.height).ceil() as i32).min(posy + CHUNK_SIZE_I32 - 1);
+        let minz = ((sphere.z - sphere.radius).floor() as i32).max(posz);
+        let maxz = ((sphere.z + sphere.radius).ceil() as i32).min(posz + CHUNK_SIZE_I32 - 1);
+
+        for x in minx..=maxx {
+            for z in minz..=maxz {
+                let surface = heights[((z - posz) * CHUNK_SIZE_I32 + (x - posx)) as usize];
+                for y in miny..=maxy {
+                    let dx = (x as f32 + 0.5 - sphere.x) / sphere.radius;
+                    let dy = (y as f32 + 0.5 - sphere.y) / sphere.height;
+                    let dz = (z as f32 + 0.5 - sphere.z) / sphere.radius;
+                    if dx * dx + dy * dy + dz * dz >= 1.0 {
+                        continue;
+                    }
+
+                    //Do not open up the floor of the ocean
+                    if surface < settings.sea_level && y >= surface - 2 {
+                        continue;
+                    }
+
+                    let block = chunk.get_block(x, y, z);
+                    if block.id == EMPTY_BLOCK || block.id == INDESTRUCTIBLE || block.is_fluid() {
+                        continue;
+                    }
+
+                    let fill = if y <= settings.lava_level {
+                        Block::new_fluid(LAVA)
+                    } else if sphere.water_level.is_some_and(|level| y <= level) {
+                        Block::new_fluid(WATER)
+                    } else {
+                        Block::new()
+                    };
+                    chunk.set_block(x, y, z, fill);
+
+                    //Remove snow that would be left floating
+                    if y == surface && chunk.get_block(x, y + 1, z).id == SNOW {
+                        chunk.set_block(x, y + 1, z, Block::new());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{block_ids::STONE, CHUNK_SIZE};
+
+    #[test]
+    fn test_caves_cross_chunks() {
+        let world_generator = WorldGenerator::new(5);
+        let mut found = false;
+        for x in 0..8 {
+            let column1 = get_carve_spheres(x, 0, &world_generator);
+            let column2 = get_carve_spheres(x + 1, 0, &world_generator);
+            //Both columns agree on the parts of the caves that they share
+            let shared1: Vec<_> = column1
+                .iter()
+                .filter(|sphere| sphere.intersects_column(x + 1, 0))
+                .collect();
+            let shared2: Vec<_> = column2
+                .iter()
+                .filter(|sphere| sphere.intersects_column(x, 0))
+                .collect();
+            assert_eq!(shared1, shared2);
+            found |= !shared1.is_empty();
+        }
+        assert!(found);
+    }
+
+    #[test]
+    fn test_carve_fill() {
+        let heights = vec![64; CHUNK_SIZE * CHUNK_SIZE];
+        let sphere = |y: f32, water_level: Option<i32>| CarveSphere {
+            x: 8.0,
+            y,
+            z: 8.0,
+            radius: 3.0,
+            height: 3.0,
+            water_level,
+        };
+
+        let carved = |y: i32, sphere: CarveSphere| {
+            let mut chunk = Chunk::new(0, y.div_euclid(CHUNK_SIZE_I32), 0);
+            for x in 0..CHUNK_SIZE_I32 {
+                for z in 0..CHUNK_SIZE_I32 {
+                    for iy in 0..CHUNK_SIZE_I32 {
+                        let pos = (x, iy + chunk.get_chunk_pos().y * CHUNK_SIZE_I32, z);
+                        chunk.set_block(pos.0, pos.1, pos.2, Block::new_id(STONE));
+                    }
+                }
+            }
+            carve_caves(&mut chunk, &[sphere], &heights, &GenSettings::default());
+            chunk.get_block(8, y, 8).id
+        };
+
+        assert_eq!(carved(8, sphere(8.0, None)), EMPTY_BLOCK);
+        assert_eq!(carved(-8, sphere(-8.0, Some(0))), WATER);
+        assert_eq!(carved(-56, sphere(-56.0, None)), LAVA);
+        //Blocks outside of the sphere are not carved
+        assert_eq!(carved(14, sphere(8.0, None)), 2);
+    }
+}
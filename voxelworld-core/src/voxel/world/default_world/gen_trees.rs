@@ -1,44 +1,41 @@
 use super::terrain::{get_height, get_height_mountain, is_noise_cave};
-use super::{is_beach, is_mountain, WorldGenerator, SEA_LEVEL};
-use crate::voxel::{Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK};
-use noise::{NoiseFn, Perlin};
+use super::{is_mountain, WorldGenerator};
+use crate::voxel::{
+    block_ids::{CACTUS, LEAVES, LOG, SNOWY_LEAVES},
+    world::{biome::TreeType, gen_rng::RngStream},
+    Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
+use noise::NoiseFn;
 use std::collections::HashSet;
 
 fn gen_tree_positions(
     chunkx: i32,
     chunkz: i32,
-    tree_noise: &Perlin,
+    world_generator: &WorldGenerator,
     positions: &mut Vec<(i32, i32)>,
     heights: &mut Vec<i32>,
-    world_seed: u32,
 ) {
     let xz = [chunkx as f64 / 9.0 + 0.5, 0.0, chunkz as f64 / 9.0 + 0.5];
-    let noise_val = (tree_noise.get(xz) + 1.0) / 2.0;
+    let noise_val = (world_generator.tree_generator.get(xz) + 1.0) / 2.0;
     let tree_count = (noise_val * noise_val * 6.0).floor() as u32;
-    let xu32 = chunkx as u32;
-    let zu32 = chunkz as u32;
-    let seed = ((xu32 as u64) << 32) | (zu32 as u64);
-    let mut tree_generator = fastrand::Rng::with_seed(seed);
-    let mut rng = fastrand::Rng::with_seed(seed + world_seed as u64);
+    let mut rng = world_generator.column_rng(RngStream::Trees, chunkx, chunkz);
     let mut generated = HashSet::<(i32, i32)>::new();
     for _ in 0..tree_count {
-        let treex =
-            (tree_generator.i32(0..CHUNK_SIZE_I32) + rng.i32(0..CHUNK_SIZE_I32)) % CHUNK_SIZE_I32;
-        let treez =
-            (tree_generator.i32(0..CHUNK_SIZE_I32) + rng.i32(0..CHUNK_SIZE_I32)) % CHUNK_SIZE_I32;
+        let treex = rng.i32(0..CHUNK_SIZE_I32);
+        let treez = rng.i32(0..CHUNK_SIZE_I32);
         if generated.contains(&(treex, treez)) {
             continue;
         }
         let x = treex + chunkx * CHUNK_SIZE_I32;
         let z = treez + chunkz * CHUNK_SIZE_I32;
-        let h = tree_generator.i32(4..=6);
+        let h = rng.i32(4..=6);
         positions.push((x, z));
         heights.push(h);
         generated.insert((treex, treez));
     }
 }
 
-fn place_leaves(chunk: &mut Chunk, x: i32, y: i32, z: i32, id: u8) {
+fn place_leaves(chunk: &mut Chunk, x: i32, y: i32, z: i32, id: u16) {
     let replace = chunk.get_block(x, y, z);
     if replace.id != EMPTY_BLOCK && replace.shape() == 0 {
         return;
@@ -46,7 +43,7 @@ fn place_leaves(chunk: &mut Chunk, x: i32, y: i32, z: i32, id: u8) {
     chunk.set_block(x, y, z, Block::new_id(id));
 }
 
-fn generate_leaves(chunk: &mut Chunk, starty: i32, x: i32, y: i32, z: i32, height: i32, id: u8) {
+fn generate_leaves(chunk: &mut Chunk, starty: i32, x: i32, y: i32, z: i32, height: i32, id: u16) {
     if y == starty + height {
         place_leaves(chunk, x, y, z, id);
         place_leaves(chunk, x - 1, y, z, id);
@@ -80,10 +77,9 @@ pub fn get_tree_gen_info(
             gen_tree_positions(
                 x + dx,
                 z + dz,
-                &world_generator.tree_generator,
+                world_generator,
                 &mut tree_positions,
                 &mut tree_heights,
-                world_generator.world_seed,
             );
         }
     }
@@ -91,59 +87,43 @@ pub fn get_tree_gen_info(
     (tree_positions, tree_heights)
 }
 
-fn gen_cactus(chunk: &mut Chunk, x: i32, z: i32, height: i32, world_generator: &WorldGenerator) {
-    let h = get_height_mountain(x, z, world_generator);
-    let terrain = get_height(x, z, world_generator);
-    if is_mountain(h, terrain) {
-        return;
-    }
-
-    //Below sea level
-    if h <= SEA_LEVEL {
-        return;
-    }
-
-    //Check to make sure we are not in a cave (an empty block)
-    if is_noise_cave(x, h, z, &world_generator.noise_cave_generator) {
+fn gen_cactus(chunk: &mut Chunk, x: i32, h: i32, z: i32, height: i32, sea_level: i32) {
+    //Cacti can not be placed at sea level
+    if h <= sea_level {
         return;
     }
 
-    //Generate cactus
     let cactus_height = (height - 3).clamp(1, 3);
     for y in (h + 1)..(h + 1 + cactus_height) {
-        chunk.set_block(x, y, z, Block::new_id(88));
+        chunk.set_block(x, y, z, Block::new_id(CACTUS));
     }
 }
 
-fn gen_tree(chunk: &mut Chunk, x: i32, z: i32, height: i32, world_generator: &WorldGenerator) {
+//Returns the height of the terrain at (x, z) if a tree can be placed on it
+fn can_place_tree(x: i32, z: i32, world_generator: &WorldGenerator) -> Option<i32> {
     let h = get_height_mountain(x, z, world_generator);
     let terrain = get_height(x, z, world_generator);
     if is_mountain(h, terrain) {
-        return;
+        return None;
     }
 
     //Below sea level
-    if h < SEA_LEVEL {
-        return;
+    if h < world_generator.settings.sea_level {
+        return None;
     }
 
     //Check to make sure we are not in a cave (an empty block)
     if is_noise_cave(x, h, z, &world_generator.noise_cave_generator) {
-        return;
+        return None;
     }
 
-    let temperature = (world_generator.get_temperature(x, z) * 100.0).ceil() as i32;
-    let leaf_id = if temperature < 25 {
-        //Snowy leaf in cold biomes
-        91
-    } else {
-        //Normal leaf
-        7
-    };
+    Some(h)
+}
 
+fn gen_oak(chunk: &mut Chunk, x: i32, h: i32, z: i32, height: i32, leaf_id: u16) {
     for y in (h + 1)..(h + 1 + height) {
         //Generate trunk
-        chunk.set_block(x, y, z, Block::new_id(8));
+        chunk.set_block(x, y, z, Block::new_id(LOG));
 
         //Generate leaves
         generate_leaves(chunk, h + 1, x, y, z, height, leaf_id);
@@ -151,6 +131,78 @@ fn gen_tree(chunk: &mut Chunk, x: i32, z: i32, height: i32, world_generator: &Wo
     generate_leaves(chunk, h + 1, x, h + 1 + height, z, height, leaf_id);
 }
 
+//Tall and narrow tree with layers of snowy leaves
+fn gen_spruce(chunk: &mut Chunk, x: i32, h: i32, z: i32, height: i32) {
+    let height = height + 2;
+    for y in (h + 1)..(h + 1 + height) {
+        chunk.set_block(x, y, z, Block::new_id(LOG));
+    }
+
+    let top = h + 1 + height;
+    for y in (h + 3)..=top {
+        let dist = top - y;
+        let radius = match dist {
+            0 => 0,
+            1 | 2 => 1,
+            _ => 2 - dist % 2,
+        };
+        for ix in (x - radius)..=(x + radius) {
+            for iz in (z - radius)..=(z + radius) {
+                //Round off the corners
+                if radius == 2 && (ix - x).abs() + (iz - z).abs() > 3 {
+                    continue;
+                }
+                place_leaves(chunk, ix, y, iz, SNOWY_LEAVES);
+            }
+        }
+    }
+}
+
+//Short tree with a wide and flat canopy
+fn gen_acacia(chunk: &mut Chunk, x: i32, h: i32, z: i32, height: i32) {
+    let height = (height - 1).max(3);
+    for y in (h + 1)..(h + 1 + height) {
+        chunk.set_block(x, y, z, Block::new_id(LOG));
+    }
+
+    let top = h + 1 + height;
+    for ix in (x - 2)..=(x + 2) {
+        for iz in (z - 2)..=(z + 2) {
+            if (ix - x).abs() == 2 && (iz - z).abs() == 2 {
+                continue;
+            }
+            place_leaves(chunk, ix, top - 1, iz, LEAVES);
+        }
+    }
+    for ix in (x - 1)..=(x + 1) {
+        for iz in (z - 1)..=(z + 1) {
+            place_leaves(chunk, ix, top, iz, LEAVES);
+        }
+    }
+}
+
+fn gen_tree(
+    chunk: &mut Chunk,
+    x: i32,
+    z: i32,
+    height: i32,
+    tree_type: TreeType,
+    world_generator: &WorldGenerator,
+) {
+    let Some(h) = can_place_tree(x, z, world_generator) else {
+        return;
+    };
+
+    match tree_type {
+        TreeType::Oak => gen_oak(chunk, x, h, z, height, LEAVES),
+        //Snowy leaf in cold biomes
+        TreeType::SnowyOak => gen_oak(chunk, x, h, z, height, SNOWY_LEAVES),
+        TreeType::Spruce => gen_spruce(chunk, x, h, z, height),
+        TreeType::Acacia => gen_acacia(chunk, x, h, z, height),
+        TreeType::Cactus => gen_cactus(chunk, x, h, z, height, world_generator.settings.sea_level),
+    }
+}
+
 //Also generates cacti as well
 pub fn generate_trees(
     chunk: &mut Chunk,
@@ -165,20 +217,12 @@ pub fn generate_trees(
     let upper_z = chunkpos.z * CHUNK_SIZE_I32 + CHUNK_SIZE_I32 - 1;
 
     for (i, (x, z)) in tree_positions.iter().copied().enumerate() {
-        //Do not generate trees in deserts
-        let temperature = (world_generator.get_temperature(x, z) * 100.0).ceil() as i32;
-
-        let height = get_height_mountain(x, z, world_generator);
-        if is_beach(temperature, height) {
+        //The biome decides the type of tree and how many of them there are
+        let Some((tree_type, frequency)) = world_generator.get_biome(x, z).tree() else {
             continue;
-        }
+        };
 
-        if temperature > 75 {
-            //Generate cacti instead
-            if i % 3 == 0 {
-                //Only a third of the time
-                gen_cactus(chunk, x, z, tree_heights[i], world_generator);
-            }
+        if i % frequency != 0 {
             continue;
         }
 
@@ -186,6 +230,6 @@ pub fn generate_trees(
             continue;
         }
 
-        gen_tree(chunk, x, z, tree_heights[i], world_generator);
+        gen_tree(chunk, x, z, tree_heights[i], tree_type, world_generator);
     }
 }
@@ -1,14 +1,19 @@
-use crate::voxel::{Block, Chunk, CHUNK_SIZE_I32};
+use crate::voxel::{
+    block_ids::{
+        AQUA_ORE, CLAY, COAL_ORE, DIAMOND_ORE, GOLD_ORE, IRON_ORE, MAGMA_STONE, RAINBOW_ORE,
+        RED_ORE, SAND, STONE, URANIUM_ORE,
+    },
+    world::gen_settings::GenSettings,
+    Block, Chunk, CHUNK_SIZE_I32,
+};
 use fastrand::Rng;
 
-use super::{BOTTOM_OF_WORLD, LAVA_LEVEL, SEA_LEVEL};
-
 fn gen_vein(
     chunk: &mut Chunk,
     xyz: (i32, i32, i32),
     size1: i32,
     size2: i32,
-    ore: u8,
+    ore: u16,
     probability: f64,
     rng: &mut Rng,
 ) {
@@ -20,7 +25,7 @@ fn gen_vein(
             for iz in (z - size2)..=(z + size1) {
                 let block = chunk.get_block(ix, iy, iz);
                 //Do not generate any ore in blocks that are not stone
-                if block.id != 2 && block.id != 15 {
+                if block.id != STONE && block.id != MAGMA_STONE {
                     continue;
                 }
 
@@ -41,82 +46,96 @@ fn get_probability(y: i32, starty: i32, endy: i32, min_prob: f64, max_prob: f64)
     frac * (max_prob - min_prob) + min_prob
 }
 
-pub fn generate_ore(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rng) {
+//`ore_frequency` in the settings multiplies the chance of every vein
+pub fn generate_ore(
+    chunk: &mut Chunk,
+    (x, y, z): (i32, i32, i32),
+    rng: &mut Rng,
+    settings: &GenSettings,
+) {
     //If it is not stone, ignore it
     let block = chunk.get_block(x, y, z);
-    if block.id != 2 && block.id != 15 {
+    if block.id != STONE && block.id != MAGMA_STONE {
         return;
     }
 
+    let bottom = settings.bottom;
+    let frequency = settings.ore_frequency;
+
     //Generate coal ore
     //Generates where ever stone generates
-    if y < -16 && rng.i32(0..2_000) == 0 {
-        gen_vein(chunk, (x, y, z), 2, 2, 18, 0.05, rng);
-    } else if y >= 0 && rng.i32(0..1_000) == 0 {
-        gen_vein(chunk, (x, y, z), 1, 1, 18, 0.2, rng);
+    if y < -16 && rng.f64() < frequency / 2_000.0 {
+        gen_vein(chunk, (x, y, z), 2, 2, COAL_ORE, 0.05, rng);
+    } else if y >= 0 && rng.f64() < frequency / 1_000.0 {
+        gen_vein(chunk, (x, y, z), 1, 1, COAL_ORE, 0.2, rng);
     }
 
     //Generate crimson crystal ore
     //Generates below y = -32
-    if rng.f64() < get_probability(y, -32, BOTTOM_OF_WORLD, 1.0 / 8_000.0, 1.0 / 2_000.0) {
-        gen_vein(chunk, (x, y, z), 1, 1, 23, 0.3, rng);
+    if rng.f64() < get_probability(y, -32, bottom, 1.0 / 8_000.0, 1.0 / 2_000.0) * frequency {
+        gen_vein(chunk, (x, y, z), 1, 1, RED_ORE, 0.3, rng);
     }
 
     //Generate uranium ore
     //Generate below y = -40
-    if rng.f64() < get_probability(y, -40, BOTTOM_OF_WORLD, 1.0 / 5_500.0, 1.0 / 4_000.0) {
-        gen_vein(chunk, (x, y, z), 1, 1, 94, 0.4, rng);
+    if rng.f64() < get_probability(y, -40, bottom, 1.0 / 5_500.0, 1.0 / 4_000.0) * frequency {
+        gen_vein(chunk, (x, y, z), 1, 1, URANIUM_ORE, 0.4, rng);
     }
 
     //Generate iron ore
     //Generates below y = 0
-    if rng.f64() < get_probability(y, 0, BOTTOM_OF_WORLD, 1.0 / 4_000.0, 1.0 / 2_000.0) {
-        gen_vein(chunk, (x, y, z), 0, 1, 19, 0.5, rng);
+    if rng.f64() < get_probability(y, 0, bottom, 1.0 / 4_000.0, 1.0 / 2_000.0) * frequency {
+        gen_vein(chunk, (x, y, z), 0, 1, IRON_ORE, 0.5, rng);
     }
 
     //Generate gold ore
     //Generates below y = -32
-    if rng.f64() < get_probability(y, -32, BOTTOM_OF_WORLD, 1.0 / 4_500.0, 1.0 / 3_000.0) {
-        gen_vein(chunk, (x, y, z), 0, 1, 20, 0.33, rng);
+    if rng.f64() < get_probability(y, -32, bottom, 1.0 / 4_500.0, 1.0 / 3_000.0) * frequency {
+        gen_vein(chunk, (x, y, z), 0, 1, GOLD_ORE, 0.33, rng);
     }
 
     //Generate diamond ore
     //Generates below y = -40
-    if rng.f64() < get_probability(y, -40, BOTTOM_OF_WORLD, 1.0 / 5_500.0, 1.0 / 4_500.0) {
-        gen_vein(chunk, (x, y, z), 0, 1, 21, 0.25, rng);
+    if rng.f64() < get_probability(y, -40, bottom, 1.0 / 5_500.0, 1.0 / 4_500.0) * frequency {
+        gen_vein(chunk, (x, y, z), 0, 1, DIAMOND_ORE, 0.25, rng);
     }
 
     //Generate rainbow ore
     //Generates below y = -50
-    if y < -50 && rng.i32(0..5_000) == 0 {
-        chunk.set_block(x, y, z, Block::new_id(22));
+    if y < -50 && rng.f64() < frequency / 5_000.0 {
+        chunk.set_block(x, y, z, Block::new_id(RAINBOW_ORE));
     }
 }
 
-pub fn generate_magma_blocks(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rng) {
+pub fn generate_magma_blocks(
+    chunk: &mut Chunk,
+    (x, y, z): (i32, i32, i32),
+    rng: &mut Rng,
+    settings: &GenSettings,
+) {
     //If it is not stone, ignore it
-    if chunk.get_block(x, y, z).id != 2 {
+    if chunk.get_block(x, y, z).id != STONE {
         return;
     }
 
     if rng.f64()
         < get_probability(
             y,
-            LAVA_LEVEL + 4,
-            BOTTOM_OF_WORLD,
+            settings.lava_level + 4,
+            settings.bottom,
             1.0 / 3_000.0,
             1.0 / 1_000.0,
         )
     {
-        gen_vein(chunk, (x, y, z), 3, 3, 15, 0.66, rng);
+        gen_vein(chunk, (x, y, z), 3, 3, MAGMA_STONE, 0.66, rng);
     }
 }
 
 const CLAY_RADIUS: i32 = 2;
 
-pub fn generate_clay(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rng) {
+pub fn generate_clay(chunk: &mut Chunk, (x, y, z): (i32, i32, i32), rng: &mut Rng, sea_level: i32) {
     //If it is not sand, ignore it
-    if chunk.get_block(x, y, z).id != 11 {
+    if chunk.get_block(x, y, z).id != SAND {
         return;
     }
 
@@ -135,7 +154,7 @@ pub fn generate_clay(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rng) {
         return;
     }
 
-    if y >= SEA_LEVEL - 2 {
+    if y >= sea_level - 2 {
         return;
     }
 
@@ -152,13 +171,13 @@ pub fn generate_clay(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rng) {
                 }
 
                 let block = chunk.get_block(ix, iy, iz);
-                if block.id != 11 {
+                if block.id != SAND {
                     continue;
                 }
-                if y >= SEA_LEVEL - 1 {
+                if y >= sea_level - 1 {
                     continue;
                 }
-                chunk.set_block(ix, iy, iz, Block::new_id(93));
+                chunk.set_block(ix, iy, iz, Block::new_id(CLAY));
             }
         }
     }
@@ -166,9 +185,14 @@ pub fn generate_clay(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rng) {
 
 const AQUA_VEIN_SIZE: i32 = 1;
 
-pub fn generate_aqua_ore(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rng) {
+pub fn generate_aqua_ore(
+    chunk: &mut Chunk,
+    (x, y, z): (i32, i32, i32),
+    rng: &mut Rng,
+    sea_level: i32,
+) {
     //If it is not sand, ignore it
-    if chunk.get_block(x, y, z).id != 11 {
+    if chunk.get_block(x, y, z).id != SAND {
         return;
     }
 
@@ -187,7 +211,7 @@ pub fn generate_aqua_ore(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rn
         return;
     }
 
-    if y >= SEA_LEVEL - 4 {
+    if y >= sea_level - 4 {
         return;
     }
 
@@ -199,16 +223,16 @@ pub fn generate_aqua_ore(chunk: &mut Chunk, x: i32, y: i32, z: i32, rng: &mut Rn
         for iy in (y - AQUA_VEIN_SIZE)..=(y + AQUA_VEIN_SIZE) {
             for iz in (z - AQUA_VEIN_SIZE)..=(z + AQUA_VEIN_SIZE) {
                 let block = chunk.get_block(ix, iy, iz);
-                if block.id != 11 {
+                if block.id != SAND {
                     continue;
                 }
-                if y >= SEA_LEVEL - 1 {
+                if y >= sea_level - 1 {
                     continue;
                 }
                 if rng.f64() > 0.66 {
                     continue;
                 }
-                chunk.set_block(ix, iy, iz, Block::new_id(96));
+                chunk.set_block(ix, iy, iz, Block::new_id(AQUA_ORE));
             }
         }
     }
@@ -0,0 +1,130 @@
+use super::{
+    is_mountain, is_noise_cave,
+    terrain::{get_height, get_height_mountain},
+    WorldGenerator,
+};
+use crate::voxel::{
+    block_ids::SUGARCANE,
+    world::{biome::PLANT_ROLL, gen_rng::RngStream},
+    Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
+use fastrand::Rng;
+
+pub fn get_plant_positions(
+    chunkx: i32,
+    chunkz: i32,
+    world_generator: &WorldGenerator,
+) -> Vec<(i32, i32)> {
+    let mut rng = world_generator.column_rng(RngStream::PlantPositions, chunkx, chunkz);
+    let count = rng.i32(0..20);
+    let mut positions = vec![];
+    for _ in 0..count {
+        let plantx = rng.i32(0..CHUNK_SIZE_I32);
+        let plantz = rng.i32(0..CHUNK_SIZE_I32);
+        let x = plantx + chunkx * CHUNK_SIZE_I32;
+        let z = plantz + chunkz * CHUNK_SIZE_I32;
+        positions.push((x, z));
+    }
+    positions
+}
+
+pub fn generate_plants(
+    chunk: &mut Chunk,
+    plant_positions: &[(i32, i32)],
+    rng: &mut Rng,
+    world_generator: &WorldGenerator,
+) {
+    for (x, z) in plant_positions {
+        let rand_val = rng.i32(0..PLANT_ROLL);
+        let h = get_height_mountain(*x, *z, world_generator);
+        let terrain = get_height(*x, *z, world_generator);
+        if is_mountain(h, terrain) {
+            continue;
+        }
+
+        let replace = chunk.get_block(*x, h + 1, *z);
+        //Replace any non solid block or empty block
+        if !(replace == Block::new() || replace.shape() != 0) {
+            continue;
+        }
+
+        //Below sea level
+        if h < world_generator.settings.sea_level {
+            continue;
+        }
+
+        //Check to make sure we are not in a cave (an empty block)
+        if is_noise_cave(*x, h, *z, &world_generator.noise_cave_generator) {
+            continue;
+        }
+
+        //Each biome has its own table of plants
+        let biome = world_generator.get_biome(*x, *z);
+        if let Some(id) = biome.pick_plant(rand_val) {
+            chunk.set_block(*x, h + 1, *z, Block::new_id(id));
+        }
+    }
+}
+
+fn is_adjacent_to_water(x: i32, z: i32, h: i32, heights: &[i32], sea_level: i32) -> bool {
+    if h != sea_level {
+        return false;
+    }
+
+    const ADJ: [(i32, i32); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+    for (dx, dz) in ADJ {
+        if x + dx < 0 || x + dx >= CHUNK_SIZE_I32 || z + dz < 0 || z + dz >= CHUNK_SIZE_I32 {
+            continue;
+        }
+        let index = ((z + dz) * CHUNK_SIZE_I32 + x + dx) as usize;
+        if heights[index] < sea_level {
+            return true;
+        }
+    }
+
+    false
+}
+
+pub fn get_water_adjacent(
+    chunkx: i32,
+    chunkz: i32,
+    heights: &[i32],
+    sea_level: i32,
+) -> Vec<(i32, i32)> {
+    let mut adjacent = vec![];
+    let posx = chunkx * CHUNK_SIZE_I32;
+    let posz = chunkz * CHUNK_SIZE_I32;
+    for x in 0..CHUNK_SIZE_I32 {
+        for z in 0..CHUNK_SIZE_I32 {
+            let index = (z * CHUNK_SIZE_I32 + x) as usize;
+            let h = heights[index];
+            if is_adjacent_to_water(x, z, h, heights, sea_level) {
+                adjacent.push((posx + x, posz + z));
+            }
+        }
+    }
+    adjacent
+}
+
+pub fn generate_sugarcane(
+    chunk: &mut Chunk,
+    water_adjacent: &[(i32, i32)],
+    rng: &mut Rng,
+    sea_level: i32,
+) {
+    for (x, z) in water_adjacent {
+        if rng.i32(0..60) != 0 {
+            continue;
+        }
+
+        let height = rng.i32(1..=3);
+        for i in 0..height {
+            let y = sea_level + 1 + i;
+            if chunk.get_block(*x, y, *z).id != EMPTY_BLOCK {
+                break;
+            }
+
+            chunk.set_block(*x, y, *z, Block::new_id(SUGARCANE));
+        }
+    }
+}
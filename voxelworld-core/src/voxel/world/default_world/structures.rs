@@ -0,0 +1,360 @@
+/*
+ * Structures (villages, dungeons and ruins)
+ *
+ * The world is split into cells of STRUCTURE_CELL_SIZE x STRUCTURE_CELL_SIZE
+ * chunks and each cell has at most one structure which is picked from the
+ * world seed and the position of the cell. A structure is made of pieces
+ * (templates that are randomly rotated and varied) that always fit inside of
+ * their cell, so a chunk only needs to plan the structure in its own cell.
+ *
+ * Structures can span many chunks, each chunk only places the parts of the
+ * pieces that are inside of it. This means that the parts of a structure that
+ * fall into chunks that have not been generated yet are placed once those
+ * chunks are generated, the same way that trees crossing chunk borders are.
+ * */
+
+mod templates;
+
+use self::templates::WallType;
+use super::terrain::get_height_mountain;
+use crate::{
+    inventory::loot::loot_tables,
+    voxel::{
+        block_ids::{CHEST, COBBLESTONE},
+        schematic::{Schematic, SchematicTransform},
+        world::{biome::Biome, gen_rng::RngStream, WorldGenerator},
+        Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK,
+    },
+};
+
+//Size of a cell in chunks
+pub const STRUCTURE_CELL_SIZE: i32 = 8;
+const CELL_BLOCKS: i32 = STRUCTURE_CELL_SIZE * CHUNK_SIZE_I32;
+//Structures are centered at least this far away from the edge of their cell
+const CELL_MARGIN: i32 = 24;
+//Pieces are not placed if the ground under them is more uneven than this
+const MAX_UNEVENNESS: i32 = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StructureKind {
+    Village,
+    Dungeon,
+    Ruins,
+}
+
+pub struct StructurePiece {
+    pub kind: StructureKind,
+    //Minimum corner of the piece
+    pub pos: (i32, i32, i32),
+    pub schematic: Schematic,
+    //Fills the space between the bottom of the piece and the terrain
+    pub foundation: Option<Block>,
+    //Loot table that the chests in this piece are filled from
+    pub loot_table: &'static str,
+}
+
+impl StructurePiece {
+    //Returns the minimum and maximum corners of the piece
+    pub fn bounds(&self) -> ((i32, i32, i32), (i32, i32, i32)) {
+        let (w, h, d) = self.schematic.size();
+        let (x, y, z) = self.pos;
+        (
+            (x, y, z),
+            (x + w as i32 - 1, y + h as i32 - 1, z + d as i32 - 1),
+        )
+    }
+
+    fn intersects_column(&self, chunkx: i32, chunkz: i32) -> bool {
+        let ((x1, _, z1), (x2, _, z2)) = self.bounds();
+        let (minx, minz) = (chunkx * CHUNK_SIZE_I32, chunkz * CHUNK_SIZE_I32);
+        let (maxx, maxz) = (minx + CHUNK_SIZE_I32 - 1, minz + CHUNK_SIZE_I32 - 1);
+        x1 <= maxx && x2 >= minx && z1 <= maxz && z2 >= minz
+    }
+
+    //Empty blocks in the schematic replace the terrain, ruins are left
+    //partially buried
+    fn clear_air(&self) -> bool {
+        self.kind != StructureKind::Ruins
+    }
+
+    //Places the part of the piece that is inside of the chunk
+    pub fn place(&self, chunk: &mut Chunk, world_generator: &WorldGenerator) {
+        let chunkpos = chunk.get_chunk_pos();
+        let posx = chunkpos.x * CHUNK_SIZE_I32;
+        let posy = chunkpos.y * CHUNK_SIZE_I32;
+        let posz = chunkpos.z * CHUNK_SIZE_I32;
+        let ((x1, y1, z1), (x2, y2, z2)) = self.bounds();
+
+        for x in x1.max(posx)..=x2.min(posx + CHUNK_SIZE_I32 - 1) {
+            for z in z1.max(posz)..=z2.min(posz + CHUNK_SIZE_I32 - 1) {
+                let (sx, sz) = ((x - x1) as usize, (z - z1) as usize);
+                for y in y1.max(posy)..=y2.min(posy + CHUNK_SIZE_I32 - 1) {
+                    let block = self.schematic.get_block(sx, (y - y1) as usize, sz);
+                    if block.id == EMPTY_BLOCK && !self.clear_air() {
+                        continue;
+                    }
+                    chunk.set_block(x, y, z, block);
+                    if block.id == CHEST {
+                        self.fill_chest(chunk, x, y, z, world_generator);
+                    }
+                }
+
+                let Some(foundation) = self.foundation else {
+                    continue;
+                };
+                if self.schematic.get_block(sx, 0, sz).id == EMPTY_BLOCK {
+                    continue;
+                }
+                let ground = get_height_mountain(x, z, world_generator);
+                for y in (ground + 1).max(posy)..y1.min(posy + CHUNK_SIZE_I32) {
+                    chunk.set_block(x, y, z, foundation);
+                }
+            }
+        }
+    }
+
+    fn fill_chest(&self, chunk: &mut Chunk, x: i32, y: i32, z: i32, wg: &WorldGenerator) {
+        let Some(loot_table) = loot_tables().get(self.loot_table) else {
+            return;
+        };
+        let mut rng = wg.position_rng(RngStream::Chests, (x, y, z));
+        chunk.set_tile_data(x, y, z, Some(loot_table.fill_chest(&mut rng)));
+    }
+}
+
+//Returns (minimum height, maximum height) of the terrain under a box
+fn ground_range(x: i32, z: i32, w: i32, d: i32, wg: &WorldGenerator) -> (i32, i32) {
+    let corners = [
+        (x, z),
+        (x + w - 1, z),
+        (x, z + d - 1),
+        (x + w - 1, z + d - 1),
+    ];
+    let center = (x + w / 2, z + d / 2);
+    let heights: Vec<i32> = corners
+        .iter()
+        .chain(std::iter::once(&center))
+        .map(|&(x, z)| get_height_mountain(x, z, wg))
+        .collect();
+    let min = heights.iter().copied().min().unwrap_or(0);
+    let max = heights.iter().copied().max().unwrap_or(0);
+    (min, max)
+}
+
+//Returns a piece centered at (x, z) that sits on the surface, None if the
+//ground there is underwater or too uneven
+fn surface_piece(
+    kind: StructureKind,
+    schematic: Schematic,
+    (x, z): (i32, i32),
+    loot_table: &'static str,
+    wg: &WorldGenerator,
+) -> Option<StructurePiece> {
+    let (w, _, d) = schematic.size();
+    let (w, d) = (w as i32, d as i32);
+    let (minx, minz) = (x - w / 2, z - d / 2);
+    let (low, high) = ground_range(minx, minz, w, d, wg);
+    if low <= wg.settings.sea_level || high - low > MAX_UNEVENNESS {
+        return None;
+    }
+
+    let floor = get_height_mountain(x, z, wg);
+    Some(StructurePiece {
+        kind,
+        pos: (minx, floor, minz),
+        schematic,
+        foundation: Some(Block::new_id(COBBLESTONE)),
+        loot_table,
+    })
+}
+
+fn random_transform(rng: &mut fastrand::Rng) -> SchematicTransform {
+    SchematicTransform::new(rng.u8(0..4), rng.bool())
+}
+
+fn plan_village(
+    center: (i32, i32),
+    rng: &mut fastrand::Rng,
+    wg: &WorldGenerator,
+) -> Vec<StructurePiece> {
+    let biome = wg.get_biome(center.0, center.1);
+    let wall_types: &[WallType] = match biome {
+        Biome::Plains | Biome::Forest => {
+            &[WallType::Plank, WallType::Cobblestone, WallType::Bricks]
+        }
+        Biome::Savanna => &[WallType::Plank, WallType::Bricks],
+        Biome::Desert => &[WallType::SandBricks],
+        Biome::Taiga | Biome::SnowyTundra => &[WallType::Plank],
+        _ => return vec![],
+    };
+
+    let kind = StructureKind::Village;
+    let Some(well) = surface_piece(kind, templates::well(), center, "village", wg) else {
+        return vec![];
+    };
+    let mut pieces = vec![well];
+
+    //Houses are placed on the sides of the well with their doors facing it,
+    //(offset, rotation) where the rotation turns the door (-z) toward the well
+    let mut sides = [((0, 1), 0), ((-1, 0), 1), ((0, -1), 2), ((1, 0), 3)];
+    rng.shuffle(&mut sides);
+    let house_count = rng.usize(2..=sides.len());
+    for ((dx, dz), rotation) in sides.into_iter().take(house_count) {
+        let wall = wall_types[rng.usize(0..wall_types.len())];
+        let house = templates::house(wall, rng.usize(5..=7), rng.usize(5..=7), rng.bool());
+        let transform = SchematicTransform::new(rotation, rng.bool());
+        let dist = rng.i32(9..=14);
+        let pos = (center.0 + dx * dist, center.1 + dz * dist);
+        if let Some(piece) = surface_piece(kind, house.transformed(transform), pos, "village", wg) {
+            pieces.push(piece);
+        }
+    }
+    pieces
+}
+
+fn plan_ruins(
+    center: (i32, i32),
+    rng: &mut fastrand::Rng,
+    wg: &WorldGenerator,
+) -> Vec<StructurePiece> {
+    let ruins = templates::ruins(rng).transformed(random_transform(rng));
+    surface_piece(StructureKind::Ruins, ruins, center, "ruins", wg)
+        .into_iter()
+        .collect()
+}
+
+fn plan_dungeon(
+    center: (i32, i32),
+    rng: &mut fastrand::Rng,
+    wg: &WorldGenerator,
+) -> Vec<StructurePiece> {
+    let dungeon = templates::dungeon(rng).transformed(random_transform(rng));
+    let (w, h, d) = dungeon.size();
+    let (w, h, d) = (w as i32, h as i32, d as i32);
+    let (minx, minz) = (center.0 - w / 2, center.1 - d / 2);
+    //Dungeons must be buried under the terrain
+    let y = rng.i32(-40..=-12);
+    let (low, _) = ground_range(minx, minz, w, d, wg);
+    if y + h >= low - 4 {
+        return vec![];
+    }
+
+    vec![StructurePiece {
+        kind: StructureKind::Dungeon,
+        pos: (minx, y, minz),
+        schematic: dungeon,
+        foundation: None,
+        loot_table: "dungeon",
+    }]
+}
+
+//Returns every piece of the structure in the cell at (cellx, cellz)
+pub fn plan_structure(cellx: i32, cellz: i32, wg: &WorldGenerator) -> Vec<StructurePiece> {
+    let mut rng = wg.column_rng(RngStream::Structures, cellx, cellz);
+    let x = cellx * CELL_BLOCKS + rng.i32(CELL_MARGIN..(CELL_BLOCKS - CELL_MARGIN));
+    let z = cellz * CELL_BLOCKS + rng.i32(CELL_MARGIN..(CELL_BLOCKS - CELL_MARGIN));
+    match rng.u32(0..100) {
+        0..25 => plan_village((x, z), &mut rng, wg),
+        25..50 => plan_ruins((x, z), &mut rng, wg),
+        50..80 => plan_dungeon((x, z), &mut rng, wg),
+        _ => vec![],
+    }
+}
+
+//Returns the pieces of structures that are in the column at (chunkx, chunkz)
+pub fn get_column_structures(chunkx: i32, chunkz: i32, wg: &WorldGenerator) -> Vec<StructurePiece> {
+    let cellx = chunkx.div_euclid(STRUCTURE_CELL_SIZE);
+    let cellz = chunkz.div_euclid(STRUCTURE_CELL_SIZE);
+    plan_structure(cellx, cellz, wg)
+        .into_iter()
+        .filter(|piece| piece.intersects_column(chunkx, chunkz))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::world::{terrain_generator::DefaultGenerator, GameMode, World};
+
+    fn same_blocks(schematic1: &Schematic, schematic2: &Schematic) -> bool {
+        let (w, h, d) = schematic1.size();
+        if schematic2.size() != (w, h, d) {
+            return false;
+        }
+        (0..w).all(|x| {
+            (0..h).all(|y| {
+                (0..d).all(|z| schematic1.get_block(x, y, z) == schematic2.get_block(x, y, z))
+            })
+        })
+    }
+
+    #[test]
+    fn test_structures_in_cell() {
+        let world_generator = WorldGenerator::new(1234);
+        let mut kinds = vec![];
+        for cellx in -6..6 {
+            for cellz in -6..6 {
+                let pieces = plan_structure(cellx, cellz, &world_generator);
+                let again = plan_structure(cellx, cellz, &world_generator);
+                assert_eq!(pieces.len(), again.len());
+                for (piece, other) in pieces.iter().zip(&again) {
+                    assert_eq!(piece.bounds(), other.bounds());
+                    assert_eq!(piece.kind, other.kind);
+                    assert!(same_blocks(&piece.schematic, &other.schematic));
+
+                    //Pieces never leave their cell
+                    let ((x1, _, z1), (x2, _, z2)) = piece.bounds();
+                    let cell = |v: i32| v.div_euclid(CELL_BLOCKS);
+                    assert_eq!((cell(x1), cell(z1)), (cellx, cellz));
+                    assert_eq!((cell(x2), cell(z2)), (cellx, cellz));
+                    kinds.push(piece.kind);
+                }
+            }
+        }
+
+        for kind in [
+            StructureKind::Village,
+            StructureKind::Dungeon,
+            StructureKind::Ruins,
+        ] {
+            assert!(kinds.contains(&kind));
+        }
+    }
+
+    #[test]
+    fn test_dungeon_chests() {
+        let mut world = World::new(1234, 0, &DefaultGenerator, GameMode::Survival);
+        world.chunks.clear();
+        let dungeon = (-6..6)
+            .flat_map(|x| (-6..6).map(move |z| (x, z)))
+            .flat_map(|(x, z)| plan_structure(x, z, &world.world_generator))
+            .find(|piece| piece.kind == StructureKind::Dungeon)
+            .unwrap();
+
+        //Generate every chunk that the dungeon is in one at a time
+        let ((x1, y1, z1), (x2, y2, z2)) = dungeon.bounds();
+        let chunk = |v: i32| v.div_euclid(CHUNK_SIZE_I32);
+        for x in chunk(x1)..=chunk(x2) {
+            for y in chunk(y1)..=chunk(y2) {
+                for z in chunk(z1)..=chunk(z2) {
+                    world.add_chunk(x, y, z);
+                }
+            }
+        }
+
+        let mut chests = 0;
+        for x in x1..=x2 {
+            for y in y1..=y2 {
+                for z in z1..=z2 {
+                    if world.get_block(x, y, z).id != CHEST {
+                        continue;
+                    }
+                    chests += 1;
+                    let chest = world.get_tile_data(x, y, z).unwrap();
+                    assert!(!chest.inventory.is_empty());
+                }
+            }
+        }
+        assert!(chests > 0);
+    }
+}
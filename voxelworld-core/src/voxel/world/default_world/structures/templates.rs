@@ -0,0 +1,162 @@
+/*
+ * Templates for the pieces of structures, every template is built with the
+ * door or opening facing -z (before it is rotated) and layer 0 is the floor.
+ * */
+
+use crate::voxel::{
+    block_ids::{
+        BRICKS, CHEST, COBBLESTONE, FENCE, GLASS, LOG, MOSS, PLANK, SAND_BRICKS, STONE_BRICKS,
+        TORCH, WATER,
+    },
+    schematic::Schematic,
+    Block,
+};
+
+//Chests face -x
+const CHEST_ORIENTATION: u8 = 4;
+
+//Block used for the walls of village houses
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WallType {
+    Plank,
+    Cobblestone,
+    Bricks,
+    SandBricks,
+}
+
+impl WallType {
+    fn block(&self) -> Block {
+        match self {
+            Self::Plank => Block::new_id(PLANK),
+            Self::Cobblestone => Block::new_id(COBBLESTONE),
+            Self::Bricks => Block::new_id(BRICKS),
+            Self::SandBricks => Block::new_id(SAND_BRICKS),
+        }
+    }
+}
+
+fn is_edge(x: usize, z: usize, w: usize, d: usize) -> bool {
+    x == 0 || z == 0 || x == w - 1 || z == d - 1
+}
+
+fn is_corner(x: usize, z: usize, w: usize, d: usize) -> bool {
+    (x == 0 || x == w - 1) && (z == 0 || z == d - 1)
+}
+
+//Fills a box with walls, a floor and a ceiling, the inside is left empty
+fn fill_shell(schematic: &mut Schematic, mut block: impl FnMut(usize, usize, usize) -> Block) {
+    let (w, h, d) = schematic.size();
+    for y in 0..h {
+        for z in 0..d {
+            for x in 0..w {
+                if y == 0 || y == h - 1 || is_edge(x, z, w, d) {
+                    schematic.set_block(x, y, z, block(x, y, z));
+                }
+            }
+        }
+    }
+}
+
+pub fn house(wall: WallType, w: usize, d: usize, has_chest: bool) -> Schematic {
+    let mut house = Schematic::new(w, 5, d);
+    fill_shell(&mut house, |x, y, z| {
+        if y == 0 || y == 4 {
+            Block::new_id(PLANK)
+        } else if is_corner(x, z, w, d) {
+            Block::new_id(LOG)
+        } else {
+            wall.block()
+        }
+    });
+
+    //Windows on the sides and the back
+    house.set_block(0, 2, d / 2, Block::new_id(GLASS));
+    house.set_block(w - 1, 2, d / 2, Block::new_id(GLASS));
+    house.set_block(w / 2, 2, d - 1, Block::new_id(GLASS));
+    //Doorway
+    house.set_block(w / 2, 1, 0, Block::new());
+    house.set_block(w / 2, 2, 0, Block::new());
+
+    house.set_block(1, 1, d - 2, Block::new_id(TORCH));
+    if has_chest {
+        let chest = Block::new_id_orientation(CHEST, CHEST_ORIENTATION);
+        house.set_block(w - 2, 1, d - 2, chest);
+    }
+    house
+}
+
+pub fn well() -> Schematic {
+    let mut well = Schematic::new(4, 5, 4);
+    for z in 0..4 {
+        for x in 0..4 {
+            well.set_block(x, 0, z, Block::new_id(COBBLESTONE));
+            well.set_block(x, 4, z, Block::new_id(PLANK));
+            if is_edge(x, z, 4, 4) {
+                well.set_block(x, 1, z, Block::new_id(COBBLESTONE));
+            } else {
+                well.set_block(x, 1, z, Block::new_fluid(WATER));
+            }
+
+            if is_corner(x, z, 4, 4) {
+                well.set_block(x, 2, z, Block::new_id(FENCE));
+                well.set_block(x, 3, z, Block::new_id(FENCE));
+            }
+        }
+    }
+    well
+}
+
+//A room of cobblestone and moss with chests against the walls
+pub fn dungeon(rng: &mut fastrand::Rng) -> Schematic {
+    let w = rng.usize(7..=11);
+    let d = rng.usize(7..=11);
+    let mut dungeon = Schematic::new(w, 5, d);
+    fill_shell(&mut dungeon, |_, _, _| {
+        if rng.u32(0..3) == 0 {
+            Block::new_id(MOSS)
+        } else {
+            Block::new_id(COBBLESTONE)
+        }
+    });
+
+    let chest_count = rng.usize(1..=2);
+    for _ in 0..chest_count {
+        let z = rng.usize(1..(d - 1));
+        let chest = Block::new_id_orientation(CHEST, CHEST_ORIENTATION);
+        dungeon.set_block(w - 2, 1, z, chest);
+    }
+    dungeon.set_block(w / 2, 1, d / 2, Block::new_id(TORCH));
+    dungeon
+}
+
+//The walls of a stone building that has fallen apart, the higher a block is
+//the more likely it is to be missing
+pub fn ruins(rng: &mut fastrand::Rng) -> Schematic {
+    let w = rng.usize(6..=9);
+    let d = rng.usize(6..=9);
+    let mut ruins = Schematic::new(w, 4, d);
+    for y in 0..4 {
+        for z in 0..d {
+            for x in 0..w {
+                if y > 0 && !is_edge(x, z, w, d) {
+                    continue;
+                }
+
+                if rng.u32(0..4) < y as u32 {
+                    continue;
+                }
+
+                let id = if rng.bool() {
+                    STONE_BRICKS
+                } else {
+                    COBBLESTONE
+                };
+                ruins.set_block(x, y, z, Block::new_id(id));
+            }
+        }
+    }
+
+    let chest = Block::new_id_orientation(CHEST, CHEST_ORIENTATION);
+    ruins.set_block(rng.usize(1..(w - 1)), 1, rng.usize(1..(d - 1)), chest);
+    ruins
+}
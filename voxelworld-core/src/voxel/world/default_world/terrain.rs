@@ -30,49 +30,27 @@ pub fn get_height(x: i32, z: i32, world_generator: &WorldGenerator) -> i32 {
     let elevation = world_generator.get_elevation(x, z);
 
     let transformed_noise = base + steepness * elevation;
-    (transformed_noise * 64.0) as i32
+    (transformed_noise * 64.0 * world_generator.settings.height_scale) as i32
 }
 
 pub fn get_temperature(x: i32, z: i32, world_generator: &WorldGenerator) -> i32 {
     (world_generator.get_temperature(x, z) * 100.0).ceil() as i32
 }
 
+pub fn get_humidity(x: i32, z: i32, world_generator: &WorldGenerator) -> i32 {
+    (world_generator.get_humidity(x, z) * 100.0).ceil() as i32
+}
+
 pub fn get_mountain(x: i32, z: i32, world_generator: &WorldGenerator) -> i32 {
-    (world_generator.get_mountain(x, z) * 80.0) as i32
+    let scale = 80.0 * world_generator.settings.height_scale;
+    (world_generator.get_mountain(x, z) * scale) as i32
 }
 
 pub fn get_height_mountain(x: i32, z: i32, world_generator: &WorldGenerator) -> i32 {
-    let mountain_h = (world_generator.get_mountain(x, z) * 80.0) as i32;
+    let mountain_h = get_mountain(x, z, world_generator);
     get_height(x, z, world_generator).max(mountain_h)
 }
 
-pub fn generate_heightmap(
-    positions: &Vec<(i32, i32, i32)>,
-    world_generator: &WorldGenerator,
-    height_func: fn(i32, i32, &WorldGenerator) -> i32,
-) -> HeightMap {
-    let mut heightmap = HeightMap::new();
-
-    for (chunkx, _, chunkz) in positions {
-        if heightmap.contains_key(&(*chunkx, *chunkz)) {
-            continue;
-        }
-        let posx = chunkx * CHUNK_SIZE_I32;
-        let posz = chunkz * CHUNK_SIZE_I32;
-        let mut heights = vec![0; (CHUNK_SIZE_I32 * CHUNK_SIZE_I32) as usize];
-        for x in posx..(posx + CHUNK_SIZE_I32) {
-            for z in posz..(posz + CHUNK_SIZE_I32) {
-                let index = ((z - posz) * CHUNK_SIZE_I32 + (x - posx)) as usize;
-                let h = height_func(x, z, world_generator);
-                heights[index] = h;
-            }
-        }
-        heightmap.insert((*chunkx, *chunkz), heights);
-    }
-
-    heightmap
-}
-
 pub fn add_to_heightmap(
     chunkx: i32,
     chunkz: i32,
@@ -0,0 +1,476 @@
+/*
+ * Dimensions are separate worlds inside of the same save that share the
+ * seed, time and game mode of the save but have their own generator, chunk
+ * storage, sky and lighting.
+ *
+ * The overworld uses the generator chosen when the world was created and
+ * keeps its data in the root of the save so that older saves load unchanged,
+ * every other dimension keeps its chunks and entities in
+ * `dimensions/<name>/`.
+ *
+ * Portal blocks move the player between dimensions, a portal in the
+ * overworld leads to the dimension that it belongs to and a portal in that
+ * dimension leads back to the overworld. The portals on both sides are
+ * linked once the player has traveled through them so that traveling back
+ * arrives at the same portal.
+ * */
+
+use super::{
+    sky_realm::SkyRealmGenerator, terrain_generator::TerrainGenerator,
+    underworld::UnderworldGenerator, World,
+};
+use crate::{
+    impfile::{self, Entry},
+    save::save_atomic,
+    voxel::{
+        block_ids::{OBSIDIAN, SKY_PORTAL, UNDERWORLD_PORTAL},
+        Block, EMPTY_BLOCK, INDESTRUCTIBLE,
+    },
+};
+use std::io::Write;
+
+pub const DIMENSIONS_PATH: &str = "dimensions/";
+pub const PORTALS_FILE: &str = "portals.impfile";
+//Block used for the platform that is built under the player when there is
+//nowhere to stand near the portal that they arrive at
+const PLATFORM_BLOCK: u16 = OBSIDIAN;
+//How far away from the target position to look for somewhere to stand
+const ARRIVAL_SEARCH_RADIUS: i32 = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sky {
+    //The sun, moon, stars and clouds move with the time of day
+    DayNight,
+    //Sky that is always the same color and brightness, with no sun, moon,
+    //stars or clouds
+    Fixed {
+        color: (f32, f32, f32),
+        brightness: f32,
+    },
+}
+
+pub struct Dimension {
+    //Stored in the world metadata and used as the name of the directory
+    //that the dimension is saved in, this should never change
+    pub name: &'static str,
+    pub display_name: &'static str,
+    //None uses the generator that the world was created with
+    pub generator: Option<&'static dyn TerrainGenerator>,
+    pub sky: Sky,
+    //Dimensions without sky light are only lit by blocks
+    pub sky_light: bool,
+    //How many overworld blocks one block in this dimension is worth when
+    //traveling through a portal
+    pub scale: f32,
+    //Portal block that leads to this dimension from the overworld
+    pub portal: Option<u16>,
+    //Range of y values that portals can be placed at when arriving
+    pub arrival_range: (i32, i32),
+}
+
+pub static OVERWORLD: Dimension = Dimension {
+    name: "overworld",
+    display_name: "Overworld",
+    generator: None,
+    sky: Sky::DayNight,
+    sky_light: true,
+    scale: 1.0,
+    portal: None,
+    arrival_range: (-64, 192),
+};
+
+pub static UNDERWORLD: Dimension = Dimension {
+    name: "underworld",
+    display_name: "Underworld",
+    generator: Some(&UnderworldGenerator),
+    sky: Sky::Fixed {
+        color: (0.25, 0.04, 0.02),
+        brightness: 0.0,
+    },
+    sky_light: false,
+    scale: 8.0,
+    portal: Some(UNDERWORLD_PORTAL),
+    arrival_range: (8, 104),
+};
+
+pub static SKY_REALM: Dimension = Dimension {
+    name: "sky_realm",
+    display_name: "Sky Realm",
+    generator: Some(&SkyRealmGenerator),
+    sky: Sky::Fixed {
+        color: (0.55, 0.8, 1.0),
+        brightness: 1.0,
+    },
+    sky_light: true,
+    scale: 1.0,
+    portal: Some(SKY_PORTAL),
+    arrival_range: (40, 96),
+};
+
+static DIMENSIONS: [&Dimension; 3] = [&OVERWORLD, &UNDERWORLD, &SKY_REALM];
+
+//Returns all dimensions
+pub fn dimensions() -> &'static [&'static Dimension] {
+    &DIMENSIONS
+}
+
+pub fn get_dimension(name: &str) -> Option<&'static Dimension> {
+    DIMENSIONS
+        .iter()
+        .copied()
+        .find(|dimension| dimension.name == name)
+}
+
+//The overworld is the dimension that new worlds start in and that players
+//respawn in
+pub fn overworld() -> &'static Dimension {
+    &OVERWORLD
+}
+
+pub fn is_portal(id: u16) -> bool {
+    DIMENSIONS
+        .iter()
+        .any(|dimension| dimension.portal == Some(id))
+}
+
+//Returns the dimension that a portal leads to, portals lead from the
+//overworld to their own dimension and from their own dimension back to
+//the overworld, other portals go straight to their dimension
+pub fn portal_destination(from: &Dimension, portal_id: u16) -> Option<&'static Dimension> {
+    if from.portal == Some(portal_id) {
+        return Some(overworld());
+    }
+
+    DIMENSIONS
+        .iter()
+        .copied()
+        .find(|dimension| dimension.portal == Some(portal_id))
+}
+
+//Converts a block position in one dimension to the position that it
+//corresponds to in another dimension
+pub fn scale_position(
+    from: &Dimension,
+    to: &Dimension,
+    (x, y, z): (i32, i32, i32),
+) -> (i32, i32, i32) {
+    let scale = from.scale / to.scale;
+    let x = (x as f32 * scale).floor() as i32;
+    let z = (z as f32 * scale).floor() as i32;
+    let (miny, maxy) = to.arrival_range;
+    (x, y.clamp(miny, maxy), z)
+}
+
+impl Dimension {
+    pub fn is_overworld(&self) -> bool {
+        self.name == OVERWORLD.name
+    }
+
+    //Returns the directory that chunks and entities of this dimension are
+    //saved in
+    pub fn data_path(&self, world_path: &str) -> String {
+        if self.is_overworld() {
+            world_path.to_string()
+        } else {
+            format!("{world_path}{DIMENSIONS_PATH}{}/", self.name)
+        }
+    }
+}
+
+//One side of a pair of linked portals
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PortalEnd {
+    pub dimension: &'static str,
+    pub pos: (i32, i32, i32),
+}
+
+impl PortalEnd {
+    pub fn new(dimension: &Dimension, pos: (i32, i32, i32)) -> Self {
+        Self {
+            dimension: dimension.name,
+            pos,
+        }
+    }
+
+    fn to_entry(self, entry: &mut Entry, prefix: &str) {
+        let (x, y, z) = self.pos;
+        entry.add_string(prefix, self.dimension);
+        entry.add_integer(&format!("{prefix}_x"), x as i64);
+        entry.add_integer(&format!("{prefix}_y"), y as i64);
+        entry.add_integer(&format!("{prefix}_z"), z as i64);
+    }
+
+    fn from_entry(entry: &Entry, prefix: &str) -> Option<Self> {
+        let dimension = get_dimension(entry.try_get_var(prefix)?)?;
+        let coord = |axis: &str| {
+            entry
+                .try_get_var(&format!("{prefix}_{axis}"))?
+                .parse::<i32>()
+                .ok()
+        };
+        Some(Self::new(
+            dimension,
+            (coord("x")?, coord("y")?, coord("z")?),
+        ))
+    }
+}
+
+//Pairs of portals that have been traveled through, saved in the root of the
+//world so that every dimension can look them up
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PortalLinks {
+    links: Vec<(PortalEnd, PortalEnd)>,
+}
+
+impl PortalLinks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //Returns the portal that `end` is linked to
+    pub fn get(&self, end: PortalEnd) -> Option<PortalEnd> {
+        self.links.iter().find_map(|&(a, b)| {
+            if a == end {
+                Some(b)
+            } else if b == end {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+
+    //Links two portals, replacing any links that either of them had before
+    pub fn link(&mut self, a: PortalEnd, b: PortalEnd) {
+        self.links
+            .retain(|&(c, d)| c != a && c != b && d != a && d != b);
+        self.links.push((a, b));
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    pub fn load(world_path: &str) -> Self {
+        let path = world_path.to_string() + PORTALS_FILE;
+        let mut links = Self::new();
+        for entry in impfile::parse_file(&path) {
+            let from = PortalEnd::from_entry(&entry, "from");
+            let to = PortalEnd::from_entry(&entry, "to");
+            match (from, to) {
+                (Some(from), Some(to)) => links.link(from, to),
+                _ => eprintln!("E: Invalid portal link in {path}"),
+            }
+        }
+        links
+    }
+
+    pub fn save(&self, world_path: &str) {
+        if self.links.is_empty() {
+            return;
+        }
+
+        let path = world_path.to_string() + PORTALS_FILE;
+        let mut contents = String::new();
+        for (from, to) in &self.links {
+            let mut entry = Entry::new("link");
+            from.to_entry(&mut entry, "from");
+            to.to_entry(&mut entry, "to");
+            contents += &entry.to_impfile_string();
+            contents += "\n";
+        }
+        let res = save_atomic(&path, |file| {
+            impfile::write_comment(file, "Linked portals");
+            file.write_all(contents.as_bytes())
+        });
+
+        if let Err(msg) = res {
+            eprintln!("E: Failed to save portal links: {msg}");
+        }
+    }
+}
+
+fn is_solid(block: Block) -> bool {
+    block.id != EMPTY_BLOCK && !block.no_hitbox() && !block.is_fluid()
+}
+
+fn is_open(block: Block) -> bool {
+    block.id == EMPTY_BLOCK || (block.no_hitbox() && !block.is_fluid())
+}
+
+impl World {
+    //Returns true if a player can stand with their feet at (x, y, z)
+    pub fn can_stand_at(&self, x: i32, y: i32, z: i32) -> bool {
+        if self.out_of_bounds(x, y - 1, z) || self.out_of_bounds(x, y + 1, z) {
+            return false;
+        }
+        is_solid(self.get_block(x, y - 1, z))
+            && is_open(self.get_block(x, y, z))
+            && is_open(self.get_block(x, y + 1, z))
+    }
+
+    //Returns the closest position to `pos` that a player can stand at, only
+    //looks at positions within the arrival range of the dimension
+    pub fn find_standing_spot(&self, pos: (i32, i32, i32), radius: i32) -> Option<(i32, i32, i32)> {
+        let (x, y, z) = pos;
+        let (miny, maxy) = self.dimension.arrival_range;
+        let mut closest: Option<((i32, i32, i32), i32)> = None;
+        for ty in (y - radius).max(miny)..=(y + radius).min(maxy) {
+            for tz in (z - radius)..=(z + radius) {
+                for tx in (x - radius)..=(x + radius) {
+                    if !self.can_stand_at(tx, ty, tz) {
+                        continue;
+                    }
+                    let (dx, dy, dz) = (tx - x, ty - y, tz - z);
+                    let dist = dx * dx + dy * dy + dz * dz;
+                    if closest.is_none_or(|(_, d)| dist < d) {
+                        closest = Some(((tx, ty, tz), dist));
+                    }
+                }
+            }
+        }
+        closest.map(|(spot, _)| spot)
+    }
+
+    //Builds a small platform with a portal on it so that the player has
+    //somewhere to arrive at, returns the position of the portal
+    pub fn build_portal_platform(
+        &mut self,
+        pos: (i32, i32, i32),
+        portal_id: u16,
+    ) -> (i32, i32, i32) {
+        let (x, y, z) = pos;
+        for tz in (z - 2)..=(z + 2) {
+            for tx in (x - 2)..=(x + 2) {
+                let below = self.get_block(tx, y - 1, tz);
+                if below.id != INDESTRUCTIBLE {
+                    self.set_block(tx, y - 1, tz, Block::new_id(PLATFORM_BLOCK));
+                }
+                for ty in y..(y + 3) {
+                    if self.get_block(tx, ty, tz).id != INDESTRUCTIBLE {
+                        self.set_block(tx, ty, tz, Block::new());
+                    }
+                }
+            }
+        }
+        self.set_block(x + 2, y, z, Block::new_id(portal_id));
+        (x + 2, y, z)
+    }
+
+    //Returns the position of the portal that the player arrives at when
+    //traveling to `target` in this dimension, a new portal is built if
+    //`linked` is not a portal anymore
+    pub fn arrival_portal(
+        &mut self,
+        linked: Option<(i32, i32, i32)>,
+        target: (i32, i32, i32),
+        portal_id: u16,
+    ) -> (i32, i32, i32) {
+        if let Some((x, y, z)) = linked {
+            if is_portal(self.get_block(x, y, z).id) {
+                return (x, y, z);
+            }
+        }
+
+        let spot = self
+            .find_standing_spot(target, ARRIVAL_SEARCH_RADIUS)
+            .unwrap_or(target);
+        self.build_portal_platform(spot, portal_id)
+    }
+
+    //Returns where the player should stand after arriving at a portal
+    pub fn portal_exit(&self, portal: (i32, i32, i32)) -> (i32, i32, i32) {
+        let (x, y, z) = portal;
+        self.find_standing_spot(portal, 2)
+            .filter(|&spot| spot != (x, y + 1, z))
+            .unwrap_or((x, y + 1, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::world::GameMode;
+
+    #[test]
+    fn test_dimensions() {
+        let mut names = Vec::new();
+        for dimension in dimensions() {
+            assert!(!names.contains(&dimension.name));
+            names.push(dimension.name);
+            assert_eq!(get_dimension(dimension.name).unwrap().name, dimension.name);
+        }
+        assert!(get_dimension("not a dimension").is_none());
+        assert_eq!(overworld().data_path("saves/world/"), "saves/world/");
+        assert_eq!(
+            UNDERWORLD.data_path("saves/world/"),
+            "saves/world/dimensions/underworld/"
+        );
+        assert!(!UNDERWORLD.sky_light);
+    }
+
+    #[test]
+    fn test_portal_destination() {
+        let dest = portal_destination(&OVERWORLD, UNDERWORLD_PORTAL).unwrap();
+        assert_eq!(dest.name, UNDERWORLD.name);
+        let dest = portal_destination(&UNDERWORLD, UNDERWORLD_PORTAL).unwrap();
+        assert!(dest.is_overworld());
+        let dest = portal_destination(&UNDERWORLD, SKY_PORTAL).unwrap();
+        assert_eq!(dest.name, SKY_REALM.name);
+        assert!(portal_destination(&OVERWORLD, 1).is_none());
+
+        //The underworld is 8 times smaller than the overworld
+        assert_eq!(
+            scale_position(&OVERWORLD, &UNDERWORLD, (80, 300, -17)),
+            (10, 104, -3)
+        );
+        assert_eq!(
+            scale_position(&UNDERWORLD, &OVERWORLD, (10, 50, -3)),
+            (80, 50, -24)
+        );
+    }
+
+    #[test]
+    fn test_portal_links() {
+        let a = PortalEnd::new(&OVERWORLD, (1, 2, 3));
+        let b = PortalEnd::new(&UNDERWORLD, (0, 40, 0));
+        let c = PortalEnd::new(&UNDERWORLD, (5, 40, 5));
+        let mut links = PortalLinks::new();
+        links.link(a, b);
+        assert_eq!(links.get(a), Some(b));
+        assert_eq!(links.get(b), Some(a));
+        //Relinking a portal removes its old link
+        links.link(a, c);
+        assert_eq!(links.get(a), Some(c));
+        assert_eq!(links.get(b), None);
+        assert_eq!(links.len(), 1);
+
+        let dir = std::env::temp_dir().join(format!("voxelworld_portals_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_string_lossy().to_string() + "/";
+        links.save(&path);
+        assert_eq!(PortalLinks::load(&path), links);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_arrival_portal() {
+        let mut world = World::new(3, 2, &UnderworldGenerator, GameMode::Survival);
+        world.set_dimension(&UNDERWORLD);
+        world.generate_world();
+        //A portal should be placed somewhere that the player can stand next to
+        let portal = world.arrival_portal(None, (0, 20, 0), UNDERWORLD_PORTAL);
+        assert!(is_portal(world.get_block(portal.0, portal.1, portal.2).id));
+        let (x, y, z) = world.portal_exit(portal);
+        assert!(world.can_stand_at(x, y, z));
+        //Arriving again at a linked portal should use that portal
+        assert_eq!(
+            world.arrival_portal(Some(portal), (16, 20, 16), UNDERWORLD_PORTAL),
+            portal
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use super::{
+    gen_settings::GenSettings,
+    terrain_generator::{ColumnGenerator, Surface, TerrainGenerator},
+    WorldGenerator,
+};
+use crate::voxel::{Block, Chunk, CHUNK_SIZE_I32};
+
+//The layers come from the generation settings of the world
+fn gen_flat_chunk(chunk: &mut Chunk, settings: &GenSettings) {
+    let chunkpos = chunk.get_chunk_pos();
+    let posx = chunkpos.x * CHUNK_SIZE_I32;
+    let posy = chunkpos.y * CHUNK_SIZE_I32;
+    let posz = chunkpos.z * CHUNK_SIZE_I32;
+
+    let top = settings.flat_surface;
+    let bottom = top - settings.flat_thickness();
+    if posy > top || posy + CHUNK_SIZE_I32 <= bottom {
+        return;
+    }
+
+    for y in posy..(posy + CHUNK_SIZE_I32) {
+        let Some(id) = settings.flat_layer_at(y) else {
+            continue;
+        };
+        for x in posx..(posx + CHUNK_SIZE_I32) {
+            for z in posz..(posz + CHUNK_SIZE_I32) {
+                chunk.set_block(x, y, z, Block::new_id(id));
+            }
+        }
+    }
+}
+
+struct FlatColumn<'a> {
+    settings: &'a GenSettings,
+}
+
+impl ColumnGenerator for FlatColumn<'_> {
+    fn gen_chunk(&self, chunk: &mut Chunk) {
+        gen_flat_chunk(chunk, self.settings);
+    }
+}
+
+pub struct FlatGenerator;
+
+impl TerrainGenerator for FlatGenerator {
+    fn name(&self) -> &'static str {
+        "flat"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Flat"
+    }
+
+    fn column<'a>(
+        &self,
+        _x: i32,
+        _z: i32,
+        world_generator: &'a WorldGenerator,
+    ) -> Box<dyn ColumnGenerator + 'a> {
+        Box::new(FlatColumn {
+            settings: &world_generator.settings,
+        })
+    }
+
+    fn surface(&self, _x: i32, _z: i32, world_generator: &WorldGenerator) -> Option<Surface> {
+        let settings = &world_generator.settings;
+        let id = settings.flat_layer_at(settings.flat_surface)?;
+        Some(Surface {
+            height: settings.flat_surface,
+            block: Block::new_id(id),
+            cover: None,
+        })
+    }
+}
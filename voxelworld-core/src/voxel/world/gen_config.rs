@@ -0,0 +1,204 @@
+/*
+ * World generation config
+ *
+ * Tunables for world generation that are loaded from WORLDGEN_CONFIG_PATH
+ * the first time they are used, every value that is missing from the file
+ * uses the default from this file. See that file for what each value does.
+ * */
+
+use crate::impfile::{
+    from_entry::print_diagnostics, parse_file_diagnostics, Diagnostic, EntryReader, FromEntry,
+    FromValue,
+};
+use std::sync::OnceLock;
+
+#[cfg(not(test))]
+pub const WORLDGEN_CONFIG_PATH: &str = "assets/worldgen.impfile";
+//Tests are run from the voxelworld-core directory
+#[cfg(test)]
+pub const WORLDGEN_CONFIG_PATH: &str = "../assets/worldgen.impfile";
+
+const CAVES_ENTRY: &str = "caves";
+
+//Reads "min,max", reports an error and returns `default` if it is invalid
+fn get_range<T: FromValue + PartialOrd + Copy>(
+    reader: &mut EntryReader,
+    var_name: &str,
+    default: (T, T),
+) -> (T, T) {
+    let Some(range) = reader.get::<Vec<T>>(var_name) else {
+        return default;
+    };
+    match range[..] {
+        [min, max] if min <= max => (min, max),
+        _ => {
+            let msg = format!("\"{var_name}\" should be \"min,max\" with min <= max");
+            reader.error(var_name, &msg);
+            default
+        }
+    }
+}
+
+//Reads a number between 0.0 and 1.0
+fn get_chance(reader: &mut EntryReader, var_name: &str, default: f32) -> f32 {
+    let chance = reader.get_or(var_name, default);
+    if !(0.0..=1.0).contains(&chance) {
+        reader.error(var_name, &format!("\"{var_name}\" should be 0.0 - 1.0"));
+        return default;
+    }
+    chance
+}
+
+//Caves that are carved out of the terrain by tunnels and ravines that wander
+//through it, see `default_world/carvers.rs`
+#[derive(Clone, Debug)]
+pub struct CaveConfig {
+    //Also generate the blobby 3D noise caves
+    pub noise_caves: bool,
+    //Chance that a chunk starts a cave system
+    pub tunnel_chance: f32,
+    //Number of tunnels in a cave system
+    pub tunnels: (u32, u32),
+    //Length of a tunnel in blocks
+    pub tunnel_length: (u32, u32),
+    pub tunnel_radius: (f32, f32),
+    //y coordinate that tunnels start at
+    pub tunnel_height: (i32, i32),
+    //Chance that a tunnel splits into two smaller tunnels
+    pub branch_chance: f32,
+    //Chance that a chunk starts a ravine
+    pub ravine_chance: f32,
+    pub ravine_length: (u32, u32),
+    pub ravine_radius: (f32, f32),
+    //Ravines are this many times taller than they are wide
+    pub ravine_depth: f32,
+    //Chance that a cave system is flooded with water up to a random level
+    //below sea level
+    pub aquifer_chance: f32,
+}
+
+impl Default for CaveConfig {
+    fn default() -> Self {
+        Self {
+            noise_caves: true,
+            tunnel_chance: 0.14,
+            tunnels: (1, 5),
+            tunnel_length: (40, 110),
+            tunnel_radius: (1.5, 3.5),
+            tunnel_height: (-56, 32),
+            branch_chance: 0.25,
+            ravine_chance: 0.015,
+            ravine_length: (50, 100),
+            ravine_radius: (2.0, 3.5),
+            ravine_depth: 3.0,
+            aquifer_chance: 0.25,
+        }
+    }
+}
+
+impl FromEntry for CaveConfig {
+    type Context = ();
+
+    fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+        let default = Self::default();
+        let ravine_depth = reader.get_or("ravine_depth", default.ravine_depth);
+        if ravine_depth < 1.0 {
+            reader.error("ravine_depth", "\"ravine_depth\" should be at least 1.0");
+        }
+
+        Some(Self {
+            noise_caves: reader.get_or("noise_caves", default.noise_caves),
+            tunnel_chance: get_chance(reader, "tunnel_chance", default.tunnel_chance),
+            tunnels: get_range(reader, "tunnels", default.tunnels),
+            tunnel_length: get_range(reader, "tunnel_length", default.tunnel_length),
+            tunnel_radius: get_range(reader, "tunnel_radius", default.tunnel_radius),
+            tunnel_height: get_range(reader, "tunnel_height", default.tunnel_height),
+            branch_chance: get_chance(reader, "branch_chance", default.branch_chance),
+            ravine_chance: get_chance(reader, "ravine_chance", default.ravine_chance),
+            ravine_length: get_range(reader, "ravine_length", default.ravine_length),
+            ravine_radius: get_range(reader, "ravine_radius", default.ravine_radius),
+            ravine_depth: ravine_depth.max(1.0),
+            aquifer_chance: get_chance(reader, "aquifer_chance", default.aquifer_chance),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WorldGenConfig {
+    pub caves: CaveConfig,
+}
+
+//Reads the world generation config, returns any problems found in the file
+//along with the config
+pub fn read_worldgen_config(path: &str) -> (WorldGenConfig, Vec<Diagnostic>) {
+    let (entries, mut diagnostics) = parse_file_diagnostics(path);
+    let mut config = WorldGenConfig::default();
+    for entry in &entries {
+        let mut reader = EntryReader::new(entry, path);
+        match entry.get_name().as_str() {
+            CAVES_ENTRY => {
+                if let Some(caves) = CaveConfig::from_entry(&mut reader, &()) {
+                    config.caves = caves;
+                }
+            }
+            name => {
+                let (line, col) = entry.pos();
+                let msg = format!("unknown entry \"{name}\"");
+                diagnostics.push(Diagnostic::warning(path, line, col, &msg));
+                continue;
+            }
+        }
+        diagnostics.extend(reader.finish());
+    }
+    (config, diagnostics)
+}
+
+static WORLDGEN_CONFIG: OnceLock<WorldGenConfig> = OnceLock::new();
+
+//Returns the world generation config, it is loaded the first time this is
+//called
+pub fn worldgen_config() -> &'static WorldGenConfig {
+    WORLDGEN_CONFIG.get_or_init(|| {
+        let (config, diagnostics) = read_worldgen_config(WORLDGEN_CONFIG_PATH);
+        print_diagnostics(&diagnostics);
+        config
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impfile::parse_str;
+
+    fn read_caves(s: &str) -> (CaveConfig, Vec<Diagnostic>) {
+        let (entries, _) = parse_str(s, "test");
+        let mut reader = EntryReader::new(&entries[0], "test");
+        let caves = CaveConfig::from_entry(&mut reader, &()).unwrap();
+        (caves, reader.finish())
+    }
+
+    #[test]
+    fn test_read_cave_config() {
+        let (caves, diagnostics) = read_caves(
+            "\"caves\" {\n\t\"tunnels\" = \"2,3\";\n\t\"noise_caves\" = \"false\";\n}\n",
+        );
+        assert!(diagnostics.is_empty());
+        assert_eq!(caves.tunnels, (2, 3));
+        assert!(!caves.noise_caves);
+        //Missing values use the default
+        assert_eq!(caves.tunnel_length, CaveConfig::default().tunnel_length);
+
+        let (caves, diagnostics) = read_caves(
+            "\"caves\" {\n\t\"tunnels\" = \"3,2\";\n\t\"tunnel_chance\" = \"2.0\";\n\t\"ravine_length\" = \"10\";\n}\n",
+        );
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(caves.tunnels, CaveConfig::default().tunnels);
+        assert_eq!(caves.tunnel_chance, CaveConfig::default().tunnel_chance);
+    }
+
+    #[test]
+    fn test_load_worldgen_config() {
+        let (_, diagnostics) = read_worldgen_config(WORLDGEN_CONFIG_PATH);
+        assert!(diagnostics.is_empty());
+    }
+}
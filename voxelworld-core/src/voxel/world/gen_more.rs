@@ -1,16 +1,14 @@
 use cgmath::Vector3;
 
-use super::{World, WorldGenType};
-use crate::{
-    gfx::{ChunkTables, ChunkVaoTable},
-    voxel::{
-        region::{chunkpos_to_regionpos, Region},
-        Chunk, CHUNK_SIZE_F32,
-    },
+use super::{block_update::schedule_chunk, World};
+use crate::voxel::{
+    region::{chunkpos_to_regionpos, Region},
+    Chunk, CHUNK_SIZE_F32,
 };
+use crossbeam::{queue::ArrayQueue, thread};
 use std::collections::{HashMap, HashSet, VecDeque};
 
-type ChunkPosSet = HashSet<(i32, i32, i32)>;
+pub type ChunkPosSet = HashSet<(i32, i32, i32)>;
 
 //Returns a hashset of chunks in range of x, y, z and a hashset of chunks out
 //of range in the form (in_range, out_of_range)
@@ -59,69 +57,6 @@ pub fn get_chunks_to_generate(
     to_generate
 }
 
-//Marks chunks that need to be deleted from the chunk vao table and those that
-//need to be updated and those that need to be added
-pub fn update_chunk_vao_table(
-    chunktable: &mut ChunkVaoTable,
-    centerx: i32,
-    centery: i32,
-    centerz: i32,
-    range: i32,
-    chunks: &HashMap<(i32, i32, i32), Chunk>,
-    to_generate: &ChunkPosSet,
-) {
-    //Delete chunks that are out of range
-    chunktable.delete_chunks(centerx, centery, centerz, range);
-
-    let mut to_update = HashSet::<(i32, i32, i32)>::new();
-    //Mark chunks that need to have a vao generated
-    for (chunkx, chunky, chunkz) in to_generate {
-        to_update.insert((*chunkx, *chunky, *chunkz));
-    }
-
-    //Mark any chunks adjacent to the new border chunks that need to be regenerated
-    for (chunkx, chunky, chunkz) in to_generate {
-        let (x, y, z) = (*chunkx, *chunky, *chunkz);
-
-        for dx in -1..=1 {
-            for dy in -1..=1 {
-                for dz in -1..=1 {
-                    if dx == 0 && dy == 0 && dz == 0 {
-                        continue;
-                    }
-
-                    if chunks.contains_key(&(x + dx, y + dy, z + dz)) {
-                        to_update.insert((x + dx, y + dy, z + dz));
-                    }
-                }
-            }
-        }
-    }
-
-    for (x, y, z) in to_update {
-        chunktable.add_to_update(x, y, z);
-    }
-}
-
-pub fn update_chunk_tables(
-    chunktables: &mut ChunkTables,
-    x: i32,
-    y: i32,
-    z: i32,
-    range: i32,
-    chunks: &HashMap<(i32, i32, i32), Chunk>,
-    to_generate: &ChunkPosSet,
-) {
-    let chunk_vaos = &mut chunktables.chunk_vaos;
-    update_chunk_vao_table(chunk_vaos, x, y, z, range, chunks, to_generate);
-    let lava_vaos = &mut chunktables.lava_vaos;
-    update_chunk_vao_table(lava_vaos, x, y, z, range, chunks, to_generate);
-    let water_vaos = &mut chunktables.water_vaos;
-    update_chunk_vao_table(water_vaos, x, y, z, range, chunks, to_generate);
-    let nonvoxel_vaos = &mut chunktables.non_voxel_vaos;
-    update_chunk_vao_table(nonvoxel_vaos, x, y, z, range, chunks, to_generate);
-}
-
 pub type Column = (i32, i32, HashSet<i32>);
 
 pub struct LoadChunkQueue {
@@ -213,6 +148,111 @@ impl LoadChunkQueue {
 }
 
 impl World {
+    //Generates the chunks at the positions in parallel and adds them to the
+    //world, returns the number of chunks generated
+    fn generate_chunks(&mut self, positions: &[(i32, i32, i32)]) -> usize {
+        if positions.is_empty() {
+            return 0;
+        }
+
+        //Generate the info shared by the chunks in each column, columns
+        //outside of the border are left empty
+        let generator = self.generator();
+        let mut columns = HashMap::new();
+        for (x, _, z) in positions.iter().copied() {
+            if !self.column_in_border(x, z) {
+                continue;
+            }
+            columns
+                .entry((x, z))
+                .or_insert_with(|| generator.column(x, z, &self.world_generator));
+        }
+
+        let generated = ArrayQueue::new(positions.len());
+        thread::scope(|s| {
+            for (x, y, z) in positions.iter().copied() {
+                let column = columns.get(&(x, z));
+                let generated = &generated;
+                s.spawn(move |_| {
+                    let mut new_chunk = Chunk::new(x, y, z);
+                    if let Some(column) = column {
+                        column.gen_chunk(&mut new_chunk);
+                    }
+                    //This should never fail
+                    generated
+                        .push(new_chunk)
+                        .expect("Error: Failed to push onto ArrayQueue");
+                });
+            }
+        })
+        .expect("Failed to generate new chunks!");
+
+        let generated_count = generated.len();
+        for mut chunk in generated {
+            schedule_chunk(&mut chunk, self.ticks + 1);
+            let chunkpos = chunk.get_chunk_pos();
+            let pos = (chunkpos.x, chunkpos.y, chunkpos.z);
+            self.chunks.insert(pos, chunk);
+        }
+        generated_count
+    }
+
+    //Generates the missing chunks in the column (x, z) that are in range
+    pub fn generate_column(&mut self, x: i32, z: i32, yvals: &HashSet<i32>) {
+        let start = std::time::Instant::now();
+        let positions: Vec<(i32, i32, i32)> = yvals
+            .iter()
+            .map(|y| (x, *y, z))
+            .filter(|(x, y, z)| self.in_range(*x, *y, *z))
+            .filter(|pos| !self.chunks.contains_key(pos))
+            .collect();
+        let generated_count = self.generate_chunks(&positions);
+        let time = start.elapsed().as_millis();
+        if time > 15 {
+            //Only report time taken if it exceeds 15 ms
+            eprintln!("Took {time} ms to generate {generated_count} new chunks");
+        }
+    }
+
+    //Generates any missing chunks on load
+    pub fn gen_on_load(&mut self) {
+        let mut to_generate = vec![];
+        for y in (self.centery - self.range)..=(self.centery + self.range) {
+            for z in (self.centerz - self.range)..=(self.centerz + self.range) {
+                for x in (self.centerx - self.range)..=(self.centerx + self.range) {
+                    if self.chunks.contains_key(&(x, y, z)) {
+                        continue;
+                    }
+                    to_generate.push((x, y, z));
+                }
+            }
+        }
+
+        if to_generate.is_empty() {
+            return;
+        }
+
+        let start = std::time::Instant::now();
+        let generated_count = self.generate_chunks(&to_generate);
+        eprintln!(
+            "Took {} ms to generate {generated_count} new chunks",
+            start.elapsed().as_millis()
+        );
+    }
+
+    //Generates a single chunk and adds it to the world
+    pub fn add_chunk(&mut self, chunkx: i32, chunky: i32, chunkz: i32) {
+        let mut chunk = Chunk::new(chunkx, chunky, chunkz);
+        if self.column_in_border(chunkx, chunkz) {
+            let column = self
+                .generator()
+                .column(chunkx, chunkz, &self.world_generator);
+            column.gen_chunk(&mut chunk);
+        }
+        schedule_chunk(&mut chunk, self.ticks + 1);
+        self.chunks.insert((chunkx, chunky, chunkz), chunk);
+    }
+
     pub fn delete_out_of_range(&mut self, out_of_range: &ChunkPosSet) {
         //Delete old chunks
         for to_delete in out_of_range {
@@ -272,18 +312,15 @@ impl World {
             }
             loaded.insert((rx, ry, rz));
 
-            if let Some(region) = Region::load_region(&self.path, rx, ry, rz) {
+            if let Some(region) =
+                Region::load_region(&self.data_path(), rx, ry, rz, Some(self.region_codec), true)
+            {
                 self.add_region_col(region, x, z);
             }
         }
 
         //Generate the new chunks
-        match self.gen_type {
-            WorldGenType::Flat => self.generate_column_flat(x, z, &yvals),
-            WorldGenType::OldGen => self.generate_column_old(x, z, &yvals),
-            WorldGenType::DefaultGen => self.generate_column_default(x, z, &yvals),
-            WorldGenType::Skyblock => self.generate_column_skyblock(x, z, &yvals),
-        }
+        self.generate_column(x, z, &yvals);
 
         for y in yvals.iter().copied() {
             update_list.insert((x, y, z));
@@ -335,19 +372,14 @@ impl World {
         loaded
     }
 
-    pub fn update_chunktables(&mut self, chunktables: &mut ChunkTables) {
-        if self.to_load.is_empty() && !self.chunktable_update_list.is_empty() {
-            update_chunk_tables(
-                chunktables,
-                self.centerx,
-                self.centery,
-                self.centerz,
-                self.range,
-                &self.chunks,
-                &self.chunktable_update_list,
-            );
-            self.chunktable_update_list.clear();
+    //Returns the chunks that were generated or loaded and need to be added to
+    //the chunk vao tables, this is empty until all of the chunks in the load
+    //queue are loaded
+    pub fn take_chunktable_updates(&mut self) -> ChunkPosSet {
+        if !self.to_load.is_empty() {
+            return ChunkPosSet::new();
         }
+        std::mem::take(&mut self.chunktable_update_list)
     }
 
     //Returns if chunk coordinates are within range
@@ -385,3 +417,62 @@ impl World {
         self.to_load.add_from_set(&to_generate);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{
+        world::{terrain_generator::terrain_generators, GameMode, TerrainGenerator},
+        Block,
+    };
+
+    type EncodedChunks = HashMap<(i32, i32, i32), Vec<(u16, Block)>>;
+
+    //Generates the chunks in `batches`, each batch is generated in parallel
+    fn generate_area(
+        generator: &'static dyn TerrainGenerator,
+        batches: &[Vec<(i32, i32, i32)>],
+    ) -> EncodedChunks {
+        let mut world = World::new(1234, 0, generator, GameMode::Creative);
+        world.chunks.clear();
+        for batch in batches {
+            world.generate_chunks(batch);
+        }
+        world
+            .chunks
+            .iter()
+            .map(|(pos, chunk)| (*pos, chunk.rle_encode()))
+            .collect()
+    }
+
+    #[test]
+    fn test_generation_order() {
+        //The same area generated in a different order and on a different
+        //number of threads at once should produce identical chunks
+        let mut positions = vec![];
+        for y in -4..=2 {
+            for z in -2..=1 {
+                for x in -2..=1 {
+                    positions.push((x, y, z));
+                }
+            }
+        }
+        let mut shuffled = positions.clone();
+        fastrand::Rng::with_seed(5).shuffle(&mut shuffled);
+        let batches: Vec<Vec<(i32, i32, i32)>> =
+            shuffled.chunks(3).map(|batch| batch.to_vec()).collect();
+
+        for generator in terrain_generators() {
+            let area = generate_area(*generator, &[positions.clone()]);
+            let shuffled_area = generate_area(*generator, &batches);
+            assert_eq!(area.len(), positions.len());
+            for (pos, encoded) in &area {
+                assert!(
+                    *encoded == shuffled_area[pos],
+                    "{} chunk {pos:?} does not match",
+                    generator.name()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+/*
+ * Position seeded random number generators for world generation
+ *
+ * Chunks are generated on many threads at once and in whatever order the
+ * player happens to load them, so every random number used to generate a
+ * chunk has to come from a generator that only depends on the world seed,
+ * the position that it is used at and what it is used for (its stream).
+ * Each part of generation gets its own stream so that changing how many
+ * numbers one part uses does not change the output of any other part.
+ *
+ * The old generator keeps its original seeds so that older worlds continue
+ * to generate the same terrain.
+ * */
+
+use super::WorldGenerator;
+
+//The values of the streams are part of the seed, changing them changes the
+//terrain of every world
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RngStream {
+    Bedrock = 1,
+    Ore = 2,
+    Clay = 3,
+    Plants = 4,
+    PlantPositions = 5,
+    Sugarcane = 6,
+    Trees = 7,
+    Structures = 8,
+    Chests = 9,
+    Caves = 10,
+}
+
+//Finalizer from splitmix64, every bit of the input affects every bit of the
+//output so that nearby positions get unrelated seeds
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+pub fn position_seed(world_seed: u32, stream: RngStream, (x, y, z): (i32, i32, i32)) -> u64 {
+    let mut seed = mix(((stream as u64) << 32) | world_seed as u64);
+    for v in [x, y, z] {
+        seed = mix(seed.wrapping_add(v as u32 as u64));
+    }
+    seed
+}
+
+impl WorldGenerator {
+    //Returns a generator for a single position (block, chunk, etc.)
+    pub fn position_rng(&self, stream: RngStream, pos: (i32, i32, i32)) -> fastrand::Rng {
+        fastrand::Rng::with_seed(position_seed(self.world_seed, stream, pos))
+    }
+
+    //Returns a generator for a column, every chunk in the column gets the
+    //same numbers so anything that can end up in any chunk of the column
+    //(plants, trees, etc.) is the same no matter which chunk places it
+    pub fn column_rng(&self, stream: RngStream, x: i32, z: i32) -> fastrand::Rng {
+        self.position_rng(stream, (x, 0, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_position_seeds() {
+        //Every position and stream should get its own seed, including
+        //negative positions that only differ in one coordinate
+        let mut seeds = HashSet::new();
+        for x in -8..8 {
+            for y in -8..8 {
+                for z in -8..8 {
+                    assert!(seeds.insert(position_seed(7, RngStream::Ore, (x, y, z))));
+                }
+            }
+        }
+        assert!(seeds.insert(position_seed(7, RngStream::Clay, (0, 0, 0))));
+        assert!(seeds.insert(position_seed(8, RngStream::Ore, (0, 0, 0))));
+        assert_eq!(
+            position_seed(7, RngStream::Ore, (-3, 2, -5)),
+            position_seed(7, RngStream::Ore, (-3, 2, -5))
+        );
+    }
+}
@@ -0,0 +1,355 @@
+/*
+ * World generation settings
+ *
+ * Unlike the config in `gen_config.rs`, these settings belong to a single
+ * world: they are picked in the create world menu (usually from one of the
+ * presets in WORLDGEN_PRESETS_PATH) and saved in the world metadata so that
+ * the world keeps generating the same terrain after it is reloaded. Worlds
+ * from before the settings were added use the defaults.
+ * */
+
+use super::terrain_generator::get_generator;
+use crate::{
+    impfile::{
+        from_entry::{load_file, print_diagnostics},
+        Diagnostic, Entry, EntryReader, FromEntry,
+    },
+    voxel::block_registry::block_registry,
+};
+use std::sync::OnceLock;
+
+#[cfg(not(test))]
+pub const WORLDGEN_PRESETS_PATH: &str = "assets/worldgen_presets.impfile";
+//Tests are run from the voxelworld-core directory
+#[cfg(test)]
+pub const WORLDGEN_PRESETS_PATH: &str = "../assets/worldgen_presets.impfile";
+
+//Name of the entry in world.impfile that the settings are saved in
+pub const GEN_SETTINGS_ENTRY: &str = "gen_settings";
+
+pub const MAX_OCTAVES: usize = 8;
+pub const HEIGHT_SCALE_RANGE: std::ops::RangeInclusive<f64> = 0.25..=4.0;
+pub const ORE_FREQUENCY_RANGE: std::ops::RangeInclusive<f64> = 0.0..=8.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenSettings {
+    //Water fills everything below this level that is not terrain
+    pub sea_level: i32,
+    //Caves at or below this level are filled with lava
+    pub lava_level: i32,
+    //Lowest y coordinate of the terrain, it is covered in bedrock
+    pub bottom: i32,
+    //Noise used for the overall shape of the terrain
+    pub octaves: usize,
+    pub persistence: f64,
+    //Multiplies the height of the hills and mountains
+    pub height_scale: f64,
+    //Multiplies how often ore veins appear
+    pub ore_frequency: f64,
+    //y coordinate of the top layer of a flat world
+    pub flat_surface: i32,
+    //Layers of a flat world from the top down, (block id, thickness)
+    pub flat_layers: Vec<(u16, u32)>,
+}
+
+impl Default for GenSettings {
+    fn default() -> Self {
+        Self {
+            sea_level: 0,
+            lava_level: -50,
+            bottom: -64,
+            octaves: 5,
+            persistence: 0.5,
+            height_scale: 1.0,
+            ore_frequency: 1.0,
+            flat_surface: -1,
+            flat_layers: vec![(1, 1), (4, 3), (2, 58), (3, 1)],
+        }
+    }
+}
+
+//Layers are "block/thickness|block/thickness|...", an empty string has no
+//layers at all
+pub fn parse_flat_layers(s: &str) -> Result<Vec<(u16, u32)>, String> {
+    if s.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut layers = vec![];
+    for layer in s.split('|') {
+        let (name, thickness) = match layer.trim().split_once('/') {
+            Some((name, thickness)) => (name.trim(), thickness.trim()),
+            None => (layer.trim(), "1"),
+        };
+        let id = block_registry()
+            .get_id(name)
+            .ok_or(format!("unknown block \"{name}\""))?;
+        let thickness = thickness
+            .parse::<u32>()
+            .map_err(|_| format!("invalid thickness \"{thickness}\" for \"{name}\""))?;
+        layers.push((id, thickness));
+    }
+    Ok(layers)
+}
+
+pub fn flat_layers_to_string(layers: &[(u16, u32)]) -> String {
+    layers
+        .iter()
+        .map(|(id, thickness)| {
+            let name = block_registry()
+                .get(*id)
+                .map(|block| block.name.clone())
+                .unwrap_or(id.to_string());
+            format!("{name}/{thickness}")
+        })
+        .collect::<Vec<String>>()
+        .join("|")
+}
+
+impl GenSettings {
+    //Total thickness of the flat world layers
+    pub fn flat_thickness(&self) -> i32 {
+        self.flat_layers.iter().map(|(_, t)| *t as i32).sum()
+    }
+
+    //Returns the block at y in a flat world
+    pub fn flat_layer_at(&self, y: i32) -> Option<u16> {
+        let mut top = self.flat_surface;
+        for (id, thickness) in &self.flat_layers {
+            let bottom = top - *thickness as i32;
+            if y <= top && y > bottom {
+                return Some(*id);
+            }
+            top = bottom;
+        }
+        None
+    }
+
+    //Returns a list of problems with the settings, an empty list means that
+    //the settings are valid
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if self.bottom > self.lava_level {
+            problems.push("the bottom of the world is above the lava level".to_string());
+        }
+        if self.octaves == 0 || self.octaves > MAX_OCTAVES {
+            problems.push(format!("octaves should be 1 - {MAX_OCTAVES}"));
+        }
+        if !(0.0..=1.0).contains(&self.persistence) {
+            problems.push("persistence should be 0.0 - 1.0".to_string());
+        }
+        if !HEIGHT_SCALE_RANGE.contains(&self.height_scale) {
+            problems.push(format!(
+                "height scale should be {} - {}",
+                HEIGHT_SCALE_RANGE.start(),
+                HEIGHT_SCALE_RANGE.end()
+            ));
+        }
+        if !ORE_FREQUENCY_RANGE.contains(&self.ore_frequency) {
+            problems.push(format!(
+                "ore frequency should be {} - {}",
+                ORE_FREQUENCY_RANGE.start(),
+                ORE_FREQUENCY_RANGE.end()
+            ));
+        }
+        problems
+    }
+
+    pub fn to_entry(&self) -> Entry {
+        let mut entry = Entry::new(GEN_SETTINGS_ENTRY);
+        entry.add_integer("sea_level", self.sea_level as i64);
+        entry.add_integer("lava_level", self.lava_level as i64);
+        entry.add_integer("bottom", self.bottom as i64);
+        entry.add_integer("octaves", self.octaves as i64);
+        //f64 is written as a string to not lose any precision
+        entry.add_string("persistence", &self.persistence.to_string());
+        entry.add_string("height_scale", &self.height_scale.to_string());
+        entry.add_string("ore_frequency", &self.ore_frequency.to_string());
+        entry.add_integer("flat_surface", self.flat_surface as i64);
+        entry.add_string("flat_layers", &flat_layers_to_string(&self.flat_layers));
+        entry
+    }
+}
+
+impl FromEntry for GenSettings {
+    type Context = ();
+
+    fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+        let default = Self::default();
+        let flat_layers = match reader.get::<String>("flat_layers") {
+            Some(layers) => parse_flat_layers(&layers).unwrap_or_else(|msg| {
+                reader.error("flat_layers", &msg);
+                default.flat_layers.clone()
+            }),
+            None => default.flat_layers.clone(),
+        };
+
+        let settings = Self {
+            sea_level: reader.get_or("sea_level", default.sea_level),
+            lava_level: reader.get_or("lava_level", default.lava_level),
+            bottom: reader.get_or("bottom", default.bottom),
+            octaves: reader.get_or("octaves", default.octaves),
+            persistence: reader.get_or("persistence", default.persistence),
+            height_scale: reader.get_or("height_scale", default.height_scale),
+            ore_frequency: reader.get_or("ore_frequency", default.ore_frequency),
+            flat_surface: reader.get_or("flat_surface", default.flat_surface),
+            flat_layers,
+        };
+
+        let problems = settings.validate();
+        for problem in &problems {
+            reader.error("", problem);
+        }
+        if !problems.is_empty() {
+            return None;
+        }
+        Some(settings)
+    }
+}
+
+//Reads the settings saved in the world metadata, returns the defaults if
+//they are missing or invalid
+pub fn read_gen_settings(entries: &[Entry], path: &str) -> GenSettings {
+    let Some(entry) = entries
+        .iter()
+        .find(|entry| entry.get_name() == GEN_SETTINGS_ENTRY)
+    else {
+        return GenSettings::default();
+    };
+    let mut reader = EntryReader::new(entry, path);
+    let settings = GenSettings::from_entry(&mut reader, &());
+    print_diagnostics(&reader.finish());
+    settings.unwrap_or_default()
+}
+
+//A named set of settings along with the terrain generator they are for
+#[derive(Clone, Debug)]
+pub struct WorldGenPreset {
+    pub name: String,
+    pub display_name: String,
+    //Name of the terrain generator
+    pub gen_type: String,
+    pub settings: GenSettings,
+}
+
+impl FromEntry for WorldGenPreset {
+    type Context = ();
+
+    fn from_entry(reader: &mut EntryReader, _: &()) -> Option<Self> {
+        let name = reader.name().to_string();
+        let display_name = reader.get_or("display_name", name.clone());
+        let gen_type: String = reader.required("gen_type")?;
+        if get_generator(&gen_type).is_none() {
+            let msg = format!("unknown terrain generator \"{gen_type}\"");
+            reader.error("gen_type", &msg);
+            return None;
+        }
+        let settings = GenSettings::from_entry(reader, &())?;
+        Some(Self {
+            name,
+            display_name,
+            gen_type,
+            settings,
+        })
+    }
+}
+
+pub fn read_worldgen_presets(path: &str) -> (Vec<WorldGenPreset>, Vec<Diagnostic>) {
+    load_file::<WorldGenPreset>(path, &())
+}
+
+static WORLDGEN_PRESETS: OnceLock<Vec<WorldGenPreset>> = OnceLock::new();
+
+//Returns the world generation presets in the order that they appear in the
+//presets file, they are loaded the first time this is called
+pub fn worldgen_presets() -> &'static [WorldGenPreset] {
+    WORLDGEN_PRESETS.get_or_init(|| {
+        let (presets, diagnostics) = read_worldgen_presets(WORLDGEN_PRESETS_PATH);
+        print_diagnostics(&diagnostics);
+        presets
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        impfile::parse_str,
+        voxel::{
+            block_ids::{DIRT, GRASS},
+            world::{
+                terrain_generator::{FlatGenerator, TerrainGenerator},
+                GameMode, World,
+            },
+            EMPTY_BLOCK,
+        },
+    };
+
+    #[test]
+    fn test_flat_layers() {
+        let layers = parse_flat_layers("grass/1|dirt/3|stone/58|bedrock/1").unwrap();
+        assert_eq!(layers, GenSettings::default().flat_layers);
+        assert_eq!(
+            flat_layers_to_string(&layers),
+            "grass/1|dirt/3|stone/58|bedrock/1"
+        );
+        assert!(parse_flat_layers("").unwrap().is_empty());
+        assert!(parse_flat_layers("grass/x").is_err());
+        assert!(parse_flat_layers("not_a_block/1").is_err());
+
+        let settings = GenSettings::default();
+        assert_eq!(settings.flat_layer_at(-1), Some(1));
+        assert_eq!(settings.flat_layer_at(-4), Some(4));
+        assert_eq!(settings.flat_layer_at(-62), Some(2));
+        assert_eq!(settings.flat_layer_at(-63), Some(3));
+        assert_eq!(settings.flat_layer_at(-64), None);
+        assert_eq!(settings.flat_layer_at(0), None);
+    }
+
+    #[test]
+    fn test_save_gen_settings() {
+        let settings = GenSettings {
+            sea_level: 12,
+            height_scale: 2.5,
+            persistence: 0.55,
+            flat_layers: vec![(2, 4)],
+            ..Default::default()
+        };
+        let saved = settings.to_entry().to_impfile_string();
+        let (entries, _) = parse_str(&saved, "test");
+        assert_eq!(read_gen_settings(&entries, "test"), settings);
+        //Older worlds do not have any settings
+        assert_eq!(read_gen_settings(&[], "test"), GenSettings::default());
+    }
+
+    #[test]
+    fn test_load_worldgen_presets() {
+        let (presets, diagnostics) = read_worldgen_presets(WORLDGEN_PRESETS_PATH);
+        assert!(diagnostics.is_empty());
+        assert_eq!(presets[0].settings, GenSettings::default());
+        for name in ["amplified", "islands", "superflat", "void"] {
+            assert!(presets.iter().any(|preset| preset.name == name));
+        }
+    }
+
+    #[test]
+    fn test_flat_presets() {
+        let (presets, _) = read_worldgen_presets(WORLDGEN_PRESETS_PATH);
+        let preset = |name: &str| {
+            let preset = presets.iter().find(|preset| preset.name == name).unwrap();
+            assert_eq!(preset.gen_type, FlatGenerator.name());
+            let mut world = World::new(0, 1, &FlatGenerator, GameMode::Creative);
+            world.set_gen_settings(preset.settings.clone());
+            world.generate_world();
+            world
+        };
+
+        let superflat = preset("superflat");
+        assert_eq!(superflat.get_block(3, -1, 5).id, GRASS);
+        assert_eq!(superflat.get_block(3, -4, 5).id, DIRT);
+        assert_eq!(superflat.get_block(3, 0, 5).id, EMPTY_BLOCK);
+
+        let void = preset("void");
+        assert!(void.chunks.values().all(|chunk| chunk.is_empty()));
+    }
+}
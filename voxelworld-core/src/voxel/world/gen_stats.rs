@@ -0,0 +1,483 @@
+/*
+ * World generation statistics
+ *
+ * Generates an area of a world without loading it into a `World` and counts
+ * what was generated: every block at every y level, the size of ore veins,
+ * how much of the underground is carved out by caves and the number of
+ * trees. This is meant for balancing ore generation with actual numbers and
+ * for catching changes to generation in tests.
+ *
+ * Run with `--gen-stats [type] [seed] [radius]` to write the statistics to
+ * BLOCKS_CSV and VEINS_CSV and print a summary.
+ * */
+
+use super::{
+    gen_settings::GenSettings,
+    terrain_generator::{default_generator, get_generator, Surface, TerrainGenerator},
+    WorldGenerator,
+};
+use crate::voxel::{
+    block_ids::LOG, block_registry::block_registry, Chunk, CHUNK_SIZE, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
+use crossbeam::thread;
+use std::collections::{BTreeMap, HashSet};
+
+pub const BLOCKS_CSV: &str = "gen_stats_blocks.csv";
+pub const VEINS_CSV: &str = "gen_stats_veins.csv";
+
+const DEFAULT_SEED: u32 = 1;
+const DEFAULT_RADIUS: i32 = 4;
+//Highest terrain that can be generated (default generator with the largest
+//height scale)
+const TOP_Y: i32 = 384;
+
+//Blocks that generate in veins, the size of each vein is recorded
+pub const VEIN_BLOCKS: &[&str] = &[
+    "coal_ore",
+    "iron_ore",
+    "gold_ore",
+    "diamond_ore",
+    "rainbow_ore",
+    "red_ore",
+    "uranium_ore",
+    "aqua_ore",
+    "magma_stone",
+    "clay",
+];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GenStats {
+    pub chunks: usize,
+    pub columns: usize,
+    //Number of blocks of each id at each y level, (y, id) -> count, air is
+    //not counted
+    pub block_counts: BTreeMap<(i32, u16), u64>,
+    //Number of veins of each size for each vein block, (id, size) -> count,
+    //a vein is a group of blocks that touch (including diagonally) within
+    //a single chunk
+    pub vein_sizes: BTreeMap<(u16, usize), u64>,
+    //Blocks below the surface of the terrain and how many of them are air,
+    //generators without a surface do not count these
+    pub underground: u64,
+    pub cave_air: u64,
+    //Logs that are not on top of another log
+    pub trees: u64,
+}
+
+fn block_name(id: u16) -> String {
+    match block_registry().get(id) {
+        Some(def) => def.name.clone(),
+        None => id.to_string(),
+    }
+}
+
+//Returns the sizes of every vein of `id` in the chunk
+fn find_veins(chunk: &Chunk, id: u16) -> Vec<usize> {
+    let index = |x: usize, y: usize, z: usize| x * CHUNK_SIZE * CHUNK_SIZE + y * CHUNK_SIZE + z;
+    let mut visited = vec![false; CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE];
+    let mut veins = vec![];
+    for x in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                if visited[index(x, y, z)] || chunk.get_block_relative(x, y, z).id != id {
+                    continue;
+                }
+
+                visited[index(x, y, z)] = true;
+                let mut stack = vec![(x, y, z)];
+                let mut size = 0;
+                while let Some((x, y, z)) = stack.pop() {
+                    size += 1;
+                    for dx in -1..=1i32 {
+                        for dy in -1..=1i32 {
+                            for dz in -1..=1i32 {
+                                let nx = x as i32 + dx;
+                                let ny = y as i32 + dy;
+                                let nz = z as i32 + dz;
+                                if [nx, ny, nz]
+                                    .iter()
+                                    .any(|v| !(0..CHUNK_SIZE_I32).contains(v))
+                                {
+                                    continue;
+                                }
+                                let (nx, ny, nz) = (nx as usize, ny as usize, nz as usize);
+                                if visited[index(nx, ny, nz)]
+                                    || chunk.get_block_relative(nx, ny, nz).id != id
+                                {
+                                    continue;
+                                }
+                                visited[index(nx, ny, nz)] = true;
+                                stack.push((nx, ny, nz));
+                            }
+                        }
+                    }
+                }
+                veins.push(size);
+            }
+        }
+    }
+    veins
+}
+
+impl GenStats {
+    //Number of blocks with `id` at every y level
+    pub fn count(&self, id: u16) -> u64 {
+        self.block_counts
+            .iter()
+            .filter(|((_, block), _)| *block == id)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    pub fn vein_count(&self, id: u16) -> u64 {
+        self.vein_sizes
+            .iter()
+            .filter(|((block, _), _)| *block == id)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    //Fraction of the underground that is air
+    pub fn cave_air_ratio(&self) -> f64 {
+        if self.underground == 0 {
+            return 0.0;
+        }
+        self.cave_air as f64 / self.underground as f64
+    }
+
+    fn merge(&mut self, other: GenStats) {
+        self.chunks += other.chunks;
+        self.columns += other.columns;
+        for (key, count) in other.block_counts {
+            *self.block_counts.entry(key).or_insert(0) += count;
+        }
+        for (key, count) in other.vein_sizes {
+            *self.vein_sizes.entry(key).or_insert(0) += count;
+        }
+        self.underground += other.underground;
+        self.cave_air += other.cave_air;
+        self.trees += other.trees;
+    }
+
+    //Adds a column of chunks that are sorted from bottom to top, `surface`
+    //is indexed by z * CHUNK_SIZE + x
+    fn add_column(&mut self, chunks: &[Chunk], surface: &[Option<Surface>], vein_ids: &[u16]) {
+        self.columns += 1;
+        self.chunks += chunks.len();
+
+        //The underground is between the lowest block and the surface, so
+        //that the void under the bottom of the world is not a cave
+        let mut floor = vec![None; CHUNK_SIZE * CHUNK_SIZE];
+        for chunk in chunks.iter().filter(|chunk| !chunk.is_empty()) {
+            let posy = chunk.get_chunk_pos().y * CHUNK_SIZE_I32;
+            for (i, floor) in floor.iter_mut().enumerate().filter(|(_, f)| f.is_none()) {
+                let (lx, lz) = (i % CHUNK_SIZE, i / CHUNK_SIZE);
+                *floor = (0..CHUNK_SIZE)
+                    .find(|ly| chunk.get_block_relative(lx, *ly, lz).id != EMPTY_BLOCK)
+                    .map(|ly| posy + ly as i32);
+            }
+        }
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let pos = chunk.get_chunk_pos();
+            let below = i.checked_sub(1).map(|i| &chunks[i]);
+            for ly in 0..CHUNK_SIZE {
+                let y = pos.y * CHUNK_SIZE_I32 + ly as i32;
+                for lz in 0..CHUNK_SIZE {
+                    for lx in 0..CHUNK_SIZE {
+                        let block = chunk.get_block_relative(lx, ly, lz);
+                        let i = lz * CHUNK_SIZE + lx;
+                        let underground = surface[i].is_some_and(|surface| y < surface.height)
+                            && floor[i].is_some_and(|floor| y >= floor);
+                        if underground {
+                            self.underground += 1;
+                        }
+
+                        if block.id == EMPTY_BLOCK {
+                            if underground {
+                                self.cave_air += 1;
+                            }
+                            continue;
+                        }
+                        *self.block_counts.entry((y, block.id)).or_insert(0) += 1;
+
+                        if block.id == LOG {
+                            let under = match (ly, below) {
+                                (0, Some(below)) => {
+                                    below.get_block_relative(lx, CHUNK_SIZE - 1, lz)
+                                }
+                                (0, None) => continue,
+                                _ => chunk.get_block_relative(lx, ly - 1, lz),
+                            };
+                            if under.id != LOG && under.id != EMPTY_BLOCK {
+                                self.trees += 1;
+                            }
+                        }
+                    }
+                }
+            }
+
+            for id in vein_ids {
+                for size in find_veins(chunk, *id) {
+                    *self.vein_sizes.entry((*id, size)).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    //"y,block,count" for every block at every y level, highest y first
+    pub fn blocks_csv(&self) -> String {
+        let mut csv = "y,block,count\n".to_string();
+        for ((y, id), count) in self.block_counts.iter().rev() {
+            csv += &format!("{y},{},{count}\n", block_name(*id));
+        }
+        csv
+    }
+
+    //"block,size,count" for every vein size of every vein block
+    pub fn veins_csv(&self) -> String {
+        let mut csv = "block,size,count\n".to_string();
+        for ((id, size), count) in &self.vein_sizes {
+            csv += &format!("{},{size},{count}\n", block_name(*id));
+        }
+        csv
+    }
+
+    //Table of the amount of each vein block along with the cave and tree
+    //statistics
+    pub fn summary(&self) -> String {
+        let mut lines = vec![
+            format!("{} chunks in {} columns", self.chunks, self.columns),
+            format!(
+                "{:<14}{:>10}{:>8}{:>10}{:>12}{:>12}",
+                "block", "total", "veins", "avg size", "per column", "y range"
+            ),
+        ];
+        let ids: HashSet<u16> = self.vein_sizes.keys().map(|(id, _)| *id).collect();
+        let mut ids: Vec<u16> = ids.into_iter().collect();
+        ids.sort_by_key(|id| std::cmp::Reverse(self.count(*id)));
+        for id in ids {
+            let total = self.count(id);
+            let veins = self.vein_count(id);
+            let ys: Vec<i32> = self
+                .block_counts
+                .keys()
+                .filter(|(_, block)| *block == id)
+                .map(|(y, _)| *y)
+                .collect();
+            let min_y = ys.iter().min().copied().unwrap_or(0);
+            let max_y = ys.iter().max().copied().unwrap_or(0);
+            lines.push(format!(
+                "{:<14}{:>10}{:>8}{:>10.2}{:>12.2}{:>12}",
+                block_name(id),
+                total,
+                veins,
+                total as f64 / veins.max(1) as f64,
+                total as f64 / self.columns.max(1) as f64,
+                format!("{min_y}..{max_y}"),
+            ));
+        }
+        lines.push(format!(
+            "cave air: {:.2}% of {} underground blocks",
+            self.cave_air_ratio() * 100.0,
+            self.underground
+        ));
+        lines.push(format!(
+            "trees: {} ({:.2} per column)",
+            self.trees,
+            self.trees as f64 / self.columns.max(1) as f64
+        ));
+        lines.join("\n")
+    }
+}
+
+fn column_stats(
+    gen_type: &dyn TerrainGenerator,
+    world_generator: &WorldGenerator,
+    (x, z): (i32, i32),
+    vein_ids: &[u16],
+) -> GenStats {
+    let column = gen_type.column(x, z, world_generator);
+    let bottom = gen_type.bottom().min(world_generator.settings.bottom);
+    let chunks: Vec<Chunk> = (bottom.div_euclid(CHUNK_SIZE_I32)..=TOP_Y / CHUNK_SIZE_I32)
+        .map(|y| {
+            let mut chunk = Chunk::new(x, y, z);
+            column.gen_chunk(&mut chunk);
+            chunk
+        })
+        .collect();
+
+    let mut surface = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
+    for lz in 0..CHUNK_SIZE_I32 {
+        for lx in 0..CHUNK_SIZE_I32 {
+            let blockx = x * CHUNK_SIZE_I32 + lx;
+            let blockz = z * CHUNK_SIZE_I32 + lz;
+            surface.push(gen_type.surface(blockx, blockz, world_generator));
+        }
+    }
+
+    let mut stats = GenStats::default();
+    stats.add_column(&chunks, &surface, vein_ids);
+    stats
+}
+
+//Generates the columns of chunks within `radius` of (0, 0) from the bottom
+//of the world to TOP_Y
+pub fn gather_gen_stats(
+    seed: u32,
+    gen_type: &dyn TerrainGenerator,
+    settings: &GenSettings,
+    radius: i32,
+) -> GenStats {
+    let world_generator = WorldGenerator::with_settings(seed, settings.clone());
+    let vein_ids: Vec<u16> = VEIN_BLOCKS
+        .iter()
+        .filter_map(|name| block_registry().get_id(name))
+        .collect();
+    let mut columns = vec![];
+    for x in -radius..=radius {
+        for z in -radius..=radius {
+            columns.push((x, z));
+        }
+    }
+
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+    let per_thread = columns.len().div_ceil(threads).max(1);
+    let mut stats = GenStats::default();
+    thread::scope(|s| {
+        let handles: Vec<_> = columns
+            .chunks(per_thread)
+            .map(|columns| {
+                let world_generator = &world_generator;
+                let vein_ids = &vein_ids;
+                s.spawn(move |_| {
+                    let mut stats = GenStats::default();
+                    for pos in columns {
+                        stats.merge(column_stats(gen_type, world_generator, *pos, vein_ids));
+                    }
+                    stats
+                })
+            })
+            .collect();
+        for handle in handles {
+            stats.merge(handle.join().expect("Failed to gather stats!"));
+        }
+    })
+    .expect("Failed to gather stats!");
+    stats
+}
+
+pub fn run_gen_stats(args: &[String]) {
+    let Some(i) = args.iter().position(|arg| arg == "--gen-stats") else {
+        return;
+    };
+    let args = &args[(i + 1)..];
+
+    let gen_type = match args.first() {
+        Some(name) => get_generator(name).unwrap_or_else(|| {
+            eprintln!("E: unknown world type {name}");
+            std::process::exit(1);
+        }),
+        None => default_generator(),
+    };
+    let seed = args
+        .get(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(DEFAULT_SEED);
+    let radius = args
+        .get(2)
+        .and_then(|radius| radius.parse().ok())
+        .unwrap_or(DEFAULT_RADIUS);
+
+    eprintln!(
+        "Generating {} world with seed {seed} (radius {radius})...",
+        gen_type.name()
+    );
+    let stats = gather_gen_stats(seed, gen_type, &GenSettings::default(), radius);
+    for (path, csv) in [
+        (BLOCKS_CSV, stats.blocks_csv()),
+        (VEINS_CSV, stats.veins_csv()),
+    ] {
+        match std::fs::write(path, csv) {
+            Ok(()) => eprintln!("Wrote {path}"),
+            Err(e) => eprintln!("E: failed to write {path}: {e}"),
+        }
+    }
+    eprintln!();
+    eprintln!("World Generation Statistics");
+    eprintln!("---------------------------");
+    eprintln!("{}", stats.summary());
+    std::process::exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{block_ids::STONE, Block};
+
+    fn id(name: &str) -> u16 {
+        block_registry().get_id(name).unwrap()
+    }
+
+    #[test]
+    fn test_find_veins() {
+        let mut chunk = Chunk::new(0, 0, 0);
+        let coal = id("coal_ore");
+        chunk.set_block(1, 1, 1, Block::new_id(coal));
+        chunk.set_block(2, 2, 2, Block::new_id(coal));
+        chunk.set_block(2, 3, 2, Block::new_id(coal));
+        chunk.set_block(8, 8, 8, Block::new_id(coal));
+        chunk.set_block(8, 9, 8, Block::new_id(STONE));
+        let mut veins = find_veins(&chunk, coal);
+        veins.sort();
+        assert_eq!(veins, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_gen_stats() {
+        let default = get_generator("default2").unwrap();
+        let stats = gather_gen_stats(1, default, &GenSettings::default(), 1);
+        assert_eq!(stats.columns, 9);
+        assert!(stats.blocks_csv().starts_with("y,block,count\n"));
+        assert!(stats.veins_csv().starts_with("block,size,count\n"));
+
+        //Ores get rarer the better the tools that they make are
+        let coal = stats.count(id("coal_ore"));
+        let iron = stats.count(id("iron_ore"));
+        let gold = stats.count(id("gold_ore"));
+        let diamond = stats.count(id("diamond_ore"));
+        let rainbow = stats.count(id("rainbow_ore"));
+        assert!(coal > iron, "coal: {coal} iron: {iron}");
+        assert!(iron > gold, "iron: {iron} gold: {gold}");
+        assert!(gold > diamond, "gold: {gold} diamond: {diamond}");
+        assert!(diamond > rainbow, "diamond: {diamond} rainbow: {rainbow}");
+        //Every vein block is in exactly one vein
+        for name in VEIN_BLOCKS {
+            let id = id(name);
+            let in_veins: u64 = stats
+                .vein_sizes
+                .iter()
+                .filter(|((block, _), _)| *block == id)
+                .map(|((_, size), count)| *size as u64 * count)
+                .sum();
+            assert_eq!(in_veins, stats.count(id), "{name}");
+        }
+        //Nothing above the bottom of the world is bedrock
+        let bottom = GenSettings::default().bottom;
+        assert!(stats
+            .block_counts
+            .keys()
+            .all(|(y, block)| *block != id("bedrock") || *y <= bottom + 2));
+
+        let caves = stats.cave_air_ratio();
+        assert!(caves > 0.0 && caves < 0.25, "cave air: {caves}");
+        assert!(stats.trees > 0);
+
+        //Flat worlds have no caves or ore
+        let flat = get_generator("flat").unwrap();
+        let stats = gather_gen_stats(1, flat, &GenSettings::default(), 0);
+        assert_eq!(stats.cave_air, 0);
+        assert!(stats.vein_sizes.is_empty());
+        assert_eq!(stats.count(id("grass")), 256);
+    }
+}
@@ -684,6 +684,10 @@ impl World {
 
     //Called when the world is first loaded
     pub fn init_sky_light(&mut self) {
+        if !self.dimension.sky_light {
+            return;
+        }
+
         let start = std::time::Instant::now();
 
         for (x, _, z) in self.chunks.keys().copied() {
@@ -749,6 +753,10 @@ impl World {
     }
 
     fn update_sky_light(&mut self, positions: &[(i32, i32, i32)]) -> ChunkList {
+        if !self.dimension.sky_light {
+            return ChunkList::new();
+        }
+
         let mut updated_map_vals = HashMap::<(i32, i32), i32>::new();
 
         for (x, y, z) in positions.iter().copied() {
@@ -929,6 +937,10 @@ impl World {
     //cases where this function causes some slight issues with performance but
     //those cases likely won't happen to most users (I hope).
     fn init_sky_light_new_chunks(&mut self, chunks: &HashSet<(i32, i32, i32)>) -> ChunkList {
+        if !self.dimension.sky_light {
+            return ChunkList::new();
+        }
+
         let start = std::time::Instant::now();
 
         //Clean out sky light map
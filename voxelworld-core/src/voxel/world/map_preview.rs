@@ -0,0 +1,379 @@
+/*
+ * Top down maps of worlds
+ *
+ * Maps can either be drawn from the world generator, which only needs the
+ * seed and settings of a world and never generates any chunks (this is what
+ * the create world menu previews), or from the regions saved in a world so
+ * that the map shows what was actually built.
+ * */
+
+use super::{
+    gen_settings::GenSettings,
+    terrain_generator::{Surface, TerrainGenerator},
+    WorldGenerator,
+};
+use crate::save::CHUNK_PATH;
+use crate::voxel::{
+    block_ids::*,
+    block_registry::block_registry,
+    region::{chunkpos_to_regionpos, save::parse_region_file_name, Region, REGION_SIZE_I32},
+    Block, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
+use crossbeam::thread;
+use std::collections::HashMap;
+
+//Pixels without a surface (the void, columns that were never generated) are
+//left transparent
+const NO_SURFACE: [u8; 4] = [0, 0, 0, 0];
+//Water this deep completely hides the floor
+const MAX_WATER_DEPTH: i32 = 16;
+
+//Area of the world covered by a map
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapArea {
+    //Block coordinates of the top left (-x, -z) corner
+    pub x: i32,
+    pub z: i32,
+    //Size of the map in pixels
+    pub width: u32,
+    pub height: u32,
+    //Blocks per pixel
+    pub scale: u32,
+}
+
+impl MapArea {
+    //Square map of size * size pixels centered on (x, z)
+    pub fn centered(x: i32, z: i32, size: u32, scale: u32) -> Self {
+        let half = (size * scale / 2) as i32;
+        Self {
+            x: x - half,
+            z: z - half,
+            width: size,
+            height: size,
+            scale,
+        }
+    }
+
+    //Returns the block position that the pixel (px, pz) is sampled from
+    fn block_pos(&self, px: i32, pz: i32) -> (i32, i32) {
+        let scale = self.scale.max(1) as i32;
+        (self.x + px * scale, self.z + pz * scale)
+    }
+}
+
+//RGBA image with 4 bytes per pixel
+pub struct MapImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl MapImage {
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y * self.width + x) as usize * 4;
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    pub fn save_png(&self, path: &str) -> Result<(), String> {
+        let file =
+            std::fs::File::create(path).map_err(|e| format!("failed to create {path}: {e}"))?;
+        let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(|e| format!("failed to write {path}: {e}"))
+    }
+}
+
+fn block_color(block: Block) -> [u8; 3] {
+    match block.id {
+        GRASS => [95, 159, 53],
+        STONE => [125, 125, 125],
+        DIRT | WET_FARMLAND | DRY_FARMLAND => [134, 96, 67],
+        LEAVES => [48, 110, 32],
+        LOG => [102, 81, 51],
+        GLASS => [200, 220, 230],
+        SAND => [219, 207, 163],
+        WATER => [52, 90, 200],
+        LAVA => [212, 90, 18],
+        OBSIDIAN => [20, 18, 30],
+        MOSS => [88, 116, 60],
+        ICE => [160, 188, 240],
+        SNOW | SNOWY_GRASS => [240, 244, 250],
+        SNOWY_LEAVES => [200, 214, 210],
+        CACTUS => [40, 120, 40],
+        CLAY => [160, 166, 178],
+        id => match block_registry().get(id).map(|def| def.name.as_str()) {
+            Some("bedrock") => [50, 50, 50],
+            Some("plank") | Some("chest") => [162, 130, 78],
+            Some("cobblestone") | Some("stone_bricks") => [110, 110, 110],
+            Some("bricks") => [150, 74, 60],
+            Some("magma_stone") | Some("magma_bricks") => [120, 40, 30],
+            _ => [150, 150, 150],
+        },
+    }
+}
+
+//Blends a towards b, t = 0.0 is a and t = 1.0 is b
+fn blend(a: [u8; 3], b: [u8; 3], t: f32) -> [u8; 3] {
+    [0, 1, 2].map(|i| (a[i] as f32 * (1.0 - t) + b[i] as f32 * t) as u8)
+}
+
+//Slopes that face north (-z) are darker and slopes facing south are lighter
+//so that hills can be seen on the map
+fn pixel_color(surface: Option<Surface>, north: Option<Surface>, scale: u32) -> [u8; 4] {
+    let Some(surface) = surface else {
+        return NO_SURFACE;
+    };
+
+    let mut color = block_color(surface.block);
+    if let Some(north) = north {
+        let slope = (surface.height - north.height) as f32 / scale.max(1) as f32;
+        let shade = 1.0 + (slope * 0.08).clamp(-0.25, 0.25);
+        color = color.map(|c| (c as f32 * shade).min(255.0) as u8);
+    }
+
+    match surface.cover {
+        Some((fluid, top)) if fluid.is_fluid() => {
+            let depth = (top - surface.height).clamp(0, MAX_WATER_DEPTH);
+            let t = 0.5 + 0.5 * depth as f32 / MAX_WATER_DEPTH as f32;
+            color = blend(color, block_color(fluid), t);
+        }
+        Some((cover, _)) => color = block_color(cover),
+        None => {}
+    }
+
+    [color[0], color[1], color[2], 255]
+}
+
+//Colors the map from the surface of every pixel, `surfaces` has an extra row
+//at the top that is only used to shade the first row
+fn draw_map(area: MapArea, surfaces: &[Option<Surface>]) -> MapImage {
+    let width = area.width as usize;
+    let mut pixels = Vec::with_capacity(width * area.height as usize * 4);
+    for (north_row, row) in surfaces.chunks(width).zip(surfaces.chunks(width).skip(1)) {
+        for (north, surface) in north_row.iter().zip(row) {
+            pixels.extend(pixel_color(*surface, *north, area.scale));
+        }
+    }
+
+    MapImage {
+        width: area.width,
+        height: area.height,
+        pixels,
+    }
+}
+
+//Draws a map of what the generator would generate in the area, generators
+//that do not support maps leave the whole map transparent
+pub fn render_generated_map(
+    gen_type: &dyn TerrainGenerator,
+    world_generator: &WorldGenerator,
+    area: MapArea,
+) -> MapImage {
+    let width = area.width as usize;
+    let mut surfaces = vec![None; width * (area.height as usize + 1)];
+    if width == 0 {
+        return draw_map(area, &surfaces);
+    }
+
+    thread::scope(|s| {
+        for (pz, row) in surfaces.chunks_mut(width).enumerate() {
+            s.spawn(move |_| {
+                for (px, surface) in row.iter_mut().enumerate() {
+                    let (x, z) = area.block_pos(px as i32, pz as i32 - 1);
+                    *surface = gen_type.surface(x, z, world_generator);
+                }
+            });
+        }
+    })
+    .expect("Failed to render map!");
+
+    draw_map(area, &surfaces)
+}
+
+//Draws a map of a world that has not been created yet
+pub fn preview_map(
+    seed: u32,
+    gen_type: &dyn TerrainGenerator,
+    settings: &GenSettings,
+    area: MapArea,
+) -> MapImage {
+    let world_generator = WorldGenerator::with_settings(seed, settings.clone());
+    render_generated_map(gen_type, &world_generator, area)
+}
+
+//Returns the highest block in a column of saved regions, fluids are skipped
+//over to find the floor under them
+fn saved_surface(x: i32, z: i32, regions: &[&Region]) -> Option<Surface> {
+    let (chunkx, chunkz) = (x.div_euclid(CHUNK_SIZE_I32), z.div_euclid(CHUNK_SIZE_I32));
+    let (regionx, _, regionz) = chunkpos_to_regionpos(chunkx, 0, chunkz);
+    let ix = (chunkx - regionx * REGION_SIZE_I32) as usize;
+    let iz = (chunkz - regionz * REGION_SIZE_I32) as usize;
+
+    let mut cover = None;
+    //The regions are sorted from highest to lowest
+    for region in regions {
+        for iy in (0..REGION_SIZE_I32 as usize).rev() {
+            let Some(chunk) = region.get_relative_chunk(ix, iy, iz) else {
+                continue;
+            };
+            if chunk.is_empty() {
+                continue;
+            }
+            let posy = chunk.get_chunk_pos().y * CHUNK_SIZE_I32;
+            for y in (posy..(posy + CHUNK_SIZE_I32)).rev() {
+                let block = chunk.get_block(x, y, z);
+                //Skip air and plants, torches, etc.
+                if block.id == EMPTY_BLOCK || (block.no_hitbox() && !block.is_fluid()) {
+                    continue;
+                }
+                if block.is_fluid() {
+                    cover = cover.or(Some((block, y)));
+                    continue;
+                }
+                return Some(Surface {
+                    height: y,
+                    block,
+                    cover,
+                });
+            }
+        }
+    }
+
+    //Fluid all the way down
+    cover.map(|(block, y)| Surface {
+        height: y,
+        block,
+        cover: None,
+    })
+}
+
+//Draws a map from the regions saved in a world, chunks that were never saved
+//are left transparent
+pub fn render_saved_map(world_path: &str, area: MapArea) -> Result<MapImage, String> {
+    let chunk_path = world_path.to_string() + CHUNK_PATH;
+    let dir_contents =
+        std::fs::read_dir(&chunk_path).map_err(|e| format!("failed to open {chunk_path}: {e}"))?;
+
+    //Only load the regions that overlap the map
+    let scale = area.scale.max(1) as i32;
+    let (minx, minz) = (area.x, area.z);
+    let maxx = area.x + area.width as i32 * scale;
+    let maxz = area.z + area.height as i32 * scale;
+    let region_blocks = REGION_SIZE_I32 * CHUNK_SIZE_I32;
+    let (min_regionx, min_regionz) = (
+        minx.div_euclid(region_blocks),
+        minz.div_euclid(region_blocks),
+    );
+    let (max_regionx, max_regionz) = (
+        maxx.div_euclid(region_blocks),
+        maxz.div_euclid(region_blocks),
+    );
+
+    let mut columns: HashMap<(i32, i32), Vec<Region>> = HashMap::new();
+    for entry in dir_contents.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some((x, y, z)) = parse_region_file_name(&name) else {
+            continue;
+        };
+        if !(min_regionx..=max_regionx).contains(&x) || !(min_regionz..=max_regionz).contains(&z) {
+            continue;
+        }
+        match Region::load_region(world_path, x, y, z, None, false) {
+            Some(region) => columns.entry((x, z)).or_default().push(region),
+            None => eprintln!("W: failed to load {name}, leaving it off of the map"),
+        }
+    }
+    for regions in columns.values_mut() {
+        regions.sort_by_key(|region| -region.y);
+    }
+
+    let width = area.width as usize;
+    let mut surfaces = Vec::with_capacity(width * (area.height as usize + 1));
+    for pz in -1..area.height as i32 {
+        for px in 0..area.width as i32 {
+            let (x, z) = area.block_pos(px, pz);
+            let regionx = x.div_euclid(region_blocks);
+            let regionz = z.div_euclid(region_blocks);
+            let regions: Vec<&Region> = columns
+                .get(&(regionx, regionz))
+                .map(|regions| regions.iter().collect())
+                .unwrap_or_default();
+            surfaces.push(saved_surface(x, z, &regions));
+        }
+    }
+
+    Ok(draw_map(area, &surfaces))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::world::{
+        gen_settings::worldgen_presets, terrain_generator::get_generator, GameMode, World,
+    };
+
+    #[test]
+    fn test_preview_map() {
+        let area = MapArea::centered(0, 0, 48, 4);
+        for preset in worldgen_presets() {
+            let gen_type = get_generator(&preset.gen_type).unwrap();
+            let map = preview_map(3, gen_type, &preset.settings, area);
+            assert_eq!(map.pixels.len(), 48 * 48 * 4);
+            //The same seed should always give the same map
+            let again = preview_map(3, gen_type, &preset.settings, area);
+            assert_eq!(map.pixels, again.pixels);
+        }
+
+        //The default generator should have some variety
+        let default = get_generator("default2").unwrap();
+        let map = preview_map(3, default, &GenSettings::default(), area);
+        let first = map.get_pixel(0, 0);
+        assert_eq!(first[3], 255);
+        assert!((0..48).any(|x| (0..48).any(|y| map.get_pixel(x, y) != first)));
+        //A different seed should give a different map
+        let other = preview_map(4, default, &GenSettings::default(), area);
+        assert_ne!(map.pixels, other.pixels);
+    }
+
+    #[test]
+    fn test_saved_map() {
+        let world_path = std::env::temp_dir()
+            .join(format!("voxelworld-map-test-{}/", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        crate::save::create_world_dir(&world_path).unwrap();
+
+        let flat = get_generator("flat").unwrap();
+        let mut world = World::new(9, 1, flat, GameMode::Creative);
+        world.path = world_path.clone();
+        world.generate_world();
+        world.set_block(2, -1, 3, Block::new_id(STONE));
+        world.save_all();
+
+        let area = MapArea {
+            x: -8,
+            z: -8,
+            width: 16,
+            height: 16,
+            scale: 1,
+        };
+        let map = render_saved_map(&world_path, area).unwrap();
+        let _ = std::fs::remove_dir_all(&world_path);
+
+        //The map should show the block that was placed on the grass
+        let stone = map.get_pixel(10, 11);
+        let grass = map.get_pixel(0, 0);
+        assert_eq!(grass[3], 255);
+        assert_ne!(stone, grass);
+        assert_eq!(map.get_pixel(15, 15), grass);
+    }
+}
@@ -0,0 +1,290 @@
+/*
+ * Old world generation
+ * */
+
+mod gen_trees;
+mod ore;
+mod plants;
+mod terrain;
+
+use self::{
+    gen_trees::get_tree_gen_info,
+    ore::{generate_magma_blocks, generate_ore},
+    plants::{generate_plants, generate_sugarcane, get_plant_positions, get_water_adjacent},
+};
+use std::collections::HashMap;
+
+use super::{
+    terrain_generator::{ColumnGenerator, Surface, TerrainGenerator},
+    WorldGenerator,
+};
+use crate::voxel::CHUNK_SIZE_I32;
+use crate::voxel::{
+    block_ids::{DIRT, GRASS, LAVA, SAND, STONE, WATER},
+    Block, Chunk, INDESTRUCTIBLE,
+};
+use gen_trees::generate_trees;
+use terrain::{add_to_heightmap, is_noise_cave, HeightMap};
+
+const SEA_LEVEL: i32 = 0;
+const SAND_LEVEL: i32 = SEA_LEVEL + 1;
+const LAVA_LEVEL: i32 = -50;
+const BOTTOM_OF_WORLD: i32 = -64;
+
+struct GenInfoTable {
+    heightmap: HeightMap,
+    tree_positions: HashMap<(i32, i32), Vec<(i32, i32)>>,
+    tree_heights: HashMap<(i32, i32), Vec<i32>>,
+    plant_positions: HashMap<(i32, i32), Vec<(i32, i32)>>,
+    sugarcane_positions: HashMap<(i32, i32), Vec<(i32, i32)>>,
+}
+
+struct GenInfo<'a> {
+    heights: &'a [i32],
+    tree_positions: &'a [(i32, i32)],
+    tree_heights: &'a [i32],
+    plant_positions: &'a [(i32, i32)],
+    sugarcane_positions: &'a [(i32, i32)],
+}
+
+impl GenInfoTable {
+    fn new() -> Self {
+        Self {
+            heightmap: HeightMap::new(),
+            tree_positions: HashMap::new(),
+            tree_heights: HashMap::new(),
+            plant_positions: HashMap::new(),
+            sugarcane_positions: HashMap::new(),
+        }
+    }
+
+    fn add_heights(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        add_to_heightmap(
+            x,
+            z,
+            &mut self.heightmap,
+            &world_generator.terrain_generator,
+            &world_generator.steepness,
+        );
+    }
+
+    fn add_trees(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        if self.tree_positions.contains_key(&(x, z)) {
+            return;
+        }
+
+        let (tree_pos, tree_h) = get_tree_gen_info(x, z, world_generator);
+        self.tree_positions.insert((x, z), tree_pos);
+        self.tree_heights.insert((x, z), tree_h);
+    }
+
+    fn add_plants(&mut self, x: i32, z: i32, world_generator: &WorldGenerator) {
+        if self.plant_positions.contains_key(&(x, z)) {
+            return;
+        }
+        let plants = get_plant_positions(x, z, world_generator.world_seed);
+        self.plant_positions.insert((x, z), plants);
+    }
+
+    //Assumes that (x, z) has been generated
+    fn add_sugarcane(&mut self, x: i32, z: i32) {
+        if self.sugarcane_positions.contains_key(&(x, z)) {
+            return;
+        }
+        let heights = self.heightmap.get(&(x, z));
+        if let Some(heights) = heights {
+            let water_adjacent = get_water_adjacent(x, z, heights);
+            self.sugarcane_positions.insert((x, z), water_adjacent);
+        }
+    }
+
+    fn get(&self, x: i32, z: i32) -> Option<GenInfo<'_>> {
+        let h = self.heightmap.get(&(x, z))?;
+        let trees = self.tree_positions.get(&(x, z))?;
+        let tree_h = self.tree_heights.get(&(x, z))?;
+        let plants = self.plant_positions.get(&(x, z))?;
+        let sugarcane = self.sugarcane_positions.get(&(x, z))?;
+
+        Some(GenInfo {
+            heights: h,
+            tree_positions: trees,
+            tree_heights: tree_h,
+            plant_positions: plants,
+            sugarcane_positions: sugarcane,
+        })
+    }
+}
+
+fn gen_chunk(chunk: &mut Chunk, gen_info: GenInfo, world_generator: &WorldGenerator) {
+    let chunkpos = chunk.get_chunk_pos();
+    let posx = chunkpos.x * CHUNK_SIZE_I32;
+    let posy = chunkpos.y * CHUNK_SIZE_I32;
+    let posz = chunkpos.z * CHUNK_SIZE_I32;
+
+    if chunkpos.y < -4 || chunkpos.y > 4 {
+        return;
+    }
+
+    let seed = ((chunkpos.x as u64) << 32) | (chunkpos.z as u64);
+    let mut rng = fastrand::Rng::with_seed(seed.wrapping_add(world_generator.world_seed as u64));
+    let ore_seed = ((chunkpos.x as u64) << 48) | ((chunkpos.y as u64) << 16) | (chunkpos.z as u64);
+    let mut ore_rng =
+        fastrand::Rng::with_seed(ore_seed.wrapping_add((world_generator.world_seed as u64) << 16));
+
+    for x in posx..(posx + CHUNK_SIZE_I32) {
+        for z in posz..(posz + CHUNK_SIZE_I32) {
+            let index = ((z - posz) * CHUNK_SIZE_I32 + (x - posx)) as usize;
+            let height = gen_info.heights[index];
+            let h = (height + 1).max(SEA_LEVEL + 1);
+
+            if h < posy {
+                continue;
+            }
+
+            for y in posy..(posy + CHUNK_SIZE_I32).min(h) {
+                let indestructible = (y == BOTTOM_OF_WORLD)
+                    || (y == BOTTOM_OF_WORLD + 1 && rng.i32(0..4) < 2)
+                    || (y == BOTTOM_OF_WORLD + 2 && rng.i32(0..6) == 0);
+                if indestructible {
+                    //Bottom of the world
+                    chunk.set_block(x, y, z, Block::new_id(INDESTRUCTIBLE));
+                    continue;
+                }
+
+                if y > BOTTOM_OF_WORLD && y <= LAVA_LEVEL {
+                    chunk.set_block(x, y, z, Block::new_fluid(LAVA));
+                }
+
+                if y <= SEA_LEVEL && y > height {
+                    chunk.set_block(x, y, z, Block::new_fluid(WATER));
+                }
+
+                //Sand
+                if y > height - 4 && y <= height && height <= SAND_LEVEL {
+                    chunk.set_block(x, y, z, Block::new_id(SAND));
+                    continue;
+                }
+
+                //Generate noise caves
+                if is_noise_cave(x, y, z, &world_generator.noise_cave_generator) {
+                    continue;
+                }
+
+                let dirt_depth = if height > 48 {
+                    2
+                } else if height > 16 {
+                    3
+                } else {
+                    4
+                };
+
+                if y == height {
+                    //Grass
+                    chunk.set_block(x, y, z, Block::new_id(GRASS));
+                } else if y > height - dirt_depth && y < height {
+                    //Dirt
+                    chunk.set_block(x, y, z, Block::new_id(DIRT));
+                } else if y < height && y > -64 {
+                    //Stone
+                    chunk.set_block(x, y, z, Block::new_id(STONE));
+                }
+            }
+        }
+    }
+
+    //Generate ore
+    for x in posx..(posx + CHUNK_SIZE_I32) {
+        for z in posz..(posz + CHUNK_SIZE_I32) {
+            let index = ((z - posz) * CHUNK_SIZE_I32 + (x - posx)) as usize;
+            let height = gen_info.heights[index];
+
+            if height < posy {
+                continue;
+            }
+
+            let h = height + 1;
+            for y in posy..(posy + CHUNK_SIZE_I32).min(h) {
+                generate_ore(chunk, x, y, z, &mut ore_rng);
+                generate_magma_blocks(chunk, x, y, z, &mut ore_rng);
+            }
+        }
+    }
+
+    //Generate trees
+    generate_trees(
+        chunk,
+        gen_info.tree_positions,
+        gen_info.tree_heights,
+        world_generator,
+    );
+    //Generate plants
+    generate_plants(chunk, gen_info.plant_positions, &mut rng, world_generator);
+    //Generate sugar cane
+    generate_sugarcane(chunk, gen_info.sugarcane_positions, &mut rng);
+    //Chunks deep underground are mostly stone
+    chunk.shrink();
+}
+
+//Generation info for a single column
+struct ColumnInfo<'a> {
+    gen_info_table: GenInfoTable,
+    world_generator: &'a WorldGenerator,
+}
+
+impl ColumnGenerator for ColumnInfo<'_> {
+    fn gen_chunk(&self, chunk: &mut Chunk) {
+        let pos = chunk.get_chunk_pos();
+        if let Some(gen_info) = self.gen_info_table.get(pos.x, pos.z) {
+            gen_chunk(chunk, gen_info, self.world_generator);
+        }
+    }
+}
+
+pub struct OldGenerator;
+
+impl TerrainGenerator for OldGenerator {
+    fn name(&self) -> &'static str {
+        "old"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Old"
+    }
+
+    fn column<'a>(
+        &self,
+        x: i32,
+        z: i32,
+        world_generator: &'a WorldGenerator,
+    ) -> Box<dyn ColumnGenerator + 'a> {
+        let mut gen_info_table = GenInfoTable::new();
+        gen_info_table.add_heights(x, z, world_generator);
+        gen_info_table.add_trees(x, z, world_generator);
+        gen_info_table.add_plants(x, z, world_generator);
+        gen_info_table.add_sugarcane(x, z);
+        Box::new(ColumnInfo {
+            gen_info_table,
+            world_generator,
+        })
+    }
+
+    fn surface(&self, x: i32, z: i32, world_generator: &WorldGenerator) -> Option<Surface> {
+        let height = terrain::get_height(
+            x,
+            z,
+            &world_generator.terrain_generator,
+            &world_generator.steepness,
+        );
+        let block = if height <= SAND_LEVEL {
+            Block::new_id(SAND)
+        } else {
+            Block::new_id(GRASS)
+        };
+        let cover = (height < SEA_LEVEL).then_some((Block::new_fluid(WATER), SEA_LEVEL));
+        Some(Surface {
+            height,
+            block,
+            cover,
+        })
+    }
+}
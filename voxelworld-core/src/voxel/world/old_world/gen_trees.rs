@@ -1,6 +1,9 @@
 use super::terrain::{get_height, is_noise_cave};
 use super::{WorldGenerator, SAND_LEVEL};
-use crate::voxel::{Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK};
+use crate::voxel::{
+    block_ids::{LEAVES, LOG},
+    Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
 use noise::{NoiseFn, Perlin};
 use std::collections::HashSet;
 
@@ -19,7 +22,7 @@ fn gen_tree_positions(
     let zu32 = chunkz as u32;
     let seed = ((xu32 as u64) << 32) | (zu32 as u64);
     let mut tree_generator = fastrand::Rng::with_seed(seed);
-    let mut rng = fastrand::Rng::with_seed(seed + world_seed as u64);
+    let mut rng = fastrand::Rng::with_seed(seed.wrapping_add(world_seed as u64));
     let mut generated = HashSet::<(i32, i32)>::new();
     for _ in 0..tree_count {
         let treex =
@@ -42,7 +45,7 @@ fn place_leaves(chunk: &mut Chunk, x: i32, y: i32, z: i32) {
     if chunk.get_block(x, y, z).id != EMPTY_BLOCK {
         return;
     }
-    chunk.set_block(x, y, z, Block::new_id(7));
+    chunk.set_block(x, y, z, Block::new_id(LEAVES));
 }
 
 fn generate_leaves(chunk: &mut Chunk, starty: i32, x: i32, y: i32, z: i32, height: i32) {
@@ -126,7 +129,7 @@ pub fn generate_trees(
 
         for y in (h + 1)..(h + 1 + tree_heights[i]) {
             //Generate trunk
-            chunk.set_block(*x, y, *z, Block::new_id(8));
+            chunk.set_block(*x, y, *z, Block::new_id(LOG));
 
             //Generate leaves
             generate_leaves(chunk, h + 1, *x, y, *z, tree_heights[i]);
@@ -1,5 +1,8 @@
 use super::{is_noise_cave, terrain::get_height, WorldGenerator, SAND_LEVEL, SEA_LEVEL};
-use crate::voxel::{Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK};
+use crate::voxel::{
+    block_ids::{BLUE_FLOWER, MUSHROOM, RED_FLOWER, SUGARCANE, TALL_GRASS, YELLOW_FLOWER},
+    Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK,
+};
 use fastrand::Rng;
 
 pub fn get_plant_positions(chunkx: i32, chunkz: i32, world_seed: u32) -> Vec<(i32, i32)> {
@@ -7,7 +10,7 @@ pub fn get_plant_positions(chunkx: i32, chunkz: i32, world_seed: u32) -> Vec<(i3
     let zu32 = chunkz as u32;
     let seed = ((zu32 as u64) << 32) | (xu32 as u64);
     let mut plant_generator = fastrand::Rng::with_seed(seed);
-    let mut rng = fastrand::Rng::with_seed(seed + ((world_seed as u64) << 32));
+    let mut rng = fastrand::Rng::with_seed(seed.wrapping_add((world_seed as u64) << 32));
     let count = plant_generator.i32(0..20);
     let mut positions = vec![];
     for _ in 0..count {
@@ -53,15 +56,15 @@ pub fn generate_plants(
 
         match rand_val {
             //Tall grass
-            0..50 => chunk.set_block(*x, h + 1, *z, Block::new_id(49)),
+            0..50 => chunk.set_block(*x, h + 1, *z, Block::new_id(TALL_GRASS)),
             //Red flower
-            50..58 => chunk.set_block(*x, h + 1, *z, Block::new_id(54)),
+            50..58 => chunk.set_block(*x, h + 1, *z, Block::new_id(RED_FLOWER)),
             //Yellow flower
-            58..66 => chunk.set_block(*x, h + 1, *z, Block::new_id(55)),
+            58..66 => chunk.set_block(*x, h + 1, *z, Block::new_id(YELLOW_FLOWER)),
             //Blue flower
-            66..70 => chunk.set_block(*x, h + 1, *z, Block::new_id(56)),
+            66..70 => chunk.set_block(*x, h + 1, *z, Block::new_id(BLUE_FLOWER)),
             //Mushroom
-            70..72 => chunk.set_block(*x, h + 1, *z, Block::new_id(48)),
+            70..72 => chunk.set_block(*x, h + 1, *z, Block::new_id(MUSHROOM)),
             //Nothing
             _ => {}
         }
@@ -116,7 +119,7 @@ pub fn generate_sugarcane(chunk: &mut Chunk, water_adjacent: &[(i32, i32)], rng:
                 break;
             }
 
-            chunk.set_block(*x, y, *z, Block::new_id(69));
+            chunk.set_block(*x, y, *z, Block::new_id(SUGARCANE));
         }
     }
 }
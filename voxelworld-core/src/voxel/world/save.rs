@@ -1,15 +1,25 @@
-use super::{LoadChunkQueue, World, WorldGenType, WorldGenerator};
+use super::{
+    block_update::{finite_fluid::FluidMode, schedule_chunk},
+    border::WorldBorder,
+    dimension::{get_dimension, overworld, PortalLinks},
+    gen_settings::read_gen_settings,
+    terrain_generator::{default_generator, get_generator, OldGenerator, TerrainGenerator},
+    GameMode, LoadChunkQueue, World, WorldGenerator,
+};
 use crate::{
-    game::GameMode,
     impfile::{self, Entry},
+    save::save_atomic,
     voxel::{
         coordinates::f32coord_to_int,
-        region::{chunkpos_to_regionpos, get_region_chunks, save::serialize_region, Region},
+        region::{
+            chunkpos_to_regionpos, codec::RegionCodec, get_region_chunks, save::serialize_region,
+            Region,
+        },
         world_to_chunk_position,
     },
 };
 use std::collections::{HashMap, HashSet};
-use std::{fs::File, io::Write};
+use std::io::Write;
 
 fn game_mode_to_string(game_mode: GameMode) -> String {
     match game_mode {
@@ -30,33 +40,22 @@ fn string_to_game_mode(s: &str) -> GameMode {
     }
 }
 
-fn gen_type_to_string(world_gen_type: WorldGenType) -> String {
-    match world_gen_type {
-        WorldGenType::Flat => "flat".to_string(),
-        WorldGenType::OldGen => "old".to_string(),
-        WorldGenType::DefaultGen => "default2".to_string(),
-        WorldGenType::Skyblock => "skyblock".to_string(),
-    }
-}
-
-fn string_to_gen_type(s: &str) -> WorldGenType {
-    if s == "flat" {
-        WorldGenType::Flat
-    } else if s == "old" || s == "default" {
-        WorldGenType::OldGen
-    } else if s == "skyblock" {
-        WorldGenType::Skyblock
-    } else {
-        WorldGenType::DefaultGen
+fn string_to_gen_type(s: &str) -> &'static dyn TerrainGenerator {
+    //"default" was the name of the old generator in older saves
+    if s == "default" {
+        return &OldGenerator;
     }
+    get_generator(s).unwrap_or_else(default_generator)
 }
 
-pub fn save_region(region: &Region, world_path: &str) {
-    if let Err(path) = serialize_region(world_path, region) {
-        if let Err(msg) = std::fs::remove_file(&path) {
-            eprintln!("Failed to remove {path}");
-            eprintln!("{msg}");
-        }
+pub fn save_region(region: &Region, world_path: &str, codec: RegionCodec) {
+    //Regions are saved atomically so on failure the old file is still intact
+    if let Err(msg) = serialize_region(world_path, region, codec) {
+        eprintln!(
+            "E: Failed to save region {}, {}, {}",
+            region.x, region.y, region.z
+        );
+        eprintln!("{msg}");
     }
 }
 
@@ -68,23 +67,30 @@ impl World {
         entry.add_integer("centerz", self.centerz as i64);
         entry.add_integer("seed", self.world_seed as i64);
         entry.add_integer("days_passed", self.days_passed as i64);
+        entry.add_integer("ticks", self.ticks as i64);
         entry.add_float("time", self.time);
-        entry.add_string("gen_type", &gen_type_to_string(self.gen_type));
+        entry.add_string("gen_type", self.gen_type.name());
+        entry.add_string("dimension", self.dimension.name);
         entry.add_string("game_mode", &game_mode_to_string(self.game_mode));
+        entry.add_string("codec", self.region_codec.name());
+        entry.add_string("fluid_mode", self.fluid_mode.name());
+        if let Some(border) = &self.border {
+            border.add_to_entry(&mut entry);
+        }
 
         let world_save_path = self.path.clone() + "world.impfile";
-        let world_entry_str = entry.to_impfile_string();
-        let res = match File::create(world_save_path) {
-            Ok(mut player_file) => {
-                impfile::write_comment(&mut player_file, "World metadata");
-                player_file.write_all(world_entry_str.as_bytes())
-            }
-            Err(msg) => Err(msg),
-        };
+        let world_entry_str = entry.to_impfile_string() + "\n";
+        let settings_entry_str = self.gen_settings().to_entry().to_impfile_string();
+        let res = save_atomic(&world_save_path, |world_file| {
+            impfile::write_comment(world_file, "World metadata");
+            world_file.write_all(world_entry_str.as_bytes())?;
+            world_file.write_all(settings_entry_str.as_bytes())
+        });
 
         if let Err(msg) = res {
-            eprintln!("E: Failed to save player: {msg}");
+            eprintln!("E: Failed to save world metadata: {msg}");
         }
+        self.portal_links.save(&self.path);
     }
 
     pub fn save(&mut self) {
@@ -100,7 +106,7 @@ impl World {
             let mut region = Region::new(rx, ry, rz);
             get_region_chunks(&mut region, &self.chunks);
             get_region_chunks(&mut region, &self.chunk_cache);
-            save_region(&region, &self.path);
+            save_region(&region, &self.data_path(), self.region_codec);
         }
         eprintln!("Saved {savedcount} regions.");
     }
@@ -130,7 +136,7 @@ impl World {
         for (x, y, z) in regions {
             let mut region = Region::new(x, y, z);
             get_region_chunks(&mut region, &chunks_to_save);
-            save_region(&region, &self.path);
+            save_region(&region, &self.data_path(), self.region_codec);
         }
         eprintln!("Saved {count} regions.");
     }
@@ -149,6 +155,8 @@ impl World {
             .unwrap_or(rand_seed);
 
         let mode = world_metadata_entries[0].get_var("game_mode");
+        //Worlds from before generation settings were added use the defaults
+        let settings = read_gen_settings(&world_metadata_entries, &path);
 
         Self {
             chunks: HashMap::new(),
@@ -167,15 +175,20 @@ impl World {
                 .parse::<i32>()
                 .unwrap_or(0),
             chunk_cache: HashMap::new(),
-            world_generator: WorldGenerator::new(seed),
+            world_generator: WorldGenerator::with_settings(seed, settings),
             world_seed: seed,
             gen_type: string_to_gen_type(&world_metadata_entries[0].get_var("gen_type")),
+            //Worlds from before dimensions were added are in the overworld
+            dimension: get_dimension(&world_metadata_entries[0].get_var("dimension"))
+                .unwrap_or_else(overworld),
+            portal_links: PortalLinks::load(world_dir_path),
             path: world_dir_path.to_string(),
             block_update_timer: 0.0,
             random_update_timer: 0.0,
-            updating: HashSet::new(),
-            in_update_range: HashSet::new(),
-            ticks: 0,
+            ticks: world_metadata_entries[0]
+                .get_var("ticks")
+                .parse::<u64>()
+                .unwrap_or(0),
             time: world_metadata_entries[0]
                 .get_var("time")
                 .parse::<f32>()
@@ -188,13 +201,28 @@ impl World {
             removed_from_cache: vec![],
             to_load: LoadChunkQueue::new(),
             chunktable_update_list: HashSet::new(),
+            mesh_updates: HashSet::new(),
             game_mode: string_to_game_mode(&mode),
+            //Worlds from before codecs were added only used rle
+            region_codec: RegionCodec::from_name(&world_metadata_entries[0].get_var("codec"))
+                .unwrap_or(RegionCodec::Rle),
+            border: WorldBorder::from_entry(&world_metadata_entries[0]),
+            signal_timers: HashMap::new(),
+            moving_blocks: vec![],
+            falling_blocks: vec![],
+            //Worlds from before finite fluids were added use the default
+            //fluid simulation
+            fluid_mode: FluidMode::from_name(&world_metadata_entries[0].get_var("fluid_mode"))
+                .unwrap_or(FluidMode::Classic),
         }
     }
 
     //Adds the chunks in a region to the world
     pub fn add_region(&mut self, region: Region) {
-        for chunk in region.chunks.iter().flatten() {
+        for mut chunk in region.chunks.into_iter().flatten() {
+            if chunk.is_unscheduled() {
+                schedule_chunk(&mut chunk, self.ticks + 1);
+            }
             let chunkpos = chunk.get_chunk_pos();
             let pos = (chunkpos.x, chunkpos.y, chunkpos.z);
 
@@ -203,24 +231,27 @@ impl World {
             }
 
             if (chunkpos.x - self.centerx).abs() > self.range {
-                self.chunk_cache.insert(pos, chunk.clone());
+                self.chunk_cache.insert(pos, chunk);
                 continue;
             }
             if (chunkpos.y - self.centery).abs() > self.range {
-                self.chunk_cache.insert(pos, chunk.clone());
+                self.chunk_cache.insert(pos, chunk);
                 continue;
             }
             if (chunkpos.z - self.centerz).abs() > self.range {
-                self.chunk_cache.insert(pos, chunk.clone());
+                self.chunk_cache.insert(pos, chunk);
                 continue;
             }
-            self.chunks.insert(pos, chunk.clone());
+            self.chunks.insert(pos, chunk);
         }
     }
 
     //Adds the chunks in a region to the world
     pub fn add_region_col(&mut self, region: Region, x: i32, z: i32) {
-        for chunk in region.chunks.iter().flatten() {
+        for mut chunk in region.chunks.into_iter().flatten() {
+            if chunk.is_unscheduled() {
+                schedule_chunk(&mut chunk, self.ticks + 1);
+            }
             let chunkpos = chunk.get_chunk_pos();
             let pos = (chunkpos.x, chunkpos.y, chunkpos.z);
 
@@ -229,23 +260,23 @@ impl World {
             }
 
             if (chunkpos.x - self.centerx).abs() > self.range {
-                self.chunk_cache.insert(pos, chunk.clone());
+                self.chunk_cache.insert(pos, chunk);
                 continue;
             }
             if (chunkpos.y - self.centery).abs() > self.range {
-                self.chunk_cache.insert(pos, chunk.clone());
+                self.chunk_cache.insert(pos, chunk);
                 continue;
             }
             if (chunkpos.z - self.centerz).abs() > self.range {
-                self.chunk_cache.insert(pos, chunk.clone());
+                self.chunk_cache.insert(pos, chunk);
                 continue;
             }
             //Anything not in the column gets put into the cache
             if chunkpos.x != x || chunkpos.z != z {
-                self.chunk_cache.insert(pos, chunk.clone());
+                self.chunk_cache.insert(pos, chunk);
                 continue;
             }
-            self.chunks.insert(pos, chunk.clone());
+            self.chunks.insert(pos, chunk);
         }
     }
 
@@ -264,19 +295,21 @@ impl World {
                     }
                     loaded.insert((rx, ry, rz));
 
-                    if let Some(region) = Region::load_region(&self.path, rx, ry, rz) {
+                    if let Some(region) = Region::load_region(
+                        &self.data_path(),
+                        rx,
+                        ry,
+                        rz,
+                        Some(self.region_codec),
+                        true,
+                    ) {
                         self.add_region(region);
                     }
                 }
             }
         }
 
-        match self.gen_type {
-            WorldGenType::Flat => self.gen_flat_on_load(),
-            WorldGenType::OldGen => self.gen_old_on_load(),
-            WorldGenType::DefaultGen => self.gen_default_on_load(),
-            WorldGenType::Skyblock => self.gen_skyblock_on_load(),
-        }
+        self.gen_on_load();
     }
 
     //I don't really have a good name for this function,
@@ -306,18 +339,15 @@ impl World {
                 continue;
             }
             let (rx, ry, rz) = chunkpos_to_regionpos(chunkx, chunky + dy, chunkz);
-            if let Some(region) = Region::load_region(&self.path, rx, ry, rz) {
+            if let Some(region) =
+                Region::load_region(&self.data_path(), rx, ry, rz, Some(self.region_codec), true)
+            {
                 self.add_region(region);
             }
 
             if !self.chunks.contains_key(&pos) {
                 //Generate new chunk
-                match self.gen_type {
-                    WorldGenType::Flat => self.add_flat_chunk(chunkx, chunky, chunkz),
-                    WorldGenType::OldGen => self.add_old_chunk(chunkx, chunky, chunkz),
-                    WorldGenType::DefaultGen => self.add_default_chunk(chunkx, chunky, chunkz),
-                    WorldGenType::Skyblock => self.add_skyblock_chunk(chunkx, chunky, chunkz),
-                }
+                self.add_chunk(chunkx, chunky + dy, chunkz);
             }
         }
     }
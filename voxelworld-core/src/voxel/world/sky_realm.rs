@@ -0,0 +1,108 @@
+/*
+ * Sky realm generation, floating islands of stone covered in grass with
+ * nothing but sky below them.
+ * */
+
+use super::{
+    terrain_generator::{ColumnGenerator, TerrainGenerator},
+    WorldGenerator,
+};
+use crate::voxel::{
+    block_ids::{DIRT, GRASS, STONE},
+    Block, Chunk, CHUNK_SIZE_I32,
+};
+use noise::NoiseFn;
+
+//The islands are centered around this height
+const ISLAND_HEIGHT: i32 = 64;
+//Islands never go further than this above or below ISLAND_HEIGHT
+const ISLAND_SPREAD: i32 = 40;
+const DIRT_DEPTH: i32 = 3;
+//Keeps the noise of the sky realm from lining up with overworld caves
+const NOISE_OFFSET: f64 = -2048.5;
+
+fn is_solid(x: i32, y: i32, z: i32, world_generator: &WorldGenerator) -> bool {
+    let dist = (y - ISLAND_HEIGHT) as f64 / ISLAND_SPREAD as f64;
+    if dist.abs() >= 1.0 {
+        return false;
+    }
+    let point = [
+        x as f64 / 64.0 + NOISE_OFFSET,
+        y as f64 / 24.0,
+        z as f64 / 64.0 + NOISE_OFFSET,
+    ];
+    let noise = world_generator.noise_cave_generator.get(point);
+    //Islands are flatter on top than on the bottom
+    let falloff = if dist > 0.0 { dist * 2.0 } else { -dist };
+    noise - falloff * 0.5 > 0.2
+}
+
+fn gen_sky_realm_chunk(chunk: &mut Chunk, world_generator: &WorldGenerator) {
+    let chunkpos = chunk.get_chunk_pos();
+    let posx = chunkpos.x * CHUNK_SIZE_I32;
+    let posy = chunkpos.y * CHUNK_SIZE_I32;
+    let posz = chunkpos.z * CHUNK_SIZE_I32;
+    if posy > ISLAND_HEIGHT + ISLAND_SPREAD || posy + CHUNK_SIZE_I32 < ISLAND_HEIGHT - ISLAND_SPREAD
+    {
+        return;
+    }
+
+    for x in posx..(posx + CHUNK_SIZE_I32) {
+        for z in posz..(posz + CHUNK_SIZE_I32) {
+            //Count the solid blocks above each block to find the surface,
+            //start above the chunk so that the surface matches between chunks
+            let mut depth = 0;
+            for y in (posy..(posy + CHUNK_SIZE_I32 + DIRT_DEPTH)).rev() {
+                if !is_solid(x, y, z, world_generator) {
+                    depth = 0;
+                    continue;
+                }
+                depth += 1;
+                if y >= posy + CHUNK_SIZE_I32 {
+                    continue;
+                }
+                let id = match depth {
+                    1 => GRASS,
+                    2..=DIRT_DEPTH => DIRT,
+                    _ => STONE,
+                };
+                chunk.set_block(x, y, z, Block::new_id(id));
+            }
+        }
+    }
+}
+
+struct SkyRealmColumn<'a> {
+    world_generator: &'a WorldGenerator,
+}
+
+impl ColumnGenerator for SkyRealmColumn<'_> {
+    fn gen_chunk(&self, chunk: &mut Chunk) {
+        gen_sky_realm_chunk(chunk, self.world_generator);
+    }
+}
+
+pub struct SkyRealmGenerator;
+
+impl TerrainGenerator for SkyRealmGenerator {
+    fn name(&self) -> &'static str {
+        "sky_realm"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Sky Realm"
+    }
+
+    fn column<'a>(
+        &self,
+        _x: i32,
+        _z: i32,
+        world_generator: &'a WorldGenerator,
+    ) -> Box<dyn ColumnGenerator + 'a> {
+        Box::new(SkyRealmColumn { world_generator })
+    }
+
+    fn bottom(&self) -> i32 {
+        0
+    }
+}
@@ -1,17 +1,22 @@
-use std::collections::HashSet;
-
-use super::World;
+use super::{
+    terrain_generator::{ColumnGenerator, TerrainGenerator},
+    WorldGenerator,
+};
 use crate::{
-    game::inventory::Item,
-    voxel::{tile_data::TileData, Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK, INDESTRUCTIBLE},
+    inventory::Item,
+    voxel::{
+        block_ids::{
+            BLUE_FLOWER, CACTUS, CHEST, COTTON, DIRT, GRASS, ICE, LEAVES, LOG, RED_FLOWER, SAND,
+            SEEDS, SUGARCANE, WHITE_FLOWER, YELLOW_FLOWER,
+        },
+        tile_data::TileData,
+        Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK, INDESTRUCTIBLE,
+    },
 };
-use crossbeam::{queue::ArrayQueue, thread};
 
 /*
- * NOTE: This code is effectively just copied and pasted from `flat_world.rs`
- * so there is a lot of repetition. I can probably improve the world generation
- * code to be better but I don't feel like doing that right now and it works fine
- * so copy-paste it is.
+ * Skyblock world generation, the world is empty apart from a few small
+ * islands near the spawn point.
  */
 
 fn place_leaves(chunk: &mut Chunk, x: usize, y: usize, z: usize) {
@@ -19,7 +24,7 @@ fn place_leaves(chunk: &mut Chunk, x: usize, y: usize, z: usize) {
     if replace.id != EMPTY_BLOCK && replace.shape() == 0 {
         return;
     }
-    chunk.set_block_relative(x, y, z, Block::new_id(7));
+    chunk.set_block_relative(x, y, z, Block::new_id(LEAVES));
 }
 
 fn generate_leaves(chunk: &mut Chunk, starty: usize, x: usize, y: usize, z: usize, height: usize) {
@@ -52,10 +57,10 @@ fn gen_spawn_island(chunk: &mut Chunk) {
             for y in 0..=2 {
                 if y == 2 {
                     //Grass layer
-                    chunk.set_block_relative(x, y, z, Block::new_id(1));
+                    chunk.set_block_relative(x, y, z, Block::new_id(GRASS));
                 } else {
                     //Dirt
-                    chunk.set_block_relative(x, y, z, Block::new_id(4));
+                    chunk.set_block_relative(x, y, z, Block::new_id(DIRT));
                 }
             }
         }
@@ -78,7 +83,7 @@ fn gen_spawn_island(chunk: &mut Chunk) {
     let tree_height = 6;
     for y in tree_y..(tree_y + tree_height) {
         //Generate trunk
-        chunk.set_block_relative(tree_x, y, tree_z, Block::new_id(8));
+        chunk.set_block_relative(tree_x, y, tree_z, Block::new_id(LOG));
 
         //Generate leaves
         generate_leaves(chunk, tree_y, tree_x, y, tree_z, tree_height);
@@ -93,7 +98,7 @@ fn gen_spawn_island(chunk: &mut Chunk) {
     );
 
     //Generate the chest on the island
-    let mut chest = Block::new_id(37);
+    let mut chest = Block::new_id(CHEST);
     chest.set_orientation(4);
     chunk.set_block_relative(3, 3, 7, chest);
     let mut chest_data = TileData::new_chest();
@@ -102,7 +107,7 @@ fn gen_spawn_island(chunk: &mut Chunk) {
     //Ice
     chest_data
         .inventory
-        .set_item(5, 1, Item::Block(Block::new_id(85), 1));
+        .set_item(5, 1, Item::Block(Block::new_id(ICE), 1));
     chunk.set_tile_data(3, 3, 7, Some(chest_data));
 }
 
@@ -111,18 +116,18 @@ fn gen_sand_island(chunk: &mut Chunk) {
     for x in 3..=5 {
         for z in 3..=5 {
             for y in 0..=2 {
-                chunk.set_block_relative(x, y, z, Block::new_id(11));
+                chunk.set_block_relative(x, y, z, Block::new_id(SAND));
             }
         }
     }
 
     //Cactus
-    chunk.set_block_relative(5, 3, 3, Block::new_id(88));
+    chunk.set_block_relative(5, 3, 3, Block::new_id(CACTUS));
     //Sugar cane
-    chunk.set_block_relative(4, 3, 5, Block::new_id(69));
+    chunk.set_block_relative(4, 3, 5, Block::new_id(SUGARCANE));
 
     //Generate chest
-    let mut chest = Block::new_id(37);
+    let mut chest = Block::new_id(CHEST);
     chest.set_orientation(1);
     chunk.set_block_relative(4, 3, 4, chest);
 
@@ -130,11 +135,11 @@ fn gen_sand_island(chunk: &mut Chunk) {
     //Ice
     chest_data
         .inventory
-        .set_item(3, 1, Item::Block(Block::new_id(85), 1));
+        .set_item(3, 1, Item::Block(Block::new_id(ICE), 1));
     //Wheat seeds
     chest_data
         .inventory
-        .set_item(5, 1, Item::Block(Block::new_id(77), 1));
+        .set_item(5, 1, Item::Block(Block::new_id(SEEDS), 1));
 
     let chunkpos = chunk.get_chunk_pos();
     let chunkx = chunkpos.x * CHUNK_SIZE_I32;
@@ -149,24 +154,24 @@ fn gen_flower_island(chunk: &mut Chunk) {
         for z in 6..=8 {
             for y in 0..=2 {
                 if y == 2 {
-                    chunk.set_block_relative(x, y, z, Block::new_id(1));
+                    chunk.set_block_relative(x, y, z, Block::new_id(GRASS));
                 } else {
-                    chunk.set_block_relative(x, y, z, Block::new_id(4));
+                    chunk.set_block_relative(x, y, z, Block::new_id(DIRT));
                 }
             }
         }
     }
 
     //Red flower
-    chunk.set_block_relative(6, 3, 6, Block::new_id(54));
+    chunk.set_block_relative(6, 3, 6, Block::new_id(RED_FLOWER));
     //Yellow flower
-    chunk.set_block_relative(6, 3, 8, Block::new_id(55));
+    chunk.set_block_relative(6, 3, 8, Block::new_id(YELLOW_FLOWER));
     //Blue flower
-    chunk.set_block_relative(8, 3, 6, Block::new_id(56));
+    chunk.set_block_relative(8, 3, 6, Block::new_id(BLUE_FLOWER));
     //White flower
-    chunk.set_block_relative(8, 3, 8, Block::new_id(111));
+    chunk.set_block_relative(8, 3, 8, Block::new_id(WHITE_FLOWER));
     //Cotton
-    chunk.set_block_relative(7, 3, 7, Block::new_id(102));
+    chunk.set_block_relative(7, 3, 7, Block::new_id(COTTON));
 }
 
 fn gen_skyblock_chunk(chunk: &mut Chunk) {
@@ -181,84 +186,28 @@ fn gen_skyblock_chunk(chunk: &mut Chunk) {
     }
 }
 
-impl World {
-    //Generates a skyblock world
-    pub fn gen_skyblock(&mut self) {
-        for chunk in &mut self.chunks.values_mut() {
-            gen_skyblock_chunk(chunk);
-        }
-    }
-
-    //Generates missing flat world chunks on load
-    pub fn gen_skyblock_on_load(&mut self) {
-        let mut to_generate = HashSet::new();
-        for y in (self.centery - self.range)..=(self.centery + self.range) {
-            for z in (self.centerz - self.range)..=(self.centerz + self.range) {
-                for x in (self.centerx - self.range)..=(self.centerx + self.range) {
-                    if self.chunks.contains_key(&(x, y, z)) {
-                        continue;
-                    }
-                    to_generate.insert((x, y, z));
-                }
-            }
-        }
+pub struct SkyblockGenerator;
 
-        //Generate new chunks
-        for (chunkx, chunky, chunkz) in &to_generate {
-            let pos = (*chunkx, *chunky, *chunkz);
-            if self.chunks.contains_key(&pos) {
-                continue;
-            }
-            let mut new_chunk = Chunk::new(*chunkx, *chunky, *chunkz);
-            gen_skyblock_chunk(&mut new_chunk);
-            self.chunks.insert(pos, new_chunk);
-        }
+impl ColumnGenerator for SkyblockGenerator {
+    fn gen_chunk(&self, chunk: &mut Chunk) {
+        gen_skyblock_chunk(chunk);
     }
+}
 
-    pub fn generate_column_skyblock(&mut self, x: i32, z: i32, yvals: &HashSet<i32>) {
-        //Generate new chunks
-        let start = std::time::Instant::now();
-        let generated = ArrayQueue::new(yvals.len());
-        let mut generated_count = 0;
-        thread::scope(|s| {
-            for y in yvals {
-                if !self.in_range(x, *y, z) {
-                    continue;
-                }
-
-                if self.chunks.contains_key(&(x, *y, z)) {
-                    continue;
-                }
-
-                generated_count += 1;
+impl TerrainGenerator for SkyblockGenerator {
+    fn name(&self) -> &'static str {
+        "skyblock"
+    }
 
-                s.spawn(|_| {
-                    let mut new_chunk = Chunk::new(x, *y, z);
-                    gen_skyblock_chunk(&mut new_chunk);
-                    //This should never fail
-                    generated
-                        .push(new_chunk)
-                        .expect("Error: Failed to push onto ArrayQueue");
-                });
-            }
-        })
-        .expect("Failed to generate new chunks!");
+    fn display_name(&self) -> &'static str {
+        "Skyblock"
+    }
 
-        for chunk in generated {
-            let chunkpos = chunk.get_chunk_pos();
-            let pos = (chunkpos.x, chunkpos.y, chunkpos.z);
-            self.chunks.insert(pos, chunk);
-        }
-        let time = start.elapsed().as_millis();
-        if time > 15 {
-            //Only report time taken if it exceeds 15 ms
-            eprintln!("Took {time} ms to generate {generated_count} new chunks");
-        }
+    fn column<'a>(&self, _x: i32, _z: i32, _: &'a WorldGenerator) -> Box<dyn ColumnGenerator + 'a> {
+        Box::new(SkyblockGenerator)
     }
 
-    pub fn add_skyblock_chunk(&mut self, chunkx: i32, chunky: i32, chunkz: i32) {
-        let mut chunk = Chunk::new(chunkx, chunky, chunkz);
-        gen_skyblock_chunk(&mut chunk);
-        self.chunks.insert((chunkx, chunky, chunkz), chunk);
+    fn bottom(&self) -> i32 {
+        -32
     }
 }
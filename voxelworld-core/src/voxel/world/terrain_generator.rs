@@ -0,0 +1,175 @@
+/*
+ * Terrain generators are registered by name in `GENERATORS`, the name is
+ * stored in the world metadata and the create world menu lists every
+ * registered generator in the order that they appear in `GENERATORS`.
+ *
+ * To add a new generator, implement `TerrainGenerator` for it and add it to
+ * `GENERATORS`, the threading and chunk loading is handled in `gen_more.rs`.
+ * Generators for dimensions other than the overworld are not registered,
+ * they are set on the dimension instead (see `dimension.rs`).
+ * */
+
+use super::{biome::Biome, WorldGenerator};
+use crate::voxel::{Block, Chunk};
+
+pub use super::{
+    default_world::DefaultGenerator, flat_world::FlatGenerator, old_world::OldGenerator,
+    sky_realm::SkyRealmGenerator, skyblock_world::SkyblockGenerator,
+    underworld::UnderworldGenerator,
+};
+
+const DEFAULT_SPAWN_POINT: (f32, f32) = (7.5, 7.5);
+const DEFAULT_BOTTOM: i32 = -128;
+
+//Top of the terrain in a column, used to draw maps of the world without
+//generating any chunks
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Surface {
+    pub height: i32,
+    pub block: Block,
+    //Water (or ice) on top of the terrain and the y coordinate of its surface
+    pub cover: Option<(Block, i32)>,
+}
+
+//Generates the chunks in a single column, chunks in the column are generated
+//in parallel so anything shared between them (heightmaps, trees, etc.)
+//should be computed when the column is created
+pub trait ColumnGenerator: Sync {
+    fn gen_chunk(&self, chunk: &mut Chunk);
+}
+
+pub trait TerrainGenerator: Sync {
+    //Stored in the world metadata, this should never change once a generator
+    //has been added otherwise older worlds will not load with it
+    fn name(&self) -> &'static str;
+    //Displayed in the create world menu
+    fn display_name(&self) -> &'static str;
+    //Returns a generator for the column of chunks at (x, z)
+    fn column<'a>(
+        &self,
+        x: i32,
+        z: i32,
+        world_generator: &'a WorldGenerator,
+    ) -> Box<dyn ColumnGenerator + 'a>;
+
+    //Returns the (x, z) position that players spawn at
+    fn spawn_point(&self, _world_generator: &WorldGenerator) -> (f32, f32) {
+        DEFAULT_SPAWN_POINT
+    }
+
+    //Returns the y coordinate for the bottom of the world
+    fn bottom(&self) -> i32 {
+        DEFAULT_BOTTOM
+    }
+
+    //Returns the biome at (x, z), generators without biomes are all plains
+    fn get_biome(&self, _x: i32, _z: i32, _world_generator: &WorldGenerator) -> Biome {
+        Biome::Plains
+    }
+
+    //Returns the surface at (x, z) without caves, trees or structures,
+    //generators that have no surface there (or can not compute it without
+    //generating chunks) return None
+    fn surface(&self, _x: i32, _z: i32, _world_generator: &WorldGenerator) -> Option<Surface> {
+        None
+    }
+}
+
+static GENERATORS: [&dyn TerrainGenerator; 4] = [
+    &DefaultGenerator,
+    &OldGenerator,
+    &SkyblockGenerator,
+    &FlatGenerator,
+];
+
+//Returns all registered generators
+pub fn terrain_generators() -> &'static [&'static dyn TerrainGenerator] {
+    &GENERATORS
+}
+
+pub fn get_generator(name: &str) -> Option<&'static dyn TerrainGenerator> {
+    GENERATORS
+        .iter()
+        .copied()
+        .find(|generator| generator.name() == name)
+}
+
+//Generator used when none is specified
+pub fn default_generator() -> &'static dyn TerrainGenerator {
+    &DefaultGenerator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{
+        world::{GameMode, World},
+        CHUNK_SIZE,
+    };
+    use std::collections::HashSet;
+
+    fn same_blocks(chunk1: &Chunk, chunk2: &Chunk) -> bool {
+        for x in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    let block1 = chunk1.get_block_relative(x, y, z);
+                    let block2 = chunk2.get_block_relative(x, y, z);
+                    if block1 != block2 {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn test_generator_names() {
+        let mut names = HashSet::new();
+        let mut display_names = HashSet::new();
+        for generator in terrain_generators() {
+            assert!(names.insert(generator.name()));
+            assert!(display_names.insert(generator.display_name()));
+            let found = get_generator(generator.name()).unwrap();
+            assert_eq!(found.name(), generator.name());
+        }
+        assert!(get_generator("not a generator").is_none());
+        //Names used by older saves
+        for name in ["flat", "old", "default2", "skyblock"] {
+            assert!(get_generator(name).is_some());
+        }
+    }
+
+    #[test]
+    fn test_generation_paths_match() {
+        //Generating the whole world at once, generating a column and adding a
+        //single chunk should all produce the same blocks
+        let dimension_generators: [&dyn TerrainGenerator; 2] =
+            [&UnderworldGenerator, &SkyRealmGenerator];
+        for generator in terrain_generators().iter().chain(&dimension_generators) {
+            let mut world = World::new(7, 1, *generator, GameMode::Creative);
+            world.generate_world();
+
+            let mut column_world = World::new(7, 1, *generator, GameMode::Creative);
+            column_world.chunks.clear();
+            for x in -1..=1 {
+                for z in -1..=1 {
+                    column_world.generate_column(x, z, &(-1..=1).collect());
+                }
+            }
+
+            let mut added_world = World::new(7, 1, *generator, GameMode::Creative);
+            added_world.chunks.clear();
+            for &(x, y, z) in world.chunks.keys() {
+                added_world.add_chunk(x, y, z);
+            }
+
+            assert_eq!(column_world.chunks.len(), world.chunks.len());
+            assert_eq!(added_world.chunks.len(), world.chunks.len());
+            for (pos, chunk) in &world.chunks {
+                assert!(same_blocks(chunk, &column_world.chunks[pos]));
+                assert!(same_blocks(chunk, &added_world.chunks[pos]));
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+/*
+ * Underworld generation, a closed cavern between a bedrock floor and a
+ * bedrock ceiling carved out of magma stone with a sea of lava at the
+ * bottom.
+ * */
+
+use super::{
+    gen_rng::RngStream,
+    terrain_generator::{ColumnGenerator, TerrainGenerator},
+    WorldGenerator,
+};
+use crate::voxel::{
+    block_ids::{LAVA, MAGMA_STONE, RED_ORE},
+    Block, Chunk, CHUNK_SIZE_I32, EMPTY_BLOCK, INDESTRUCTIBLE,
+};
+use noise::NoiseFn;
+
+const FLOOR: i32 = 0;
+const CEILING: i32 = 127;
+const LAVA_LEVEL: i32 = 24;
+//Out of 1000 blocks of magma stone
+const RED_ORE_CHANCE: u32 = 6;
+//Keeps the noise of the underworld from lining up with overworld caves
+const NOISE_OFFSET: f64 = 1024.5;
+
+//Returns true if the cavern is open at (x, y, z)
+fn is_open(x: i32, y: i32, z: i32, world_generator: &WorldGenerator) -> bool {
+    let point = [
+        x as f64 / 48.0 + NOISE_OFFSET,
+        y as f64 / 32.0,
+        z as f64 / 48.0 + NOISE_OFFSET,
+    ];
+    let noise = world_generator.noise_cave_generator.get(point);
+    //Close the cavern off near the floor and ceiling
+    let mid = (FLOOR + CEILING) as f64 / 2.0;
+    let dist = ((y as f64 - mid) / (mid - FLOOR as f64)).abs();
+    noise - dist.powi(4) * 0.8 > -0.15
+}
+
+fn gen_underworld_chunk(chunk: &mut Chunk, world_generator: &WorldGenerator) {
+    let chunkpos = chunk.get_chunk_pos();
+    let posx = chunkpos.x * CHUNK_SIZE_I32;
+    let posy = chunkpos.y * CHUNK_SIZE_I32;
+    let posz = chunkpos.z * CHUNK_SIZE_I32;
+    if posy > CEILING || posy + CHUNK_SIZE_I32 <= FLOOR {
+        return;
+    }
+
+    let pos = (chunkpos.x, chunkpos.y, chunkpos.z);
+    let mut bedrock_rng = world_generator.position_rng(RngStream::Bedrock, pos);
+    let mut ore_rng = world_generator.position_rng(RngStream::Ore, pos);
+    let starty = posy.max(FLOOR);
+    let endy = (posy + CHUNK_SIZE_I32).min(CEILING + 1);
+    for x in posx..(posx + CHUNK_SIZE_I32) {
+        for z in posz..(posz + CHUNK_SIZE_I32) {
+            for y in starty..endy {
+                let bedrock = y == FLOOR
+                    || y == CEILING
+                    || ((y == FLOOR + 1 || y == CEILING - 1) && bedrock_rng.i32(0..4) < 2);
+                let id = if bedrock {
+                    INDESTRUCTIBLE
+                } else if is_open(x, y, z, world_generator) {
+                    if y <= LAVA_LEVEL {
+                        LAVA
+                    } else {
+                        EMPTY_BLOCK
+                    }
+                } else if ore_rng.u32(0..1000) < RED_ORE_CHANCE {
+                    RED_ORE
+                } else {
+                    MAGMA_STONE
+                };
+
+                if id == LAVA {
+                    chunk.set_block(x, y, z, Block::new_fluid(LAVA));
+                } else if id != EMPTY_BLOCK {
+                    chunk.set_block(x, y, z, Block::new_id(id));
+                }
+            }
+        }
+    }
+}
+
+struct UnderworldColumn<'a> {
+    world_generator: &'a WorldGenerator,
+}
+
+impl ColumnGenerator for UnderworldColumn<'_> {
+    fn gen_chunk(&self, chunk: &mut Chunk) {
+        gen_underworld_chunk(chunk, self.world_generator);
+    }
+}
+
+pub struct UnderworldGenerator;
+
+impl TerrainGenerator for UnderworldGenerator {
+    fn name(&self) -> &'static str {
+        "underworld"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Underworld"
+    }
+
+    fn column<'a>(
+        &self,
+        _x: i32,
+        _z: i32,
+        world_generator: &'a WorldGenerator,
+    ) -> Box<dyn ColumnGenerator + 'a> {
+        Box::new(UnderworldColumn { world_generator })
+    }
+
+    fn bottom(&self) -> i32 {
+        FLOOR
+    }
+}
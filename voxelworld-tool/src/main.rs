@@ -0,0 +1,293 @@
+/*
+ * voxelworld-tool: headless utility for inspecting and maintaining worlds
+ * without opening a window. Block definitions are loaded from assets/ so this
+ * should be run from the same directory as the game.
+ * */
+
+mod regions;
+
+use std::{collections::HashMap, path::Path, process::ExitCode};
+use voxelworld_core::{
+    impfile,
+    save::{backup, create_world_dir, SAVE_PATH},
+    voxel::{
+        block_registry::block_registry,
+        region::{chunkpos_to_regionpos, codec::benchmark_codecs, Region, REGION_SIZE},
+        world::{
+            map_preview::{render_saved_map, MapArea},
+            terrain_generator::{
+                default_generator, get_generator, terrain_generators, TerrainGenerator,
+            },
+            GameMode,
+        },
+        World, CHUNK_SIZE, CHUNK_SIZE_I32, EMPTY_BLOCK,
+    },
+};
+
+const USAGE: &str = "usage: voxelworld-tool <command> <world> [args]
+
+commands:
+  info <world>                                 print world metadata
+  list-regions <world>                         list region files
+  dump-chunk <world> <x> <y> <z>               print the contents of a chunk
+  pregenerate <world> <radius> [type] [seed]   generate chunks around the center
+  map <world> <png> [radius] [scale]           draw a map of the saved regions
+  prune <world>                                delete regions with no blocks
+  verify <world>                               check that every region loads
+  repair <world>                               restore/quarantine broken regions
+  bench-codecs                                 compare the region codecs on
+                                               generated terrain
+
+<world> is either a path to a world directory or the name of a world in saves/";
+
+type CommandResult = Result<(), String>;
+
+//Accepts either a path to a world or the name of a world in the save folder,
+//the returned path always ends with a '/'
+fn resolve_world_path(world: &str) -> String {
+    let path = if !Path::new(world).exists() && Path::new(&(SAVE_PATH.to_string() + world)).exists()
+    {
+        SAVE_PATH.to_string() + world
+    } else {
+        world.to_string()
+    };
+
+    if path.ends_with('/') {
+        path
+    } else {
+        path + "/"
+    }
+}
+
+fn parse_arg<T: std::str::FromStr>(args: &[String], index: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or(format!("missing argument: {name}"))?;
+    arg.parse::<T>()
+        .map_err(|_| format!("invalid {name}: {arg}"))
+}
+
+fn world_exists(world_path: &str) -> bool {
+    Path::new(&(world_path.to_string() + "world.impfile")).exists()
+}
+
+fn info(world_path: &str) -> CommandResult {
+    let entries = impfile::parse_file(&(world_path.to_string() + "world.impfile"));
+    let metadata = entries
+        .first()
+        .ok_or(format!("{world_path} does not contain a world"))?;
+
+    println!("world: {world_path}");
+    for (name, value) in metadata.get_all_vars() {
+        println!("  {name}: {value}");
+    }
+
+    let region_files = regions::list_region_files(world_path)?;
+    let total_size: u64 = region_files.iter().map(|file| file.size).sum();
+    println!("regions: {} ({} bytes)", region_files.len(), total_size);
+
+    let backups = backup::list_backups(&backup::get_backup_dir(world_path));
+    println!("backups: {}", backups.len());
+    if let Some(newest) = backups.first() {
+        println!("  newest: {}", newest.name());
+    }
+
+    Ok(())
+}
+
+fn dump_chunk(world_path: &str, x: i32, y: i32, z: i32) -> CommandResult {
+    let (rx, ry, rz) = chunkpos_to_regionpos(x, y, z);
+    let region = Region::load_region(world_path, rx, ry, rz, None, false).ok_or(format!(
+        "region {rx}, {ry}, {rz} does not exist or is corrupted"
+    ))?;
+    let size = REGION_SIZE as i32;
+    let chunk = region
+        .get_relative_chunk(
+            (x - rx * size) as usize,
+            (y - ry * size) as usize,
+            (z - rz * size) as usize,
+        )
+        .ok_or(format!("chunk {x}, {y}, {z} has not been generated"))?;
+
+    //Count how many of each block there are in the chunk
+    let mut counts = HashMap::new();
+    let mut tile_data = vec![];
+    for ix in 0..CHUNK_SIZE {
+        for iy in 0..CHUNK_SIZE {
+            for iz in 0..CHUNK_SIZE {
+                let block = chunk.get_block_relative(ix, iy, iz);
+                *counts.entry(block.id).or_insert(0u32) += 1;
+
+                let bx = x * CHUNK_SIZE_I32 + ix as i32;
+                let by = y * CHUNK_SIZE_I32 + iy as i32;
+                let bz = z * CHUNK_SIZE_I32 + iz as i32;
+                if let Some(data) = chunk.get_tile_data(bx, by, bz) {
+                    tile_data.push(((bx, by, bz), block.id, data));
+                }
+            }
+        }
+    }
+
+    let mut counts: Vec<(u16, u32)> = counts.into_iter().collect();
+    counts.sort_by(|(id1, count1), (id2, count2)| count2.cmp(count1).then(id1.cmp(id2)));
+    println!("chunk {x}, {y}, {z} (region {rx}, {ry}, {rz})");
+    println!("blocks:");
+    for (id, count) in counts {
+        let name = match block_registry().get(id) {
+            Some(def) => def.name.as_str(),
+            None if id == EMPTY_BLOCK => "air",
+            None => "unknown",
+        };
+        println!("  {id:>5} {name:<24} {count}");
+    }
+
+    if !tile_data.is_empty() {
+        println!("tile data:");
+    }
+    for ((bx, by, bz), id, data) in tile_data {
+        let items = data.get_items();
+        let stacks = items.iter().filter(|item| !item.is_empty()).count();
+        println!("  {bx}, {by}, {bz} (block {id}): {stacks} item stack(s)");
+    }
+
+    Ok(())
+}
+
+fn parse_gen_type(name: &str) -> Result<&'static dyn TerrainGenerator, String> {
+    get_generator(name).ok_or_else(|| {
+        let names: Vec<&str> = terrain_generators()
+            .iter()
+            .map(|gen_type| gen_type.name())
+            .collect();
+        format!(
+            "unknown world type {name} (expected one of: {})",
+            names.join(", ")
+        )
+    })
+}
+
+fn pregenerate(
+    world_path: &str,
+    radius: i32,
+    gen_type: Option<&String>,
+    seed: Option<u32>,
+) -> CommandResult {
+    if radius < 0 {
+        return Err("radius must not be negative".to_string());
+    }
+
+    let world = if world_exists(world_path) {
+        if gen_type.is_some() || seed.is_some() {
+            eprintln!("W: {world_path} already exists, ignoring world type and seed");
+        }
+        let mut world = World::load_world_metadata(world_path, radius);
+        eprintln!("Loading existing chunks...");
+        world.load_chunks();
+        world
+    } else {
+        let gen_type = match gen_type {
+            Some(name) => parse_gen_type(name)?,
+            None => default_generator(),
+        };
+        create_world_dir(world_path)?;
+        let seed = seed.unwrap_or_else(|| fastrand::u32(..));
+        let mut world = World::new(seed, radius, gen_type, GameMode::Survival);
+        world.path = world_path.to_string();
+        eprintln!("Generating new world with seed {seed}...");
+        world.generate_world();
+        world
+    };
+
+    world.save_all();
+    let (x, y, z) = world.get_center();
+    println!(
+        "generated {} chunks around {x}, {y}, {z}",
+        world.chunks.len()
+    );
+    Ok(())
+}
+
+//Draws a map centered on the player, radius is in blocks
+fn map(world_path: &str, output: &str, radius: u32, scale: u32) -> CommandResult {
+    if scale == 0 {
+        return Err("scale must be at least 1".to_string());
+    }
+    if !world_exists(world_path) {
+        return Err(format!("{world_path} does not contain a world"));
+    }
+
+    let world = World::load_world_metadata(world_path, 0);
+    let (x, _, z) = world.get_center();
+    let size = (radius * 2).div_ceil(scale);
+    let area = MapArea::centered(x * CHUNK_SIZE_I32, z * CHUNK_SIZE_I32, size, scale);
+    render_saved_map(world_path, area)?.save_png(output)?;
+    println!("saved {size}x{size} map to {output}");
+    Ok(())
+}
+
+fn run(args: &[String]) -> CommandResult {
+    let command = args.first().ok_or("missing command")?;
+    //The only command that does not take a world
+    if command == "bench-codecs" {
+        for line in benchmark_codecs() {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+    let world_path = resolve_world_path(args.get(1).ok_or("missing argument: world")?);
+
+    match command.as_str() {
+        "info" => info(&world_path),
+        "list-regions" => regions::list_regions(&world_path),
+        "dump-chunk" => {
+            let x = parse_arg(args, 2, "x")?;
+            let y = parse_arg(args, 3, "y")?;
+            let z = parse_arg(args, 4, "z")?;
+            dump_chunk(&world_path, x, y, z)
+        }
+        "pregenerate" => {
+            let radius = parse_arg(args, 2, "radius")?;
+            let seed = if args.len() > 4 {
+                Some(parse_arg(args, 4, "seed")?)
+            } else {
+                None
+            };
+            pregenerate(&world_path, radius, args.get(3), seed)
+        }
+        "map" => {
+            let output = args.get(2).ok_or("missing argument: png")?;
+            let radius = if args.len() > 3 {
+                parse_arg(args, 3, "radius")?
+            } else {
+                256
+            };
+            let scale = if args.len() > 4 {
+                parse_arg(args, 4, "scale")?
+            } else {
+                1
+            };
+            map(&world_path, output, radius, scale)
+        }
+        "prune" => regions::prune(&world_path),
+        "verify" => regions::verify(&world_path),
+        "repair" => regions::repair(&world_path),
+        _ => Err(format!("unknown command: {command}")),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args[0] == "help" || args[0] == "--help" {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("E: {msg}");
+            if msg.starts_with("missing") || msg.starts_with("unknown command") {
+                eprintln!("{USAGE}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use super::{world_exists, CommandResult};
+use voxelworld_core::{
+    save::{backup, save_bytes_atomic, CHUNK_PATH},
+    voxel::{
+        region::{
+            codec::RegionCodec,
+            format::{read_header, REGION_FORMAT_VERSION},
+            load::{decode_region, CORRUPT_SUFFIX},
+            save::parse_region_file_name,
+            Region,
+        },
+        world::dimension::{dimensions, Dimension},
+        World,
+    },
+};
+
+pub struct RegionFile {
+    pub dimension: &'static Dimension,
+    pub name: String,
+    pub path: String,
+    pub pos: (i32, i32, i32),
+    pub size: u64,
+}
+
+impl RegionFile {
+    fn decode(&self) -> Result<(Region, u16), String> {
+        let bytes = std::fs::read(&self.path).map_err(|e| e.to_string())?;
+        let (x, y, z) = self.pos;
+        decode_region(&bytes, x, y, z)
+    }
+
+    //Name of the file along with the dimension that it is in
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.dimension.name, self.name)
+    }
+}
+
+//Returns all region files in every dimension of the world, sorted by
+//dimension and then by position
+pub fn list_region_files(world_path: &str) -> Result<Vec<RegionFile>, String> {
+    let mut region_files = vec![];
+    for dimension in dimensions().iter().copied() {
+        let chunk_path = dimension.data_path(world_path) + CHUNK_PATH;
+        let dir_contents = match std::fs::read_dir(&chunk_path) {
+            Ok(dir_contents) => dir_contents,
+            //Dimensions that were never visited do not have any regions
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !dimension.is_overworld() => {
+                continue
+            }
+            Err(e) => return Err(format!("failed to open {chunk_path}: {e}")),
+        };
+
+        let mut dimension_files = vec![];
+        for entry in dir_contents.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            //Ignore temporary files and regions that were quarantined by repair
+            let Some(pos) = parse_region_file_name(&name) else {
+                continue;
+            };
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            dimension_files.push(RegionFile {
+                dimension,
+                path: chunk_path.clone() + name.as_str(),
+                name,
+                pos,
+                size,
+            });
+        }
+        dimension_files.sort_by_key(|file| file.pos);
+        region_files.extend(dimension_files);
+    }
+    Ok(region_files)
+}
+
+//Codec used by the block data, versions before 2 did not have a codec byte
+fn codec_name(version: u16, data: &[u8]) -> &'static str {
+    if version < 2 {
+        return RegionCodec::Rle.name();
+    }
+    data.first()
+        .and_then(|id| RegionCodec::from_id(*id))
+        .map(|codec| codec.name())
+        .unwrap_or("unknown")
+}
+
+//Returns true if there is no region data worth keeping
+fn is_empty_region(region: &Region) -> bool {
+    region.chunks.iter().flatten().all(|chunk| chunk.is_empty())
+}
+
+pub fn list_regions(world_path: &str) -> CommandResult {
+    let region_files = list_region_files(world_path)?;
+    println!(
+        "{:<12} {:<24} {:>7} {:>7} {:>10} {:>6}",
+        "dimension", "position", "version", "codec", "bytes", "chunks"
+    );
+    for file in &region_files {
+        let bytes = std::fs::read(&file.path).map_err(|e| e.to_string())?;
+        let (version, data) = read_header(&bytes);
+        let (x, y, z) = file.pos;
+        let chunks = match decode_region(&bytes, x, y, z) {
+            Ok((region, _)) => region
+                .chunks
+                .iter()
+                .flatten()
+                .filter(|chunk| !chunk.is_empty())
+                .count()
+                .to_string(),
+            Err(_) => "error".to_string(),
+        };
+        println!(
+            "{:<12} {:<24} {:>7} {:>7} {:>10} {:>6}",
+            file.dimension.name,
+            format!("{x}, {y}, {z}"),
+            version,
+            codec_name(version, data),
+            file.size,
+            chunks
+        );
+    }
+    println!("{} region(s)", region_files.len());
+    Ok(())
+}
+
+pub fn prune(world_path: &str) -> CommandResult {
+    let mut removed = 0;
+    for file in list_region_files(world_path)? {
+        match file.decode() {
+            Ok((region, _)) if is_empty_region(&region) => {
+                std::fs::remove_file(&file.path).map_err(|e| e.to_string())?;
+                println!("removed {}", file.label());
+                removed += 1;
+            }
+            Ok(_) => {}
+            Err(msg) => eprintln!("W: skipping {}: {msg}", file.label()),
+        }
+    }
+    println!("removed {removed} empty region(s)");
+    Ok(())
+}
+
+pub fn verify(world_path: &str) -> CommandResult {
+    let region_files = list_region_files(world_path)?;
+    let mut failed = 0;
+    let mut outdated = 0;
+    for file in &region_files {
+        match file.decode() {
+            Ok((_, version)) if version < REGION_FORMAT_VERSION => {
+                println!("{}: ok (version {version}, outdated)", file.label());
+                outdated += 1;
+            }
+            Ok(_) => {}
+            Err(msg) => {
+                println!("{}: CORRUPTED ({msg})", file.label());
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "checked {} region(s): {failed} corrupted, {outdated} outdated",
+        region_files.len()
+    );
+    if failed > 0 {
+        return Err(format!(
+            "{failed} region(s) failed to load, run repair to fix them"
+        ));
+    }
+    Ok(())
+}
+
+//Looks for the newest backup of a region file that can still be loaded
+fn find_backup(world_path: &str, file: &RegionFile) -> Option<(backup::Backup, Vec<u8>)> {
+    let (x, y, z) = file.pos;
+    backup::list_backups(&backup::get_backup_dir(world_path))
+        .into_iter()
+        .find_map(|b| {
+            let path = file.dimension.data_path(&b.path) + CHUNK_PATH + file.name.as_str();
+            let bytes = std::fs::read(path).ok()?;
+            decode_region(&bytes, x, y, z).ok()?;
+            Some((b, bytes))
+        })
+}
+
+//Corrupted regions are replaced with the newest backup that loads, if there
+//is none then the file is renamed so that the game regenerates the region.
+//Outdated regions are rewritten in the current format.
+pub fn repair(world_path: &str) -> CommandResult {
+    if !world_exists(world_path) {
+        return Err(format!("{world_path} does not contain a world"));
+    }
+    let codec = World::load_world_metadata(world_path, 0).region_codec;
+
+    let mut repaired = 0;
+    for file in list_region_files(world_path)? {
+        match file.decode() {
+            Ok((region, version)) if version < REGION_FORMAT_VERSION => {
+                region.save_region(&file.dimension.data_path(world_path), codec)?;
+                println!("{}: upgraded from version {version}", file.label());
+                repaired += 1;
+            }
+            Ok(_) => {}
+            Err(msg) => {
+                println!("{}: CORRUPTED ({msg})", file.label());
+                if let Some((b, bytes)) = find_backup(world_path, &file) {
+                    save_bytes_atomic(&file.path, &bytes)?;
+                    println!("  restored from backup {}", b.name());
+                } else {
+                    let quarantine_path = file.path.clone() + CORRUPT_SUFFIX;
+                    std::fs::rename(&file.path, &quarantine_path).map_err(|e| e.to_string())?;
+                    println!("  no usable backup, moved to {quarantine_path}");
+                }
+                repaired += 1;
+            }
+        }
+    }
+    println!("repaired {repaired} region(s)");
+    Ok(())
+}